@@ -107,7 +107,8 @@ fn identifier_matches(
         }
         IdentifierKind::AutomationId
         | IdentifierKind::RuntimeId
-        | IdentifierKind::AtspiObjectPath => false,
+        | IdentifierKind::AtspiObjectPath
+        | IdentifierKind::CdpNode => false,
     }
 }
 
@@ -0,0 +1,82 @@
+use std::fmt::Write;
+
+use super::{Finding, RULES, Severity};
+
+/// One `<testcase>` per rule so CI dashboards track each rule over time. A
+/// rule fails when any of its findings is an error; warning and info findings
+/// are listed in `<system-out>` without failing the case.
+pub(crate) fn render_junit(suite: &str, findings: &[Finding]) -> String {
+    let mut cases = String::new();
+    let mut failures = 0;
+    for (rule, _, description) in RULES {
+        let matching: Vec<&Finding> = findings.iter().filter(|f| f.rule == *rule).collect();
+        let failing = matching.iter().any(|f| f.severity == Severity::Error);
+        let _ = write!(
+            cases,
+            "    <testcase classname=\"{}\" name=\"{}\">",
+            escape(suite),
+            escape(rule)
+        );
+        if !matching.is_empty() {
+            let body = matching
+                .iter()
+                .map(|f| describe(f))
+                .collect::<Vec<_>>()
+                .join("\n");
+            if failing {
+                failures += 1;
+                let _ = write!(
+                    cases,
+                    "\n      <failure message=\"{}: {} finding(s)\" type=\"{}\">{}</failure>\n    ",
+                    escape(description),
+                    matching.len(),
+                    escape(rule),
+                    escape(&body)
+                );
+            } else {
+                let _ = write!(
+                    cases,
+                    "\n      <system-out>{}</system-out>\n    ",
+                    escape(&body)
+                );
+            }
+        }
+        cases.push_str("</testcase>\n");
+    }
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites>\n  <testsuite name=\"{}\" tests=\"{}\" failures=\"{failures}\">\n{cases}  </testsuite>\n</testsuites>\n",
+        escape(suite),
+        RULES.len()
+    )
+}
+
+fn describe(finding: &Finding) -> String {
+    format!(
+        "[{}] {} {}{}: {}",
+        finding.severity.as_str(),
+        finding.ref_id.as_deref().unwrap_or("-"),
+        finding.role,
+        finding
+            .name
+            .as_deref()
+            .map(|name| format!(" \"{name}\""))
+            .unwrap_or_default(),
+        finding.message
+    )
+}
+
+pub(crate) fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            c if c.is_control() && c != '\n' && c != '\t' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
@@ -0,0 +1,93 @@
+mod junit;
+mod rules;
+
+pub(crate) use junit::{escape as escape_xml, render_junit};
+
+use serde::Serialize;
+
+use crate::{AccessibilityNode, Rect};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Info => "info",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        }
+    }
+}
+
+/// One rule violation, addressed by the snapshot ref of the offending node
+/// when it has one. Unreffed nodes carry their role and name only.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct Finding {
+    pub rule: &'static str,
+    pub severity: Severity,
+    #[serde(rename = "ref", skip_serializing_if = "Option::is_none")]
+    pub ref_id: Option<String>,
+    pub role: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub message: String,
+}
+
+/// Rule catalog: id, severity, and the one-line description reported in
+/// JUnit and in `rules` so CI output is self-describing.
+pub(crate) const RULES: &[(&str, Severity, &str)] = &[
+    (
+        rules::INTERACTIVE_UNNAMED,
+        Severity::Error,
+        "Interactive element has no computed accessible name",
+    ),
+    (
+        rules::DUPLICATE_SIBLING_NAME,
+        Severity::Warning,
+        "Sibling buttons share the same accessible name",
+    ),
+    (
+        rules::MISSING_NATIVE_ID,
+        Severity::Info,
+        "Interactive element has no native automation identifier",
+    ),
+    (
+        rules::ZERO_SIZE_BOUNDS,
+        Severity::Error,
+        "Interactive element has zero-size bounds",
+    ),
+    (
+        rules::OFFSCREEN_BOUNDS,
+        Severity::Warning,
+        "Interactive element lies outside its window",
+    ),
+    (
+        rules::DISABLED_FOCUSABLE,
+        Severity::Warning,
+        "Disabled element still accepts keyboard focus",
+    ),
+];
+
+pub(crate) fn severity_of(rule: &str) -> Severity {
+    RULES
+        .iter()
+        .find(|(id, _, _)| *id == rule)
+        .map_or(Severity::Info, |(_, severity, _)| *severity)
+}
+
+/// Walks a snapshot tree and reports every rule violation in document order.
+pub(crate) fn audit_tree(tree: &AccessibilityNode, window_bounds: Option<Rect>) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    rules::visit(tree, window_bounds, &mut findings);
+    findings
+}
+
+#[cfg(test)]
+#[path = "mod_tests.rs"]
+mod tests;
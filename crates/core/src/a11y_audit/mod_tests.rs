@@ -0,0 +1,146 @@
+use super::*;
+use crate::NodeIdentity;
+
+fn node(role: &str, name: Option<&str>, children: Vec<AccessibilityNode>) -> AccessibilityNode {
+    AccessibilityNode {
+        ref_id: None,
+        role: role.into(),
+        identity: NodeIdentity {
+            name: name.map(str::to_string),
+            native_id: Some(crate::ElementIdentifier {
+                kind: crate::IdentifierKind::AxIdentifier,
+                value: format!("{role}-id"),
+            }),
+            ..Default::default()
+        },
+        presentation: crate::NodePresentation {
+            bounds: Some(rect(10.0, 10.0, 40.0, 20.0)),
+            ..Default::default()
+        },
+        children_count: None,
+        subtree_truncated: false,
+        children,
+    }
+}
+
+fn rect(x: f64, y: f64, width: f64, height: f64) -> Rect {
+    Rect {
+        x,
+        y,
+        width,
+        height,
+    }
+}
+
+fn rules_of(findings: &[Finding]) -> Vec<&'static str> {
+    findings.iter().map(|f| f.rule).collect()
+}
+
+const WINDOW: Option<Rect> = Some(Rect {
+    x: 0.0,
+    y: 0.0,
+    width: 800.0,
+    height: 600.0,
+});
+
+#[test]
+fn clean_tree_has_no_findings() {
+    let tree = node(
+        "window",
+        Some("Doc"),
+        vec![node("button", Some("Save"), vec![])],
+    );
+
+    assert!(audit_tree(&tree, WINDOW).is_empty());
+}
+
+#[test]
+fn unnamed_interactive_element_is_an_error_but_value_counts_as_a_name() {
+    let mut named_by_value = node("textfield", None, vec![]);
+    named_by_value.identity.value = Some("hello".into());
+    let tree = node(
+        "window",
+        None,
+        vec![node("button", None, vec![]), named_by_value],
+    );
+
+    let findings = audit_tree(&tree, WINDOW);
+
+    assert_eq!(rules_of(&findings), ["interactive-unnamed"]);
+    assert_eq!(findings[0].severity, Severity::Error);
+    assert_eq!(findings[0].role, "button");
+}
+
+#[test]
+fn duplicate_sibling_buttons_are_reported_once_per_name() {
+    let tree = node(
+        "toolbar",
+        Some("Main"),
+        vec![
+            node("button", Some("Close"), vec![]),
+            node("button", Some("close"), vec![]),
+            node("button", Some("Close"), vec![]),
+            node("group", Some("Close"), vec![]),
+        ],
+    );
+
+    let findings = audit_tree(&tree, WINDOW);
+
+    assert_eq!(rules_of(&findings), ["duplicate-sibling-name"]);
+}
+
+#[test]
+fn bounds_and_identity_rules_apply_to_interactive_nodes_only() {
+    let mut zero = node("button", Some("Zero"), vec![]);
+    zero.presentation.bounds = Some(rect(5.0, 5.0, 0.0, 12.0));
+    let mut away = node("button", Some("Away"), vec![]);
+    away.presentation.bounds = Some(rect(2000.0, 10.0, 30.0, 10.0));
+    let mut anonymous = node("checkbox", Some("Agree"), vec![]);
+    anonymous.identity.native_id = None;
+    let mut label = node("statictext", Some("Hint"), vec![]);
+    label.identity.native_id = None;
+    label.presentation.bounds = Some(rect(0.0, 0.0, 0.0, 0.0));
+    let tree = node("window", None, vec![zero, away, anonymous, label]);
+
+    let findings = audit_tree(&tree, WINDOW);
+
+    assert_eq!(
+        rules_of(&findings),
+        ["zero-size-bounds", "offscreen-bounds", "missing-native-id"]
+    );
+}
+
+#[test]
+fn disabled_element_that_takes_focus_is_flagged() {
+    let mut field = node("textfield", Some("Email"), vec![]);
+    field
+        .presentation
+        .states
+        .push(crate::state::DISABLED.into());
+    field
+        .presentation
+        .available_actions
+        .push(crate::capability::SET_FOCUS.into());
+    let tree = node("window", None, vec![field]);
+
+    let findings = audit_tree(&tree, WINDOW);
+
+    assert_eq!(rules_of(&findings), ["disabled-focusable"]);
+    assert_eq!(findings[0].severity, Severity::Warning);
+}
+
+#[test]
+fn junit_has_one_case_per_rule_and_fails_only_on_errors() {
+    let mut away = node("button", Some("A & B"), vec![]);
+    away.presentation.bounds = Some(rect(-500.0, 10.0, 30.0, 10.0));
+    let tree = node("window", None, vec![node("button", None, vec![]), away]);
+    let findings = audit_tree(&tree, WINDOW);
+
+    let xml = render_junit("audit-a11y <App>", &findings);
+
+    assert!(xml.contains(&format!("tests=\"{}\" failures=\"1\"", RULES.len())));
+    assert!(xml.contains("name=\"audit-a11y &lt;App&gt;\""));
+    assert!(xml.contains("type=\"interactive-unnamed\""));
+    assert!(xml.contains("<system-out>[warning] - button &quot;A &amp; B&quot;"));
+    assert_eq!(xml.matches("<testcase ").count(), RULES.len());
+}
@@ -0,0 +1,128 @@
+use rustc_hash::FxHashMap;
+
+use super::{Finding, severity_of};
+use crate::{AccessibilityNode, NameEvidence, Rect};
+
+pub(super) const INTERACTIVE_UNNAMED: &str = "interactive-unnamed";
+pub(super) const DUPLICATE_SIBLING_NAME: &str = "duplicate-sibling-name";
+pub(super) const MISSING_NATIVE_ID: &str = "missing-native-id";
+pub(super) const ZERO_SIZE_BOUNDS: &str = "zero-size-bounds";
+pub(super) const OFFSCREEN_BOUNDS: &str = "offscreen-bounds";
+pub(super) const DISABLED_FOCUSABLE: &str = "disabled-focusable";
+
+pub(super) fn visit(
+    node: &AccessibilityNode,
+    window_bounds: Option<Rect>,
+    findings: &mut Vec<Finding>,
+) {
+    check_node(node, window_bounds, findings);
+    check_sibling_names(&node.children, findings);
+    for child in &node.children {
+        visit(child, window_bounds, findings);
+    }
+}
+
+fn check_node(node: &AccessibilityNode, window_bounds: Option<Rect>, findings: &mut Vec<Finding>) {
+    if has_state(node, crate::state::DISABLED) && is_focusable(node) {
+        findings.push(finding(
+            DISABLED_FOCUSABLE,
+            node,
+            "Element is disabled but still advertises keyboard focus".into(),
+        ));
+    }
+    if !crate::ref_alloc::is_ref_able(node) {
+        return;
+    }
+    if computed_name(node).is_none() {
+        findings.push(finding(
+            INTERACTIVE_UNNAMED,
+            node,
+            format!("{} has no label, title, value, or description", node.role),
+        ));
+    }
+    if node.identity.native_id.is_none() {
+        findings.push(finding(
+            MISSING_NATIVE_ID,
+            node,
+            format!("{} exposes no native automation id", node.role),
+        ));
+    }
+    let Some(bounds) = node.presentation.bounds else {
+        return;
+    };
+    if bounds.width <= 0.0 || bounds.height <= 0.0 {
+        findings.push(finding(
+            ZERO_SIZE_BOUNDS,
+            node,
+            format!("Bounds are {}x{}", bounds.width, bounds.height),
+        ));
+    } else if window_bounds.is_some_and(|window| !intersects(bounds, window))
+        || has_state(node, crate::state::OFFSCREEN)
+    {
+        findings.push(finding(
+            OFFSCREEN_BOUNDS,
+            node,
+            format!(
+                "Bounds ({}, {}, {}x{}) are outside the window",
+                bounds.x, bounds.y, bounds.width, bounds.height
+            ),
+        ));
+    }
+}
+
+fn check_sibling_names(children: &[AccessibilityNode], findings: &mut Vec<Finding>) {
+    let mut seen: FxHashMap<String, usize> = FxHashMap::default();
+    for child in children.iter().filter(|child| child.role == "button") {
+        let Some(name) = computed_name(child) else {
+            continue;
+        };
+        let count = seen.entry(name.to_lowercase()).or_default();
+        *count += 1;
+        if *count == 2 {
+            findings.push(finding(
+                DUPLICATE_SIBLING_NAME,
+                child,
+                format!("More than one sibling button is named \"{name}\""),
+            ));
+        }
+    }
+}
+
+/// The tree already carries the adapter's resolved name, so the remaining
+/// name sources are the value and description the computation falls back to.
+fn computed_name(node: &AccessibilityNode) -> Option<String> {
+    crate::compute_name(&NameEvidence {
+        explicit_label: node.identity.name.clone(),
+        static_value: node.identity.value.clone(),
+        description: node.identity.description.clone(),
+        ..Default::default()
+    })
+}
+
+fn is_focusable(node: &AccessibilityNode) -> bool {
+    has_state(node, crate::state::FOCUSED)
+        || node
+            .presentation
+            .available_actions
+            .iter()
+            .any(|action| action == crate::capability::SET_FOCUS)
+}
+
+fn has_state(node: &AccessibilityNode, state: &str) -> bool {
+    node.presentation.states.iter().any(|s| s == state)
+}
+
+fn intersects(a: Rect, b: Rect) -> bool {
+    a.x < b.x + b.width && b.x < a.x + a.width && a.y < b.y + b.height && b.y < a.y + a.height
+}
+
+fn finding(rule: &'static str, node: &AccessibilityNode, message: String) -> Finding {
+    Finding {
+        rule,
+        severity: severity_of(rule),
+        ref_id: node.ref_id.clone(),
+        role: node.role.clone(),
+        name: node.identity.name.clone().filter(|name| !name.is_empty()),
+        message,
+    }
+}
@@ -250,6 +250,7 @@ mod tests {
             ErrorCode::SnapshotNotFound,
             ErrorCode::PolicyDenied,
             ErrorCode::AppUnresponsive,
+            ErrorCode::AssertionFailed,
             ErrorCode::Internal,
         ];
         let retryable = [
@@ -0,0 +1,245 @@
+use serde_json::{Value, json};
+
+use crate::{
+    Action, ActionResult, AdapterError, AppError, Deadline, DeliverySemantics, ErrorCode,
+    IdentifierKind, InteractionLease, action_request::ActionRequest, cdp_page,
+    cdp_socket::CdpSocket, ref_action::into_adapter_error, ref_action_context::RefActionContext,
+    refs::RefEntry,
+};
+
+/// Sets a form control's value through the prototype setter, so frameworks
+/// that track the last value they wrote (React among them) see the change,
+/// then fires the events a typing user would.
+const SET_VALUE_FUNCTION: &str = "function (value) {
+  this.focus();
+  if (this.isContentEditable) {
+    this.textContent = value;
+  } else {
+    const proto = Object.getPrototypeOf(this);
+    const setter = Object.getOwnPropertyDescriptor(proto, 'value')?.set;
+    if (setter) { setter.call(this, value); } else { this.value = value; }
+  }
+  this.dispatchEvent(new Event('input', { bubbles: true }));
+  this.dispatchEvent(new Event('change', { bubbles: true }));
+}";
+
+/// True for refs grafted from a page's DevTools tree by `snapshot`.
+pub(crate) fn targets(entry: &RefEntry) -> bool {
+    entry
+        .identity
+        .native_id
+        .as_ref()
+        .is_some_and(|id| id.kind == IdentifierKind::CdpNode)
+}
+
+/// The auto-wait check for a web ref: the action must be one web refs
+/// support, and the node must still be in its page with a box to act on.
+pub(crate) fn probe(
+    entry: &RefEntry,
+    ref_id: &str,
+    action: &Action,
+    deadline: Deadline,
+) -> Result<(), AdapterError> {
+    if !supports(action) {
+        return Err(unsupported(action));
+    }
+    let (_, mut socket) = open_page(entry, ref_id, deadline).map_err(into_adapter_error)?;
+    let node = node_param(entry, ref_id).map_err(into_adapter_error)?;
+    socket
+        .call("DOM.getBoxModel", node)
+        .map(drop)
+        .map_err(|error| {
+            into_adapter_error(error).with_disposition(DeliverySemantics::not_delivered())
+        })
+}
+
+/// The dispatch step of the ref action pipeline for a web ref: delivers the
+/// request through the page's DevTools endpoint. Input is synthesized
+/// inside the page, so no window focus, cursor, or headed mode is involved.
+pub(crate) fn dispatch_resolved(
+    target: RefActionContext<'_>,
+    request: ActionRequest,
+    _lease: &InteractionLease,
+) -> Result<ActionResult, AppError> {
+    let action = request.action.name();
+    let ref_id = target.ref_id;
+    target.context.trace_lazy(
+        "action.cdp.start",
+        || json!({ "ref": ref_id, "action": action }),
+    )?;
+    let (page_id, mut socket) = open_page(target.entry, ref_id, target.deadline)?;
+    let node = node_param(target.entry, ref_id)?;
+    dispatch(&mut socket, &request.action, node)?;
+    target
+        .context
+        .trace_lazy(
+            "action.cdp.ok",
+            || json!({ "ref": ref_id, "action": action }),
+        )
+        .map_err(|error| {
+            crate::context::trace_error_with_disposition(
+                error,
+                DeliverySemantics::delivered_unverified(),
+            )
+        })?;
+    Ok(ActionResult::delivered_unverified(action)
+        .with_details(json!({ "via": "cdp", "page_id": page_id })))
+}
+
+fn supports(action: &Action) -> bool {
+    matches!(
+        action,
+        Action::Click
+            | Action::DoubleClick
+            | Action::TripleClick
+            | Action::RightClick
+            | Action::SetFocus
+            | Action::ScrollTo
+            | Action::SetValue(_)
+            | Action::Clear
+            | Action::TypeText(_)
+    )
+}
+
+fn unsupported(action: &Action) -> AdapterError {
+    AdapterError::new(
+        ErrorCode::ActionNotSupported,
+        format!("{} is not available on web content refs", action.name()),
+    )
+    .with_suggestion(
+        "Web refs support click, double-click, triple-click, right-click, focus, \
+         scroll-to, set-value, clear, and type.",
+    )
+    .with_disposition(DeliverySemantics::not_delivered())
+}
+
+fn dispatch(socket: &mut CdpSocket, action: &Action, node: Value) -> Result<(), AppError> {
+    match action {
+        Action::Click => click(socket, node, "left", 1),
+        Action::DoubleClick => click(socket, node, "left", 2),
+        Action::TripleClick => click(socket, node, "left", 3),
+        Action::RightClick => click(socket, node, "right", 1),
+        Action::SetFocus => socket.call("DOM.focus", node).map(drop),
+        Action::ScrollTo => socket.call("DOM.scrollIntoViewIfNeeded", node).map(drop),
+        Action::SetValue(text) => set_value(socket, node, text),
+        Action::Clear => set_value(socket, node, ""),
+        Action::TypeText(text) => {
+            socket.call("DOM.focus", node)?;
+            socket
+                .call("Input.insertText", json!({ "text": text }))
+                .map(drop)
+        }
+        other => Err(unsupported(other).into()),
+    }
+}
+
+fn open_page(
+    entry: &RefEntry,
+    ref_id: &str,
+    deadline: Deadline,
+) -> Result<(String, CdpSocket), AppError> {
+    let (page_id, _) = native_parts(entry, ref_id)?;
+    let process = &entry.process;
+    let endpoint = crate::cdp_registry::lookup(process.pid, process.process_instance.as_deref())
+        .ok_or_else(|| gone(ref_id, "its app's endpoint is no longer registered"))?;
+    let page = cdp_page::list(endpoint.port, deadline)?
+        .into_iter()
+        .find(|page| page.id == page_id)
+        .ok_or_else(|| gone(ref_id, "its page is no longer open"))?;
+    Ok((page.id.clone(), page.open(endpoint.port, deadline)?))
+}
+
+fn node_param(entry: &RefEntry, ref_id: &str) -> Result<Value, AppError> {
+    let (_, backend_id) = native_parts(entry, ref_id)?;
+    Ok(json!({ "backendNodeId": backend_id }))
+}
+
+fn native_parts<'a>(entry: &'a RefEntry, ref_id: &str) -> Result<(&'a str, u64), AppError> {
+    entry
+        .identity
+        .native_id
+        .as_ref()
+        .and_then(|id| id.value.split_once(':'))
+        .and_then(|(page, node)| Some((page, node.parse().ok()?)))
+        .ok_or_else(|| gone(ref_id, "its DevTools node id is malformed"))
+}
+
+fn click(socket: &mut CdpSocket, node: Value, button: &str, count: u32) -> Result<(), AppError> {
+    socket.call("DOM.scrollIntoViewIfNeeded", node.clone())?;
+    let model = socket.call("DOM.getBoxModel", node)?;
+    let quad: Vec<f64> = model
+        .pointer("/model/content")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_f64)
+        .collect();
+    if quad.len() != 8 {
+        return Err(
+            AdapterError::new(ErrorCode::ActionFailed, "Web element has no box to click")
+                .with_disposition(DeliverySemantics::not_delivered())
+                .into(),
+        );
+    }
+    let x = quad.iter().step_by(2).sum::<f64>() / 4.0;
+    let y = quad.iter().skip(1).step_by(2).sum::<f64>() / 4.0;
+    let event = |kind: &str, click_count: u32| {
+        json!({
+            "type": kind,
+            "x": x,
+            "y": y,
+            "button": button,
+            "clickCount": click_count,
+        })
+    };
+    socket.call("Input.dispatchMouseEvent", event("mouseMoved", 0))?;
+    for click_count in 1..=count {
+        socket.call(
+            "Input.dispatchMouseEvent",
+            event("mousePressed", click_count),
+        )?;
+        socket.call(
+            "Input.dispatchMouseEvent",
+            event("mouseReleased", click_count),
+        )?;
+    }
+    Ok(())
+}
+
+fn set_value(socket: &mut CdpSocket, node: Value, text: &str) -> Result<(), AppError> {
+    let resolved = socket.call("DOM.resolveNode", node)?;
+    let object_id = resolved
+        .pointer("/object/objectId")
+        .cloned()
+        .ok_or_else(|| AdapterError::new(ErrorCode::ActionFailed, "Web element did not resolve"))?;
+    let outcome = socket.call(
+        "Runtime.callFunctionOn",
+        json!({
+            "objectId": object_id,
+            "functionDeclaration": SET_VALUE_FUNCTION,
+            "arguments": [{ "value": text }],
+        }),
+    )?;
+    if let Some(exception) = outcome.get("exceptionDetails") {
+        return Err(
+            AdapterError::new(ErrorCode::ActionFailed, "Web element rejected the value")
+                .with_platform_detail(exception.to_string())
+                .into(),
+        );
+    }
+    Ok(())
+}
+
+fn gone(ref_id: &str, reason: &str) -> AppError {
+    AdapterError::new(
+        ErrorCode::StaleRef,
+        format!("{ref_id} is web content, but {reason}"),
+    )
+    .with_suggestion("Run snapshot again; relaunch the app with --cdp if it was restarted.")
+    .with_disposition(DeliverySemantics::not_delivered())
+    .into()
+}
+
+#[cfg(test)]
+#[path = "cdp_action_tests.rs"]
+mod tests;
@@ -0,0 +1,214 @@
+use super::*;
+use crate::adapter::{ActionOps, InputOps, ObservationOps, SystemOps};
+use crate::cdp_test_support::{PAGE_ID, StubCdp};
+use crate::refs_test_support::HomeGuard;
+use crate::{context::CommandContext, ref_action_wait_context::RefActionWaitContext};
+
+/// Has no accessibility tree: web refs must never resolve through it.
+struct LeaseOnlyAdapter;
+
+impl ObservationOps for LeaseOnlyAdapter {}
+impl ActionOps for LeaseOnlyAdapter {}
+impl InputOps for LeaseOnlyAdapter {}
+
+impl SystemOps for LeaseOnlyAdapter {
+    fn acquire_interaction_lease(
+        &self,
+        deadline: Deadline,
+    ) -> Result<InteractionLease, AdapterError> {
+        InteractionLease::guarded(deadline, ())
+    }
+}
+
+fn web_entry(native: &str) -> RefEntry {
+    RefEntry {
+        process: crate::RefProcess {
+            pid: crate::ProcessId::new(4242),
+            process_instance: Some("4242:1".into()),
+        },
+        identity: crate::RefEntryIdentity {
+            role: "button".into(),
+            name: Some("Save".into()),
+            value: None,
+            description: None,
+            native_id: Some(crate::ElementIdentifier {
+                kind: IdentifierKind::CdpNode,
+                value: native.into(),
+            }),
+        },
+        geometry: crate::RefGeometry {
+            bounds: None,
+            bounds_hash: None,
+        },
+        capabilities: crate::RefCapabilities {
+            states: vec![],
+            available_actions: vec![],
+        },
+        source: crate::RefSource {
+            source_app: None,
+            source_window_id: None,
+            source_window_title: None,
+            source_window_bounds_hash: None,
+            source_surface: crate::SnapshotSurface::Window,
+        },
+        scope: crate::RefScope {
+            root_ref: None,
+            path_is_absolute: false,
+            path: smallvec::SmallVec::new(),
+        },
+    }
+}
+
+fn registered_stub() -> StubCdp {
+    let stub = StubCdp::serve("Inbox", |method, _| match method {
+        "DOM.getBoxModel" => Ok(json!({ "model": {
+            "content": [10.0, 20.0, 30.0, 20.0, 30.0, 40.0, 10.0, 40.0],
+        }})),
+        "DOM.resolveNode" => Ok(json!({ "object": { "objectId": "obj-1" } })),
+        _ => Ok(json!({})),
+    });
+    crate::cdp_registry::record(
+        crate::ProcessId::new(4242),
+        Some("4242:1"),
+        &stub.endpoint(),
+    )
+    .unwrap();
+    stub
+}
+
+fn request(action: Action) -> ActionRequest {
+    ActionRequest {
+        action,
+        policy: Default::default(),
+        timeout_ms: Some(5_000),
+        verified_point: None,
+        expected_process: None,
+    }
+}
+
+fn run(entry: &RefEntry, action: Action) -> Result<Value, AppError> {
+    let result = crate::ref_action_wait::execute_with_auto_wait(
+        RefActionWaitContext {
+            adapter: &LeaseOnlyAdapter,
+            entry,
+            ref_id: "@e1",
+            context: &CommandContext::default(),
+        },
+        request(action),
+        dispatch_resolved,
+    )?;
+    Ok(serde_json::to_value(result)?)
+}
+
+#[test]
+fn double_click_presses_twice_at_the_content_center() {
+    let _home = HomeGuard::new();
+    let stub = registered_stub();
+
+    let result = run(&web_entry(&format!("{PAGE_ID}:103")), Action::DoubleClick).unwrap();
+
+    assert_eq!(result["details"]["via"], "cdp");
+    assert_eq!(
+        stub.params_of("DOM.scrollIntoViewIfNeeded")[0]["backendNodeId"],
+        103
+    );
+    let events = stub.params_of("Input.dispatchMouseEvent");
+    let kinds: Vec<(&str, u64)> = events
+        .iter()
+        .map(|event| {
+            (
+                event["type"].as_str().unwrap(),
+                event["clickCount"].as_u64().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        kinds,
+        [
+            ("mouseMoved", 0),
+            ("mousePressed", 1),
+            ("mouseReleased", 1),
+            ("mousePressed", 2),
+            ("mouseReleased", 2),
+        ]
+    );
+    assert_eq!(
+        (events[1]["x"].as_f64(), events[1]["y"].as_f64()),
+        (Some(20.0), Some(30.0))
+    );
+}
+
+#[test]
+fn set_value_runs_the_setter_on_the_resolved_element() {
+    let _home = HomeGuard::new();
+    let stub = registered_stub();
+
+    run(
+        &web_entry(&format!("{PAGE_ID}:105")),
+        Action::SetValue("a@b.c".into()),
+    )
+    .unwrap();
+
+    let call = &stub.params_of("Runtime.callFunctionOn")[0];
+    assert_eq!(call["objectId"], "obj-1");
+    assert_eq!(call["arguments"][0]["value"], "a@b.c");
+    assert_eq!(
+        stub.methods(),
+        [
+            "DOM.getBoxModel",
+            "DOM.resolveNode",
+            "Runtime.callFunctionOn"
+        ]
+    );
+}
+
+#[test]
+fn unsupported_actions_are_refused_before_delivery() {
+    let _home = HomeGuard::new();
+    let stub = registered_stub();
+
+    let error = run(&web_entry(&format!("{PAGE_ID}:103")), Action::Expand).unwrap_err();
+
+    assert_eq!(error.code(), "ACTION_NOT_SUPPORTED");
+    assert!(stub.methods().is_empty());
+}
+
+#[test]
+fn a_ref_from_a_closed_page_or_unregistered_app_is_stale() {
+    let _home = HomeGuard::new();
+    let unregistered = run(&web_entry(&format!("{PAGE_ID}:103")), Action::Click).unwrap_err();
+    assert_eq!(unregistered.code(), "STALE_REF");
+
+    let _stub = registered_stub();
+    let closed = run(&web_entry("GONE:103"), Action::Click).unwrap_err();
+    assert_eq!(closed.code(), "STALE_REF");
+}
+
+#[test]
+fn a_web_element_without_a_box_yet_is_waited_for_before_dispatch() {
+    let _home = HomeGuard::new();
+    let boxless_polls = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(2));
+    let polls = boxless_polls.clone();
+    let stub = StubCdp::serve("Inbox", move |method, _| match method {
+        "DOM.getBoxModel" if polls.load(std::sync::atomic::Ordering::SeqCst) > 0 => {
+            polls.fetch_sub(1, std::sync::atomic::Ordering::SeqCst);
+            Err("Could not compute box model.".into())
+        }
+        "DOM.getBoxModel" => Ok(json!({ "model": {
+            "content": [10.0, 20.0, 30.0, 20.0, 30.0, 40.0, 10.0, 40.0],
+        }})),
+        _ => Ok(json!({})),
+    });
+    crate::cdp_registry::record(
+        crate::ProcessId::new(4242),
+        Some("4242:1"),
+        &stub.endpoint(),
+    )
+    .unwrap();
+
+    let result = run(&web_entry(&format!("{PAGE_ID}:103")), Action::Click).unwrap();
+
+    assert_eq!(result["details"]["via"], "cdp");
+    assert_eq!(stub.params_of("Input.dispatchMouseEvent").len(), 3);
+    assert!(stub.params_of("DOM.getBoxModel").len() >= 4);
+}
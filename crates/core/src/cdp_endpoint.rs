@@ -73,22 +73,7 @@ pub(crate) fn probe(port: u16, deadline: Deadline) -> Result<CdpEndpoint, AppErr
 }
 
 fn attempt(port: u16, timeout: Duration) -> Attempt {
-    let address = SocketAddr::from(([127, 0, 0, 1], port));
-    let Ok(mut stream) = TcpStream::connect_timeout(&address, timeout) else {
-        return Attempt::NotYetListening;
-    };
-    if stream.set_read_timeout(Some(timeout)).is_err()
-        || stream.set_write_timeout(Some(timeout)).is_err()
-    {
-        return Attempt::NotYetListening;
-    }
-    let request = format!(
-        "GET /json/version HTTP/1.1\r\nHost: 127.0.0.1:{port}\r\nConnection: close\r\n\r\n"
-    );
-    if stream.write_all(request.as_bytes()).is_err() {
-        return Attempt::NotYetListening;
-    }
-    let Some(raw) = read_http_response(&mut stream, timeout) else {
+    let Some(raw) = http_get(port, "/json/version", timeout) else {
         return Attempt::NotYetListening;
     };
     match parse_response(port, &raw) {
@@ -97,6 +82,19 @@ fn attempt(port: u16, timeout: Duration) -> Attempt {
     }
 }
 
+/// One GET against the DevTools HTTP server on loopback, returning the raw
+/// response, or `None` when nothing answered within `timeout`.
+pub(crate) fn http_get(port: u16, path: &str, timeout: Duration) -> Option<Vec<u8>> {
+    let address = SocketAddr::from(([127, 0, 0, 1], port));
+    let mut stream = TcpStream::connect_timeout(&address, timeout).ok()?;
+    stream.set_read_timeout(Some(timeout)).ok()?;
+    stream.set_write_timeout(Some(timeout)).ok()?;
+    let request =
+        format!("GET {path} HTTP/1.1\r\nHost: 127.0.0.1:{port}\r\nConnection: close\r\n\r\n");
+    stream.write_all(request.as_bytes()).ok()?;
+    read_http_response(&mut stream, timeout)
+}
+
 /// Chromium's DevTools server answers and then holds the connection open,
 /// ignoring `Connection: close`, so reading to EOF waits for a close that
 /// never comes and times out on every live endpoint. The response is done
@@ -146,13 +144,18 @@ fn content_length(headers: &[u8]) -> Option<usize> {
     })
 }
 
-fn parse_response(port: u16, raw: &[u8]) -> Option<CdpEndpoint> {
+/// The JSON body of a raw HTTP response, if it has one.
+pub(crate) fn json_body(raw: &[u8]) -> Option<serde_json::Value> {
     let text = String::from_utf8_lossy(raw);
     let body = text
         .split_once("\r\n\r\n")
         .map_or("", |(_, body)| body)
         .trim();
-    let parsed: serde_json::Value = serde_json::from_str(body).ok()?;
+    serde_json::from_str(body).ok()
+}
+
+fn parse_response(port: u16, raw: &[u8]) -> Option<CdpEndpoint> {
+    let parsed = json_body(raw)?;
     let websocket_url = parsed
         .get("webSocketDebuggerUrl")
         .and_then(serde_json::Value::as_str)?
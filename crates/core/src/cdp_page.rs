@@ -0,0 +1,49 @@
+use serde_json::Value;
+
+use crate::{AdapterError, AppError, Deadline, ErrorCode, cdp_socket::CdpSocket};
+
+/// One `page` target from the endpoint's `/json/list`.
+#[derive(Debug, Clone)]
+pub(crate) struct PageTarget {
+    pub id: String,
+    pub title: String,
+    path: String,
+}
+
+impl PageTarget {
+    pub(crate) fn open(&self, port: u16, deadline: Deadline) -> Result<CdpSocket, AppError> {
+        CdpSocket::connect(port, &self.path, deadline)
+    }
+}
+
+/// The page targets the endpoint reports. Workers, iframes, and other
+/// target types carry no window contents of their own and are left out.
+pub(crate) fn list(port: u16, deadline: Deadline) -> Result<Vec<PageTarget>, AppError> {
+    let listing = crate::cdp_endpoint::http_get(port, "/json/list", deadline.remaining())
+        .as_deref()
+        .and_then(crate::cdp_endpoint::json_body)
+        .ok_or_else(|| {
+            AdapterError::new(
+                ErrorCode::ActionFailed,
+                format!("DevTools endpoint on port {port} did not list its pages"),
+            )
+            .with_suggestion("The app may have quit; launch it again with --cdp.")
+        })?;
+    Ok(listing
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|target| target.get("type").and_then(Value::as_str) == Some("page"))
+        .filter_map(|target| {
+            let text = |key: &str| target.get(key).and_then(Value::as_str);
+            let url = text("webSocketDebuggerUrl")?;
+            let authority_and_path = url.strip_prefix("ws://")?;
+            let path = &authority_and_path[authority_and_path.find('/')?..];
+            Some(PageTarget {
+                id: text("id")?.to_owned(),
+                title: text("title").unwrap_or_default().to_owned(),
+                path: path.to_owned(),
+            })
+        })
+        .collect())
+}
@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::{AppError, ProcessId, cdp_endpoint::CdpEndpoint};
+
+const MAX_REGISTRATION_BYTES: u64 = 16 * 1024;
+
+/// A verified DevTools endpoint remembered for the process `launch --cdp`
+/// started, so later `snapshot` and action processes can reach its web
+/// contents. Keyed by pid under the state root; `process_instance` keeps a
+/// recycled pid from inheriting a dead process's endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CdpRegistration {
+    pid: ProcessId,
+    process_instance: Option<String>,
+    endpoint: CdpEndpoint,
+}
+
+fn registration_path(pid: ProcessId) -> Result<PathBuf, AppError> {
+    Ok(crate::state_root::resolve_configured_state_root()?
+        .join("cdp")
+        .join(format!("{pid}.json")))
+}
+
+pub(crate) fn record(
+    pid: ProcessId,
+    process_instance: Option<&str>,
+    endpoint: &CdpEndpoint,
+) -> Result<(), AppError> {
+    let registration = CdpRegistration {
+        pid,
+        process_instance: process_instance.map(str::to_owned),
+        endpoint: endpoint.clone(),
+    };
+    crate::private_file::write_atomic(
+        &registration_path(pid)?,
+        &serde_json::to_vec(&registration)?,
+    )?;
+    Ok(())
+}
+
+/// The endpoint recorded for this process, if any. A registration from
+/// another instance of the pid, or one that cannot be read, counts as none.
+pub(crate) fn lookup(pid: ProcessId, process_instance: Option<&str>) -> Option<CdpEndpoint> {
+    let path = registration_path(pid).ok()?;
+    let bytes = crate::private_file::read_private_bounded(&path, MAX_REGISTRATION_BYTES).ok()?;
+    let registration: CdpRegistration = serde_json::from_slice(&bytes).ok()?;
+    let same_instance = match (registration.process_instance.as_deref(), process_instance) {
+        (Some(recorded), Some(live)) => recorded == live,
+        _ => true,
+    };
+    (registration.pid == pid && same_instance).then_some(registration.endpoint)
+}
+
+#[cfg(test)]
+#[path = "cdp_registry_tests.rs"]
+mod tests;
@@ -0,0 +1,39 @@
+use super::*;
+use crate::refs_test_support::HomeGuard;
+
+fn endpoint(port: u16) -> CdpEndpoint {
+    CdpEndpoint {
+        port,
+        http_endpoint: format!("http://127.0.0.1:{port}"),
+        websocket_url: format!("ws://127.0.0.1:{port}/devtools/browser/a"),
+        product: None,
+    }
+}
+
+#[test]
+fn recorded_endpoint_is_found_for_the_same_process_instance() {
+    let _home = HomeGuard::new();
+    record(ProcessId::new(42), Some("42:1"), &endpoint(9222)).unwrap();
+
+    let found = lookup(ProcessId::new(42), Some("42:1")).unwrap();
+
+    assert_eq!(found.port, 9222);
+    assert!(lookup(ProcessId::new(43), Some("43:1")).is_none());
+}
+
+#[test]
+fn a_recycled_pid_does_not_inherit_the_endpoint() {
+    let _home = HomeGuard::new();
+    record(ProcessId::new(42), Some("42:1"), &endpoint(9222)).unwrap();
+
+    assert!(lookup(ProcessId::new(42), Some("42:2")).is_none());
+}
+
+#[test]
+fn a_later_launch_replaces_the_registration() {
+    let _home = HomeGuard::new();
+    record(ProcessId::new(42), Some("42:1"), &endpoint(9222)).unwrap();
+    record(ProcessId::new(42), Some("42:1"), &endpoint(9333)).unwrap();
+
+    assert_eq!(lookup(ProcessId::new(42), Some("42:1")).unwrap().port, 9333);
+}
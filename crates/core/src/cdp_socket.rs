@@ -0,0 +1,242 @@
+use std::hash::{BuildHasher, RandomState};
+use std::io::{ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::time::Duration;
+
+use base64::Engine;
+use serde_json::{Value, json};
+
+use crate::{AdapterError, AppError, Deadline, ErrorCode};
+
+const MAX_HANDSHAKE_BYTES: usize = 16 * 1024;
+/// `Accessibility.getFullAXTree` on a large page runs to tens of megabytes.
+const MAX_MESSAGE_BYTES: usize = 64 * 1024 * 1024;
+
+const OP_CONTINUATION: u8 = 0x0;
+const OP_TEXT: u8 = 0x1;
+const OP_BINARY: u8 = 0x2;
+const OP_CLOSE: u8 = 0x8;
+const OP_PING: u8 = 0x9;
+const OP_PONG: u8 = 0xA;
+
+/// A DevTools protocol session over one WebSocket to a loopback endpoint.
+/// Only what CDP needs is implemented: masked client text frames,
+/// fragmented server messages, and ping replies. The handshake checks for a
+/// `101` upgrade but not `Sec-WebSocket-Accept`; the endpoint was already
+/// verified as DevTools through `/json/version` before anything dials it.
+pub(crate) struct CdpSocket {
+    stream: TcpStream,
+    next_id: u64,
+    deadline: Deadline,
+}
+
+impl CdpSocket {
+    /// Opens `path` (such as `/devtools/page/<id>`) on `127.0.0.1:port`.
+    /// Every later call shares `deadline`.
+    pub(crate) fn connect(port: u16, path: &str, deadline: Deadline) -> Result<Self, AppError> {
+        let address = SocketAddr::from(([127, 0, 0, 1], port));
+        let timeout = remaining(deadline)?;
+        let stream = TcpStream::connect_timeout(&address, timeout).map_err(connection_error)?;
+        stream.set_nodelay(true).map_err(connection_error)?;
+        let mut socket = Self {
+            stream,
+            next_id: 0,
+            deadline,
+        };
+        socket.handshake(port, path)?;
+        Ok(socket)
+    }
+
+    /// Sends one command and waits for its response, skipping the events
+    /// the browser interleaves. A protocol error becomes `ACTION_FAILED`.
+    pub(crate) fn call(&mut self, method: &str, params: Value) -> Result<Value, AppError> {
+        self.next_id += 1;
+        let id = self.next_id;
+        let request = json!({ "id": id, "method": method, "params": params });
+        self.write_frame(OP_TEXT, request.to_string().as_bytes())?;
+        loop {
+            let message: Value = serde_json::from_slice(&self.read_message()?)?;
+            if message.get("id").and_then(Value::as_u64) != Some(id) {
+                continue;
+            }
+            if let Some(error) = message.get("error") {
+                let detail = error.get("message").and_then(Value::as_str).unwrap_or("");
+                return Err(AdapterError::new(
+                    ErrorCode::ActionFailed,
+                    format!("DevTools {method} failed: {detail}"),
+                )
+                .into());
+            }
+            return Ok(message.get("result").cloned().unwrap_or(Value::Null));
+        }
+    }
+
+    fn handshake(&mut self, port: u16, path: &str) -> Result<(), AppError> {
+        let key = base64::engine::general_purpose::STANDARD.encode(random_bytes::<16>());
+        let request = format!(
+            "GET {path} HTTP/1.1\r\nHost: 127.0.0.1:{port}\r\nUpgrade: websocket\r\n\
+             Connection: Upgrade\r\nSec-WebSocket-Key: {key}\r\nSec-WebSocket-Version: 13\r\n\r\n"
+        );
+        self.write_all(request.as_bytes())?;
+        let mut head = Vec::new();
+        while !head.ends_with(b"\r\n\r\n") {
+            if head.len() > MAX_HANDSHAKE_BYTES {
+                return Err(protocol_error("DevTools handshake response is too large"));
+            }
+            let mut byte = [0_u8; 1];
+            self.read_exact(&mut byte)?;
+            head.push(byte[0]);
+        }
+        let head = String::from_utf8_lossy(&head).to_ascii_lowercase();
+        let upgraded = head.starts_with("http/1.1 101")
+            && head
+                .lines()
+                .any(|line| line.replace(' ', "") == "upgrade:websocket");
+        if !upgraded {
+            let status = head.lines().next().unwrap_or_default().to_owned();
+            return Err(protocol_error(&format!(
+                "DevTools refused the WebSocket upgrade ({status})"
+            )));
+        }
+        Ok(())
+    }
+
+    fn read_message(&mut self) -> Result<Vec<u8>, AppError> {
+        let mut message = Vec::new();
+        loop {
+            let mut head = [0_u8; 2];
+            self.read_exact(&mut head)?;
+            let fin = head[0] & 0x80 != 0;
+            let opcode = head[0] & 0x0F;
+            let payload = self.read_payload(head[1], message.len())?;
+            match opcode {
+                OP_PING => self.write_frame(OP_PONG, &payload)?,
+                OP_PONG => {}
+                OP_CLOSE => return Err(protocol_error("DevTools closed the connection")),
+                OP_TEXT | OP_BINARY | OP_CONTINUATION => {
+                    message.extend_from_slice(&payload);
+                    if fin {
+                        return Ok(message);
+                    }
+                }
+                _ => return Err(protocol_error("DevTools sent an unknown frame type")),
+            }
+        }
+    }
+
+    fn read_payload(&mut self, length_byte: u8, buffered: usize) -> Result<Vec<u8>, AppError> {
+        let length = match length_byte & 0x7F {
+            126 => {
+                let mut bytes = [0_u8; 2];
+                self.read_exact(&mut bytes)?;
+                u64::from(u16::from_be_bytes(bytes))
+            }
+            127 => {
+                let mut bytes = [0_u8; 8];
+                self.read_exact(&mut bytes)?;
+                u64::from_be_bytes(bytes)
+            }
+            short => u64::from(short),
+        };
+        let length = usize::try_from(length)
+            .ok()
+            .filter(|length| buffered.saturating_add(*length) <= MAX_MESSAGE_BYTES)
+            .ok_or_else(|| protocol_error("DevTools message exceeds the size limit"))?;
+        let mask = if length_byte & 0x80 != 0 {
+            let mut mask = [0_u8; 4];
+            self.read_exact(&mut mask)?;
+            Some(mask)
+        } else {
+            None
+        };
+        let mut payload = vec![0_u8; length];
+        self.read_exact(&mut payload)?;
+        if let Some(mask) = mask {
+            apply_mask(&mut payload, mask);
+        }
+        Ok(payload)
+    }
+
+    fn write_frame(&mut self, opcode: u8, payload: &[u8]) -> Result<(), AppError> {
+        let mut frame = vec![0x80 | opcode];
+        match payload.len() {
+            length @ 0..=125 => frame.push(0x80 | length as u8),
+            length @ 126..=0xFFFF => {
+                frame.push(0x80 | 126);
+                frame.extend_from_slice(&(length as u16).to_be_bytes());
+            }
+            length => {
+                frame.push(0x80 | 127);
+                frame.extend_from_slice(&(length as u64).to_be_bytes());
+            }
+        }
+        let mask = random_bytes::<4>();
+        frame.extend_from_slice(&mask);
+        let start = frame.len();
+        frame.extend_from_slice(payload);
+        apply_mask(&mut frame[start..], mask);
+        self.write_all(&frame)
+    }
+
+    fn write_all(&mut self, bytes: &[u8]) -> Result<(), AppError> {
+        self.stream
+            .set_write_timeout(Some(remaining(self.deadline)?))
+            .map_err(connection_error)?;
+        self.stream
+            .write_all(bytes)
+            .map_err(|error| io_error(error, self.deadline))
+    }
+
+    fn read_exact(&mut self, buffer: &mut [u8]) -> Result<(), AppError> {
+        self.stream
+            .set_read_timeout(Some(remaining(self.deadline)?))
+            .map_err(connection_error)?;
+        self.stream
+            .read_exact(buffer)
+            .map_err(|error| io_error(error, self.deadline))
+    }
+}
+
+fn apply_mask(payload: &mut [u8], mask: [u8; 4]) {
+    for (index, byte) in payload.iter_mut().enumerate() {
+        *byte ^= mask[index % 4];
+    }
+}
+
+fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0_u8; N];
+    for (index, chunk) in bytes.chunks_mut(8).enumerate() {
+        let random = RandomState::new().hash_one(index).to_le_bytes();
+        chunk.copy_from_slice(&random[..chunk.len()]);
+    }
+    bytes
+}
+
+fn remaining(deadline: Deadline) -> Result<Duration, AppError> {
+    if deadline.is_expired() {
+        return Err(deadline.timeout_error().into());
+    }
+    Ok(deadline.remaining().max(Duration::from_millis(1)))
+}
+
+fn io_error(error: std::io::Error, deadline: Deadline) -> AppError {
+    match error.kind() {
+        ErrorKind::WouldBlock | ErrorKind::TimedOut => deadline.timeout_error().into(),
+        _ => connection_error(error),
+    }
+}
+
+fn connection_error(error: std::io::Error) -> AppError {
+    AdapterError::new(ErrorCode::ActionFailed, "DevTools connection failed")
+        .with_platform_detail(error.to_string())
+        .with_suggestion("The app may have quit or closed the page; run snapshot again.")
+        .into()
+}
+
+fn protocol_error(message: &str) -> AppError {
+    AdapterError::new(ErrorCode::ActionFailed, message).into()
+}
+
+#[cfg(test)]
+#[path = "cdp_socket_tests.rs"]
+mod tests;
@@ -0,0 +1,58 @@
+use super::*;
+use crate::cdp_test_support::{PAGE_ID, StubCdp};
+
+fn page_path() -> String {
+    format!("/devtools/page/{PAGE_ID}")
+}
+
+#[test]
+fn call_returns_the_matching_result_past_interleaved_events() {
+    let stub = StubCdp::serve("Fixture", |method, params| {
+        Ok(json!({ "echo": method, "params": params }))
+    });
+    let mut socket =
+        CdpSocket::connect(stub.port, &page_path(), Deadline::after(2_000).unwrap()).unwrap();
+
+    let first = socket
+        .call("DOM.focus", json!({ "backendNodeId": 7 }))
+        .unwrap();
+    let large = "x".repeat(70_000);
+    let second = socket
+        .call("Runtime.evaluate", json!({ "expression": large }))
+        .unwrap();
+
+    assert_eq!(first["echo"], "DOM.focus");
+    assert_eq!(first["params"]["backendNodeId"], 7);
+    assert_eq!(
+        second["params"]["expression"].as_str().unwrap().len(),
+        70_000
+    );
+    assert_eq!(stub.methods(), vec!["DOM.focus", "Runtime.evaluate"]);
+}
+
+#[test]
+fn protocol_errors_become_action_failed() {
+    let stub = StubCdp::serve("Fixture", |_, _| Err("No node with given id found".into()));
+    let mut socket =
+        CdpSocket::connect(stub.port, &page_path(), Deadline::after(2_000).unwrap()).unwrap();
+
+    let error = socket.call("DOM.focus", json!({})).unwrap_err();
+
+    assert_eq!(error.code(), "ACTION_FAILED");
+    assert!(error.to_string().contains("DOM.focus"));
+    assert!(error.to_string().contains("No node with given id found"));
+}
+
+#[test]
+fn a_server_that_does_not_upgrade_is_refused() {
+    let stub = StubCdp::serve("Fixture", |_, _| Ok(json!({})));
+
+    let error =
+        match CdpSocket::connect(stub.port, "/json/version", Deadline::after(2_000).unwrap()) {
+            Ok(_) => panic!("a plain HTTP answer must not be accepted as a WebSocket"),
+            Err(error) => error,
+        };
+
+    assert_eq!(error.code(), "ACTION_FAILED");
+    assert!(error.to_string().contains("upgrade"));
+}
@@ -0,0 +1,178 @@
+use serde_json::{Value, json};
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+
+pub(crate) const PAGE_ID: &str = "PAGE1";
+
+type Responder = dyn Fn(&str, &Value) -> Result<Value, String> + Send + Sync;
+
+/// A loopback DevTools server: `/json/version`, a one-page `/json/list`,
+/// and a WebSocket that answers each command through `respond`. An event
+/// precedes every response so clients are exercised on skipping them.
+/// Every command received is kept in `calls`.
+pub(crate) struct StubCdp {
+    pub port: u16,
+    pub calls: Arc<Mutex<Vec<(String, Value)>>>,
+}
+
+impl StubCdp {
+    pub(crate) fn serve(
+        page_title: &str,
+        respond: impl Fn(&str, &Value) -> Result<Value, String> + Send + Sync + 'static,
+    ) -> Self {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&calls);
+        let title = page_title.to_owned();
+        let respond: Arc<Responder> = Arc::new(respond);
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let recorded = Arc::clone(&recorded);
+                let respond = Arc::clone(&respond);
+                let title = title.clone();
+                std::thread::spawn(move || {
+                    serve_connection(stream, port, &title, &recorded, &*respond)
+                });
+            }
+        });
+        Self { port, calls }
+    }
+
+    pub(crate) fn endpoint(&self) -> crate::cdp_endpoint::CdpEndpoint {
+        crate::cdp_endpoint::CdpEndpoint {
+            port: self.port,
+            http_endpoint: format!("http://127.0.0.1:{}", self.port),
+            websocket_url: format!("ws://127.0.0.1:{}/devtools/browser/stub", self.port),
+            product: Some("Stub/1.0".into()),
+        }
+    }
+
+    pub(crate) fn methods(&self) -> Vec<String> {
+        self.calls
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(method, _)| method.clone())
+            .collect()
+    }
+
+    pub(crate) fn params_of(&self, method: &str) -> Vec<Value> {
+        self.calls
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(called, _)| called == method)
+            .map(|(_, params)| params.clone())
+            .collect()
+    }
+}
+
+fn serve_connection(
+    mut stream: TcpStream,
+    port: u16,
+    title: &str,
+    calls: &Mutex<Vec<(String, Value)>>,
+    respond: &Responder,
+) {
+    let mut head = Vec::new();
+    let mut byte = [0_u8; 1];
+    while !head.ends_with(b"\r\n\r\n") {
+        if stream.read_exact(&mut byte).is_err() {
+            return;
+        }
+        head.push(byte[0]);
+    }
+    let head = String::from_utf8_lossy(&head).to_string();
+    if head.starts_with("GET /json/version") {
+        let body = json!({ "Browser": "Stub/1.0", "webSocketDebuggerUrl": format!("ws://127.0.0.1:{port}/devtools/browser/stub") });
+        write_http(&mut stream, &body);
+    } else if head.starts_with("GET /json/list") {
+        let body = json!([
+            { "id": "WORKER", "type": "service_worker", "title": "worker", "webSocketDebuggerUrl": format!("ws://127.0.0.1:{port}/devtools/page/WORKER") },
+            { "id": PAGE_ID, "type": "page", "title": title, "webSocketDebuggerUrl": format!("ws://127.0.0.1:{port}/devtools/page/{PAGE_ID}") },
+        ]);
+        write_http(&mut stream, &body);
+    } else if head.contains("Upgrade: websocket") {
+        let _ = stream.write_all(
+            b"HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\r\n",
+        );
+        while let Some(request) = read_client_frame(&mut stream) {
+            let method = request["method"].as_str().unwrap_or_default().to_owned();
+            let params = request["params"].clone();
+            calls.lock().unwrap().push((method.clone(), params.clone()));
+            let event = json!({ "method": "Stub.event", "params": {} });
+            let response = match respond(&method, &params) {
+                Ok(result) => json!({ "id": request["id"], "result": result }),
+                Err(message) => {
+                    json!({ "id": request["id"], "error": { "code": -32000, "message": message } })
+                }
+            };
+            if write_server_frame(&mut stream, &event).is_err()
+                || write_server_frame(&mut stream, &response).is_err()
+            {
+                return;
+            }
+        }
+    }
+}
+
+fn write_http(stream: &mut TcpStream, body: &Value) {
+    let body = body.to_string();
+    let _ = stream.write_all(
+        format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        )
+        .as_bytes(),
+    );
+}
+
+fn read_client_frame(stream: &mut TcpStream) -> Option<Value> {
+    let mut head = [0_u8; 2];
+    stream.read_exact(&mut head).ok()?;
+    let length = match head[1] & 0x7F {
+        126 => {
+            let mut bytes = [0_u8; 2];
+            stream.read_exact(&mut bytes).ok()?;
+            usize::from(u16::from_be_bytes(bytes))
+        }
+        127 => {
+            let mut bytes = [0_u8; 8];
+            stream.read_exact(&mut bytes).ok()?;
+            usize::try_from(u64::from_be_bytes(bytes)).ok()?
+        }
+        short => usize::from(short),
+    };
+    let mut mask = [0_u8; 4];
+    stream.read_exact(&mut mask).ok()?;
+    let mut payload = vec![0_u8; length];
+    stream.read_exact(&mut payload).ok()?;
+    for (index, byte) in payload.iter_mut().enumerate() {
+        *byte ^= mask[index % 4];
+    }
+    serde_json::from_slice(&payload).ok()
+}
+
+/// Writes `message` unmasked, split into two frames so clients reassemble
+/// continuations.
+fn write_server_frame(stream: &mut TcpStream, message: &Value) -> std::io::Result<()> {
+    let payload = message.to_string().into_bytes();
+    let (first, rest) = payload.split_at(payload.len() / 2);
+    for (opcode, fin, part) in [(0x1_u8, false, first), (0x0_u8, true, rest)] {
+        let mut frame = vec![if fin { 0x80 | opcode } else { opcode }];
+        if part.len() < 126 {
+            frame.push(part.len() as u8);
+        } else if part.len() <= 0xFFFF {
+            frame.push(126);
+            frame.extend_from_slice(&(part.len() as u16).to_be_bytes());
+        } else {
+            frame.push(127);
+            frame.extend_from_slice(&(part.len() as u64).to_be_bytes());
+        }
+        frame.extend_from_slice(part);
+        stream.write_all(&frame)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,285 @@
+use rustc_hash::FxHashMap;
+use serde_json::{Value, json};
+
+use crate::{
+    AccessibilityNode, Deadline, ElementIdentifier, IdentifierKind, NodeIdentity, NodePresentation,
+    Rect, WindowInfo, capability, cdp_page, cdp_socket::CdpSocket, state,
+};
+
+/// Replaces the children of the window's web area with the page's
+/// DevTools accessibility tree when `launch --cdp` recorded an endpoint for
+/// this process. Web nodes carry a [`IdentifierKind::CdpNode`] id, so their
+/// refs act through the endpoint instead of the platform adapter.
+///
+/// Returns `None` when the process has no endpoint. Otherwise returns what
+/// `snapshot` reports as `web_content`; a graft that cannot complete leaves
+/// the native subtree in place and says why rather than failing the snapshot.
+pub(crate) fn graft(
+    tree: &mut AccessibilityNode,
+    window: &WindowInfo,
+    max_depth: u8,
+    deadline: Deadline,
+) -> Option<Value> {
+    let endpoint = crate::cdp_registry::lookup(window.pid, window.process_instance.as_deref())?;
+    let port = endpoint.port;
+    let Some((web_area, depth)) = find_web_area(tree, 0) else {
+        return Some(json!({ "port": port, "skipped": "no_web_area" }));
+    };
+    let depth_left = max_depth.saturating_sub(depth);
+    if depth_left == 0 {
+        return Some(json!({ "port": port, "skipped": "max_depth" }));
+    }
+    let pages = match cdp_page::list(port, deadline) {
+        Ok(pages) => pages,
+        Err(error) => return Some(json!({ "port": port, "error": error.to_string() })),
+    };
+    let titled: Vec<_> = pages
+        .iter()
+        .filter(|page| page.title == window.title)
+        .collect();
+    let page = match (titled.as_slice(), pages.as_slice()) {
+        ([page], _) => *page,
+        (_, [page]) => page,
+        (_, []) => return Some(json!({ "port": port, "skipped": "no_page" })),
+        _ => return Some(json!({ "port": port, "skipped": "ambiguous_page" })),
+    };
+    let origin = web_area
+        .presentation
+        .bounds
+        .map(|bounds| (bounds.x, bounds.y));
+    match page
+        .open(port, deadline)
+        .and_then(|mut socket| web_children(&mut socket, &page.id, origin, depth_left))
+    {
+        Ok((children, nodes)) => {
+            web_area.children = children;
+            web_area.children_count = None;
+            web_area.subtree_truncated = false;
+            Some(json!({ "port": port, "page_id": page.id, "nodes": nodes }))
+        }
+        Err(error) => Some(json!({ "port": port, "error": error.to_string() })),
+    }
+}
+
+fn find_web_area(node: &mut AccessibilityNode, depth: u8) -> Option<(&mut AccessibilityNode, u8)> {
+    if node.role == crate::Role::WebArea.as_str() {
+        return Some((node, depth));
+    }
+    node.children
+        .iter_mut()
+        .find_map(|child| find_web_area(child, depth.saturating_add(1)))
+}
+
+fn web_children(
+    socket: &mut CdpSocket,
+    target_id: &str,
+    origin: Option<(f64, f64)>,
+    depth_left: u8,
+) -> Result<(Vec<AccessibilityNode>, usize), crate::AppError> {
+    let result = socket.call("Accessibility.getFullAXTree", json!({}))?;
+    let nodes = result.get("nodes").and_then(Value::as_array);
+    let index: FxHashMap<&str, &Value> = nodes
+        .into_iter()
+        .flatten()
+        .filter_map(|node| Some((node.get("nodeId")?.as_str()?, node)))
+        .collect();
+    let Some(root) = nodes.and_then(|nodes| nodes.first()) else {
+        return Ok((Vec::new(), 0));
+    };
+    let mut grafter = Grafter {
+        index,
+        target_id,
+        socket,
+        origin,
+        nodes: 0,
+    };
+    let children = grafter.children_of(root, depth_left);
+    Ok((children, grafter.nodes))
+}
+
+struct Grafter<'a> {
+    index: FxHashMap<&'a str, &'a Value>,
+    target_id: &'a str,
+    socket: &'a mut CdpSocket,
+    origin: Option<(f64, f64)>,
+    nodes: usize,
+}
+
+impl Grafter<'_> {
+    fn children_of(&mut self, node: &Value, depth_left: u8) -> Vec<AccessibilityNode> {
+        let Some(depth_left) = depth_left.checked_sub(1) else {
+            return Vec::new();
+        };
+        let mut converted = Vec::new();
+        for id in node
+            .get("childIds")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            if let Some(child) = id.as_str().and_then(|id| self.index.get(id).copied()) {
+                converted.extend(self.convert(child, depth_left));
+            }
+        }
+        converted
+    }
+
+    /// Converts one DevTools node. Ignored nodes and unnamed generic
+    /// containers are replaced by their children; inline text boxes and line
+    /// breaks are dropped because their static text parent already says it.
+    fn convert(&mut self, node: &Value, depth_left: u8) -> Vec<AccessibilityNode> {
+        let cdp_role = text_field(node, "role").unwrap_or_default();
+        let name = text_field(node, "name").filter(|name| !name.is_empty());
+        if matches!(cdp_role.as_str(), "InlineTextBox" | "LineBreak") {
+            return Vec::new();
+        }
+        let ignored = node.get("ignored").and_then(Value::as_bool) == Some(true);
+        let hoisted = matches!(cdp_role.as_str(), "generic" | "none" | "presentation");
+        if ignored || (hoisted && name.is_none()) {
+            return self.children_of(node, depth_left);
+        }
+        let role = web_role(&cdp_role);
+        let available_actions = actions(node, &role);
+        let mut converted = AccessibilityNode {
+            ref_id: None,
+            role,
+            identity: NodeIdentity {
+                name,
+                value: text_field(node, "value").filter(|value| !value.is_empty()),
+                description: text_field(node, "description").filter(|text| !text.is_empty()),
+                native_id: None,
+                value_masked: false,
+            },
+            presentation: NodePresentation {
+                hint: None,
+                states: states(node),
+                available_actions,
+                bounds: None,
+            },
+            children_count: None,
+            subtree_truncated: false,
+            children: Vec::new(),
+        };
+        if let Some(backend_id) = node.get("backendDOMNodeId").and_then(Value::as_u64) {
+            converted.identity.native_id = Some(ElementIdentifier {
+                kind: IdentifierKind::CdpNode,
+                value: format!("{}:{backend_id}", self.target_id),
+            });
+            if crate::ref_alloc::is_ref_able(&converted) {
+                converted.presentation.bounds = self.bounds(backend_id);
+            }
+        }
+        if converted.role != crate::Role::StaticText.as_str() {
+            converted.children = self.children_of(node, depth_left);
+        }
+        self.nodes += 1;
+        vec![converted]
+    }
+
+    /// Screen bounds from the node's border box, offset by the web area's
+    /// origin. CSS pixels are taken as points, which holds at 100% zoom.
+    fn bounds(&mut self, backend_id: u64) -> Option<Rect> {
+        let (origin_x, origin_y) = self.origin?;
+        let model = self
+            .socket
+            .call("DOM.getBoxModel", json!({ "backendNodeId": backend_id }))
+            .ok()?;
+        let quad: Vec<f64> = model
+            .pointer("/model/border")?
+            .as_array()?
+            .iter()
+            .filter_map(Value::as_f64)
+            .collect();
+        let xs = quad.iter().step_by(2);
+        let ys = quad.iter().skip(1).step_by(2);
+        let (left, right) = xs.fold((f64::MAX, f64::MIN), |(lo, hi), x| (lo.min(*x), hi.max(*x)));
+        let (top, bottom) = ys.fold((f64::MAX, f64::MIN), |(lo, hi), y| (lo.min(*y), hi.max(*y)));
+        (quad.len() == 8 && right > left && bottom > top).then_some(Rect {
+            x: origin_x + left,
+            y: origin_y + top,
+            width: right - left,
+            height: bottom - top,
+        })
+    }
+}
+
+fn text_field(node: &Value, key: &str) -> Option<String> {
+    match node.get(key)?.get("value")? {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    }
+}
+
+fn web_role(cdp_role: &str) -> String {
+    let role = match cdp_role.to_ascii_lowercase().as_str() {
+        "columnheader" | "rowheader" => crate::Role::Cell,
+        "menuitemcheckbox" | "menuitemradio" => crate::Role::MenuItem,
+        "rootwebarea" => crate::Role::WebArea,
+        "generic" | "none" | "presentation" => crate::Role::Group,
+        other => match crate::Role::from_token(&crate::roles::normalize_role_query(other)) {
+            crate::Role::Unknown => crate::Role::Group,
+            known => known,
+        },
+    };
+    role.as_str().to_owned()
+}
+
+fn property<'a>(node: &'a Value, name: &str) -> Option<&'a Value> {
+    node.get("properties")?
+        .as_array()?
+        .iter()
+        .find(|property| property.get("name").and_then(Value::as_str) == Some(name))?
+        .pointer("/value/value")
+}
+
+fn states(node: &Value) -> Vec<String> {
+    let flag = |name: &str| {
+        property(node, name)
+            .is_some_and(|value| value.as_bool() == Some(true) || value.as_str() == Some("true"))
+    };
+    let mut states: Vec<String> = [
+        ("focused", state::FOCUSED),
+        ("disabled", state::DISABLED),
+        ("checked", state::CHECKED),
+        ("pressed", state::PRESSED),
+        ("expanded", state::EXPANDED),
+        ("selected", state::SELECTED),
+        ("required", state::REQUIRED),
+        ("readonly", state::READONLY),
+        ("invalid", state::INVALID),
+        ("busy", state::BUSY),
+        ("modal", state::MODAL),
+        ("multiselectable", state::MULTISELECTABLE),
+    ]
+    .into_iter()
+    .filter(|(name, _)| flag(name))
+    .map(|(_, token)| token.to_owned())
+    .collect();
+    if property(node, "checked").and_then(Value::as_str) == Some("mixed") {
+        states.push(state::INDETERMINATE.to_owned());
+    }
+    if property(node, "hasPopup").is_some_and(|value| value.as_str() != Some("false")) {
+        states.push(state::HASPOPUP.to_owned());
+    }
+    states
+}
+
+/// The actions a web ref advertises are the ones CDP dispatch implements.
+fn actions(node: &Value, role: &str) -> Vec<String> {
+    let mut actions = Vec::new();
+    if crate::roles::is_interactive_role(role) {
+        actions.extend([capability::CLICK, capability::SCROLL_TO]);
+    }
+    if property(node, "focusable").and_then(Value::as_bool) == Some(true) {
+        actions.push(capability::SET_FOCUS);
+    }
+    if property(node, "editable").is_some() {
+        actions.extend([capability::SET_VALUE, capability::TYPE_TEXT]);
+    }
+    actions.into_iter().map(str::to_owned).collect()
+}
+
+#[cfg(test)]
+#[path = "cdp_tree_tests.rs"]
+mod tests;
@@ -0,0 +1,172 @@
+use super::*;
+use crate::cdp_test_support::{PAGE_ID, StubCdp};
+use crate::refs_test_support::HomeGuard;
+
+fn node(role: &str, children: Vec<AccessibilityNode>) -> AccessibilityNode {
+    AccessibilityNode {
+        ref_id: None,
+        role: role.into(),
+        identity: Default::default(),
+        presentation: Default::default(),
+        children_count: None,
+        subtree_truncated: false,
+        children,
+    }
+}
+
+fn window(title: &str) -> WindowInfo {
+    WindowInfo {
+        id: "w-1".into(),
+        title: title.into(),
+        app: "Electron".into(),
+        pid: crate::ProcessId::new(4242),
+        process_instance: Some("4242:1".into()),
+        bounds: None,
+        state: Default::default(),
+    }
+}
+
+fn native_tree() -> AccessibilityNode {
+    let mut web_area = node("webarea", vec![node("group", Vec::new())]);
+    web_area.presentation.bounds = Some(Rect {
+        x: 100.0,
+        y: 50.0,
+        width: 800.0,
+        height: 600.0,
+    });
+    node("window", vec![web_area])
+}
+
+fn ax(id: &str, role: &str, name: &str, children: &[&str]) -> Value {
+    json!({
+        "nodeId": id,
+        "role": { "type": "role", "value": role },
+        "name": { "type": "computedString", "value": name },
+        "childIds": children,
+        "backendDOMNodeId": id.parse::<u64>().unwrap() + 100,
+        "properties": [],
+    })
+}
+
+fn full_tree() -> Value {
+    let mut button = ax("3", "button", "Save", &["4"]);
+    button["properties"] =
+        json!([{ "name": "focusable", "value": { "type": "boolean", "value": true } }]);
+    let mut input = ax("5", "textbox", "Email", &[]);
+    input["properties"] = json!([
+        { "name": "editable", "value": { "type": "token", "value": "plaintext" } },
+        { "name": "required", "value": { "type": "boolean", "value": true } },
+    ]);
+    let mut ignored = ax("6", "generic", "", &["7"]);
+    ignored["ignored"] = json!(true);
+    json!({ "nodes": [
+        ax("1", "RootWebArea", "Inbox", &["2"]),
+        ax("2", "generic", "", &["3", "5", "6"]),
+        button,
+        ax("4", "StaticText", "Save", &["8"]),
+        input,
+        ignored,
+        ax("7", "link", "Help", &[]),
+        ax("8", "InlineTextBox", "Save", &[]),
+    ]})
+}
+
+fn serve(title: &str) -> StubCdp {
+    StubCdp::serve(title, |method, _| match method {
+        "Accessibility.getFullAXTree" => Ok(full_tree()),
+        "DOM.getBoxModel" => Ok(json!({ "model": {
+            "border": [10.0, 20.0, 70.0, 20.0, 70.0, 40.0, 10.0, 40.0],
+        }})),
+        other => Err(format!("unexpected {other}")),
+    })
+}
+
+#[test]
+fn web_area_children_are_replaced_by_the_devtools_tree() {
+    let _home = HomeGuard::new();
+    let stub = serve("Inbox");
+    let window = window("Inbox");
+    crate::cdp_registry::record(
+        window.pid,
+        window.process_instance.as_deref(),
+        &stub.endpoint(),
+    )
+    .unwrap();
+    let mut tree = native_tree();
+
+    let report = graft(&mut tree, &window, 20, Deadline::after(5_000).unwrap()).unwrap();
+
+    assert_eq!(report["page_id"], PAGE_ID);
+    assert_eq!(report["nodes"], 4);
+    let web = &tree.children[0].children;
+    let roles: Vec<&str> = web.iter().map(|child| child.role.as_str()).collect();
+    assert_eq!(roles, ["button", "textfield", "link"]);
+    let button = &web[0];
+    assert_eq!(button.children.len(), 1, "inline text boxes are dropped");
+    assert!(button.children[0].children.is_empty());
+    assert_eq!(
+        button.identity.native_id.as_ref().unwrap().value,
+        format!("{PAGE_ID}:103")
+    );
+    assert!(
+        button
+            .presentation
+            .available_actions
+            .contains(&capability::SET_FOCUS.to_owned())
+    );
+    let bounds = button.presentation.bounds.unwrap();
+    assert_eq!(
+        (bounds.x, bounds.y, bounds.width, bounds.height),
+        (110.0, 70.0, 60.0, 20.0)
+    );
+    let input = &web[1];
+    assert!(
+        input
+            .presentation
+            .states
+            .contains(&state::REQUIRED.to_owned())
+    );
+    assert!(
+        input
+            .presentation
+            .available_actions
+            .contains(&capability::TYPE_TEXT.to_owned())
+    );
+}
+
+#[test]
+fn a_process_without_an_endpoint_is_left_alone() {
+    let _home = HomeGuard::new();
+    let mut tree = native_tree();
+
+    assert!(
+        graft(
+            &mut tree,
+            &window("Inbox"),
+            20,
+            Deadline::after(5_000).unwrap()
+        )
+        .is_none()
+    );
+    assert_eq!(tree.children[0].children[0].role, "group");
+}
+
+#[test]
+fn an_unreachable_endpoint_keeps_the_native_subtree_and_reports_why() {
+    let _home = HomeGuard::new();
+    let stub = serve("Inbox");
+    let window = window("Inbox");
+    let mut endpoint = stub.endpoint();
+    endpoint.port = std::net::TcpListener::bind(("127.0.0.1", 0))
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    crate::cdp_registry::record(window.pid, window.process_instance.as_deref(), &endpoint).unwrap();
+    let mut tree = native_tree();
+
+    let report = graft(&mut tree, &window, 20, Deadline::after(2_000).unwrap()).unwrap();
+
+    assert!(report.get("error").is_some(), "{report}");
+    assert_eq!(tree.children[0].children[0].role, "group");
+}
@@ -0,0 +1,75 @@
+use crate::{
+    AppError,
+    commands::helpers::load_ref_entry,
+    context::CommandContext,
+    ref_identity::has_meaningful_identity,
+    ref_token::validate_alias_name,
+    refs_store::{RefStore, alias::RefAlias},
+};
+use serde_json::{Value, json};
+
+#[derive(Debug, Clone)]
+pub enum AliasAction {
+    Set {
+        name: String,
+        ref_id: String,
+        snapshot_id: Option<String>,
+    },
+    List,
+    Rm {
+        name: String,
+    },
+}
+
+pub fn execute(action: AliasAction, context: &CommandContext) -> Result<Value, AppError> {
+    let store = RefStore::for_session(context.session_id())?;
+    match action {
+        AliasAction::Set {
+            name,
+            ref_id,
+            snapshot_id,
+        } => {
+            validate_alias_name(&name)?;
+            let entry = load_ref_entry(&ref_id, snapshot_id.as_deref(), context)?;
+            if !has_meaningful_identity(&entry) {
+                return Err(AppError::invalid_input_with_suggestion(
+                    format!("{ref_id} has no native identifier or stable name to alias"),
+                    "Alias an element that shows a name or identifier in the snapshot",
+                ));
+            }
+            let mut record = describe(&name, &ref_id, &entry);
+            let replaced = store.set_alias(
+                &name,
+                RefAlias {
+                    source_ref: ref_id,
+                    entry,
+                },
+            )?;
+            record["replaced"] = json!(replaced);
+            Ok(record)
+        }
+        AliasAction::List => {
+            let aliases: Vec<Value> = store
+                .load_aliases()?
+                .iter()
+                .map(|(name, alias)| describe(name, &alias.source_ref, &alias.entry))
+                .collect();
+            Ok(json!({ "aliases": aliases }))
+        }
+        AliasAction::Rm { name } => {
+            validate_alias_name(&name)?;
+            let removed = store.remove_alias(&name)?;
+            Ok(json!({ "alias": format!("@{name}"), "removed": removed }))
+        }
+    }
+}
+
+fn describe(name: &str, source_ref: &str, entry: &crate::RefEntry) -> Value {
+    json!({
+        "alias": format!("@{name}"),
+        "source_ref": source_ref,
+        "role": entry.identity.role,
+        "name": entry.identity.name,
+        "app": entry.source.source_app,
+    })
+}
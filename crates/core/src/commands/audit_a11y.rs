@@ -0,0 +1,103 @@
+use crate::{
+    AppError,
+    a11y_audit::{self, Finding, Severity},
+    adapter::{PlatformAdapter, TreeOptions},
+    context::CommandContext,
+    snapshot,
+};
+use serde_json::{Value, json};
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AuditFormat {
+    #[default]
+    Json,
+    Junit,
+}
+
+pub struct AuditA11yArgs {
+    pub app: Option<String>,
+    pub window_id: Option<String>,
+    pub max_depth: u8,
+    pub format: AuditFormat,
+    pub junit_path: Option<PathBuf>,
+}
+
+/// Snapshots the window with bounds, lints every node, and reports findings
+/// against the persisted refs so each one can be inspected or acted on.
+pub fn execute(
+    args: AuditA11yArgs,
+    adapter: &dyn PlatformAdapter,
+    context: &CommandContext,
+) -> Result<Value, AppError> {
+    let opts = TreeOptions {
+        max_depth: args.max_depth,
+        include_bounds: true,
+        ..TreeOptions::default()
+    };
+    let result = snapshot::run_with_context(
+        adapter,
+        &opts,
+        args.app.as_deref(),
+        args.window_id.as_deref(),
+        context,
+    )?;
+    let findings = a11y_audit::audit_tree(&result.tree, result.window.bounds);
+    let suite = suite_name(&result.window);
+    let junit = a11y_audit::render_junit(&suite, &findings);
+    if let Some(path) = &args.junit_path {
+        crate::refs::write_user_file(path, junit.as_bytes())?;
+    }
+
+    let mut body = json!({
+        "app": result.window.app,
+        "window": { "id": result.window.id, "title": result.window.title },
+        "snapshot_id": result.snapshot_id,
+        "complete": result.complete,
+        "passed": !findings.iter().any(|f| f.severity == Severity::Error),
+        "summary": summary(&findings),
+        "rules": rules(),
+    });
+    if let Some(path) = &args.junit_path {
+        body["junit_path"] = json!(path.to_string_lossy());
+    }
+    match args.format {
+        AuditFormat::Json => body["findings"] = serde_json::to_value(&findings)?,
+        AuditFormat::Junit => {
+            body["format"] = json!("junit");
+            body["content"] = json!(junit);
+        }
+    }
+    Ok(body)
+}
+
+fn suite_name(window: &crate::WindowInfo) -> String {
+    if window.title.is_empty() {
+        format!("audit-a11y {}", window.app)
+    } else {
+        format!("audit-a11y {} - {}", window.app, window.title)
+    }
+}
+
+fn summary(findings: &[Finding]) -> Value {
+    let count = |severity| findings.iter().filter(|f| f.severity == severity).count();
+    json!({
+        "total": findings.len(),
+        "errors": count(Severity::Error),
+        "warnings": count(Severity::Warning),
+        "info": count(Severity::Info),
+    })
+}
+
+fn rules() -> Value {
+    a11y_audit::RULES
+        .iter()
+        .map(|(id, severity, description)| {
+            json!({ "id": id, "severity": severity.as_str(), "description": description })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[path = "audit_a11y_tests.rs"]
+mod tests;
@@ -0,0 +1,133 @@
+use super::*;
+use crate::adapter::{ActionOps, InputOps, ObservationOps, SystemOps, WindowFilter};
+use crate::refs_test_support::HomeGuard;
+use crate::{AccessibilityNode, AdapterError, WindowInfo};
+
+struct AuditAdapter;
+
+fn button(name: Option<&str>) -> AccessibilityNode {
+    AccessibilityNode {
+        ref_id: None,
+        role: "button".into(),
+        identity: crate::NodeIdentity {
+            name: name.map(str::to_string),
+            native_id: Some(crate::ElementIdentifier {
+                kind: crate::IdentifierKind::AxIdentifier,
+                value: "btn".into(),
+            }),
+            ..Default::default()
+        },
+        presentation: crate::NodePresentation {
+            bounds: Some(crate::Rect {
+                x: 10.0,
+                y: 10.0,
+                width: 60.0,
+                height: 20.0,
+            }),
+            ..Default::default()
+        },
+        children_count: None,
+        subtree_truncated: false,
+        children: vec![],
+    }
+}
+
+impl ObservationOps for AuditAdapter {
+    fn observe_tree(
+        &self,
+        root: crate::live_locator::ObservationRoot<'_>,
+        _request: &crate::live_locator::ObservationRequest,
+    ) -> Result<crate::live_locator::ObservedTree, AdapterError> {
+        crate::adapter::observed_tree(
+            &root,
+            AccessibilityNode {
+                ref_id: None,
+                role: "window".into(),
+                identity: crate::NodeIdentity {
+                    name: Some("Doc".into()),
+                    ..Default::default()
+                },
+                presentation: Default::default(),
+                children_count: None,
+                subtree_truncated: false,
+                children: vec![button(Some("Save")), button(None)],
+            },
+        )
+    }
+
+    fn list_windows(
+        &self,
+        _filter: &WindowFilter,
+        _deadline: crate::Deadline,
+    ) -> Result<Vec<WindowInfo>, AdapterError> {
+        Ok(vec![WindowInfo {
+            id: "w-1".into(),
+            title: "Doc".into(),
+            app: "FixtureApp".into(),
+            pid: crate::ProcessId::new(1),
+            process_instance: Some("test-instance".into()),
+            bounds: None,
+            state: crate::WindowState {
+                is_focused: true,
+                ..Default::default()
+            },
+        }])
+    }
+}
+
+impl ActionOps for AuditAdapter {}
+impl InputOps for AuditAdapter {}
+impl SystemOps for AuditAdapter {}
+
+fn args(format: AuditFormat, junit_path: Option<PathBuf>) -> AuditA11yArgs {
+    AuditA11yArgs {
+        app: Some("FixtureApp".into()),
+        window_id: None,
+        max_depth: 10,
+        format,
+        junit_path,
+    }
+}
+
+#[test]
+fn findings_reference_refs_from_the_persisted_snapshot() {
+    let _guard = HomeGuard::new();
+
+    let value = execute(
+        args(AuditFormat::Json, None),
+        &AuditAdapter,
+        &CommandContext::default(),
+    )
+    .unwrap();
+
+    assert_eq!(value["passed"], false);
+    assert_eq!(value["summary"]["errors"], 1);
+    let finding = &value["findings"][0];
+    assert_eq!(finding["rule"], "interactive-unnamed");
+    let snapshot_id = value["snapshot_id"].as_str().unwrap();
+    assert!(
+        finding["ref"]
+            .as_str()
+            .unwrap()
+            .starts_with(&format!("@{snapshot_id}:"))
+    );
+}
+
+#[test]
+fn junit_report_is_returned_inline_and_written_to_path() {
+    let guard = HomeGuard::new();
+    let path = guard.path().join("audit.xml");
+
+    let value = execute(
+        args(AuditFormat::Junit, Some(path.clone())),
+        &AuditAdapter,
+        &CommandContext::default(),
+    )
+    .unwrap();
+
+    let written = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(value["format"], "junit");
+    assert_eq!(value["content"], written);
+    assert!(value.get("findings").is_none());
+    assert!(written.contains("failures=\"1\""));
+}
@@ -1,6 +1,7 @@
 use crate::AppError;
 use serde::Deserialize;
 use serde_json::Value;
+use std::collections::BTreeMap;
 
 #[derive(Debug, Deserialize)]
 pub struct BatchCommand {
@@ -8,6 +9,11 @@ pub struct BatchCommand {
     pub session: Option<String>,
     #[serde(default)]
     pub args: Value,
+    /// Capture name to JSON pointer into the entry's `data`, either a bare
+    /// pointer string or `{"pointer": "...", "type": "..."}`. Later entries
+    /// reference captured values as `${name}` inside `args`.
+    #[serde(default)]
+    pub capture: BTreeMap<String, Value>,
 }
 
 pub fn parse_commands(json_str: &str) -> Result<Vec<BatchCommand>, AppError> {
@@ -0,0 +1,215 @@
+use crate::{
+    AdapterError, AppError, ErrorCode,
+    adapter::PlatformAdapter,
+    commands::{
+        expect_matcher::{self, Assertion, Matcher},
+        expect_screenshot::ScreenshotExpectation,
+        expect_target::{ExpectTarget, LocatorScope, Observation, settle},
+    },
+    context::CommandContext,
+    ref_resolve_deadline::POLL_INTERVAL,
+    secure_mask::SecureMask,
+};
+use serde_json::{Value, json};
+use std::collections::VecDeque;
+use std::time::Instant;
+
+/// Evidence entries kept on failure; older changes are dropped first and
+/// counted in `evidence_dropped`.
+const MAX_EVIDENCE: usize = 20;
+
+pub struct ExpectArgs {
+    pub target: String,
+    /// The matcher phrase split into words: `[not] <matcher> [expected]`.
+    pub matcher: Vec<String>,
+    pub snapshot_id: Option<String>,
+    pub app: Option<String>,
+    pub window_id: Option<String>,
+    pub timeout_ms: u64,
+    pub screenshot: ScreenshotOptions,
+    /// Compare and report secure values instead of reading them as absent.
+    pub reveal_secure: bool,
+}
+
+/// Options that only apply to `to-match-screenshot`.
+#[derive(Default)]
+pub struct ScreenshotOptions {
+    /// Highest diff score that still matches; see `image_diff::DEFAULT_THRESHOLD`.
+    pub threshold: Option<f64>,
+    /// Regions left out of the comparison: `x,y,width,height` or refs.
+    pub ignore: Vec<String>,
+}
+
+/// What the polls saw. Distinct observations are kept in poll order; a poll
+/// that saw the same thing as the previous one is not recorded again.
+#[derive(Default)]
+struct PollHistory {
+    polls: u32,
+    actual: Value,
+    last_error: Option<Value>,
+    evidence: VecDeque<Value>,
+    last_recorded: Option<Value>,
+    dropped: usize,
+}
+
+impl PollHistory {
+    fn seen(&mut self, elapsed_ms: u128, actual: Value) {
+        self.polls += 1;
+        self.record(elapsed_ms, json!({ "actual": actual }));
+        self.actual = actual;
+        self.last_error = None;
+    }
+
+    fn gap(&mut self, elapsed_ms: u128, error: Value) {
+        self.polls += 1;
+        self.record(elapsed_ms, json!({ "error": error }));
+        self.last_error = Some(error);
+    }
+
+    fn record(&mut self, elapsed_ms: u128, observed: Value) {
+        if self.last_recorded.as_ref() == Some(&observed) {
+            return;
+        }
+        if self.evidence.len() == MAX_EVIDENCE {
+            self.evidence.pop_front();
+            self.dropped += 1;
+        }
+        let mut entry = observed.clone();
+        entry["elapsed_ms"] = json!(elapsed_ms);
+        self.evidence.push_back(entry);
+        self.last_recorded = Some(observed);
+    }
+}
+
+/// Polls the target until the assertion holds or `timeout_ms` passes. A
+/// missing or unreadable target is retried like a failed match; only
+/// non-transient errors (permissions, a bad snapshot) end the wait early.
+/// A secure value reads as absent, as in `get`, unless `reveal_secure`.
+pub fn execute(
+    args: ExpectArgs,
+    adapter: &dyn PlatformAdapter,
+    context: &CommandContext,
+) -> Result<Value, AppError> {
+    let assertion = Assertion::parse(&args.matcher)?;
+    let start = Instant::now();
+    let deadline = crate::Deadline::at(start, args.timeout_ms)?;
+    let target = ExpectTarget::parse(&args.target, args.snapshot_id.as_deref(), context)?;
+    let visual = ScreenshotExpectation::prepare(
+        &assertion,
+        args.screenshot.threshold,
+        &args.screenshot.ignore,
+        context,
+    )?;
+    if let ExpectTarget::Ref(_) = target {
+        if matches!(assertion.matcher, Matcher::Count(_)) {
+            return Err(AppError::invalid_input_with_suggestion(
+                "to-have-count needs a locator target, not a ref",
+                "Write: expect \"button:Save\" to-have-count 1",
+            ));
+        }
+        if args.app.is_some() || args.window_id.is_some() {
+            return Err(AppError::invalid_input(
+                "--app and --window-id scope a locator target; a ref already names its window",
+            ));
+        }
+    } else if visual.is_some() {
+        return Err(AppError::invalid_input_with_suggestion(
+            "to-match-screenshot needs a ref target, not a locator",
+            "Snapshot first, then write: expect @<snapshot_id>:eN to-match-screenshot baseline.png",
+        ));
+    }
+    let scope = LocatorScope {
+        app: args.app.as_deref(),
+        window_id: args.window_id.as_deref(),
+    };
+    let mask = if args.reveal_secure {
+        None
+    } else {
+        Some(SecureMask::load()?)
+    };
+
+    let mut history = PollHistory::default();
+    loop {
+        let observation = match (&visual, &target) {
+            (Some(visual), ExpectTarget::Ref(entry)) => {
+                settle(visual.observe(entry, adapter, deadline), deadline)?
+            }
+            _ => target.observe(&assertion, adapter, &scope, mask.as_ref(), deadline)?,
+        };
+        match observation {
+            Observation::Seen(seen)
+                if assertion.holds(&seen)
+                    && !visual.as_ref().is_some_and(|v| v.needs_baseline()) =>
+            {
+                return Ok(json!({
+                    "passed": true,
+                    "target": args.target,
+                    "assertion": assertion.phrase(),
+                    "expected": assertion.expected(),
+                    "actual": seen,
+                    "polls": history.polls + 1,
+                    "elapsed_ms": start.elapsed().as_millis(),
+                }));
+            }
+            Observation::Seen(seen) => history.seen(start.elapsed().as_millis(), seen),
+            Observation::Gap(error) => history.gap(start.elapsed().as_millis(), error),
+            Observation::Expired => break,
+        }
+
+        let remaining = deadline.remaining();
+        if remaining.is_zero() || visual.as_ref().is_some_and(|v| v.needs_baseline()) {
+            break;
+        }
+        std::thread::sleep(remaining.min(POLL_INTERVAL));
+    }
+    let mut error = failure(&args, &assertion, history);
+    if let (Some(visual), ExpectTarget::Ref(entry), AppError::Adapter(adapter_error)) =
+        (&visual, &target, &mut error)
+    {
+        if let Some(details) = adapter_error.details.as_mut() {
+            details["screenshot"] =
+                visual.failure_details(&args.target, entry.process.pid, context)?;
+        }
+    }
+    Err(error)
+}
+
+/// The `ASSERTION_FAILED` error: the expected/actual diff for the last value
+/// observed, plus the trail of distinct observations that led there.
+fn failure(args: &ExpectArgs, assertion: &Assertion, history: PollHistory) -> AppError {
+    let actual = history.actual;
+    let mut details = json!({
+        "kind": "assertion_failed",
+        "target": args.target,
+        "assertion": assertion.phrase(),
+        "diff": expect_matcher::diff(assertion, &actual),
+        "polls": history.polls,
+        "timeout_ms": args.timeout_ms,
+        "evidence": Vec::from(history.evidence),
+        "evidence_dropped": history.dropped,
+    });
+    if let Some(error) = history.last_error {
+        details["last_error"] = error;
+    }
+    let expectation = match assertion.expected() {
+        Value::Bool(_) => assertion.phrase(),
+        expected => format!("{} {expected}", assertion.phrase()),
+    };
+    AppError::Adapter(
+        AdapterError::new(
+            ErrorCode::AssertionFailed,
+            format!(
+                "Expected {} {expectation} within {}ms; last observed {actual}",
+                args.target, args.timeout_ms
+            ),
+        )
+        .with_suggestion(
+            "Compare details.diff and details.evidence with the app state; fix the state or the expectation rather than re-running it unchanged.",
+        )
+        .with_details(details),
+    )
+}
+
+#[cfg(test)]
+#[path = "expect_tests.rs"]
+mod tests;
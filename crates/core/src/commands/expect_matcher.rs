@@ -0,0 +1,179 @@
+use crate::{
+    AppError,
+    state::{self, CHECKED, DISABLED, FOCUSED},
+};
+use serde_json::{Value, json};
+
+const MATCHERS: &str = "to-have-value <text>, to-have-text <text>, to-be-checked, to-be-enabled, to-be-visible, to-be-focused, to-have-count <n>, to-match-screenshot <baseline.png>";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Matcher {
+    Value(String),
+    Text(String),
+    Checked,
+    Enabled,
+    Visible,
+    Focused,
+    Count(u64),
+    /// Path of the baseline PNG the element's crop is compared with.
+    Screenshot(String),
+}
+
+/// A parsed `[not] <matcher> [expected]` phrase.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Assertion {
+    pub(crate) matcher: Matcher,
+    pub(crate) negated: bool,
+}
+
+/// What one poll saw of a single element. Ref targets fill what the
+/// matcher compares from live reads, and the rest from the snapshot entry;
+/// locator targets fill it from the match the live traversal returned.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ElementView {
+    pub(crate) name: Option<String>,
+    pub(crate) value: Option<String>,
+    pub(crate) states: Vec<String>,
+    pub(crate) enabled: Option<bool>,
+    pub(crate) visible: bool,
+}
+
+impl Assertion {
+    pub(crate) fn parse(words: &[String]) -> Result<Self, AppError> {
+        let (negated, rest) = match words.split_first() {
+            Some((first, rest)) if first == "not" => (true, rest),
+            _ => (false, words),
+        };
+        let Some((name, operands)) = rest.split_first() else {
+            return Err(unknown_matcher("(missing)"));
+        };
+        let matcher = match (name.as_str(), operands) {
+            ("to-have-value", [expected]) => Matcher::Value(expected.clone()),
+            ("to-have-text", [expected]) => Matcher::Text(expected.clone()),
+            ("to-match-screenshot", [baseline]) => Matcher::Screenshot(baseline.clone()),
+            ("to-have-count", [count]) => Matcher::Count(count.parse().map_err(|_| {
+                AppError::invalid_input(format!(
+                    "to-have-count expects a non-negative integer, got '{count}'"
+                ))
+            })?),
+            ("to-be-checked", []) => Matcher::Checked,
+            ("to-be-enabled", []) => Matcher::Enabled,
+            ("to-be-visible", []) => Matcher::Visible,
+            ("to-be-focused", []) => Matcher::Focused,
+            ("to-have-value" | "to-have-text" | "to-have-count" | "to-match-screenshot", _) => {
+                return Err(AppError::invalid_input_with_suggestion(
+                    format!("{name} takes exactly one expected value"),
+                    format!("Write: expect <target> {name} <expected>"),
+                ));
+            }
+            ("to-be-checked" | "to-be-enabled" | "to-be-visible" | "to-be-focused", _) => {
+                return Err(AppError::invalid_input(format!(
+                    "{name} takes no expected value"
+                )));
+            }
+            (other, _) => return Err(unknown_matcher(other)),
+        };
+        Ok(Self { matcher, negated })
+    }
+
+    /// The phrase as typed, e.g. `not to-be-checked`.
+    pub(crate) fn phrase(&self) -> String {
+        let name = match self.matcher {
+            Matcher::Value(_) => "to-have-value",
+            Matcher::Text(_) => "to-have-text",
+            Matcher::Checked => "to-be-checked",
+            Matcher::Enabled => "to-be-enabled",
+            Matcher::Visible => "to-be-visible",
+            Matcher::Focused => "to-be-focused",
+            Matcher::Count(_) => "to-have-count",
+            Matcher::Screenshot(_) => "to-match-screenshot",
+        };
+        if self.negated {
+            format!("not {name}")
+        } else {
+            name.to_string()
+        }
+    }
+
+    pub(crate) fn expected(&self) -> Value {
+        match &self.matcher {
+            Matcher::Value(expected) | Matcher::Text(expected) | Matcher::Screenshot(expected) => {
+                json!(expected)
+            }
+            Matcher::Count(count) => json!(count),
+            Matcher::Checked | Matcher::Enabled | Matcher::Visible | Matcher::Focused => {
+                json!(true)
+            }
+        }
+    }
+
+    /// Projects an element view onto the value this matcher compares.
+    pub(crate) fn actual(&self, view: &ElementView) -> Value {
+        match self.matcher {
+            Matcher::Value(_) => json!(view.value),
+            Matcher::Text(_) => json!(element_text(view)),
+            Matcher::Checked => json!(state::has_state(&view.states, CHECKED)),
+            Matcher::Enabled => json!(
+                view.enabled
+                    .unwrap_or_else(|| !state::has_state(&view.states, DISABLED))
+            ),
+            Matcher::Visible => json!(view.visible),
+            Matcher::Focused => json!(state::has_state(&view.states, FOCUSED)),
+            Matcher::Count(_) | Matcher::Screenshot(_) => Value::Null,
+        }
+    }
+
+    /// Whether an observed value satisfies the assertion, negation included.
+    /// `to-have-text` compares case-insensitively with whitespace collapsed,
+    /// like `find --text`; `to-have-value` is an exact match. A screenshot
+    /// observation carries its own verdict against the threshold.
+    pub(crate) fn holds(&self, actual: &Value) -> bool {
+        let matched = match &self.matcher {
+            Matcher::Value(expected) => actual.as_str() == Some(expected.as_str()),
+            Matcher::Text(expected) => actual.as_str().is_some_and(|text| {
+                crate::search_text::normalize(text) == crate::search_text::normalize(expected)
+            }),
+            Matcher::Count(expected) => actual.as_u64() == Some(*expected),
+            Matcher::Screenshot(_) => actual.get("passed").and_then(Value::as_bool) == Some(true),
+            Matcher::Checked | Matcher::Enabled | Matcher::Visible | Matcher::Focused => {
+                actual.as_bool() == Some(true)
+            }
+        };
+        matched != self.negated
+    }
+}
+
+fn element_text(view: &ElementView) -> Option<&str> {
+    view.value
+        .as_deref()
+        .filter(|value| !value.trim().is_empty())
+        .or(view.name.as_deref())
+}
+
+/// Expected-versus-observed summary for a failed assertion. String pairs
+/// also report the first differing character index.
+pub(crate) fn diff(assertion: &Assertion, actual: &Value) -> Value {
+    let mut diff = json!({
+        "expected": assertion.expected(),
+        "actual": actual,
+        "negated": assertion.negated,
+    });
+    if let (Some(expected), Some(actual)) = (assertion.expected().as_str(), actual.as_str()) {
+        let mismatch_at = expected
+            .chars()
+            .zip(actual.chars())
+            .position(|(left, right)| left != right)
+            .unwrap_or_else(|| expected.chars().count().min(actual.chars().count()));
+        diff["mismatch_at"] = json!(mismatch_at);
+        diff["expected_chars"] = json!(expected.chars().count());
+        diff["actual_chars"] = json!(actual.chars().count());
+    }
+    diff
+}
+
+fn unknown_matcher(name: &str) -> AppError {
+    AppError::invalid_input_with_suggestion(
+        format!("Unknown expect matcher '{name}'"),
+        format!("Use one of: {MATCHERS}; prefix with 'not' to negate."),
+    )
+}
@@ -0,0 +1,162 @@
+use crate::{
+    AdapterError, AppError, ErrorCode,
+    adapter::{PlatformAdapter, ScreenshotTarget, optional_live_read},
+    commands::{
+        expect_matcher::{Assertion, Matcher},
+        expect_target::Observation,
+        visual_compare::{self, IgnoreRegion, source_window},
+    },
+    context::CommandContext,
+    image_diff::{self, ImageDiff},
+    raster::{PixelBox, Placement, Raster},
+    ref_resolve_deadline::resolve_within_deadline,
+    refs::RefEntry,
+    resolve_attempt_outcome::ResolveAttemptOutcome,
+};
+use serde_json::{Value, json};
+use std::{cell::RefCell, path::PathBuf};
+
+/// `to-match-screenshot` state kept across polls: the baseline decoded once,
+/// and the latest crop and diff so a failure can store them as artifacts.
+/// A missing baseline is created from the first crop and the assertion fails.
+pub(crate) struct ScreenshotExpectation {
+    baseline_path: PathBuf,
+    baseline: Option<Raster>,
+    threshold: f64,
+    ignore: Vec<IgnoreRegion>,
+    last: RefCell<Option<(Raster, Option<ImageDiff>)>>,
+}
+
+impl ScreenshotExpectation {
+    /// Builds the state for a `to-match-screenshot` assertion, or `None` for
+    /// any other matcher, which must not carry screenshot options.
+    pub(crate) fn prepare(
+        assertion: &Assertion,
+        threshold: Option<f64>,
+        ignore: &[String],
+        context: &CommandContext,
+    ) -> Result<Option<Self>, AppError> {
+        let Matcher::Screenshot(baseline) = &assertion.matcher else {
+            if threshold.is_some() || !ignore.is_empty() {
+                return Err(AppError::invalid_input(
+                    "--threshold and --ignore only apply to to-match-screenshot",
+                ));
+            }
+            return Ok(None);
+        };
+        let baseline_path = PathBuf::from(baseline);
+        let baseline = match baseline_path.try_exists() {
+            Ok(false) => None,
+            _ => Some(visual_compare::read_png(&baseline_path, "baseline")?),
+        };
+        Ok(Some(Self {
+            baseline_path,
+            baseline,
+            threshold: visual_compare::threshold(threshold)?,
+            ignore: visual_compare::parse_ignore(ignore, context)?,
+            last: RefCell::new(None),
+        }))
+    }
+
+    /// True once a crop exists to seed a missing baseline with; polling
+    /// longer cannot make the assertion pass.
+    pub(crate) fn needs_baseline(&self) -> bool {
+        self.baseline.is_none() && self.last.borrow().is_some()
+    }
+
+    /// Captures the element's window, crops it to the element's live bounds,
+    /// and compares the crop with the baseline.
+    pub(crate) fn observe(
+        &self,
+        entry: &RefEntry,
+        adapter: &dyn PlatformAdapter,
+        deadline: crate::Deadline,
+    ) -> Result<Observation, AppError> {
+        let handle = match resolve_within_deadline(adapter, entry, deadline) {
+            ResolveAttemptOutcome::DeadlinePassed => return Ok(Observation::Expired),
+            ResolveAttemptOutcome::Failed(error) => return Err(error.into()),
+            ResolveAttemptOutcome::Resolved(handle) => handle,
+        };
+        let element = optional_live_read(adapter.get_element_bounds(&handle, deadline))?
+            .or(entry.geometry.bounds)
+            .ok_or_else(|| {
+                AdapterError::new(
+                    ErrorCode::ActionNotSupported,
+                    "Element bounds are unavailable",
+                )
+            })?;
+        let (window, window_bounds) = source_window(entry, adapter, deadline)?;
+        let capture = adapter.screenshot(ScreenshotTarget::ExactWindow(window), deadline)?;
+        let image = Raster::decode_png(&capture.data).map_err(|error| {
+            AdapterError::new(
+                ErrorCode::ActionFailed,
+                format!("Screenshot could not be decoded for comparison: {error}"),
+            )
+        })?;
+        let placement = Placement::new(&window_bounds, capture.scale_factor);
+        let Some(area) = placement.pixel_box(&element, image.width, image.height) else {
+            return Ok(Observation::Gap(
+                json!({ "kind": "element_outside_window" }),
+            ));
+        };
+        let crop = image.crop(area);
+        let Some(baseline) = &self.baseline else {
+            *self.last.borrow_mut() = Some((crop, None));
+            return Ok(Observation::Seen(
+                json!({ "passed": false, "baseline_missing": true }),
+            ));
+        };
+        let crop_placement = Placement {
+            origin_x: placement.origin_x + f64::from(area.x) / placement.scale,
+            origin_y: placement.origin_y + f64::from(area.y) / placement.scale,
+            scale: placement.scale,
+        };
+        let ignore: Vec<PixelBox> =
+            visual_compare::pixel_boxes(&self.ignore, baseline.width, baseline.height, |_| {
+                Ok(crop_placement)
+            })?;
+        let diff = image_diff::compare(baseline, &crop, &ignore);
+        let summary = diff.summary(self.threshold);
+        *self.last.borrow_mut() = Some((crop, Some(diff)));
+        Ok(Observation::Seen(summary))
+    }
+
+    /// Failure evidence: the last score, plus where the crop and diff mask
+    /// were stored. A missing baseline is written from the last crop.
+    pub(crate) fn failure_details(
+        &self,
+        target: &str,
+        pid: crate::ProcessId,
+        context: &CommandContext,
+    ) -> Result<Value, AppError> {
+        let mut details = json!({
+            "baseline": self.baseline_path.to_string_lossy(),
+            "threshold": self.threshold,
+        });
+        let Some((crop, diff)) = self.last.borrow_mut().take() else {
+            return Ok(details);
+        };
+        let crop_png = crop.encode_png()?;
+        let mut images = vec![("actual", crop_png.as_slice())];
+        let mask_png;
+        match &diff {
+            Some(diff) => {
+                mask_png = diff.mask.encode_png()?;
+                images.push(("diff", mask_png.as_slice()));
+            }
+            None => {
+                crate::refs::write_user_file(&self.baseline_path, &crop_png)?;
+                details["baseline_written"] = json!(true);
+            }
+        }
+        let deadline = crate::Deadline::standard()?;
+        details["artifacts"] = crate::trace_artifacts::store_comparison_artifacts(
+            context, target, pid, &images, deadline,
+        );
+        Ok(details)
+    }
+}
+
+#[cfg(test)]
+#[path = "expect_screenshot_tests.rs"]
+mod tests;
@@ -0,0 +1,221 @@
+use crate::{
+    AdapterError, ImageBuffer, ImageFormat, Rect, WindowInfo,
+    adapter::{
+        ActionOps, InputOps, NativeHandle, ObservationOps, ScreenshotTarget, SystemOps,
+        WindowFilter,
+    },
+    commands::expect::{ExpectArgs, ScreenshotOptions, execute},
+    context::CommandContext,
+    raster::Raster,
+    refs::RefEntry,
+    refs_test_support::{HomeGuard, TempDir},
+};
+use serde_json::Value;
+use std::path::Path;
+
+const WHITE: [u8; 4] = [255, 255, 255, 255];
+const BLACK: [u8; 4] = [0, 0, 0, 255];
+
+/// A 40x30 window at (100, 100) showing a black 10x10 square at (10, 5).
+struct WindowAdapter;
+
+impl ObservationOps for WindowAdapter {
+    fn resolve_element_strict(
+        &self,
+        _entry: &RefEntry,
+        _deadline: crate::Deadline,
+    ) -> Result<NativeHandle, AdapterError> {
+        Ok(NativeHandle::null())
+    }
+
+    fn list_windows(
+        &self,
+        _filter: &WindowFilter,
+        _deadline: crate::Deadline,
+    ) -> Result<Vec<WindowInfo>, AdapterError> {
+        Ok(vec![WindowInfo {
+            id: "w-1".into(),
+            title: "Window".into(),
+            app: "Example".into(),
+            pid: crate::ProcessId::new(1),
+            process_instance: Some("test-instance".into()),
+            bounds: Some(Rect {
+                x: 100.0,
+                y: 100.0,
+                width: 40.0,
+                height: 30.0,
+            }),
+            state: Default::default(),
+        }])
+    }
+}
+
+impl ActionOps for WindowAdapter {}
+impl InputOps for WindowAdapter {}
+
+impl SystemOps for WindowAdapter {
+    fn screenshot(
+        &self,
+        _target: ScreenshotTarget,
+        _deadline: crate::Deadline,
+    ) -> Result<ImageBuffer, AdapterError> {
+        let mut window = solid(40, 30, WHITE);
+        for y in 5..15 {
+            for x in 10..20 {
+                window.set_pixel(x, y, BLACK);
+            }
+        }
+        Ok(ImageBuffer {
+            data: window.encode_png().unwrap(),
+            format: ImageFormat::Png,
+            width: 40,
+            height: 30,
+            scale_factor: 1.0,
+        })
+    }
+}
+
+fn solid(width: u32, height: u32, color: [u8; 4]) -> Raster {
+    Raster {
+        width,
+        height,
+        rgba: color.repeat(width as usize * height as usize),
+    }
+}
+
+fn square_ref() -> String {
+    let bounds = Rect {
+        x: 110.0,
+        y: 105.0,
+        width: 10.0,
+        height: 10.0,
+    };
+    let entry: RefEntry = serde_json::from_value(serde_json::json!({
+        "pid": 1,
+        "process_instance": "test-instance",
+        "role": "image",
+        "name": "Logo",
+        "bounds": bounds,
+        "bounds_hash": bounds.bounds_hash(),
+        "states": [],
+        "available_actions": [],
+        "source_app": "Example",
+        "source_window_id": "w-1",
+        "path": [0],
+    }))
+    .unwrap();
+    let mut refmap = crate::RefMap::new();
+    refmap.allocate(entry);
+    let snapshot_id = crate::refs_store::RefStore::new()
+        .unwrap()
+        .save_new_snapshot(&refmap)
+        .unwrap();
+    format!("@{snapshot_id}:e1")
+}
+
+fn expect_screenshot(
+    target: &str,
+    baseline: &Path,
+    threshold: Option<f64>,
+) -> Result<Value, crate::AppError> {
+    execute(
+        ExpectArgs {
+            target: target.into(),
+            matcher: vec![
+                "to-match-screenshot".into(),
+                baseline.to_string_lossy().into_owned(),
+            ],
+            snapshot_id: None,
+            app: None,
+            window_id: None,
+            timeout_ms: 300,
+            screenshot: ScreenshotOptions {
+                threshold,
+                ignore: vec![],
+            },
+            reveal_secure: false,
+        },
+        &WindowAdapter,
+        &CommandContext::default(),
+    )
+}
+
+#[test]
+fn element_crop_matching_the_baseline_passes() {
+    let _home = HomeGuard::new();
+    let dir = TempDir::new();
+    let baseline = dir.path().join("logo.png");
+    std::fs::write(&baseline, solid(10, 10, BLACK).encode_png().unwrap()).unwrap();
+
+    let result = expect_screenshot(&square_ref(), &baseline, None).unwrap();
+
+    assert_eq!(result["passed"], true);
+    assert_eq!(result["actual"]["score"], 0.0);
+    assert_eq!(result["actual"]["width"], 10);
+}
+
+#[test]
+fn differing_crop_fails_with_the_score_and_artifact_outcome() {
+    let _home = HomeGuard::new();
+    let dir = TempDir::new();
+    let baseline = dir.path().join("logo.png");
+    std::fs::write(&baseline, solid(10, 10, WHITE).encode_png().unwrap()).unwrap();
+
+    let error = expect_screenshot(&square_ref(), &baseline, Some(0.5)).unwrap_err();
+
+    assert_eq!(error.code(), "ASSERTION_FAILED");
+    let crate::AppError::Adapter(error) = error else {
+        panic!("expected an adapter error");
+    };
+    let details = error.details.unwrap();
+    assert_eq!(details["diff"]["actual"]["score"], 1.0);
+    assert_eq!(details["screenshot"]["threshold"], 0.5);
+    assert_eq!(details["screenshot"]["artifacts"]["skipped"], "disabled");
+}
+
+#[test]
+fn missing_baseline_is_written_from_the_first_crop_and_fails() {
+    let _home = HomeGuard::new();
+    let dir = TempDir::new();
+    let baseline = dir.path().join("new.png");
+
+    let error = expect_screenshot(&square_ref(), &baseline, None).unwrap_err();
+
+    assert_eq!(error.code(), "ASSERTION_FAILED");
+    let crate::AppError::Adapter(error) = error else {
+        panic!("expected an adapter error");
+    };
+    let details = error.details.unwrap();
+    assert_eq!(details["polls"], 1);
+    assert_eq!(details["screenshot"]["baseline_written"], true);
+    let written = Raster::decode_png(&std::fs::read(&baseline).unwrap()).unwrap();
+    assert_eq!(written, solid(10, 10, BLACK));
+    assert_eq!(
+        expect_screenshot(&square_ref(), &baseline, None).unwrap()["passed"],
+        true
+    );
+}
+
+#[test]
+fn screenshot_options_are_rejected_for_other_matchers() {
+    let error = execute(
+        ExpectArgs {
+            target: "button:Save".into(),
+            matcher: vec!["to-be-enabled".into()],
+            snapshot_id: None,
+            app: None,
+            window_id: None,
+            timeout_ms: 300,
+            screenshot: ScreenshotOptions {
+                threshold: Some(0.1),
+                ignore: vec![],
+            },
+            reveal_secure: false,
+        },
+        &WindowAdapter,
+        &CommandContext::default(),
+    )
+    .unwrap_err();
+
+    assert_eq!(error.code(), "INVALID_ARGS");
+}
@@ -0,0 +1,308 @@
+use crate::{
+    AdapterError, AppError, ErrorCode, LocatorField, LocatorQuery,
+    adapter::{NativeHandle, PlatformAdapter, optional_live_read},
+    commands::{
+        expect_matcher::{Assertion, ElementView, Matcher},
+        helpers::load_ref_entry,
+        query,
+    },
+    context::CommandContext,
+    live_locator::{
+        LocatorMaterialization, LocatorResolveRequest, LocatorSelection, ObservationRoot,
+        resolve_query,
+    },
+    ref_resolve_deadline::resolve_within_deadline,
+    refs::RefEntry,
+    resolve_attempt_outcome::ResolveAttemptOutcome,
+    secure_mask::SecureMask,
+    state::{self, VisibilityEvidence},
+};
+use serde_json::{Value, json};
+
+/// What an `expect` observes: a snapshot ref re-identified on every poll, or
+/// a `role:text` locator re-run against the live window on every poll.
+pub(crate) enum ExpectTarget {
+    Ref(Box<RefEntry>),
+    Locator(LocatorQuery),
+}
+
+/// One poll's result. A gap means the target could not be observed this
+/// time (missing, ambiguous, stale, or a transient read failure); the
+/// assertion keeps retrying and the gap lands in the evidence trail. For
+/// `to-be-visible` a missing or stale target is seen as not visible.
+pub(crate) enum Observation {
+    Seen(Value),
+    Gap(Value),
+    /// The deadline passed before the target could be read at all.
+    Expired,
+}
+
+/// Window scope for locator targets.
+pub(crate) struct LocatorScope<'a> {
+    pub(crate) app: Option<&'a str>,
+    pub(crate) window_id: Option<&'a str>,
+}
+
+impl ExpectTarget {
+    pub(crate) fn parse(
+        raw: &str,
+        snapshot_id: Option<&str>,
+        context: &CommandContext,
+    ) -> Result<Self, AppError> {
+        if raw.starts_with('@') {
+            return load_ref_entry(raw, snapshot_id, context)
+                .map(|entry| Self::Ref(Box::new(entry)));
+        }
+        if snapshot_id.is_some() {
+            return Err(AppError::invalid_input(
+                "--snapshot only applies to a ref target, not a locator",
+            ));
+        }
+        query::validate_selector(raw).map(Self::Locator)
+    }
+
+    pub(crate) fn observe(
+        &self,
+        assertion: &Assertion,
+        adapter: &dyn PlatformAdapter,
+        scope: &LocatorScope<'_>,
+        mask: Option<&SecureMask>,
+        deadline: crate::Deadline,
+    ) -> Result<Observation, AppError> {
+        let observed = match self {
+            Self::Ref(entry) => observe_ref(entry, assertion, adapter, mask, deadline),
+            Self::Locator(query) => {
+                observe_locator(query, assertion, adapter, scope, mask, deadline)
+            }
+        };
+        if let Err(AppError::Adapter(error)) = &observed
+            && is_absent(&error.code)
+            && let Some(observation) = absent(assertion)
+        {
+            return Ok(observation);
+        }
+        settle(observed, deadline)
+    }
+}
+
+/// Turns a transient failure into a gap to retry, and a timeout at the
+/// deadline into `Expired`; anything else ends the wait.
+pub(crate) fn settle(
+    observed: Result<Observation, AppError>,
+    deadline: crate::Deadline,
+) -> Result<Observation, AppError> {
+    match observed {
+        Ok(observation) => Ok(observation),
+        Err(AppError::Adapter(error))
+            if error.code == ErrorCode::Timeout && deadline.is_expired() =>
+        {
+            Ok(Observation::Expired)
+        }
+        Err(AppError::Adapter(error)) if is_gap(&error) => Ok(Observation::Gap(json!({
+            "code": error.code.as_str(),
+            "message": error.message,
+        }))),
+        Err(error) => Err(error),
+    }
+}
+
+fn observe_ref(
+    entry: &RefEntry,
+    assertion: &Assertion,
+    adapter: &dyn PlatformAdapter,
+    mask: Option<&SecureMask>,
+    deadline: crate::Deadline,
+) -> Result<Observation, AppError> {
+    match resolve_within_deadline(adapter, entry, deadline) {
+        ResolveAttemptOutcome::DeadlinePassed => Ok(Observation::Expired),
+        ResolveAttemptOutcome::Failed(error) => Err(AppError::Adapter(error)),
+        ResolveAttemptOutcome::Resolved(handle) => {
+            let mut view = live_view(entry, &handle, &assertion.matcher, adapter, deadline)?;
+            mask_view(&mut view, entry, mask);
+            Ok(Observation::Seen(assertion.actual(&view)))
+        }
+    }
+}
+
+/// Reads only what the matcher needs, falling back to the snapshot entry
+/// when the adapter cannot read a property live, as `is` and `get` do.
+fn live_view(
+    entry: &RefEntry,
+    handle: &NativeHandle,
+    matcher: &Matcher,
+    adapter: &dyn PlatformAdapter,
+    deadline: crate::Deadline,
+) -> Result<ElementView, AdapterError> {
+    let live_state = optional_live_read(adapter.get_live_state(handle, deadline))?;
+    let states_from_live = live_state.is_some();
+    let (states, enabled, state_value) = match live_state {
+        Some(state) => (state.states, state.enabled, state.value),
+        None => (
+            entry.capabilities.states.clone(),
+            None,
+            entry.identity.value.clone(),
+        ),
+    };
+    let value = match matcher {
+        Matcher::Value(_) | Matcher::Text(_) => {
+            optional_live_read(adapter.get_live_value(handle, deadline))?.or(state_value)
+        }
+        _ => state_value,
+    };
+    let visible = match matcher {
+        Matcher::Visible => {
+            let live_bounds = optional_live_read(adapter.get_element_bounds(handle, deadline))?;
+            VisibilityEvidence {
+                bounds: live_bounds.or(entry.geometry.bounds),
+                states: states.clone(),
+                bounds_from_live: live_bounds.is_some(),
+                states_from_live,
+            }
+            .result()
+        }
+        _ => false,
+    };
+    let name = match matcher {
+        Matcher::Text(_) => live_name(entry, handle, adapter, deadline)?,
+        _ => entry.identity.name.clone(),
+    };
+    Ok(ElementView {
+        name,
+        value,
+        states,
+        enabled,
+        visible,
+    })
+}
+
+/// The element's current name, for text that lives in a button or label
+/// name; the snapshot name stands in only when the adapter cannot say.
+fn live_name(
+    entry: &RefEntry,
+    handle: &NativeHandle,
+    adapter: &dyn PlatformAdapter,
+    deadline: crate::Deadline,
+) -> Result<Option<String>, AdapterError> {
+    let live = optional_live_read(adapter.get_live_element(handle, deadline).map(Some))?;
+    Ok(match live.map(|live| live.identity.name) {
+        Some(LocatorField::Known(name)) => Some(name),
+        Some(LocatorField::Absent) => None,
+        Some(LocatorField::Unknown) | None => entry.identity.name.clone(),
+    })
+}
+
+fn observe_locator(
+    query: &LocatorQuery,
+    assertion: &Assertion,
+    adapter: &dyn PlatformAdapter,
+    scope: &LocatorScope<'_>,
+    mask: Option<&SecureMask>,
+    deadline: crate::Deadline,
+) -> Result<Observation, AppError> {
+    let window = crate::snapshot::resolve_window_for_surface(
+        adapter,
+        scope.app,
+        scope.window_id,
+        crate::SnapshotSurface::Window,
+        deadline,
+    )?;
+    let count_only = matches!(assertion.matcher, Matcher::Count(_));
+    let resolution = resolve_query(
+        adapter,
+        query,
+        ObservationRoot::Window(&window),
+        &LocatorResolveRequest {
+            selection: if count_only {
+                LocatorSelection::Count
+            } else {
+                LocatorSelection::All { limit: Some(2) }
+            },
+            deadline,
+            max_raw_depth: 50,
+            surface: None,
+            materialization: if count_only {
+                LocatorMaterialization::None
+            } else {
+                LocatorMaterialization::SelectedMatches
+            },
+        },
+    )?;
+    let total = resolution.meta.total_matches;
+    if !resolution.meta.selection_complete {
+        return Ok(Observation::Gap(
+            json!({ "kind": "locator_incomplete", "observed_matches": total }),
+        ));
+    }
+    if count_only {
+        return Ok(Observation::Seen(json!(total)));
+    }
+    let mut matches = resolution.matches.into_iter();
+    let (Some(only), None) = (matches.next(), matches.next()) else {
+        let code = if total == 0 {
+            ErrorCode::ElementNotFound
+        } else {
+            ErrorCode::AmbiguousTarget
+        };
+        if let Some(observation) = absent(assertion).filter(|_| total == 0) {
+            return Ok(observation);
+        }
+        return Ok(Observation::Gap(
+            json!({ "code": code.as_str(), "matches": total }),
+        ));
+    };
+    let entry = only.into_entry();
+    let visible = VisibilityEvidence {
+        bounds: entry.geometry.bounds,
+        states: entry.capabilities.states.clone(),
+        bounds_from_live: true,
+        states_from_live: true,
+    }
+    .result();
+    let mut view = ElementView {
+        name: entry.identity.name.clone(),
+        value: entry.identity.value.clone(),
+        states: entry.capabilities.states.clone(),
+        enabled: None,
+        visible,
+    };
+    mask_view(&mut view, &entry, mask);
+    Ok(Observation::Seen(assertion.actual(&view)))
+}
+
+/// Drops the value of a target `mask` covers, judged on the snapshot entry
+/// and on the states just read, so it is neither compared nor reported.
+fn mask_view(view: &mut ElementView, entry: &RefEntry, mask: Option<&SecureMask>) {
+    if mask.is_some_and(|mask| {
+        mask.masks_entry(entry) || state::has_state(&view.states, state::SECURE)
+    }) {
+        view.value = None;
+    }
+}
+
+/// What a poll sees of a target that is no longer there: only visibility
+/// has an answer, `false`; every other matcher keeps waiting for it.
+fn absent(assertion: &Assertion) -> Option<Observation> {
+    matches!(assertion.matcher, Matcher::Visible).then(|| {
+        Observation::Seen(assertion.actual(&ElementView {
+            name: None,
+            value: None,
+            states: Vec::new(),
+            enabled: None,
+            visible: false,
+        }))
+    })
+}
+
+fn is_absent(code: &ErrorCode) -> bool {
+    matches!(code, ErrorCode::StaleRef | ErrorCode::ElementNotFound)
+}
+
+fn is_gap(error: &AdapterError) -> bool {
+    matches!(
+        error.code,
+        ErrorCode::StaleRef
+            | ErrorCode::ElementNotFound
+            | ErrorCode::AmbiguousTarget
+            | ErrorCode::WindowNotFound
+    ) || error.is_explicitly_retryable()
+}
@@ -0,0 +1,290 @@
+use super::*;
+use crate::adapter::{ActionOps, InputOps, NativeHandle, ObservationOps, SystemOps};
+use crate::commands::expect_matcher::ElementView;
+use crate::commands::helpers::test_support::save_one_ref_snapshot;
+use crate::{element_state::ElementState, refs::RefEntry, refs_test_support::HomeGuard};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// Replays one scripted observation per poll, repeating the last one.
+struct ScriptedAdapter {
+    values: Mutex<Vec<&'static str>>,
+    names: Mutex<Vec<&'static str>>,
+    states: Vec<String>,
+    stale_polls: AtomicU32,
+}
+
+impl ScriptedAdapter {
+    fn new(values: &[&'static str], states: &[&str]) -> Self {
+        Self {
+            values: Mutex::new(values.iter().rev().copied().collect()),
+            names: Mutex::default(),
+            states: states.iter().map(|state| state.to_string()).collect(),
+            stale_polls: AtomicU32::new(0),
+        }
+    }
+}
+
+impl ObservationOps for ScriptedAdapter {
+    fn resolve_element_strict(
+        &self,
+        _entry: &RefEntry,
+        _deadline: crate::Deadline,
+    ) -> Result<NativeHandle, AdapterError> {
+        let pending = self.stale_polls.load(Ordering::SeqCst);
+        if pending > 0 {
+            self.stale_polls.store(pending - 1, Ordering::SeqCst);
+            return Err(AdapterError::stale_ref("@e1"));
+        }
+        Ok(NativeHandle::null())
+    }
+
+    fn get_live_state(
+        &self,
+        _handle: &NativeHandle,
+        _deadline: crate::Deadline,
+    ) -> Result<Option<ElementState>, AdapterError> {
+        Ok(Some(ElementState {
+            role: "checkbox".into(),
+            states: self.states.clone(),
+            value: None,
+            enabled: Some(true),
+            hidden: None,
+            offscreen: None,
+        }))
+    }
+
+    fn get_live_value(
+        &self,
+        _handle: &NativeHandle,
+        _deadline: crate::Deadline,
+    ) -> Result<Option<String>, AdapterError> {
+        Ok(next_scripted(&self.values).map(str::to_string))
+    }
+
+    fn get_live_element(
+        &self,
+        handle: &NativeHandle,
+        deadline: crate::Deadline,
+    ) -> Result<crate::LiveElement, AdapterError> {
+        let name = next_scripted(&self.names)
+            .ok_or_else(|| AdapterError::not_supported("get_live_element"))?;
+        Ok(crate::LiveElement {
+            identity: crate::LiveIdentity {
+                name: crate::LocatorField::Known(name.into()),
+                description: crate::LocatorField::Absent,
+                identifiers: crate::IdentifierEvidence::absent(),
+            },
+            state: self.get_live_state(handle, deadline)?.unwrap(),
+            states_complete: true,
+            bounds: None,
+            available_actions: Vec::new(),
+        })
+    }
+}
+
+fn next_scripted(script: &Mutex<Vec<&'static str>>) -> Option<&'static str> {
+    let mut script = script.lock().unwrap();
+    if script.len() > 1 {
+        script.pop()
+    } else {
+        script.last().copied()
+    }
+}
+
+impl ActionOps for ScriptedAdapter {}
+
+impl InputOps for ScriptedAdapter {}
+
+impl SystemOps for ScriptedAdapter {}
+
+fn expect(
+    adapter: &ScriptedAdapter,
+    target: &str,
+    matcher: &str,
+    timeout_ms: u64,
+) -> Result<Value, AppError> {
+    expect_revealing(adapter, target, matcher, timeout_ms, false)
+}
+
+fn expect_revealing(
+    adapter: &ScriptedAdapter,
+    target: &str,
+    matcher: &str,
+    timeout_ms: u64,
+    reveal_secure: bool,
+) -> Result<Value, AppError> {
+    execute(
+        ExpectArgs {
+            target: target.into(),
+            matcher: matcher.split(' ').map(str::to_string).collect(),
+            snapshot_id: None,
+            app: None,
+            window_id: None,
+            timeout_ms,
+            screenshot: ScreenshotOptions::default(),
+            reveal_secure,
+        },
+        adapter,
+        &CommandContext::default(),
+    )
+}
+
+fn qualified_ref() -> String {
+    format!("@{}:e1", save_one_ref_snapshot("checkbox", "Click"))
+}
+
+#[test]
+fn value_matcher_retries_until_the_value_arrives() {
+    let _guard = HomeGuard::new();
+    let target = qualified_ref();
+    let adapter = ScriptedAdapter::new(&["Saving", "Saving", "Done"], &[]);
+    adapter.stale_polls.store(1, Ordering::SeqCst);
+
+    let value = expect(&adapter, &target, "to-have-value Done", 5_000).unwrap();
+
+    assert_eq!(value["passed"], true);
+    assert_eq!(value["assertion"], "to-have-value");
+    assert_eq!(value["actual"], "Done");
+    assert_eq!(value["polls"], 4);
+}
+
+#[test]
+fn unmet_matcher_fails_with_assertion_failed_diff_and_deduplicated_evidence() {
+    let _guard = HomeGuard::new();
+    let target = qualified_ref();
+    let adapter = ScriptedAdapter::new(&["Draft", "Draft", "Drafted"], &[]);
+    adapter.stale_polls.store(1, Ordering::SeqCst);
+
+    let err = expect(&adapter, &target, "to-have-value Done", 450).unwrap_err();
+
+    assert_eq!(err.code(), "ASSERTION_FAILED");
+    let AppError::Adapter(error) = err else {
+        panic!("expected adapter error");
+    };
+    let details = error.details.unwrap();
+    assert_eq!(details["kind"], "assertion_failed");
+    assert_eq!(details["diff"]["expected"], "Done");
+    assert_eq!(details["diff"]["actual"], "Drafted");
+    assert_eq!(details["diff"]["mismatch_at"], 1);
+    let evidence = details["evidence"].as_array().unwrap();
+    assert_eq!(evidence.len(), 3, "{evidence:?}");
+    assert_eq!(evidence[0]["error"]["code"], "STALE_REF");
+    assert_eq!(evidence[1]["actual"], "Draft");
+    assert_eq!(evidence[2]["actual"], "Drafted");
+    assert!(details["polls"].as_u64().unwrap() > 3);
+    assert!(details.get("last_error").is_none());
+}
+
+#[test]
+fn negated_state_matchers_hold_on_the_opposite_state() {
+    let _guard = HomeGuard::new();
+    let target = qualified_ref();
+    let unchecked = ScriptedAdapter::new(&[], &[]);
+
+    let value = expect(&unchecked, &target, "not to-be-checked", 1_000).unwrap();
+    assert_eq!(value["assertion"], "not to-be-checked");
+    assert_eq!(value["actual"], false);
+
+    let checked = ScriptedAdapter::new(&[], &["checked"]);
+    let err = expect(&checked, &target, "not to-be-checked", 1).unwrap_err();
+    assert_eq!(err.code(), "ASSERTION_FAILED");
+    assert!(err.to_string().contains("not to-be-checked within 1ms"));
+}
+
+#[test]
+fn malformed_assertions_are_rejected_before_polling() {
+    let _guard = HomeGuard::new();
+    let target = qualified_ref();
+    let adapter = ScriptedAdapter::new(&[], &[]);
+
+    for matcher in [
+        "to-be-shiny",
+        "not",
+        "to-have-value",
+        "to-be-checked yes",
+        "to-have-count many",
+        "to-have-count 2",
+    ] {
+        let err = expect(&adapter, &target, matcher, 1_000).unwrap_err();
+        assert_eq!(err.code(), "INVALID_ARGS", "{matcher}");
+    }
+}
+
+#[test]
+fn text_matcher_collapses_whitespace_and_falls_back_to_the_name() {
+    let text = Assertion::parse(&["to-have-text".into(), "Saved  changes".into()]).unwrap();
+    let named = ElementView {
+        name: Some("saved changes".into()),
+        value: Some("  ".into()),
+        ..ElementView::default()
+    };
+
+    assert!(text.holds(&text.actual(&named)));
+    assert!(!text.holds(&json!(null)));
+}
+
+#[test]
+fn text_matcher_reads_the_live_name_on_every_poll() {
+    let _guard = HomeGuard::new();
+    let target = qualified_ref();
+    let adapter = ScriptedAdapter::new(&[""], &[]);
+    *adapter.names.lock().unwrap() = vec!["Saved", "Saving", "Saving"];
+
+    let value = expect(&adapter, &target, "to-have-text Saved", 5_000).unwrap();
+
+    assert_eq!(value["passed"], true);
+    assert_eq!(value["actual"], "Saved");
+    assert_eq!(value["polls"], 3);
+}
+
+#[test]
+fn a_target_that_disappears_is_not_visible() {
+    let _guard = HomeGuard::new();
+    let target = qualified_ref();
+    let gone = ScriptedAdapter::new(&[], &[]);
+    gone.stale_polls.store(u32::MAX, Ordering::SeqCst);
+
+    let value = expect(&gone, &target, "not to-be-visible", 1_000).unwrap();
+    assert_eq!(value["passed"], true);
+    assert_eq!(value["actual"], false);
+
+    let err = expect(&gone, &target, "to-be-visible", 1).unwrap_err();
+    assert_eq!(err.code(), "ASSERTION_FAILED");
+}
+
+#[test]
+fn a_secure_value_is_neither_matched_nor_reported_without_reveal_secure() {
+    let _guard = HomeGuard::new();
+    let target = qualified_ref();
+    let adapter = ScriptedAdapter::new(&["hunter2"], &[crate::state::SECURE]);
+
+    let err = expect(&adapter, &target, "to-have-value hunter3", 300).unwrap_err();
+    let AppError::Adapter(error) = &err else {
+        panic!("expected an adapter error, got {err:?}");
+    };
+    let details = error.details.as_ref().unwrap().to_string();
+    assert!(!details.contains("hunter2"), "{details}");
+    assert!(!error.message.contains("hunter2"), "{}", error.message);
+    assert_eq!(
+        error.details.as_ref().unwrap()["evidence"][0]["actual"],
+        json!(null)
+    );
+
+    let value = expect_revealing(&adapter, &target, "to-have-value hunter2", 1_000, true).unwrap();
+    assert_eq!(value["actual"], "hunter2");
+}
+
+#[test]
+fn a_configured_secure_locator_masks_the_expected_value_too() {
+    let _guard = HomeGuard::new();
+    let target = qualified_ref();
+    let path = crate::state_root::resolve_configured_state_root()
+        .unwrap()
+        .join(crate::secure_mask::SECURE_FIELDS_FILE);
+    crate::private_file::write_atomic(&path, br#"[{"role": "checkbox"}]"#).unwrap();
+    let adapter = ScriptedAdapter::new(&["hunter2"], &[]);
+
+    let err = expect(&adapter, &target, "to-have-value hunter2", 300).unwrap_err();
+    assert_eq!(err.code(), "ASSERTION_FAILED");
+}
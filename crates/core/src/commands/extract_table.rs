@@ -0,0 +1,178 @@
+use crate::{
+    AdapterError, AppError, ErrorCode, Rect,
+    adapter::{PlatformAdapter, TreeOptions},
+    context::CommandContext,
+    snapshot::SnapshotResult,
+    snapshot_ref,
+    table_extract::{self, ExtractedTable},
+};
+use serde_json::{Value, json};
+
+const MAX_SCROLL_PAGES: u32 = 20;
+
+pub struct ExtractTableArgs {
+    pub ref_id: String,
+    pub snapshot_id: Option<String>,
+    pub max_depth: u8,
+    pub max_rows: usize,
+    pub scroll: bool,
+}
+
+/// Drills into `ref_id`, reads the first table, grid, outline, or list at or
+/// below it, and returns `{columns, rows}` with per-cell refs. With `scroll`,
+/// the last collected row is scrolled into view and the table re-read until
+/// no new rows appear, `max_rows` is reached, or the page cap is hit.
+pub fn execute(
+    args: ExtractTableArgs,
+    adapter: &dyn PlatformAdapter,
+    context: &CommandContext,
+) -> Result<Value, AppError> {
+    if args.max_rows == 0 {
+        return Err(AppError::invalid_input_with_suggestion(
+            "--max-rows must be greater than zero",
+            "Pass a positive row limit, or omit --max-rows for the default of 100.",
+        ));
+    }
+    let opts = TreeOptions {
+        max_depth: args.max_depth,
+        ..TreeOptions::default()
+    };
+    let (mut result, mut table) = observe_page(&args, &opts, adapter, context)?;
+    let mut scroll = None;
+    if args.scroll {
+        let mut pages = 0;
+        let mut exhausted = false;
+        while table.rows.len() <= args.max_rows && pages < MAX_SCROLL_PAGES {
+            let Some(target) = last_row_ref(&table) else {
+                exhausted = true;
+                break;
+            };
+            let anchor = scroll_row_into_view(&result, &target, adapter)?;
+            let (next_result, page) = observe_page(&args, &opts, adapter, context)?;
+            pages += 1;
+            let anchor_row = anchor.and_then(|bounds| row_at(&next_result, &page, &bounds));
+            result = next_result;
+            let added = table.merge_page(page, anchor_row);
+            context.trace_lazy(
+                "extract_table.page",
+                || json!({ "page": pages, "rows_added": added, "rows_total": table.rows.len() }),
+            )?;
+            if added == 0 {
+                exhausted = true;
+                break;
+            }
+        }
+        scroll = Some(json!({ "pages": pages, "exhausted": exhausted }));
+    }
+    Ok(format_response(&args, &result, table, scroll))
+}
+
+fn observe_page(
+    args: &ExtractTableArgs,
+    opts: &TreeOptions,
+    adapter: &dyn PlatformAdapter,
+    context: &CommandContext,
+) -> Result<(SnapshotResult, ExtractedTable), AppError> {
+    let result = snapshot_ref::run_from_ref_with_context(
+        adapter,
+        opts,
+        &args.ref_id,
+        args.snapshot_id.as_deref(),
+        context,
+    )?;
+    let table = table_extract::find_table(&result.tree)
+        .map(table_extract::extract)
+        .ok_or_else(|| not_a_table(&args.ref_id, &result.tree.role))?;
+    Ok((result, table))
+}
+
+fn not_a_table(ref_id: &str, role: &str) -> AppError {
+    AdapterError::new(
+        ErrorCode::InvalidArgs,
+        format!("{ref_id} ({role}) contains no table, grid, outline, or list"),
+    )
+    .with_suggestion(
+        "Pass the ref of a table, grid, outline, list, or listbox, or a container that holds one.",
+    )
+    .into()
+}
+
+fn last_row_ref(table: &ExtractedTable) -> Option<String> {
+    table
+        .rows
+        .iter()
+        .rev()
+        .find_map(|row| row.iter().rev().find_map(|cell| cell.ref_id.clone()))
+}
+
+/// Scrolls `target` into view and returns where it landed, when the platform
+/// reports bounds, so the next page can be aligned by position.
+fn scroll_row_into_view(
+    result: &SnapshotResult,
+    target: &str,
+    adapter: &dyn PlatformAdapter,
+) -> Result<Option<Rect>, AppError> {
+    let (_, local_ref) = crate::ref_token::resolve_ref_target(target, None)?;
+    let entry = result
+        .refmap
+        .get(&local_ref)
+        .ok_or_else(|| AppError::stale_ref(target))?;
+    let deadline = crate::Deadline::standard()?;
+    let lease = adapter.acquire_interaction_lease(deadline)?;
+    let handle = adapter.resolve_element_strict(entry, deadline)?;
+    adapter.scroll_into_view(&handle, &lease)?;
+    Ok(adapter.get_element_bounds(&handle, deadline).ok().flatten())
+}
+
+/// Index of the page row holding a cell at `bounds`. Ref entries keep only
+/// the bounds hash unless bounds were requested, so that is what is compared.
+fn row_at(result: &SnapshotResult, page: &ExtractedTable, bounds: &Rect) -> Option<usize> {
+    let hash = bounds.bounds_hash()?;
+    page.rows.iter().position(|row| {
+        row.iter()
+            .filter_map(|cell| cell.ref_id.as_deref())
+            .any(|ref_id| {
+                crate::ref_token::resolve_ref_target(ref_id, None)
+                    .ok()
+                    .and_then(|(_, local)| result.refmap.get(&local))
+                    .is_some_and(|entry| entry.geometry.bounds_hash == Some(hash))
+            })
+    })
+}
+
+fn format_response(
+    args: &ExtractTableArgs,
+    result: &SnapshotResult,
+    mut table: ExtractedTable,
+    scroll: Option<Value>,
+) -> Value {
+    let rows_observed = table.rows.len();
+    let row_limit_reached = rows_observed > args.max_rows;
+    table.rows.truncate(args.max_rows);
+    let scroll_exhausted = scroll
+        .as_ref()
+        .is_none_or(|scroll| scroll["exhausted"] == true);
+    let mut completeness = json!({
+        "complete": !row_limit_reached && !table.subtree_truncated && scroll_exhausted,
+        "row_limit_reached": row_limit_reached,
+        "subtree_truncated": table.subtree_truncated,
+        "rows_observed": rows_observed,
+    });
+    if let Some(scroll) = scroll {
+        completeness["scroll"] = scroll;
+    }
+    json!({
+        "ref": args.ref_id,
+        "snapshot_id": result.snapshot_id,
+        "kind": table.kind,
+        "header_source": table.header_source,
+        "columns": table.columns(),
+        "rows": table.padded_rows(),
+        "row_count": table.rows.len(),
+        "completeness": completeness,
+    })
+}
+
+#[cfg(test)]
+#[path = "extract_table_tests.rs"]
+mod tests;
@@ -0,0 +1,260 @@
+use super::*;
+use crate::adapter::{ActionOps, InputOps, NativeHandle, ObservationOps, SystemOps};
+use crate::ref_alloc::ref_entry_from_node;
+use crate::refs::RefMap;
+use crate::refs_store::RefStore;
+use crate::refs_test_support::HomeGuard;
+use crate::{AccessibilityNode, WindowInfo};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+fn node(role: &str, name: Option<&str>, children: Vec<AccessibilityNode>) -> AccessibilityNode {
+    AccessibilityNode {
+        ref_id: None,
+        role: role.into(),
+        identity: crate::NodeIdentity {
+            name: name.map(str::to_string),
+            ..Default::default()
+        },
+        presentation: Default::default(),
+        children_count: None,
+        subtree_truncated: false,
+        children,
+    }
+}
+
+fn row(text: &str) -> AccessibilityNode {
+    node(
+        "row",
+        None,
+        vec![node(
+            "cell",
+            None,
+            vec![node("statictext", Some(text), vec![])],
+        )],
+    )
+}
+
+/// Serves a two-row viewport over `total` rows; each scroll advances it by one,
+/// leaving the scrolled-to row in the first slot.
+struct PagedTable {
+    total: u32,
+    root_role: &'static str,
+    same_text: bool,
+    scrolls: AtomicU32,
+}
+
+fn slot_bounds(slot: u32) -> crate::Rect {
+    crate::Rect {
+        x: 0.0,
+        y: f64::from(slot) * 20.0,
+        width: 100.0,
+        height: 20.0,
+    }
+}
+
+impl PagedTable {
+    fn new(total: u32) -> Self {
+        Self {
+            total,
+            root_role: "table",
+            same_text: false,
+            scrolls: AtomicU32::new(0),
+        }
+    }
+}
+
+impl ObservationOps for PagedTable {
+    fn observe_tree(
+        &self,
+        root: crate::live_locator::ObservationRoot<'_>,
+        _request: &crate::live_locator::ObservationRequest,
+    ) -> Result<crate::live_locator::ObservedTree, AdapterError> {
+        let first = self
+            .scrolls
+            .load(Ordering::SeqCst)
+            .min(self.total.saturating_sub(2));
+        let mut rows: Vec<_> = (first..(first + 2).min(self.total))
+            .map(|index| {
+                let text = if self.same_text {
+                    "same".to_string()
+                } else {
+                    format!("row {index}")
+                };
+                let mut row = row(&text);
+                row.children[0].presentation.bounds = Some(slot_bounds(index - first));
+                row
+            })
+            .collect();
+        rows.push(node("column", Some("Title"), vec![]));
+        crate::adapter::observed_tree(&root, node(self.root_role, Some("Items"), rows))
+    }
+
+    fn list_windows(
+        &self,
+        _filter: &crate::adapter::WindowFilter,
+        _deadline: crate::Deadline,
+    ) -> Result<Vec<WindowInfo>, AdapterError> {
+        Ok(vec![WindowInfo {
+            id: "w-42".into(),
+            title: "Test".into(),
+            app: "TestApp".into(),
+            pid: crate::ProcessId::new(42),
+            process_instance: Some("test-instance".into()),
+            bounds: None,
+            state: crate::WindowState {
+                is_focused: true,
+                ..Default::default()
+            },
+        }])
+    }
+
+    fn resolve_element_strict(
+        &self,
+        _entry: &crate::refs::RefEntry,
+        _deadline: crate::Deadline,
+    ) -> Result<NativeHandle, AdapterError> {
+        Ok(NativeHandle::new(()))
+    }
+
+    fn get_element_bounds(
+        &self,
+        _handle: &NativeHandle,
+        _deadline: crate::Deadline,
+    ) -> Result<Option<crate::Rect>, AdapterError> {
+        let last_first = self.total.saturating_sub(2);
+        let scrolls = self.scrolls.load(Ordering::SeqCst);
+        let anchor = scrolls.saturating_sub(1).min(last_first) + 1;
+        Ok(Some(slot_bounds(anchor - scrolls.min(last_first))))
+    }
+}
+
+impl ActionOps for PagedTable {
+    fn scroll_into_view(
+        &self,
+        _handle: &NativeHandle,
+        _lease: &crate::InteractionLease,
+    ) -> Result<(), AdapterError> {
+        self.scrolls.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+impl InputOps for PagedTable {}
+
+impl SystemOps for PagedTable {
+    crate::adapter::guarded_interaction_lease!();
+}
+
+fn seed_table_ref() -> String {
+    let source = crate::ref_alloc_source::RefAllocSource {
+        pid: crate::ProcessId::new(42),
+        app: Some("TestApp"),
+        window_id: None,
+        window_title: None,
+        window_bounds_hash: None,
+        process_instance: Some("test-instance"),
+        surface: crate::adapter::SnapshotSurface::Window,
+    };
+    let mut map = RefMap::new();
+    let entry = ref_entry_from_node(&node("table", Some("Items"), vec![]), &source, None, &[0]);
+    let local = map.allocate(entry);
+    let snapshot_id = RefStore::new().unwrap().save_new_snapshot(&map).unwrap();
+    crate::ref_token::qualify_ref_id(&snapshot_id, &local)
+}
+
+fn args(ref_id: String) -> ExtractTableArgs {
+    ExtractTableArgs {
+        ref_id,
+        snapshot_id: None,
+        max_depth: 10,
+        max_rows: 100,
+        scroll: false,
+    }
+}
+
+#[test]
+fn extracts_visible_rows_with_cell_refs() {
+    let _guard = HomeGuard::new();
+    let adapter = PagedTable::new(5);
+
+    let value = execute(args(seed_table_ref()), &adapter, &CommandContext::default()).unwrap();
+
+    assert_eq!(value["kind"], "table");
+    assert_eq!(value["columns"], json!(["Title"]));
+    assert_eq!(value["rows"][1][0]["text"], "row 1");
+    assert!(
+        value["rows"][0][0]["ref"]
+            .as_str()
+            .unwrap()
+            .starts_with('@')
+    );
+    assert_eq!(value["completeness"]["complete"], true);
+    assert_eq!(adapter.scrolls.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn scrolling_collects_rows_beyond_the_viewport_until_exhausted() {
+    let _guard = HomeGuard::new();
+    let adapter = PagedTable::new(5);
+    let mut args = args(seed_table_ref());
+    args.scroll = true;
+
+    let value = execute(args, &adapter, &CommandContext::default()).unwrap();
+
+    let texts: Vec<_> = value["rows"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|row| row[0]["text"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(texts, ["row 0", "row 1", "row 2", "row 3", "row 4"]);
+    assert_eq!(value["completeness"]["scroll"]["exhausted"], true);
+    assert_eq!(value["completeness"]["complete"], true);
+    assert!(value["rows"][0][0].get("ref").is_none());
+    assert!(value["rows"][4][0]["ref"].is_string());
+}
+
+#[test]
+fn scrolling_keeps_rows_whose_text_repeats() {
+    let _guard = HomeGuard::new();
+    let mut adapter = PagedTable::new(5);
+    adapter.same_text = true;
+    let mut args = args(seed_table_ref());
+    args.scroll = true;
+
+    let value = execute(args, &adapter, &CommandContext::default()).unwrap();
+
+    assert_eq!(value["row_count"], 5);
+    assert_eq!(value["completeness"]["scroll"]["exhausted"], true);
+}
+
+#[test]
+fn row_limit_truncates_and_marks_the_table_incomplete() {
+    let _guard = HomeGuard::new();
+    let adapter = PagedTable::new(10);
+    let mut args = args(seed_table_ref());
+    args.scroll = true;
+    args.max_rows = 3;
+
+    let value = execute(args, &adapter, &CommandContext::default()).unwrap();
+
+    assert_eq!(value["row_count"], 3);
+    assert_eq!(value["completeness"]["row_limit_reached"], true);
+    assert_eq!(value["completeness"]["complete"], false);
+}
+
+#[test]
+fn non_table_target_and_zero_row_limit_are_rejected() {
+    let _guard = HomeGuard::new();
+    let mut adapter = PagedTable::new(2);
+    adapter.root_role = "group";
+    let mut zero = args("@s1:e1".into());
+    zero.max_rows = 0;
+
+    let err = execute(zero, &adapter, &CommandContext::default()).unwrap_err();
+    assert_eq!(err.code(), "INVALID_ARGS");
+
+    let err = execute(args(seed_table_ref()), &adapter, &CommandContext::default()).unwrap_err();
+    assert_eq!(err.code(), "INVALID_ARGS");
+    assert!(err.to_string().contains("no table"));
+}
@@ -45,6 +45,7 @@ pub struct FindArgs {
     pub filter: FindFilterArgs,
     pub states: Vec<StatePredicate>,
     pub selection: FindSelectionArgs,
+    pub reveal_secure: bool,
 }
 
 pub fn execute(
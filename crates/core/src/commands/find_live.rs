@@ -9,6 +9,7 @@ use crate::{
     },
     refs::RefMap,
     refs_store::RefStore,
+    secure_mask::SecureMask,
     snapshot, trace_artifacts,
 };
 use serde_json::{Value, json};
@@ -58,6 +59,9 @@ pub(super) fn execute(
         }
     };
     require_complete(&resolution)?;
+    if !args.reveal_secure {
+        mask_resolution(&mut resolution, &SecureMask::load()?);
+    }
     let ref_count = resolution.refmap.as_ref().map(RefMap::len);
     let snapshot_id = match resolution.refmap.take() {
         Some(refmap) => Some(persist_refmap(context, &refmap)?),
@@ -106,6 +110,32 @@ fn resolve_request(args: &FindArgs, deadline: crate::Deadline) -> LocatorResolve
     }
 }
 
+/// Drops the values `mask` covers from the matches and from the refmap the
+/// ref store keeps, as a snapshot without `--reveal-secure` does. A match
+/// that was named after its value is named like an unnamed one instead.
+fn mask_resolution(resolution: &mut LocatorResolution, mask: &SecureMask) {
+    if let Some(refmap) = resolution.refmap.as_mut() {
+        refmap.clear_values(|entry| mask.masks_entry(entry));
+    }
+    for found in &mut resolution.matches {
+        if !mask.masks_entry(&found.entry) {
+            continue;
+        }
+        let hidden = found.data.value.take();
+        found.data.value_masked = true;
+        found.entry.identity.value = None;
+        if hidden.as_deref() == Some(found.data.name.as_str()) {
+            found.data.name = found
+                .entry
+                .identity
+                .description
+                .clone()
+                .filter(|description| !description.trim().is_empty())
+                .unwrap_or_else(|| format!("(unnamed {})", found.data.role));
+        }
+    }
+}
+
 fn require_complete(resolution: &LocatorResolution) -> Result<(), AppError> {
     if resolution.meta.selection_complete {
         return Ok(());
@@ -240,6 +270,7 @@ mod tests {
                 nth: None,
                 limit,
             },
+            reveal_secure: false,
         }
     }
 
@@ -9,18 +9,30 @@ use crate::{
 
 pub(crate) struct LiveFindAdapter {
     structurally_complete: bool,
+    field: Option<(&'static str, &'static [&'static str])>,
 }
 
 impl LiveFindAdapter {
     pub(crate) fn complete() -> Self {
         Self {
             structurally_complete: true,
+            field: None,
         }
     }
 
     pub(crate) fn incomplete() -> Self {
         Self {
             structurally_complete: false,
+            field: None,
+        }
+    }
+
+    /// Adds an unnamed text field holding `value` with `states` to every
+    /// window.
+    pub(crate) fn with_field(value: &'static str, states: &'static [&'static str]) -> Self {
+        Self {
+            field: Some((value, states)),
+            ..Self::complete()
         }
     }
 
@@ -41,6 +53,21 @@ impl LiveFindAdapter {
         }
     }
 
+    fn field_evidence(&self) -> Option<LocatorEvidence> {
+        let (value, states) = self.field?;
+        let mut evidence = Self::evidence("textfield", None);
+        evidence.value = LocatorField::Known(value.into());
+        evidence.states =
+            LocatorField::Known(states.iter().map(|state| state.to_string()).collect());
+        evidence.ref_evidence.bounds = LocatorField::Known(crate::Rect {
+            x: 10.0,
+            y: 10.0,
+            width: 120.0,
+            height: 24.0,
+        });
+        Some(evidence)
+    }
+
     fn node(&self, evidence: LocatorEvidence, children: Vec<ObservedSubtree>) -> ObservedSubtree {
         ObservedSubtree::new(evidence, children, self.structurally_complete, None)
     }
@@ -53,11 +80,12 @@ impl ObservationOps for LiveFindAdapter {
         request: &ObservationRequest,
     ) -> Result<ObservedTree, AdapterError> {
         if let ObservationRoot::Element { entry, .. } = &root {
+            let evidence = match self.field_evidence() {
+                Some(field) if entry.identity.role == "textfield" => field,
+                _ => Self::evidence(&entry.identity.role, entry.identity.name.as_deref()),
+            };
             return ObservedTree::from_roots(
-                vec![self.node(
-                    Self::evidence(&entry.identity.role, entry.identity.name.as_deref()),
-                    Vec::new(),
-                )],
+                vec![self.node(evidence, Vec::new())],
                 ObservationSource::from_root(&root, request.surface),
                 LocatorStats::default(),
                 self.structurally_complete,
@@ -72,8 +100,11 @@ impl ObservationOps for LiveFindAdapter {
         } else {
             "OnlyInWindowOne"
         };
-        let child = self.node(Self::evidence("button", Some(marker)), Vec::new());
-        let root_node = self.node(Self::evidence("window", Some(&window.title)), vec![child]);
+        let mut children = vec![self.node(Self::evidence("button", Some(marker)), Vec::new())];
+        if let Some(evidence) = self.field_evidence() {
+            children.push(self.node(evidence, Vec::new()));
+        }
+        let root_node = self.node(Self::evidence("window", Some(&window.title)), children);
         ObservedTree::from_roots(
             vec![root_node],
             ObservationSource::from_root(&root, request.surface),
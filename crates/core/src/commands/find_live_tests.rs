@@ -35,6 +35,7 @@ fn named_find(window_id: &str, name: &str) -> FindArgs {
             nth: None,
             limit: None,
         },
+        reveal_secure: false,
     }
 }
 
@@ -56,6 +57,7 @@ fn unfiltered_find(window_id: &str, selection: FindSelectionArgs) -> FindArgs {
         },
         states: Vec::new(),
         selection,
+        reveal_secure: false,
     }
 }
 
@@ -275,3 +277,50 @@ fn strict_live_resolution_reports_bounded_ambiguous_candidates() {
         true
     );
 }
+
+fn field_find(reveal_secure: bool) -> FindArgs {
+    let mut args = named_find("w-1", "unused");
+    args.filter.name = None;
+    args.filter.role = Some("textfield".into());
+    args.reveal_secure = reveal_secure;
+    args
+}
+
+#[test]
+fn secure_field_values_are_masked_in_matches_and_the_persisted_refmap() {
+    let _guard = HomeGuard::new();
+    let adapter = LiveFindAdapter::with_field("hunter2", &[crate::state::SECURE]);
+
+    let response = execute(field_find(false), &adapter, &CommandContext::default()).unwrap();
+
+    assert!(!response.to_string().contains("hunter2"), "{response}");
+    let found = &response["matches"][0];
+    assert_eq!(found["value"], Value::Null);
+    assert_eq!(found["value_masked"], true);
+    assert_eq!(found["name"], "(unnamed textfield)");
+    let snapshot_id = response["snapshot_id"].as_str().unwrap();
+    let refmap = RefStore::new().unwrap().load_snapshot(snapshot_id).unwrap();
+    assert!(
+        refmap
+            .iter()
+            .all(|(_, entry)| entry.identity.value.is_none())
+    );
+
+    let revealed = execute(field_find(true), &adapter, &CommandContext::default()).unwrap();
+    assert_eq!(revealed["matches"][0]["value"], "hunter2");
+}
+
+#[test]
+fn configured_secure_locators_mask_find_matches() {
+    let _guard = HomeGuard::new();
+    let path = crate::state_root::resolve_configured_state_root()
+        .unwrap()
+        .join(crate::secure_mask::SECURE_FIELDS_FILE);
+    crate::private_file::write_atomic(&path, br#"[{"role": "textfield"}]"#).unwrap();
+    let adapter = LiveFindAdapter::with_field("sk-live-123", &[]);
+
+    let response = execute(field_find(false), &adapter, &CommandContext::default()).unwrap();
+
+    assert!(!response.to_string().contains("sk-live-123"), "{response}");
+    assert_eq!(response["matches"][0]["value_masked"], true);
+}
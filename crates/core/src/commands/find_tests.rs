@@ -12,6 +12,7 @@ fn node(name: Option<&str>, value: Option<&str>, description: Option<&str>) -> A
             value: value.map(String::from),
             description: description.map(String::from),
             native_id: None,
+            value_masked: false,
         },
         presentation: Default::default(),
         children_count: None,
@@ -140,6 +141,7 @@ fn limit_conflicts_with_single_result_modes_for_batch_too() {
             limit: Some(10),
             ..no_selection()
         },
+        reveal_secure: false,
     })
     .unwrap_err();
 
@@ -188,6 +190,7 @@ fn role_alias_is_preserved_until_live_validation() {
         },
         states: vec![],
         selection: no_selection(),
+        reveal_secure: false,
     });
 
     assert_eq!(query.identity.role.as_deref(), Some("textarea"));
@@ -212,6 +215,7 @@ fn unknown_role_is_preserved_until_validation() {
         },
         states: vec![],
         selection: no_selection(),
+        reveal_secure: false,
     });
 
     assert_eq!(query.identity.role.as_deref(), Some("navbar"));
@@ -242,6 +246,7 @@ fn empty_role_filtered_result_reports_roles_present_from_tree() {
         },
         states: vec![],
         selection: no_selection(),
+        reveal_secure: false,
     });
     let response = single_match_response(None, &query, &root);
 
@@ -268,6 +273,7 @@ fn roles_present_hint_is_omitted_when_a_match_is_found() {
         },
         states: vec![],
         selection: no_selection(),
+        reveal_secure: false,
     });
 
     let mut matches = Vec::new();
@@ -292,6 +298,7 @@ fn find_args_scoped_to_window(window_id: &str) -> FindArgs {
         },
         states: vec![],
         selection: no_selection(),
+        reveal_secure: false,
     }
 }
 
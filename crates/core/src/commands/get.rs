@@ -10,6 +10,7 @@ pub struct GetArgs {
     pub ref_id: String,
     pub snapshot_id: Option<String>,
     pub property: GetProperty,
+    pub reveal_secure: bool,
 }
 
 pub enum GetProperty {
@@ -29,6 +30,23 @@ pub fn execute(
     let (entry, handle) =
         resolve_ref_with_context(&args.ref_id, args.snapshot_id.as_deref(), adapter, context)?;
     let deadline = crate::Deadline::standard()?;
+    let reads_value = matches!(args.property, GetProperty::Text | GetProperty::Value);
+    if reads_value
+        && !args.reveal_secure
+        && crate::secure_mask::SecureMask::load()?.masks_entry(&entry)
+    {
+        let prop_name = if matches!(args.property, GetProperty::Text) {
+            "text"
+        } else {
+            "value"
+        };
+        return Ok(json!({
+            "property": prop_name,
+            "ref": args.ref_id,
+            "value": null,
+            "masked": true,
+        }));
+    }
 
     let (prop_name, value) = match args.property {
         GetProperty::Role => ("role", json!(entry.identity.role)),
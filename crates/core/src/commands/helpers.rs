@@ -107,35 +107,64 @@ pub(crate) fn execute_ref_action_with_context(
     let request = request.with_timeout_ms(args.timeout_ms);
     validate_post_action_wait(context)?;
     let entry = load_ref_entry(&args.ref_id, args.snapshot_id.as_deref(), context)?;
-    let (result, lease, pre, deadline, lease_started) =
-        crate::ref_action_wait::execute_with_auto_wait_and_lease(
-            RefActionWaitContext {
+    match deliver_ref_action(adapter, &entry, &args.ref_id, request.clone(), context) {
+        Err(AppError::Adapter(error)) if crate::ref_heal::applies(&error, context) => {
+            crate::ref_heal::retry(
                 adapter,
-                entry: &entry,
-                ref_id: &args.ref_id,
+                &entry,
+                &args.ref_id,
+                error,
                 context,
-            },
+                |entry, ref_id| deliver_ref_action(adapter, entry, ref_id, request, context),
+            )
+        }
+        outcome => outcome,
+    }
+}
+
+/// Runs the ref action pipeline: auto-wait, lease, dispatch, post-action
+/// wait, and trace artifacts. Web refs swap in DevTools dispatch, which
+/// needs no focus, so they always run headless.
+fn deliver_ref_action(
+    adapter: &dyn PlatformAdapter,
+    entry: &RefEntry,
+    ref_id: &str,
+    mut request: ActionRequest,
+    context: &CommandContext,
+) -> Result<Value, AppError> {
+    let web = crate::cdp_action::targets(entry);
+    if web {
+        request.policy = crate::InteractionPolicy::headless();
+    }
+    let wait = RefActionWaitContext {
+        adapter,
+        entry,
+        ref_id,
+        context,
+    };
+    let (result, lease, pre, deadline, lease_started) = if web {
+        crate::ref_action_wait::execute_with_auto_wait_and_lease(
+            wait,
+            request,
+            crate::cdp_action::dispatch_resolved,
+        )
+    } else {
+        crate::ref_action_wait::execute_with_auto_wait_and_lease(
+            wait,
             request,
             crate::ref_action::dispatch_resolved,
         )
-        .map_err(AppError::Adapter)?;
+    }
+    .map_err(AppError::Adapter)?;
     let value = serde_json::to_value(result).map_err(|error| {
         post_delivery_error(AppError::Json(error), json!({ "action": "delivered" }))
     })?;
     let lease_hold_ms = u64::try_from(lease_started.elapsed().as_millis()).unwrap_or(u64::MAX);
     drop(lease);
-    let mut outcome = apply_post_action_wait(value, Some(&entry), adapter, context);
+    let mut outcome = apply_post_action_wait(value, Some(entry), adapter, context);
     update_lease_hold_ms(&mut outcome, lease_hold_ms);
     crate::ref_action::finish_artifacts(
-        crate::ref_action_context::RefActionContext::new(
-            RefActionWaitContext {
-                adapter,
-                entry: &entry,
-                ref_id: &args.ref_id,
-                context,
-            },
-            deadline,
-        ),
+        crate::ref_action_context::RefActionContext::new(wait, deadline),
         &pre,
     );
     outcome
@@ -283,6 +312,9 @@ pub(crate) fn load_ref_entry(
     snapshot_id: Option<&str>,
     context: &CommandContext,
 ) -> Result<RefEntry, AppError> {
+    if crate::ref_token::is_alias_token(ref_id) {
+        return crate::ref_alias::resolve(ref_id, snapshot_id, context);
+    }
     let (resolved_snapshot_id, local_ref) =
         crate::ref_token::resolve_ref_target(ref_id, snapshot_id)?;
     let store = RefStore::for_session(context.session_id())?;
@@ -354,3 +386,7 @@ mod ref_action_dispatch_tests;
 #[cfg(test)]
 #[path = "helpers_ref_action_wait_tests.rs"]
 mod ref_action_wait_tests;
+
+#[cfg(test)]
+#[path = "helpers_ref_heal_tests.rs"]
+mod ref_heal_tests;
@@ -0,0 +1,173 @@
+use super::test_support::entry;
+use super::*;
+use crate::adapter::{ActionOps, InputOps, NativeHandle, ObservationOps, SystemOps, WindowFilter};
+use crate::live_locator::{
+    IdentifierEvidence, LocatorEvidence, LocatorField, LocatorRefEvidence, LocatorStats,
+    ObservationRequest, ObservationRoot, ObservationSource, ObservedSubtree, ObservedTree,
+};
+use crate::refs::RefMap;
+use crate::refs_test_support::HomeGuard;
+use crate::{AdapterError, WindowInfo, action::Action, action_result::ActionResult};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// The snapshot's `button "OK"` was re-rendered: the stored entry no longer
+/// resolves, while the live tree holds `ok_buttons` fresh copies of it.
+struct RerenderedAdapter {
+    ok_buttons: usize,
+    executed: AtomicU32,
+}
+
+fn evidence(role: &str, name: &str) -> LocatorEvidence {
+    LocatorEvidence {
+        role: LocatorField::Known(role.into()),
+        name: LocatorField::Known(name.into()),
+        description: LocatorField::Absent,
+        value: LocatorField::Absent,
+        identifiers: IdentifierEvidence::absent(),
+        states: LocatorField::Known(Vec::new()),
+        ref_evidence: LocatorRefEvidence {
+            bounds: LocatorField::Absent,
+            available_actions: LocatorField::Known(vec![crate::capability::CLICK.into()]),
+        },
+    }
+}
+
+impl ObservationOps for RerenderedAdapter {
+    fn resolve_element_strict(
+        &self,
+        entry: &RefEntry,
+        _deadline: crate::Deadline,
+    ) -> Result<NativeHandle, AdapterError> {
+        match entry.source.source_window_id {
+            Some(_) => Ok(NativeHandle::null()),
+            None => Err(AdapterError::stale_ref("@e1")),
+        }
+    }
+
+    fn observe_tree(
+        &self,
+        root: ObservationRoot<'_>,
+        request: &ObservationRequest,
+    ) -> Result<ObservedTree, AdapterError> {
+        let source = ObservationSource::from_root(&root, request.surface);
+        let button = || ObservedSubtree::new(evidence("button", "OK"), Vec::new(), true, None);
+        let roots = match &root {
+            ObservationRoot::Element { .. } => vec![button()],
+            _ => vec![ObservedSubtree::new(
+                evidence("window", "Dialog"),
+                (0..self.ok_buttons).map(|_| button()).collect(),
+                true,
+                None,
+            )],
+        };
+        ObservedTree::from_roots(roots, source, LocatorStats::default(), true)
+    }
+
+    fn list_windows(
+        &self,
+        _filter: &WindowFilter,
+        _deadline: crate::Deadline,
+    ) -> Result<Vec<WindowInfo>, AdapterError> {
+        Ok(vec![WindowInfo {
+            id: "w-1".into(),
+            title: "Dialog".into(),
+            app: "FixtureApp".into(),
+            pid: crate::ProcessId::new(1),
+            process_instance: Some("test-instance".into()),
+            bounds: None,
+            state: crate::WindowState {
+                is_focused: true,
+                ..Default::default()
+            },
+        }])
+    }
+
+    fn resolve_locator_anchor(
+        &self,
+        _entry: &RefEntry,
+        _deadline: crate::Deadline,
+    ) -> Result<NativeHandle, AdapterError> {
+        Ok(NativeHandle::null())
+    }
+
+    crate::adapter::complete_live_observation!("button", "OK", [crate::capability::CLICK]);
+}
+
+impl ActionOps for RerenderedAdapter {
+    fn execute_action(
+        &self,
+        _handle: &NativeHandle,
+        _request: ActionRequest,
+        _lease: &crate::InteractionLease,
+    ) -> Result<ActionResult, AdapterError> {
+        self.executed.fetch_add(1, Ordering::SeqCst);
+        Ok(ActionResult::delivered_unverified("ok"))
+    }
+}
+
+impl InputOps for RerenderedAdapter {}
+
+impl SystemOps for RerenderedAdapter {
+    crate::adapter::guarded_interaction_lease!();
+}
+
+fn click(adapter: &RerenderedAdapter, heal: bool) -> Result<Value, AppError> {
+    let mut refmap = RefMap::new();
+    refmap.allocate(entry());
+    let snapshot_id = RefStore::new().unwrap().save_new_snapshot(&refmap).unwrap();
+    execute_ref_action_with_context(
+        RefArgs {
+            ref_id: format!("@{snapshot_id}:e1"),
+            snapshot_id: None,
+            timeout_ms: Some(1_000),
+        },
+        adapter,
+        ActionRequest::headless(Action::Click),
+        &CommandContext::default().with_heal(heal),
+    )
+}
+
+#[test]
+fn heal_reidentifies_a_stale_ref_and_reports_both_refs() {
+    let _guard = HomeGuard::new();
+    let adapter = RerenderedAdapter {
+        ok_buttons: 1,
+        executed: AtomicU32::new(0),
+    };
+
+    let err = click(&adapter, false).unwrap_err();
+    assert_eq!(err.code(), "STALE_REF");
+    assert_eq!(adapter.executed.load(Ordering::SeqCst), 0);
+
+    let value = click(&adapter, true).unwrap();
+    assert_eq!(adapter.executed.load(Ordering::SeqCst), 1);
+    let healed_from = value["healed_from"].as_str().unwrap();
+    let healed_to = value["healed_to"].as_str().unwrap();
+    assert!(healed_from.ends_with(":e1") && healed_to.ends_with(":e1"));
+    assert_ne!(healed_from, healed_to);
+    assert_eq!(value["heal"]["confidence"], "medium");
+    assert_eq!(
+        value["heal"]["matched_on"],
+        serde_json::json!(["role", "name"])
+    );
+}
+
+#[test]
+fn heal_refuses_an_ambiguous_match_and_keeps_the_stale_ref_error() {
+    let _guard = HomeGuard::new();
+    let adapter = RerenderedAdapter {
+        ok_buttons: 2,
+        executed: AtomicU32::new(0),
+    };
+
+    let err = click(&adapter, true).unwrap_err();
+
+    assert_eq!(err.code(), "STALE_REF");
+    assert_eq!(adapter.executed.load(Ordering::SeqCst), 0);
+    let AppError::Adapter(error) = err else {
+        panic!("expected adapter error");
+    };
+    let heal = &error.details.unwrap()["heal"];
+    assert_eq!(heal["outcome"], "ambiguous");
+    assert_eq!(heal["candidates"], 2);
+}
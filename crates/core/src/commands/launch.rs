@@ -44,7 +44,15 @@ pub fn execute(args: LaunchArgs, adapter: &dyn PlatformAdapter) -> Result<Value,
     let mut launched = adapter.launch_app(&app, &options, &lease)?;
     if let Some(port) = options.cdp_port {
         let granted = deadline.remaining();
-        launched.cdp = Some(verify_cdp_endpoint(port, deadline, granted, &launched)?);
+        let endpoint = verify_cdp_endpoint(port, deadline, granted, &launched)?;
+        if let Err(error) = crate::cdp_registry::record(
+            launched.pid,
+            launched.process_instance.as_deref(),
+            &endpoint,
+        ) {
+            tracing::warn!(%error, "could not record the DevTools endpoint for snapshot");
+        }
+        launched.cdp = Some(endpoint);
     }
     launched.suggestion = launch_suggestion(requested_cdp_port.is_some(), &launched);
     Ok(serde_json::to_value(launched)?)
@@ -66,9 +74,11 @@ fn probe_reserve(total: Duration) -> Duration {
 fn launch_suggestion(cdp_requested: bool, launched: &LaunchResult) -> Option<String> {
     if let Some(cdp) = &launched.cdp {
         return Some(format!(
-            "Next: run `agent-browser connect {}` (preferred; `agent-browser skills get \
-             electron` has the guide) or connect any CDP client such as Playwright or \
-             Puppeteer. If neither is available, ask the user to install agent-browser or \
+            "Next: snapshot now merges this app's web content into the native tree, and \
+             its web refs act through the endpoint. For deeper browser work, run \
+             `agent-browser connect {}` (preferred; `agent-browser skills get electron` has \
+             the guide) or connect any CDP client such as Playwright or Puppeteer. If \
+             neither is available, ask the user to install agent-browser or \
              continue with accessibility commands. Do not hand-roll raw CDP or call \
              app-internal APIs — that path is unverified and app-specific. Native menus, \
              dialogs, windows, and screenshots stay with agent-desktop.",
@@ -153,6 +153,7 @@ fn cdp_launch_appends_the_switch_and_resolves_the_port_before_verification_fails
 
 #[test]
 fn cdp_launch_returns_a_verified_endpoint_when_the_process_answers() {
+    let _home = crate::refs_test_support::HomeGuard::new();
     let adapter = empty_cdp_adapter(true);
     let options = LaunchOptions {
         cdp_port: Some(0),
@@ -182,6 +183,9 @@ fn cdp_launch_returns_a_verified_endpoint_when_the_process_answers() {
             .unwrap()
             .contains("devtools/browser")
     );
+    let pid = crate::ProcessId::new(u32::try_from(value["pid"].as_u64().unwrap()).unwrap());
+    let recorded = crate::cdp_registry::lookup(pid, value["process_instance"].as_str()).unwrap();
+    assert_eq!(Some(u64::from(recorded.port)), cdp["port"].as_u64());
 }
 
 /// A Chromium app launched without `--cdp` gets nudged toward the flag that
@@ -216,6 +220,7 @@ fn chromium_renderer_without_cdp_gets_the_relaunch_suggestion() {
 
 #[test]
 fn cdp_success_gets_the_handoff_suggestion() {
+    let _home = crate::refs_test_support::HomeGuard::new();
     let adapter = empty_cdp_adapter(true);
     let options = LaunchOptions {
         cdp_port: Some(0),
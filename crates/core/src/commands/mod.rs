@@ -1,3 +1,5 @@
+pub mod alias;
+pub mod audit_a11y;
 pub mod batch;
 pub mod check;
 pub mod clear;
@@ -14,6 +16,11 @@ pub mod double_click;
 pub mod drag;
 pub mod execute_by_ref;
 pub mod expand;
+pub mod expect;
+pub(crate) mod expect_matcher;
+pub(crate) mod expect_screenshot;
+pub(crate) mod expect_target;
+pub mod extract_table;
 pub mod find;
 pub mod focus;
 pub mod focus_window;
@@ -48,10 +55,13 @@ pub(crate) mod point_resolve;
 pub(crate) mod pointer_action;
 pub mod press;
 pub mod query;
+pub mod read_text;
 pub mod resize_window;
 pub mod restore;
 pub mod right_click;
+pub mod run;
 pub mod screenshot;
+pub mod screenshot_compare;
 #[cfg(test)]
 mod screenshot_tests;
 pub mod scroll;
@@ -69,6 +79,7 @@ pub mod triple_click;
 pub mod type_text;
 pub mod uncheck;
 pub mod version;
+pub(crate) mod visual_compare;
 pub mod wait;
 pub(crate) mod wait_element;
 pub(crate) mod wait_event;
@@ -0,0 +1,120 @@
+use crate::{
+    AppError, WindowInfo,
+    adapter::{PlatformAdapter, TreeOptions},
+    context::CommandContext,
+    live_locator::{ObservationRequest, ObservationRoot, ObservedTree},
+    read_text,
+    refs_store::RefStore,
+};
+use serde_json::{Value, json};
+
+pub struct ReadTextArgs {
+    pub app: Option<String>,
+    pub window_id: Option<String>,
+    pub root_ref: Option<String>,
+    pub snapshot_id: Option<String>,
+    pub max_depth: u8,
+    pub max_chars: usize,
+    pub cursor: Option<String>,
+}
+
+/// Reads a window, or the subtree under `root_ref`, as markdown. The read is
+/// observation-only: no refs are allocated and no snapshot is written.
+pub fn execute(
+    args: ReadTextArgs,
+    adapter: &dyn PlatformAdapter,
+    context: &CommandContext,
+) -> Result<Value, AppError> {
+    if args.max_chars == 0 {
+        return Err(AppError::invalid_input_with_suggestion(
+            "--max-chars must be greater than zero",
+            "Pass a positive character budget, or omit --max-chars for the default of 20000.",
+        ));
+    }
+    if let Some(root) = args.root_ref.as_deref() {
+        crate::refs::validate_ref_id(root)?;
+    }
+    let opts = TreeOptions {
+        max_depth: args.max_depth,
+        ..TreeOptions::default()
+    };
+    let deadline = crate::Deadline::after(3_000)?;
+    let request = ObservationRequest::snapshot(&opts, deadline).validate()?;
+    let (observed, window) = match args.root_ref.as_deref() {
+        Some(root) => observe_root(adapter, &request, root, &args, context)?,
+        None => {
+            let window = crate::snapshot::resolve_window_for_surface(
+                adapter,
+                args.app.as_deref(),
+                args.window_id.as_deref(),
+                crate::SnapshotSurface::Window,
+                deadline,
+            )?;
+            let observed = crate::renderer_accessibility::observe_tree(
+                adapter,
+                ObservationRoot::Window(&window),
+                &request,
+            )?;
+            (observed, window)
+        }
+    };
+    observed.validate_document_order()?;
+    let (mut tree, complete, _) = observed.into_accessibility_tree_partial()?;
+    crate::secure_mask::mask_observed_tree(&mut tree, &opts)?;
+    let blocks = read_text::render_blocks(&tree);
+    let page = read_text::page(&blocks, args.cursor.as_deref(), args.max_chars)?;
+
+    let mut body = json!({
+        "app": window.app,
+        "window": { "id": window.id, "title": window.title },
+        "markdown": page.markdown,
+        "blocks": { "start": page.start, "end": page.end, "total": blocks.len() },
+        "truncated": page.next_cursor.is_some(),
+        "next_cursor": page.next_cursor,
+        "complete": complete,
+    });
+    if let Some(root) = args.root_ref {
+        body["root"] = json!(root);
+    }
+    Ok(body)
+}
+
+fn observe_root(
+    adapter: &dyn PlatformAdapter,
+    request: &ObservationRequest,
+    root: &str,
+    args: &ReadTextArgs,
+    context: &CommandContext,
+) -> Result<(ObservedTree, WindowInfo), AppError> {
+    let (snapshot_id, local_ref) =
+        crate::ref_token::resolve_ref_target(root, args.snapshot_id.as_deref())?;
+    let refmap = RefStore::for_session(context.session_id())?.load_snapshot(&snapshot_id)?;
+    let entry = refmap
+        .get(&local_ref)
+        .ok_or_else(|| AppError::stale_ref(root))?;
+    let handle = adapter.resolve_element_strict(entry, request.deadline)?;
+    let observed = crate::renderer_accessibility::observe_tree(
+        adapter,
+        ObservationRoot::Element {
+            handle: &handle,
+            entry,
+            root_ref: Some(&local_ref),
+        },
+        request,
+    )?;
+    let instance = entry.process.process_instance.as_deref().ok_or_else(|| {
+        AppError::Adapter(crate::AdapterError::stale_ref(
+            "root ref has no process-instance identity",
+        ))
+    })?;
+    let window = crate::window_lookup::find_window_for_process(
+        crate::ProcessIdentity::new(entry.process.pid, instance),
+        adapter,
+        request.deadline,
+    )?;
+    Ok((observed, window))
+}
+
+#[cfg(test)]
+#[path = "read_text_tests.rs"]
+mod tests;
@@ -0,0 +1,98 @@
+use super::*;
+use crate::adapter::{ActionOps, InputOps, ObservationOps, SystemOps, WindowFilter};
+use crate::refs_test_support::HomeGuard;
+use crate::{AccessibilityNode, AdapterError};
+
+struct ArticleAdapter;
+
+fn node(role: &str, name: &str, children: Vec<AccessibilityNode>) -> AccessibilityNode {
+    AccessibilityNode {
+        ref_id: None,
+        role: role.into(),
+        identity: crate::NodeIdentity {
+            name: Some(name.into()),
+            ..Default::default()
+        },
+        presentation: Default::default(),
+        children_count: None,
+        subtree_truncated: false,
+        children,
+    }
+}
+
+impl ObservationOps for ArticleAdapter {
+    fn observe_tree(
+        &self,
+        root: crate::live_locator::ObservationRoot<'_>,
+        _request: &crate::live_locator::ObservationRequest,
+    ) -> Result<crate::live_locator::ObservedTree, AdapterError> {
+        let paragraphs = (1..=3)
+            .map(|index| node("statictext", &format!("Paragraph {index}"), vec![]))
+            .collect();
+        crate::adapter::observed_tree(&root, node("window", "Article", paragraphs))
+    }
+
+    fn list_windows(
+        &self,
+        _filter: &WindowFilter,
+        _deadline: crate::Deadline,
+    ) -> Result<Vec<WindowInfo>, AdapterError> {
+        Ok(vec![WindowInfo {
+            id: "w-1".into(),
+            title: "Article".into(),
+            app: "Reader".into(),
+            pid: crate::ProcessId::new(1),
+            process_instance: Some("test-instance".into()),
+            bounds: None,
+            state: crate::WindowState {
+                is_focused: true,
+                ..Default::default()
+            },
+        }])
+    }
+}
+
+impl ActionOps for ArticleAdapter {}
+impl InputOps for ArticleAdapter {}
+impl SystemOps for ArticleAdapter {}
+
+fn args(max_chars: usize, cursor: Option<String>) -> ReadTextArgs {
+    ReadTextArgs {
+        app: Some("Reader".into()),
+        window_id: None,
+        root_ref: None,
+        snapshot_id: None,
+        max_depth: 20,
+        max_chars,
+        cursor,
+    }
+}
+
+#[test]
+fn window_reads_as_markdown_across_cursor_pages() {
+    let _guard = HomeGuard::new();
+    let context = CommandContext::default();
+
+    let first = execute(args(24, None), &ArticleAdapter, &context).unwrap();
+    assert_eq!(first["markdown"], "# Article\n\nParagraph 1");
+    assert_eq!(first["truncated"], true);
+    assert_eq!(first["blocks"]["total"], 4);
+
+    let cursor = first["next_cursor"].as_str().unwrap().to_string();
+    let rest = execute(args(10_000, Some(cursor)), &ArticleAdapter, &context).unwrap();
+    assert_eq!(rest["markdown"], "Paragraph 2\n\nParagraph 3");
+    assert_eq!(rest["truncated"], false);
+    assert!(rest["next_cursor"].is_null());
+}
+
+#[test]
+fn zero_budget_and_malformed_root_are_rejected() {
+    let context = CommandContext::default();
+    let err = execute(args(0, None), &ArticleAdapter, &context).unwrap_err();
+    assert_eq!(err.code(), "INVALID_ARGS");
+
+    let mut bad_root = args(100, None);
+    bad_root.root_ref = Some("e1".into());
+    let err = execute(bad_root, &ArticleAdapter, &context).unwrap_err();
+    assert_eq!(err.code(), "INVALID_ARGS");
+}
@@ -207,6 +207,7 @@ fn default_ref_commands_use_least_permissive_supported_policy() {
             ref_id: "@e1".into(),
             snapshot_id: Some(snapshot_id.clone()),
             value: "value".into(),
+            secret: None,
             timeout_ms: None,
         },
         &adapter,
@@ -230,6 +231,7 @@ fn default_ref_commands_use_least_permissive_supported_policy() {
             ref_id: "@e1".into(),
             snapshot_id: Some(snapshot_id.clone()),
             text: "text".into(),
+            secret: None,
             timeout_ms: None,
         },
         &adapter,
@@ -293,6 +295,7 @@ fn headed_context_reaches_every_ref_action_without_policy_downgrade() {
             ref_id: "@e1".into(),
             snapshot_id: Some(snapshot_id.clone()),
             value: "value".into(),
+            secret: None,
             timeout_ms: None,
         },
         &adapter,
@@ -315,6 +318,7 @@ fn headed_context_reaches_every_ref_action_without_policy_downgrade() {
             ref_id: "@e1".into(),
             snapshot_id: Some(snapshot_id.clone()),
             text: "text".into(),
+            secret: None,
             timeout_ms: None,
         },
         &adapter,
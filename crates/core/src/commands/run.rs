@@ -0,0 +1,236 @@
+use crate::{AppError, a11y_audit::escape_xml, surface_handler::SurfaceHandler};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::Path;
+
+pub const MAX_SCENARIO_BYTES: u64 = 1024 * 1024;
+pub const MAX_SCENARIO_STEPS: usize = 256;
+pub const MAX_STEP_EXPECTATIONS: usize = 16;
+const MAX_PARAM_NAME_CHARS: usize = 64;
+
+/// A scenario file: named steps run in order through the same decode,
+/// policy, and dispatch path as batch entries.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    pub name: Option<String>,
+    /// Declared `${name}` parameters. A string is the default value; `null`
+    /// makes the parameter required on the command line.
+    #[serde(default)]
+    pub params: BTreeMap<String, Option<String>>,
+    /// Alert and sheet handlers checked before every step.
+    #[serde(default)]
+    pub handlers: Vec<SurfaceHandler>,
+    pub steps: Vec<ScenarioStep>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScenarioStep {
+    pub name: String,
+    pub command: String,
+    #[serde(default)]
+    pub args: Value,
+    /// Budget for the command and its expectations, capped by what is left
+    /// of the whole run's timeout.
+    pub timeout_ms: Option<u64>,
+    #[serde(default)]
+    pub expect: Vec<Expectation>,
+    /// Values read from the step's `data` by JSON pointer, as a batch
+    /// entry's `capture`; later steps reference them as `${name}` in args.
+    #[serde(default)]
+    pub capture: BTreeMap<String, Value>,
+}
+
+/// One assertion checked after its step succeeds. Each runs the named
+/// read-only command with the remaining fields as that command's args.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub enum Expectation {
+    /// Holds when `is` reports `result` equal to `equals` (default `true`).
+    Is(ValueAssertion),
+    /// Holds when `get` reports `value` equal to `equals`.
+    Get(ValueAssertion),
+    /// Holds when the `find` match count satisfies `equals`, `min`, and `max`.
+    Count(CountAssertion),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ValueAssertion {
+    pub equals: Option<Value>,
+    #[serde(flatten)]
+    pub args: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CountAssertion {
+    pub equals: Option<u64>,
+    pub min: Option<u64>,
+    pub max: Option<u64>,
+    #[serde(flatten)]
+    pub args: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepStatus {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+impl StepStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Passed => "passed",
+            Self::Failed => "failed",
+            Self::Skipped => "skipped",
+        }
+    }
+}
+
+/// One step as it appears in the JUnit report.
+pub struct StepCase<'a> {
+    pub name: &'a str,
+    pub command: &'a str,
+    pub status: StepStatus,
+    pub elapsed_ms: u64,
+    pub failure: Option<String>,
+}
+
+pub fn read_scenario(path: &Path) -> Result<String, AppError> {
+    let bytes =
+        crate::private_file::read_regular_bounded(path, MAX_SCENARIO_BYTES).map_err(|error| {
+            AppError::invalid_input_with_suggestion(
+                format!("Cannot read scenario file '{}': {error}", path.display()),
+                format!(
+                    "Pass a local regular YAML or JSON file no larger than {} MiB",
+                    MAX_SCENARIO_BYTES / (1024 * 1024)
+                ),
+            )
+        })?;
+    String::from_utf8(bytes)
+        .map_err(|_| AppError::invalid_input("Scenario file is not valid UTF-8"))
+}
+
+/// Binds `--param KEY=VALUE` flags over the scenario's declared defaults.
+/// Every supplied key must be declared and every declared key must end up
+/// with a value, so a typo fails before any step runs.
+pub fn bind_params(
+    declared: &BTreeMap<String, Option<String>>,
+    supplied: &[String],
+) -> Result<BTreeMap<String, String>, AppError> {
+    for name in declared.keys() {
+        validate_param_name(name)?;
+    }
+    let mut overrides = BTreeMap::new();
+    for raw in supplied {
+        let Some((name, value)) = raw.split_once('=') else {
+            return Err(AppError::invalid_input_with_suggestion(
+                format!("Invalid --param '{raw}'"),
+                "Write parameters as --param KEY=VALUE",
+            ));
+        };
+        if !declared.contains_key(name) {
+            return Err(AppError::invalid_input_with_suggestion(
+                format!("Parameter '{name}' is not declared by the scenario"),
+                "Declare it under the scenario's params map or fix the --param key",
+            ));
+        }
+        if overrides
+            .insert(name.to_string(), value.to_string())
+            .is_some()
+        {
+            return Err(AppError::invalid_input(format!(
+                "Parameter '{name}' was supplied more than once"
+            )));
+        }
+    }
+    let mut bound = BTreeMap::new();
+    for (name, default) in declared {
+        let value = overrides
+            .remove(name)
+            .or_else(|| default.clone())
+            .ok_or_else(|| {
+                AppError::invalid_input_with_suggestion(
+                    format!("Required parameter '{name}' has no value"),
+                    format!("Pass --param {name}=VALUE"),
+                )
+            })?;
+        bound.insert(name.clone(), value);
+    }
+    Ok(bound)
+}
+
+fn validate_param_name(name: &str) -> Result<(), AppError> {
+    let mut chars = name.chars();
+    let valid_start = chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_');
+    let valid_rest = chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '_');
+    if valid_start && valid_rest && name.len() <= MAX_PARAM_NAME_CHARS {
+        return Ok(());
+    }
+    Err(AppError::invalid_input_with_suggestion(
+        format!("Invalid parameter name '{name}'"),
+        "Parameter names start with a letter or '_' and contain only ASCII letters, digits, and '_'",
+    ))
+}
+
+/// One `<testcase>` per step, in file order. Failed steps carry the error
+/// or the failed expectations; steps after a failure are `<skipped/>`.
+pub fn render_junit(suite: &str, cases: &[StepCase<'_>]) -> String {
+    let mut body = String::new();
+    let mut failures = 0;
+    let mut skipped = 0;
+    let mut total_ms = 0u64;
+    for case in cases {
+        total_ms = total_ms.saturating_add(case.elapsed_ms);
+        let _ = write!(
+            body,
+            "    <testcase classname=\"{}\" name=\"{}\" time=\"{}\">",
+            escape_xml(suite),
+            escape_xml(case.name),
+            seconds(case.elapsed_ms)
+        );
+        match case.status {
+            StepStatus::Passed => {}
+            StepStatus::Skipped => {
+                skipped += 1;
+                body.push_str("\n      <skipped/>\n    ");
+            }
+            StepStatus::Failed => {
+                failures += 1;
+                let detail = case.failure.as_deref().unwrap_or("step failed");
+                let message = detail.lines().next().unwrap_or(detail);
+                let _ = write!(
+                    body,
+                    "\n      <failure message=\"{}\" type=\"{}\">{}</failure>\n    ",
+                    escape_xml(message),
+                    escape_xml(case.command),
+                    escape_xml(detail)
+                );
+            }
+        }
+        body.push_str("</testcase>\n");
+    }
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites>\n  <testsuite name=\"{}\" tests=\"{}\" failures=\"{failures}\" skipped=\"{skipped}\" time=\"{}\">\n{body}  </testsuite>\n</testsuites>\n",
+        escape_xml(suite),
+        cases.len(),
+        seconds(total_ms)
+    )
+}
+
+pub fn write_junit(path: &Path, suite: &str, cases: &[StepCase<'_>]) -> Result<(), AppError> {
+    crate::refs::write_user_file(path, render_junit(suite, cases).as_bytes())
+}
+
+fn seconds(elapsed_ms: u64) -> String {
+    format!("{}.{:03}", elapsed_ms / 1000, elapsed_ms % 1000)
+}
+
+#[cfg(test)]
+#[path = "run_tests.rs"]
+mod tests;
@@ -0,0 +1,114 @@
+use super::*;
+
+fn declared(pairs: &[(&str, Option<&str>)]) -> BTreeMap<String, Option<String>> {
+    pairs
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.map(str::to_string)))
+        .collect()
+}
+
+#[test]
+fn params_override_defaults_and_fill_required_values() {
+    let bound = bind_params(
+        &declared(&[("user", None), ("app", Some("TextEdit"))]),
+        &["user=alice=admin".into()],
+    )
+    .unwrap();
+
+    assert_eq!(bound["user"], "alice=admin");
+    assert_eq!(bound["app"], "TextEdit");
+}
+
+#[test]
+fn params_reject_undeclared_duplicate_missing_and_malformed_values() {
+    let declared = declared(&[("user", None)]);
+    for supplied in [
+        vec!["other=1".to_string()],
+        vec!["user=a".to_string(), "user=b".to_string()],
+        vec![],
+        vec!["user".to_string()],
+    ] {
+        let error = bind_params(&declared, &supplied).unwrap_err();
+        assert_eq!(error.code(), "INVALID_ARGS", "{supplied:?}");
+    }
+    let bad_name = self::declared(&[("not-valid", Some("x"))]);
+    assert!(bind_params(&bad_name, &[]).is_err());
+}
+
+#[test]
+fn scenario_schema_parses_expectations_and_rejects_unknown_fields() {
+    let scenario: Scenario = serde_json::from_value(serde_json::json!({
+        "name": "login",
+        "params": { "user": null },
+        "steps": [{
+            "name": "type user",
+            "command": "type",
+            "args": { "ref_id": "@e1", "text": "${user}" },
+            "timeout_ms": 5000,
+            "expect": [
+                { "get": { "ref_id": "@e1", "property": "value", "equals": "${user}" } },
+                { "count": { "role": "button", "min": 1 } }
+            ]
+        }]
+    }))
+    .unwrap();
+
+    let step = &scenario.steps[0];
+    assert_eq!(step.timeout_ms, Some(5000));
+    match &step.expect[0] {
+        Expectation::Get(assertion) => {
+            assert_eq!(assertion.equals, Some(serde_json::json!("${user}")));
+            assert_eq!(assertion.args["property"], "value");
+        }
+        other => panic!("unexpected expectation {other:?}"),
+    }
+    match &step.expect[1] {
+        Expectation::Count(assertion) => {
+            assert_eq!(assertion.min, Some(1));
+            assert!(assertion.args.contains_key("role"));
+        }
+        other => panic!("unexpected expectation {other:?}"),
+    }
+
+    let unknown = serde_json::from_value::<Scenario>(serde_json::json!({
+        "steps": [{ "name": "x", "command": "status", "retries": 2 }]
+    }));
+    assert!(unknown.is_err());
+}
+
+#[test]
+fn junit_reports_one_case_per_step_with_failures_and_skips() {
+    let cases = [
+        StepCase {
+            name: "launch",
+            command: "launch",
+            status: StepStatus::Passed,
+            elapsed_ms: 1250,
+            failure: None,
+        },
+        StepCase {
+            name: "check <title>",
+            command: "get",
+            status: StepStatus::Failed,
+            elapsed_ms: 40,
+            failure: Some("expected \"a\"\nactual \"b\"".into()),
+        },
+        StepCase {
+            name: "close",
+            command: "close-app",
+            status: StepStatus::Skipped,
+            elapsed_ms: 0,
+            failure: None,
+        },
+    ];
+
+    let xml = render_junit("login & logout", &cases);
+
+    assert!(xml.contains(
+        "<testsuite name=\"login &amp; logout\" tests=\"3\" failures=\"1\" skipped=\"1\" time=\"1.290\">"
+    ));
+    assert!(xml.contains("name=\"launch\" time=\"1.250\"></testcase>"));
+    assert!(xml.contains("name=\"check &lt;title&gt;\""));
+    assert!(xml.contains("<failure message=\"expected &quot;a&quot;\" type=\"get\">"));
+    assert!(xml.contains("<skipped/>"));
+}
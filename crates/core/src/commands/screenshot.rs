@@ -1,6 +1,10 @@
 use crate::{
-    AdapterError, AppError, ErrorCode,
+    AdapterError, AppError, ErrorCode, ImageBuffer, Rect, RefMap,
     adapter::{PlatformAdapter, ScreenshotTarget, WindowFilter},
+    context::CommandContext,
+    raster::Placement,
+    refs_store::RefStore,
+    screenshot_annotate,
 };
 use base64::Engine;
 use serde_json::{Value, json};
@@ -11,34 +15,110 @@ pub struct ScreenshotArgs {
     pub window_id: Option<String>,
     pub screen: Option<usize>,
     pub output_path: Option<PathBuf>,
+    pub annotate: Option<String>,
 }
 
-pub fn execute(args: ScreenshotArgs, adapter: &dyn PlatformAdapter) -> Result<Value, AppError> {
+pub fn execute(
+    mut args: ScreenshotArgs,
+    adapter: &dyn PlatformAdapter,
+    context: &CommandContext,
+) -> Result<Value, AppError> {
     let deadline = crate::Deadline::standard()?;
+    let marks = match args.annotate.take() {
+        Some(snapshot_id) => {
+            let refmap =
+                RefStore::for_session(context.session_id())?.load_snapshot(&snapshot_id)?;
+            if args.app.is_none() && args.window_id.is_none() && args.screen.is_none() {
+                args.window_id = Some(snapshot_window(&refmap, &snapshot_id)?);
+            }
+            Some((snapshot_id, refmap))
+        }
+        None => None,
+    };
     let target = resolve_target(&args, adapter, deadline)?;
-    let buf = adapter.screenshot(target, deadline)?;
+    let origin = match &marks {
+        Some(_) => Some(capture_origin(&target, adapter, deadline)?),
+        None => None,
+    };
+    let mut buf = adapter.screenshot(target, deadline)?;
+    let mut annotations = None;
+    if let (Some((snapshot_id, refmap)), Some(origin)) = (marks, origin) {
+        let placement = Placement::new(&origin, buf.scale_factor);
+        let (annotated, legend) =
+            screenshot_annotate::annotate(&buf, &refmap, &snapshot_id, placement)?;
+        buf = annotated;
+        annotations = Some(legend);
+    }
 
+    let mut body = image_fields(&buf);
     if let Some(path) = args.output_path {
         crate::refs::write_user_file(&path, &buf.data)?;
-        Ok(json!({
-            "path": path.to_string_lossy(),
-            "format": buf.format.as_str(),
-            "width": buf.width,
-            "height": buf.height,
-            "scale_factor": buf.scale_factor
-        }))
+        body["path"] = json!(path.to_string_lossy());
     } else {
-        let encoded = base64::engine::general_purpose::STANDARD.encode(&buf.data);
-        Ok(json!({
-            "data": encoded,
-            "format": buf.format.as_str(),
-            "width": buf.width,
-            "height": buf.height,
-            "scale_factor": buf.scale_factor
-        }))
+        body["data"] = json!(base64::engine::general_purpose::STANDARD.encode(&buf.data));
+    }
+    if let Some(annotations) = annotations {
+        body["annotations"] = annotations;
+    }
+    Ok(body)
+}
+
+fn image_fields(buf: &ImageBuffer) -> Value {
+    json!({
+        "format": buf.format.as_str(),
+        "width": buf.width,
+        "height": buf.height,
+        "scale_factor": buf.scale_factor
+    })
+}
+
+/// The one window every ref in the snapshot was observed in, used as the
+/// capture target when `--annotate` is given without an explicit target.
+fn snapshot_window(refmap: &RefMap, snapshot_id: &str) -> Result<String, AppError> {
+    let mut windows = refmap
+        .iter()
+        .filter_map(|(_, entry)| entry.source.source_window_id.clone())
+        .collect::<Vec<_>>();
+    windows.sort();
+    windows.dedup();
+    match windows.as_slice() {
+        [window_id] => Ok(window_id.clone()),
+        _ => Err(AppError::invalid_input_with_suggestion(
+            format!("Snapshot '{snapshot_id}' does not belong to exactly one window"),
+            "Pass --app, --window-id, or --screen to choose what to capture.",
+        )),
     }
 }
 
+/// Screen-point rectangle whose top-left corner is the capture's pixel (0, 0).
+fn capture_origin(
+    target: &ScreenshotTarget,
+    adapter: &dyn PlatformAdapter,
+    deadline: crate::Deadline,
+) -> Result<Rect, AppError> {
+    let origin = match target {
+        ScreenshotTarget::ExactWindow(window) => window.bounds,
+        ScreenshotTarget::Display { expected, .. } => Some(expected.bounds),
+        ScreenshotTarget::Screen(index) => adapter
+            .list_displays(deadline)?
+            .get(*index)
+            .map(|display| display.bounds),
+        ScreenshotTarget::FullScreen => adapter
+            .list_displays(deadline)?
+            .into_iter()
+            .find(|display| display.is_primary)
+            .map(|display| display.bounds),
+    };
+    origin.ok_or_else(|| {
+        AdapterError::new(
+            ErrorCode::ActionNotSupported,
+            "Capture bounds are unavailable, so refs cannot be placed on the screenshot",
+        )
+        .with_suggestion("Capture a window or display whose bounds are reported by list-windows or list-displays.")
+        .into()
+    })
+}
+
 fn resolve_target(
     args: &ScreenshotArgs,
     adapter: &dyn PlatformAdapter,
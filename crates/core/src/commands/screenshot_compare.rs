@@ -0,0 +1,55 @@
+use crate::{
+    AppError,
+    adapter::PlatformAdapter,
+    commands::visual_compare::{self, source_window},
+    context::CommandContext,
+    image_diff,
+    raster::Placement,
+};
+use serde_json::{Value, json};
+use std::path::PathBuf;
+
+pub struct ScreenshotCompareArgs {
+    pub expected: PathBuf,
+    pub actual: PathBuf,
+    pub threshold: Option<f64>,
+    pub ignore: Vec<String>,
+    pub diff_path: Option<PathBuf>,
+}
+
+/// Compares two PNG files and reports a perceptual diff score. A score over
+/// the threshold is a result (`passed: false`), not an error; use
+/// `expect ... to-match-screenshot` for an assertion that fails.
+///
+/// Ignore refs are located through their window's live bounds, so the
+/// images are expected to be captures of that window.
+pub fn execute(
+    args: ScreenshotCompareArgs,
+    adapter: &dyn PlatformAdapter,
+    context: &CommandContext,
+) -> Result<Value, AppError> {
+    let threshold = visual_compare::threshold(args.threshold)?;
+    let regions = visual_compare::parse_ignore(&args.ignore, context)?;
+    let expected = visual_compare::read_png(&args.expected, "expected image")?;
+    let actual = visual_compare::read_png(&args.actual, "actual image")?;
+    let deadline = crate::Deadline::standard()?;
+    let ignore = visual_compare::pixel_boxes(&regions, expected.width, expected.height, |entry| {
+        let (_, bounds) = source_window(entry, adapter, deadline)?;
+        Ok(Placement::new(
+            &bounds,
+            f64::from(expected.width) / bounds.width,
+        ))
+    })?;
+
+    let diff = image_diff::compare(&expected, &actual, &ignore);
+    let mut body = diff.summary(threshold);
+    if let Some(path) = args.diff_path {
+        crate::refs::write_user_file(&path, &diff.mask.encode_png()?)?;
+        body["diff_path"] = json!(path.to_string_lossy());
+    }
+    Ok(body)
+}
+
+#[cfg(test)]
+#[path = "screenshot_compare_tests.rs"]
+mod tests;
@@ -0,0 +1,99 @@
+use super::*;
+use crate::{
+    adapter::{ActionOps, InputOps, ObservationOps, SystemOps},
+    raster::Raster,
+    refs_test_support::TempDir,
+};
+
+struct NoAdapter;
+
+impl ObservationOps for NoAdapter {}
+impl ActionOps for NoAdapter {}
+impl InputOps for NoAdapter {}
+impl SystemOps for NoAdapter {}
+
+fn write_png(dir: &TempDir, name: &str, image: &Raster) -> PathBuf {
+    let path = dir.path().join(name);
+    std::fs::write(&path, image.encode_png().unwrap()).unwrap();
+    path
+}
+
+fn white(width: u32, height: u32) -> Raster {
+    Raster {
+        width,
+        height,
+        rgba: [255, 255, 255, 255].repeat(width as usize * height as usize),
+    }
+}
+
+fn compare(
+    expected: PathBuf,
+    actual: PathBuf,
+    ignore: &[&str],
+    diff_path: Option<PathBuf>,
+) -> Result<Value, AppError> {
+    execute(
+        ScreenshotCompareArgs {
+            expected,
+            actual,
+            threshold: None,
+            ignore: ignore.iter().map(|value| value.to_string()).collect(),
+            diff_path,
+        },
+        &NoAdapter,
+        &CommandContext::default(),
+    )
+}
+
+#[test]
+fn reports_the_score_and_writes_the_diff_mask() {
+    let dir = TempDir::new();
+    let mut changed = white(10, 10);
+    changed.set_pixel(2, 3, [0, 0, 0, 255]);
+    let expected = write_png(&dir, "a.png", &white(10, 10));
+    let actual = write_png(&dir, "b.png", &changed);
+    let mask = dir.path().join("diff.png");
+
+    let result = compare(expected, actual, &[], Some(mask.clone())).unwrap();
+
+    assert_eq!(result["passed"], false);
+    assert_eq!(result["score"], 0.01);
+    assert_eq!(result["mismatched_pixels"], 1);
+    assert_eq!(result["threshold"], 0.001);
+    assert_eq!(result["diff_path"], mask.to_string_lossy().as_ref());
+    let mask = Raster::decode_png(&std::fs::read(mask).unwrap()).unwrap();
+    assert_eq!(mask.pixel(2, 3), [255, 0, 0, 255]);
+}
+
+#[test]
+fn pixel_rect_ignore_regions_remove_differences_from_the_score() {
+    let dir = TempDir::new();
+    let mut changed = white(10, 10);
+    changed.set_pixel(2, 3, [0, 0, 0, 255]);
+    let expected = write_png(&dir, "a.png", &white(10, 10));
+    let actual = write_png(&dir, "b.png", &changed);
+
+    let result = compare(expected, actual, &["0,0,5,5"], None).unwrap();
+
+    assert_eq!(result["passed"], true);
+    assert_eq!(result["ignored_pixels"], 25);
+    assert_eq!(result["compared_pixels"], 75);
+}
+
+#[test]
+fn malformed_inputs_are_invalid_args() {
+    let dir = TempDir::new();
+    let image = write_png(&dir, "a.png", &white(2, 2));
+    let text = dir.path().join("notes.txt");
+    std::fs::write(&text, "not a png").unwrap();
+
+    for (actual, ignore) in [
+        (image.clone(), vec!["1,2,3"]),
+        (image.clone(), vec!["0,0,0,4"]),
+        (text, vec![]),
+        (dir.path().join("missing.png"), vec![]),
+    ] {
+        let error = compare(image.clone(), actual, &ignore, None).unwrap_err();
+        assert_eq!(error.code(), "INVALID_ARGS");
+    }
+}
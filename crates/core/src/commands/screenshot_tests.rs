@@ -9,6 +9,7 @@ use std::{path::PathBuf, sync::Mutex};
 struct ScreenshotAdapter {
     displays: Vec<DisplayInfo>,
     windows: Vec<WindowInfo>,
+    image: Vec<u8>,
     target: Mutex<Option<ScreenshotTarget>>,
 }
 
@@ -19,6 +20,7 @@ impl ScreenshotAdapter {
         Self {
             displays: vec![display("main", true, 2.0), display("secondary", false, 1.0)],
             windows: vec![window("w-41", 700), focused],
+            image: vec![1, 2, 3],
             target: Mutex::new(None),
         }
     }
@@ -59,7 +61,7 @@ impl SystemOps for ScreenshotAdapter {
     ) -> Result<ImageBuffer, AdapterError> {
         *self.target.lock().expect("target lock") = Some(target);
         Ok(ImageBuffer {
-            data: vec![1, 2, 3],
+            data: self.image.clone(),
             format: ImageFormat::Png,
             width: 640,
             height: 480,
@@ -101,12 +103,17 @@ fn window(id: &str, pid: u32) -> WindowInfo {
     }
 }
 
+fn context() -> crate::context::CommandContext {
+    crate::context::CommandContext::new(None, None, false).expect("context")
+}
+
 fn args() -> ScreenshotArgs {
     ScreenshotArgs {
         app: None,
         window_id: None,
         screen: None,
         output_path: None,
+        annotate: None,
     }
 }
 
@@ -116,7 +123,7 @@ fn screen_target_pins_selected_display_identity() {
     let mut request = args();
     request.screen = Some(1);
 
-    screenshot::execute(request, &adapter).expect("screenshot");
+    screenshot::execute(request, &adapter, &context()).expect("screenshot");
 
     match adapter.take_target() {
         Some(ScreenshotTarget::Display { index, expected }) => {
@@ -141,7 +148,8 @@ fn screen_rejects_app_and_window_target_conflicts() {
         request.app = app;
         request.window_id = window_id;
 
-        let error = screenshot::execute(request, &adapter).expect_err("targets conflict");
+        let error =
+            screenshot::execute(request, &adapter, &context()).expect_err("targets conflict");
         assert_eq!(error.code(), "INVALID_ARGS");
         assert!(adapter.take_target().is_none());
     }
@@ -153,7 +161,7 @@ fn window_id_target_pins_exact_window_not_only_its_pid() {
     let mut request = args();
     request.window_id = Some("w-42".into());
 
-    screenshot::execute(request, &adapter).expect("screenshot");
+    screenshot::execute(request, &adapter, &context()).expect("screenshot");
 
     match adapter.take_target() {
         Some(ScreenshotTarget::ExactWindow(window)) => {
@@ -170,7 +178,7 @@ fn app_target_resolves_one_exact_focused_window() {
     let mut request = args();
     request.app = Some("Example".into());
 
-    screenshot::execute(request, &adapter).expect("screenshot");
+    screenshot::execute(request, &adapter, &context()).expect("screenshot");
 
     match adapter.take_target() {
         Some(ScreenshotTarget::ExactWindow(window)) => {
@@ -187,7 +195,7 @@ fn missing_window_returns_an_error_without_capturing() {
     let mut request = args();
     request.window_id = Some("w-404".into());
 
-    let error = screenshot::execute(request, &adapter).expect_err("missing window");
+    let error = screenshot::execute(request, &adapter, &context()).expect_err("missing window");
 
     assert_eq!(error.code(), "INVALID_ARGS");
     assert!(adapter.take_target().is_none());
@@ -200,7 +208,7 @@ fn output_path_response_keeps_image_metadata() {
     let mut request = args();
     request.output_path = Some(path.clone());
 
-    let response = screenshot::execute(request, &adapter).expect("screenshot");
+    let response = screenshot::execute(request, &adapter, &context()).expect("screenshot");
 
     assert_eq!(std::fs::read(&path).expect("saved screenshot"), [1, 2, 3]);
     assert_eq!(response["path"], path.to_string_lossy().as_ref());
@@ -231,13 +239,15 @@ fn output_path_rejects_symlinks_and_creates_private_files() {
 
     let mut request = args();
     request.output_path = Some(link);
-    screenshot::execute(request, &ScreenshotAdapter::new()).expect_err("reject symlink");
+    screenshot::execute(request, &ScreenshotAdapter::new(), &context())
+        .expect_err("reject symlink");
     assert_eq!(std::fs::read(&victim).expect("read victim"), b"unchanged");
 
     let output = parent.join("private.png");
     let mut request = args();
     request.output_path = Some(output.clone());
-    screenshot::execute(request, &ScreenshotAdapter::new()).expect("write private screenshot");
+    screenshot::execute(request, &ScreenshotAdapter::new(), &context())
+        .expect("write private screenshot");
     assert_eq!(
         std::fs::metadata(output).expect("output metadata").mode() & 0o777,
         0o600
@@ -248,6 +258,113 @@ fn output_path_rejects_symlinks_and_creates_private_files() {
     std::fs::remove_dir(parent).expect("remove output directory");
 }
 
+#[test]
+fn annotate_captures_the_snapshot_window_and_returns_a_legend() {
+    let _home = crate::refs_test_support::HomeGuard::new();
+    let mut adapter = ScreenshotAdapter::new();
+    adapter.windows[1].bounds = Some(Rect {
+        x: 100.0,
+        y: 50.0,
+        width: 320.0,
+        height: 240.0,
+    });
+    adapter.image = blank_png(640, 480);
+    let snapshot_id = save_snapshot_with_button("w-42");
+    let mut request = args();
+    request.annotate = Some(snapshot_id.clone());
+
+    let response = screenshot::execute(request, &adapter, &context()).expect("annotate");
+
+    match adapter.take_target() {
+        Some(ScreenshotTarget::ExactWindow(window)) => assert_eq!(window.id, "w-42"),
+        _ => panic!("expected the snapshot's window as target"),
+    }
+    let legend = &response["annotations"]["legend"];
+    assert_eq!(legend[0]["ref_id"], format!("@{snapshot_id}:e1"));
+    assert_eq!(
+        legend[0]["box"],
+        serde_json::json!({"x": 20, "y": 20, "width": 100, "height": 40})
+    );
+    assert_eq!(response["width"], 640);
+    assert!(
+        response["data"]
+            .as_str()
+            .is_some_and(|data| !data.is_empty())
+    );
+}
+
+#[test]
+fn annotate_without_window_bounds_fails_before_capturing() {
+    let _home = crate::refs_test_support::HomeGuard::new();
+    let adapter = ScreenshotAdapter::new();
+    let mut request = args();
+    request.annotate = Some(save_snapshot_with_button("w-42"));
+
+    let error = screenshot::execute(request, &adapter, &context()).expect_err("no bounds");
+
+    assert_eq!(error.code(), "ACTION_NOT_SUPPORTED");
+    assert!(adapter.take_target().is_none());
+}
+
+fn save_snapshot_with_button(window_id: &str) -> String {
+    let bounds = Rect {
+        x: 110.0,
+        y: 60.0,
+        width: 50.0,
+        height: 20.0,
+    };
+    let mut refmap = crate::RefMap::new();
+    refmap.allocate(crate::RefEntry {
+        process: crate::RefProcess {
+            pid: crate::ProcessId::new(700),
+            process_instance: Some("instance-700".into()),
+        },
+        identity: crate::RefEntryIdentity {
+            role: "button".into(),
+            name: Some("Send".into()),
+            value: None,
+            description: None,
+            native_id: None,
+        },
+        geometry: crate::RefGeometry {
+            bounds: Some(bounds),
+            bounds_hash: bounds.bounds_hash(),
+        },
+        capabilities: crate::RefCapabilities {
+            states: vec![],
+            available_actions: vec!["Click".into()],
+        },
+        source: crate::RefSource {
+            source_app: Some("Example".into()),
+            source_window_id: Some(window_id.into()),
+            source_window_title: None,
+            source_window_bounds_hash: None,
+            source_surface: crate::SnapshotSurface::Window,
+        },
+        scope: crate::RefScope {
+            root_ref: None,
+            path_is_absolute: false,
+            path: smallvec::smallvec![0],
+        },
+    });
+    crate::refs_store::RefStore::new()
+        .expect("store")
+        .save_new_snapshot(&refmap)
+        .expect("save snapshot")
+}
+
+fn blank_png(width: u32, height: u32) -> Vec<u8> {
+    let mut data = Vec::new();
+    let mut encoder = png::Encoder::new(&mut data, width, height);
+    encoder.set_color(png::ColorType::Grayscale);
+    let mut writer = encoder.write_header().expect("png header");
+    writer
+        .write_image_data(&vec![255; (width * height) as usize])
+        .expect("png data");
+    writer.finish().expect("png finish");
+    data
+}
+
 fn output_path() -> PathBuf {
     std::env::temp_dir().join(format!(
         "agent-desktop-core-screenshot-{}-{:?}.png",
@@ -1,9 +1,11 @@
 use crate::AppError;
 use crate::session::{
-    ArtifactsMode, GcOptions, SessionTraceMode, StartSessionOptions, end_session, gc,
-    list_sessions, start_session,
+    ArtifactsMode, GcOptions, SessionQuota, SessionTraceMode, StartSessionOptions, end_session,
+    enforce_retention, export_session, gc, import_session, list_sessions, read_global_quota,
+    start_session, write_global_quota,
 };
 use serde_json::{Value, json};
+use std::path::PathBuf;
 use std::time::Duration;
 
 #[derive(Debug, Clone)]
@@ -12,6 +14,7 @@ pub enum SessionAction {
         name: Option<String>,
         no_trace: bool,
         screenshots: bool,
+        quota: SessionQuota,
     },
     End {
         id: String,
@@ -21,6 +24,20 @@ pub enum SessionAction {
         older_than_secs: Option<u64>,
         ended_only: bool,
     },
+    Export {
+        id: String,
+        out: Option<PathBuf>,
+    },
+    Import {
+        path: PathBuf,
+        as_id: Option<String>,
+    },
+    /// Shows the global quota; non-empty `update` fields replace the
+    /// stored ones, and `clear` drops them all first.
+    Quota {
+        update: SessionQuota,
+        clear: bool,
+    },
 }
 
 pub fn execute(action: SessionAction) -> Result<Value, AppError> {
@@ -29,6 +46,7 @@ pub fn execute(action: SessionAction) -> Result<Value, AppError> {
             name,
             no_trace,
             screenshots,
+            quota,
         } => {
             let manifest = start_session(StartSessionOptions {
                 name,
@@ -42,14 +60,26 @@ pub fn execute(action: SessionAction) -> Result<Value, AppError> {
                 } else {
                     ArtifactsMode::Events
                 },
+                quota,
             })?;
-            Ok(json!({
+            let mut result = json!({
                 "session_id": manifest.id,
                 "name": manifest.name,
                 "trace": manifest.trace,
                 "artifacts": manifest.artifacts,
                 "created_at": manifest.created_at,
-            }))
+            });
+            if !manifest.quota.is_empty() {
+                result["quota"] = json!(manifest.quota);
+            }
+            match enforce_retention(&manifest.id) {
+                Ok(report) if !report.removed.is_empty() => {
+                    result["retention"] = json!({ "removed": report.removed });
+                }
+                Ok(_) => {}
+                Err(err) => tracing::warn!("session retention skipped: {err}"),
+            }
+            Ok(result)
         }
         SessionAction::End { id } => {
             let manifest = end_session(&id)?;
@@ -69,6 +99,9 @@ pub fn execute(action: SessionAction) -> Result<Value, AppError> {
                         "ended_at": manifest.ended_at,
                         "trace": manifest.trace,
                         "artifacts": manifest.artifacts,
+                        "read_only": manifest.read_only,
+                        "imported_from": manifest.imported_from,
+                        "quota": manifest.quota,
                     })
                 })
                 .collect();
@@ -84,5 +117,24 @@ pub fn execute(action: SessionAction) -> Result<Value, AppError> {
             })?;
             Ok(json!({ "removed": report.removed }))
         }
+        SessionAction::Export { id, out } => {
+            let out = out.unwrap_or_else(|| PathBuf::from(format!("session-{id}.tar.zst")));
+            Ok(json!(export_session(&id, &out)?))
+        }
+        SessionAction::Import { path, as_id } => {
+            Ok(json!(import_session(&path, as_id.as_deref())?))
+        }
+        SessionAction::Quota { update, clear } => {
+            let current = if clear {
+                SessionQuota::default()
+            } else {
+                read_global_quota()?
+            };
+            let quota = update.or(current);
+            if clear || !update.is_empty() {
+                write_global_quota(&quota)?;
+            }
+            Ok(json!({ "quota": quota }))
+        }
     }
 }
@@ -4,6 +4,7 @@ use crate::{
     adapter::PlatformAdapter,
     commands::helpers::{RefArgs, execute_ref_action_with_context},
     context::CommandContext,
+    secret_source::Secret,
 };
 use serde_json::Value;
 
@@ -11,6 +12,9 @@ pub struct SetValueArgs {
     pub ref_id: String,
     pub snapshot_id: Option<String>,
     pub value: String,
+    /// Name of a secret to set instead of `value`; its value never appears
+    /// in the response or the trace.
+    pub secret: Option<String>,
     pub timeout_ms: Option<u64>,
 }
 
@@ -19,8 +23,16 @@ pub fn execute(
     adapter: &dyn PlatformAdapter,
     context: &CommandContext,
 ) -> Result<Value, AppError> {
-    let request = context.request_base(Action::SetValue(args.value));
-    execute_ref_action_with_context(
+    let secret = args
+        .secret
+        .as_deref()
+        .map(|name| Secret::resolve(name, context))
+        .transpose()?;
+    let value = secret
+        .as_ref()
+        .map_or(args.value, |secret| secret.value().to_owned());
+    let request = context.request_base(Action::SetValue(value));
+    let result = execute_ref_action_with_context(
         RefArgs {
             ref_id: args.ref_id,
             snapshot_id: args.snapshot_id,
@@ -29,7 +41,11 @@ pub fn execute(
         adapter,
         request,
         context,
-    )
+    );
+    match secret {
+        Some(secret) => secret.scrub(result),
+        None => result,
+    }
 }
 
 #[cfg(test)]
@@ -70,6 +70,7 @@ fn transient_stale_ref_retries_then_succeeds_when_timeout_wired() {
             ref_id: "@e1".into(),
             snapshot_id: Some(snapshot_id),
             value: "hi".into(),
+            secret: None,
             timeout_ms: Some(5_000),
         },
         &adapter,
@@ -92,6 +93,7 @@ fn timeout_none_makes_exactly_one_resolve_attempt() {
             ref_id: "@e1".into(),
             snapshot_id: Some(snapshot_id),
             value: "hi".into(),
+            secret: None,
             timeout_ms: None,
         },
         &adapter,
@@ -102,3 +104,81 @@ fn timeout_none_makes_exactly_one_resolve_attempt() {
     assert_eq!(err.code(), "STALE_REF");
     assert_eq!(adapter.retry.calls(), 1);
 }
+
+struct EchoAdapter;
+
+impl ObservationOps for EchoAdapter {
+    fn resolve_element_strict(
+        &self,
+        _entry: &RefEntry,
+        _deadline: crate::Deadline,
+    ) -> Result<NativeHandle, AdapterError> {
+        Ok(NativeHandle::null())
+    }
+
+    crate::adapter::complete_live_observation!(
+        "textfield",
+        "Target",
+        [crate::capability::SET_VALUE]
+    );
+}
+
+impl ActionOps for EchoAdapter {
+    fn execute_action(
+        &self,
+        _handle: &NativeHandle,
+        request: ActionRequest,
+        _lease: &crate::InteractionLease,
+    ) -> Result<ActionResult, AdapterError> {
+        let crate::action::Action::SetValue(value) = request.action else {
+            return Err(AdapterError::internal("unexpected action"));
+        };
+        Ok(
+            ActionResult::delivered_unverified("set_value").with_state(crate::ElementState {
+                role: "textfield".into(),
+                states: vec![],
+                value: Some(value),
+                enabled: None,
+                hidden: None,
+                offscreen: None,
+            }),
+        )
+    }
+}
+
+impl InputOps for EchoAdapter {}
+impl SystemOps for EchoAdapter {
+    crate::adapter::guarded_interaction_lease!();
+}
+
+#[test]
+fn a_secret_is_set_but_never_echoed_back() {
+    let _guard = HomeGuard::new();
+    let snapshot_id = snapshot_id();
+    let root = crate::state_root::resolve_configured_state_root().unwrap();
+    let secret_path = root.join("db-password");
+    crate::private_file::write_atomic(&secret_path, b"s3cret-set-value\n").unwrap();
+    crate::private_file::write_atomic(
+        &root.join(crate::secret_source::SECRETS_FILE),
+        serde_json::json!({ "db": { "file": secret_path } })
+            .to_string()
+            .as_bytes(),
+    )
+    .unwrap();
+
+    let value = execute(
+        SetValueArgs {
+            ref_id: "@e1".into(),
+            snapshot_id: Some(snapshot_id),
+            value: String::new(),
+            secret: Some("db".into()),
+            timeout_ms: None,
+        },
+        &EchoAdapter,
+        &CommandContext::default(),
+    )
+    .unwrap();
+
+    assert_eq!(value["post_state"]["value"], "[redacted]");
+    assert!(!value.to_string().contains("s3cret-set-value"));
+}
@@ -4,7 +4,7 @@ use crate::{
     commands::{wait_selector, wait_selector::WaitSelectorInput},
     context::CommandContext,
     refs::validate_ref_id,
-    snapshot, snapshot_ref,
+    snapshot, snapshot_budget, snapshot_ref,
 };
 use serde_json::{Value, json};
 
@@ -19,6 +19,10 @@ pub struct SnapshotArgs {
     pub skeleton: bool,
     pub root_ref: Option<String>,
     pub snapshot_id: Option<String>,
+    pub max_tokens: Option<u32>,
+    pub format: crate::SnapshotFormat,
+    pub stable_refs: bool,
+    pub reveal_secure: bool,
 }
 
 fn tree_options(args: &SnapshotArgs) -> crate::adapter::TreeOptions {
@@ -36,6 +40,8 @@ fn tree_options(args: &SnapshotArgs) -> crate::adapter::TreeOptions {
         compact: args.compact,
         surface: args.surface,
         skeleton: skeleton_applies,
+        max_tokens: args.max_tokens,
+        reveal_secure: args.reveal_secure,
     }
 }
 
@@ -53,6 +59,13 @@ pub fn execute(
         args.compact
     );
 
+    if args.stable_refs && (args.root_ref.is_some() || context.wait_selector().is_some()) {
+        return Err(AppError::invalid_input_with_suggestion(
+            "--stable-refs cannot be combined with --root, --wait-for, or --wait-for-gone",
+            "Stable refs renumber a full window snapshot; drop --stable-refs for a drill-down or selector wait.",
+        ));
+    }
+
     if let Some(root) = args.root_ref.as_deref() {
         if context.wait_selector().is_some() {
             return Err(AppError::invalid_input_with_suggestion(
@@ -64,14 +77,35 @@ pub fn execute(
         validate_ref_id(root)?;
     }
 
+    if args.max_tokens == Some(0) {
+        return Err(AppError::invalid_input_with_suggestion(
+            "--max-tokens must be greater than zero",
+            "Pass a positive token budget, or omit --max-tokens for an unbounded snapshot.",
+        ));
+    }
+
     super::surface_scope::require_supported(args.surface, adapter)?;
 
     let opts = tree_options(&args);
+    let format = args.format;
+    let body = observe(args, &opts, adapter, context)?;
+    let body = match opts.max_tokens {
+        Some(max_tokens) => with_token_budget(body, max_tokens),
+        None => body,
+    };
+    format.apply(body)
+}
 
+fn observe(
+    args: SnapshotArgs,
+    opts: &crate::adapter::TreeOptions,
+    adapter: &dyn PlatformAdapter,
+    context: &CommandContext,
+) -> Result<Value, AppError> {
     if let Some(root) = args.root_ref {
         return format_result(snapshot_ref::run_from_ref_with_context(
             adapter,
-            &opts,
+            opts,
             &root,
             args.snapshot_id.as_deref(),
             context,
@@ -85,7 +119,7 @@ pub fn execute(
                 gone: wait.gone,
                 app: args.app.clone(),
                 window_id: args.window_id.clone(),
-                opts,
+                opts: *opts,
                 timeout_ms: wait.timeout_ms,
             },
             adapter,
@@ -93,9 +127,14 @@ pub fn execute(
         );
     }
 
-    let result = snapshot::run_with_context(
+    let run = if args.stable_refs {
+        crate::snapshot_stable::run_with_context
+    } else {
+        snapshot::run_with_context
+    };
+    let result = run(
         adapter,
-        &opts,
+        opts,
         args.app.as_deref(),
         args.window_id.as_deref(),
         context,
@@ -104,6 +143,23 @@ pub fn execute(
     format_result(result)
 }
 
+/// Reports the estimated cost of the tree actually emitted, measured after
+/// ref qualification, so callers can compare it against the requested budget.
+fn with_token_budget(mut body: Value, max_tokens: u32) -> Value {
+    let Some(tree) = body.get("tree") else {
+        return body;
+    };
+    let estimated_tokens = snapshot_budget::estimate_tokens(tree);
+    let truncated_containers = snapshot_budget::count_truncated(tree);
+    body["token_budget"] = json!({
+        "max_tokens": max_tokens,
+        "estimated_tokens": estimated_tokens,
+        "bytes_per_token": snapshot_budget::BYTES_PER_TOKEN,
+        "truncated_containers": truncated_containers,
+    });
+    body
+}
+
 fn format_result(result: snapshot::SnapshotResult) -> Result<Value, AppError> {
     format_snapshot_fields(&result, None, None)
 }
@@ -135,6 +191,10 @@ pub(crate) fn format_snapshot_fields(
         "complete": result.complete,
         "tree": tree
     });
+    let masked_values = crate::secure_mask::count_masked(&result.tree);
+    if masked_values > 0 {
+        body["masked_values"] = json!(masked_values);
+    }
     if !result.complete {
         body["truncated"] = json!(true);
         body["nodes_observed"] = json!(result.nodes_observed);
@@ -142,6 +202,12 @@ pub(crate) fn format_snapshot_fields(
     if let Some(ms) = elapsed_ms {
         body["elapsed_ms"] = json!(ms);
     }
+    if let Some(stable_refs) = &result.extras.stable_refs {
+        body["stable_refs"] = serde_json::to_value(stable_refs)?;
+    }
+    if let Some(web_content) = &result.extras.web_content {
+        body["web_content"] = web_content.clone();
+    }
     if let Some(selector) = matched_selector {
         body["matched_selector"] = json!(selector);
     }
@@ -151,3 +217,7 @@ pub(crate) fn format_snapshot_fields(
 #[cfg(test)]
 #[path = "snapshot_tests.rs"]
 mod tests;
+
+#[cfg(test)]
+#[path = "snapshot_secure_tests.rs"]
+mod secure_tests;
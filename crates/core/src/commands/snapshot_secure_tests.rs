@@ -0,0 +1,102 @@
+use super::tests::{WaitSnapshotAdapter, base_args};
+use super::*;
+use crate::adapter::{ActionOps, InputOps, ObservationOps, SystemOps, WindowFilter};
+use crate::context::CommandContext;
+use crate::refs_test_support::HomeGuard;
+use crate::{AccessibilityNode, AdapterError, WindowInfo};
+
+struct SecureFieldAdapter;
+
+impl ObservationOps for SecureFieldAdapter {
+    fn observe_tree(
+        &self,
+        root: crate::live_locator::ObservationRoot<'_>,
+        _request: &crate::live_locator::ObservationRequest,
+    ) -> Result<crate::live_locator::ObservedTree, AdapterError> {
+        crate::adapter::observed_tree(
+            &root,
+            AccessibilityNode {
+                ref_id: None,
+                role: "window".into(),
+                identity: crate::NodeIdentity {
+                    name: Some("Login".into()),
+                    ..Default::default()
+                },
+                presentation: Default::default(),
+                children_count: None,
+                subtree_truncated: false,
+                children: vec![AccessibilityNode {
+                    ref_id: None,
+                    role: "textfield".into(),
+                    identity: crate::NodeIdentity {
+                        name: Some("Password".into()),
+                        value: Some("hunter2".into()),
+                        ..Default::default()
+                    },
+                    presentation: crate::NodePresentation {
+                        states: vec![crate::state::SECURE.into()],
+                        ..Default::default()
+                    },
+                    children_count: None,
+                    subtree_truncated: false,
+                    children: vec![],
+                }],
+            },
+        )
+    }
+
+    fn list_windows(
+        &self,
+        filter: &WindowFilter,
+        deadline: crate::Deadline,
+    ) -> Result<Vec<WindowInfo>, AdapterError> {
+        WaitSnapshotAdapter.list_windows(filter, deadline)
+    }
+}
+
+impl ActionOps for SecureFieldAdapter {}
+
+impl InputOps for SecureFieldAdapter {}
+
+impl SystemOps for SecureFieldAdapter {
+    fn supported_surfaces(&self) -> Vec<SnapshotSurface> {
+        vec![SnapshotSurface::Window]
+    }
+}
+
+fn latest_refmap_json() -> String {
+    let store = crate::refs_store::RefStore::for_session(None).unwrap();
+    serde_json::to_string(&store.load_latest().unwrap()).unwrap()
+}
+
+#[test]
+fn secure_field_values_are_masked_in_the_tree_and_the_refmap() {
+    let _guard = HomeGuard::new();
+    let args = SnapshotArgs {
+        app: Some("FixtureApp".into()),
+        ..base_args()
+    };
+
+    let value = execute(args, &SecureFieldAdapter, &CommandContext::default()).unwrap();
+
+    let field = &value["tree"]["children"][0];
+    assert_eq!(field["value_masked"], true);
+    assert!(field.get("value").is_none());
+    assert_eq!(value["masked_values"], 1);
+    assert!(!latest_refmap_json().contains("hunter2"));
+}
+
+#[test]
+fn reveal_secure_keeps_secure_field_values() {
+    let _guard = HomeGuard::new();
+    let args = SnapshotArgs {
+        app: Some("FixtureApp".into()),
+        reveal_secure: true,
+        ..base_args()
+    };
+
+    let value = execute(args, &SecureFieldAdapter, &CommandContext::default()).unwrap();
+
+    assert_eq!(value["tree"]["children"][0]["value"], "hunter2");
+    assert!(value.get("masked_values").is_none());
+}
@@ -25,7 +25,7 @@ impl ActionOps for DefaultSurfaceAdapter {}
 impl InputOps for DefaultSurfaceAdapter {}
 impl SystemOps for DefaultSurfaceAdapter {}
 
-struct WaitSnapshotAdapter;
+pub(super) struct WaitSnapshotAdapter;
 
 impl ObservationOps for WaitSnapshotAdapter {
     fn observe_tree(
@@ -144,7 +144,7 @@ impl SystemOps for WaitSnapshotAdapter {
     }
 }
 
-fn base_args() -> SnapshotArgs {
+pub(super) fn base_args() -> SnapshotArgs {
     SnapshotArgs {
         app: None,
         window_id: None,
@@ -156,6 +156,10 @@ fn base_args() -> SnapshotArgs {
         skeleton: false,
         root_ref: None,
         snapshot_id: None,
+        max_tokens: None,
+        format: crate::SnapshotFormat::Json,
+        stable_refs: false,
+        reveal_secure: false,
     }
 }
 
@@ -351,3 +355,36 @@ fn root_and_wait_for_are_mutually_exclusive() {
         other => panic!("expected InvalidArgs, got {other:?}"),
     }
 }
+
+#[test]
+fn zero_token_budget_is_rejected_before_traversal() {
+    let args = SnapshotArgs {
+        max_tokens: Some(0),
+        ..base_args()
+    };
+
+    let err = execute(args, &NoopAdapter, &CommandContext::default()).unwrap_err();
+
+    assert_eq!(err.code(), "INVALID_ARGS");
+}
+
+#[test]
+fn token_budget_reports_the_estimate_of_the_emitted_tree() {
+    let _guard = HomeGuard::new();
+    let args = SnapshotArgs {
+        app: Some("FixtureApp".into()),
+        max_tokens: Some(20),
+        ..base_args()
+    };
+
+    let value = execute(args, &WaitSnapshotAdapter, &CommandContext::default()).unwrap();
+
+    let budget = &value["token_budget"];
+    assert_eq!(budget["max_tokens"], 20);
+    assert_eq!(
+        budget["estimated_tokens"].as_u64(),
+        Some(snapshot_budget::estimate_tokens(&value["tree"]) as u64)
+    );
+    assert_eq!(value["tree"]["subtree_truncated"], true);
+    assert_eq!(budget["truncated_containers"], 1);
+}
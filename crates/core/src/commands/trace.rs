@@ -2,8 +2,11 @@ use crate::{
     AppError,
     context::CommandContext,
     refs_store::RefStore,
-    session::resolve_active_session,
-    trace_read::{ExportOptions, ReadOptions, export_html, read_merged},
+    session::{read_manifest, resolve_active_session},
+    trace_read::{
+        ExportOptions, FollowOptions, ReadOptions, RecordedCommand, TRACE_FOLLOW_POLL_INTERVAL,
+        export_diff_html, export_html, read_merged, read_recorded_commands, summarize,
+    },
 };
 use serde_json::{Value, json};
 use std::path::PathBuf;
@@ -32,15 +35,80 @@ fn resolve_trace_session(context: &CommandContext) -> Result<(String, RefStore),
             "Run `session start` or pass `--session <id>`.",
         )
     })?;
-    let store = RefStore::for_session(Some(&session_id))?;
-    let trace_dir = store.trace_dir();
-    if !trace_dir.is_dir() {
+    let store = traced_store(&session_id)?;
+    Ok((session_id, store))
+}
+
+fn traced_store(session_id: &str) -> Result<RefStore, AppError> {
+    let store = RefStore::for_session(Some(session_id))?;
+    if !store.trace_dir().is_dir() {
         return Err(AppError::invalid_input_with_suggestion(
             format!("Session '{session_id}' has no trace directory"),
             "Run `session start` with tracing enabled before recording commands.",
         ));
     }
-    Ok((session_id, store))
+    Ok(store)
+}
+
+/// The replayable commands `session_id` recorded, in timeline order.
+pub fn recorded_commands(session_id: &str) -> Result<Vec<RecordedCommand>, AppError> {
+    let store = traced_store(session_id)?;
+    let commands = read_recorded_commands(&store.trace_dir())?;
+    if commands.is_empty() {
+        return Err(AppError::invalid_input_with_suggestion(
+            format!("Session '{session_id}' recorded no replayable commands"),
+            "Record the run with `session start` and tracing enabled, then replay that session.",
+        ));
+    }
+    Ok(commands)
+}
+
+/// `trace stats`: per-command latency and failure counts, auto-wait time per
+/// actionability gate, retries, and delivery mechanisms over `session_id`'s
+/// whole trace.
+pub fn stats(session_id: &str) -> Result<Value, AppError> {
+    let store = traced_store(session_id)?;
+    let merged = read_merged(&store.trace_dir(), &ReadOptions::default())?;
+    if merged.segments.is_empty() {
+        return Err(empty_trace_dir_error(session_id));
+    }
+    let mut body = json!(summarize(&merged.events));
+    body["session_id"] = json!(session_id);
+    body["total_events"] = json!(merged.total_events);
+    if !merged.warnings.is_empty() {
+        body["warnings"] = json!(merged.warnings);
+    }
+    Ok(body)
+}
+
+/// `trace diff`: aligns the commands sessions `a` and `b` recorded and
+/// reports where they diverge, optionally also as an HTML page at `out`.
+pub fn diff(
+    a: &str,
+    b: &str,
+    latency_shift_ms: u64,
+    out: Option<PathBuf>,
+) -> Result<Value, AppError> {
+    let a_steps = crate::trace_diff::load_steps(a, recorded_commands(a)?)?;
+    let b_steps = crate::trace_diff::load_steps(b, recorded_commands(b)?)?;
+    let (a_count, b_count) = (a_steps.len(), b_steps.len());
+    let report = crate::trace_diff::diff(a_steps, b_steps, latency_shift_ms);
+    let mut body = json!(report);
+    body["a"] = json!(a);
+    body["b"] = json!(b);
+    body["a_steps"] = json!(a_count);
+    body["b_steps"] = json!(b_count);
+    if let Some(out) = out {
+        let bytes = export_diff_html(&body, &out)?;
+        body["path"] = json!(out.to_string_lossy());
+        body["bytes"] = json!(bytes);
+    }
+    Ok(body)
+}
+
+/// Writes a script `trace to-script` derived from a recorded session.
+pub fn write_script(out: &std::path::Path, script: &str) -> Result<(), AppError> {
+    crate::refs::write_user_file(out, script.as_bytes())
 }
 
 fn empty_trace_dir_error(session_id: &str) -> AppError {
@@ -83,6 +151,46 @@ fn show(context: &CommandContext, limit: usize, event: Option<String>) -> Result
     Ok(body)
 }
 
+/// `trace show --follow`: streams the session's events to `emit` as they
+/// are written, starting with the last `limit` already recorded, and returns
+/// a summary once the session has ended.
+pub fn follow(
+    context: &CommandContext,
+    limit: usize,
+    event: Option<String>,
+    emit: &mut dyn FnMut(&Value) -> Result<(), AppError>,
+) -> Result<Value, AppError> {
+    let (session_id, store) = resolve_trace_session(context)?;
+    let mut ended_at = None;
+    let mut ended = || {
+        let manifest = read_manifest(&session_id)?.ok_or_else(|| {
+            AppError::invalid_input_with_suggestion(
+                format!("Session '{session_id}' has no manifest to follow"),
+                "Follow a session created with `session start`.",
+            )
+        })?;
+        ended_at = manifest.ended_at;
+        Ok(ended_at.is_some())
+    };
+    let stats = crate::trace_read::follow(
+        &store.trace_dir(),
+        &FollowOptions {
+            limit,
+            event_prefix: event,
+            poll_interval: TRACE_FOLLOW_POLL_INTERVAL,
+        },
+        &mut ended,
+        emit,
+    )?;
+    Ok(json!({
+        "session_id": session_id,
+        "ended_at": ended_at,
+        "streamed_events": stats.streamed_events,
+        "segments": stats.segments,
+        "rotated_segments": stats.rotated_segments,
+    }))
+}
+
 fn export(context: &CommandContext, limit: usize, out: Option<PathBuf>) -> Result<Value, AppError> {
     let (session_id, store) = resolve_trace_session(context)?;
     let (_html, stats) = export_html(
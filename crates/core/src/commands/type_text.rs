@@ -4,6 +4,7 @@ use crate::{
     adapter::PlatformAdapter,
     commands::helpers::{RefArgs, execute_ref_action_with_context},
     context::CommandContext,
+    secret_source::Secret,
 };
 use serde_json::Value;
 
@@ -13,6 +14,9 @@ pub struct TypeArgs {
     pub ref_id: String,
     pub snapshot_id: Option<String>,
     pub text: String,
+    /// Name of a secret to type instead of `text`; its value never appears
+    /// in the response or the trace.
+    pub secret: Option<String>,
     pub timeout_ms: Option<u64>,
 }
 
@@ -21,14 +25,22 @@ pub fn execute(
     adapter: &dyn PlatformAdapter,
     context: &CommandContext,
 ) -> Result<Value, AppError> {
-    if args.text.len() > MAX_TEXT_LEN {
+    let secret = args
+        .secret
+        .as_deref()
+        .map(|name| Secret::resolve(name, context))
+        .transpose()?;
+    let text = secret
+        .as_ref()
+        .map_or(args.text, |secret| secret.value().to_owned());
+    if text.len() > MAX_TEXT_LEN {
         return Err(AppError::invalid_input(format!(
             "Text exceeds maximum length of {MAX_TEXT_LEN} characters"
         )));
     }
 
-    let request = context.request_base(Action::TypeText(args.text));
-    execute_ref_action_with_context(
+    let request = context.request_base(Action::TypeText(text));
+    let result = execute_ref_action_with_context(
         RefArgs {
             ref_id: args.ref_id,
             snapshot_id: args.snapshot_id,
@@ -37,7 +49,11 @@ pub fn execute(
         adapter,
         request,
         context,
-    )
+    );
+    match secret {
+        Some(secret) => secret.scrub(result),
+        None => result,
+    }
 }
 
 #[cfg(test)]
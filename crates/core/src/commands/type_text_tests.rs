@@ -71,6 +71,7 @@ fn transient_stale_ref_retries_then_succeeds_when_timeout_wired() {
             ref_id: "@e1".into(),
             snapshot_id: Some(snapshot_id),
             text: "hi".into(),
+            secret: None,
             timeout_ms: Some(5_000),
         },
         &adapter,
@@ -93,6 +94,7 @@ fn timeout_none_makes_exactly_one_resolve_attempt() {
             ref_id: "@e1".into(),
             snapshot_id: Some(snapshot_id),
             text: "hi".into(),
+            secret: None,
             timeout_ms: None,
         },
         &adapter,
@@ -0,0 +1,158 @@
+use crate::{
+    AdapterError, AppError, ErrorCode, Rect,
+    adapter::{PlatformAdapter, WindowFilter},
+    commands::helpers::load_ref_entry,
+    context::CommandContext,
+    image_diff::DEFAULT_THRESHOLD,
+    raster::{PixelBox, Placement, Raster},
+    refs::RefEntry,
+};
+use std::path::Path;
+
+/// A region left out of a screenshot comparison: a rectangle in the
+/// compared image's pixels, or a snapshot ref whose bounds are mapped into it.
+pub(crate) enum IgnoreRegion {
+    Pixels(Rect),
+    Ref(Box<RefEntry>),
+}
+
+/// Parses `--ignore` values: `x,y,width,height` in image pixels, or a ref.
+pub(crate) fn parse_ignore(
+    raw: &[String],
+    context: &CommandContext,
+) -> Result<Vec<IgnoreRegion>, AppError> {
+    raw.iter()
+        .map(|value| {
+            if value.starts_with('@') {
+                let entry = load_ref_entry(value, None, context)?;
+                if entry.geometry.bounds.is_none() {
+                    return Err(AppError::invalid_input(format!(
+                        "Ignore ref '{value}' has no recorded bounds"
+                    )));
+                }
+                return Ok(IgnoreRegion::Ref(Box::new(entry)));
+            }
+            parse_rect(value).map(IgnoreRegion::Pixels)
+        })
+        .collect()
+}
+
+fn parse_rect(value: &str) -> Result<Rect, AppError> {
+    let numbers: Vec<f64> = value
+        .split(',')
+        .map(|part| part.trim().parse::<f64>())
+        .collect::<Result<_, _>>()
+        .unwrap_or_default();
+    match numbers.as_slice() {
+        &[x, y, width, height]
+            if [x, y, width, height]
+                .iter()
+                .all(|number| number.is_finite() && *number >= 0.0)
+                && width > 0.0
+                && height > 0.0 =>
+        {
+            Ok(Rect {
+                x,
+                y,
+                width,
+                height,
+            })
+        }
+        _ => Err(AppError::invalid_input_with_suggestion(
+            format!("Invalid ignore region '{value}'"),
+            "Use x,y,width,height in image pixels, or a ref such as @s8f3k2p9:e4.",
+        )),
+    }
+}
+
+/// Maps every region into a `width` x `height` image. `ref_placement`
+/// says where a ref's screen-point bounds land in that image; regions that
+/// fall entirely outside it are dropped.
+pub(crate) fn pixel_boxes(
+    regions: &[IgnoreRegion],
+    width: u32,
+    height: u32,
+    mut ref_placement: impl FnMut(&RefEntry) -> Result<Placement, AppError>,
+) -> Result<Vec<PixelBox>, AppError> {
+    let identity = Placement {
+        origin_x: 0.0,
+        origin_y: 0.0,
+        scale: 1.0,
+    };
+    let mut boxes = Vec::new();
+    for region in regions {
+        let mapped = match region {
+            IgnoreRegion::Pixels(rect) => identity.pixel_box(rect, width, height),
+            IgnoreRegion::Ref(entry) => {
+                let placement = ref_placement(entry)?;
+                entry
+                    .geometry
+                    .bounds
+                    .and_then(|bounds| placement.pixel_box(&bounds, width, height))
+            }
+        };
+        boxes.extend(mapped);
+    }
+    Ok(boxes)
+}
+
+pub(crate) fn threshold(value: Option<f64>) -> Result<f64, AppError> {
+    match value {
+        None => Ok(DEFAULT_THRESHOLD),
+        Some(value) if (0.0..=1.0).contains(&value) => Ok(value),
+        Some(value) => Err(AppError::invalid_input(format!(
+            "--threshold must be between 0 and 1, got {value}"
+        ))),
+    }
+}
+
+/// Reads and decodes a PNG the caller named. `label` says which input it is
+/// in errors, so the path itself never reaches a trace.
+pub(crate) fn read_png(path: &Path, label: &str) -> Result<Raster, AppError> {
+    let data = crate::private_file::read_regular_bounded(path, crate::MAX_PNG_INPUT_BYTES as u64)
+        .map_err(|error| match error.kind() {
+        std::io::ErrorKind::InvalidData
+        | std::io::ErrorKind::NotFound
+        | std::io::ErrorKind::PermissionDenied => AppError::invalid_input(format!(
+            "The {label} must be a local regular PNG no larger than {} MiB",
+            crate::MAX_PNG_INPUT_BYTES / (1024 * 1024)
+        )),
+        _ => AppError::Io(error),
+    })?;
+    Raster::decode_png(&data).map_err(|error| {
+        AppError::invalid_input(format!("The {label} is not a valid PNG: {error}"))
+    })
+}
+
+/// The live window a ref was observed in, with its bounds.
+pub(crate) fn source_window(
+    entry: &RefEntry,
+    adapter: &dyn PlatformAdapter,
+    deadline: crate::Deadline,
+) -> Result<(crate::WindowInfo, Rect), AppError> {
+    let window_id = entry.source.source_window_id.as_deref().unwrap_or_default();
+    let window = adapter
+        .list_windows(
+            &WindowFilter {
+                focused_only: false,
+                app: None,
+            },
+            deadline,
+        )?
+        .into_iter()
+        .find(|window| window.id == window_id && window.pid == entry.process.pid)
+        .ok_or_else(|| {
+            AdapterError::new(
+                ErrorCode::WindowNotFound,
+                "The ref's window is no longer open",
+            )
+            .with_suggestion("Run snapshot again and use a fresh ref.")
+        })?;
+    let bounds = window.bounds.ok_or_else(|| {
+        AdapterError::new(
+            ErrorCode::ActionNotSupported,
+            "Window bounds are unavailable, so the ref cannot be located in the image",
+        )
+    })?;
+    Ok((window, bounds))
+}
@@ -30,16 +30,20 @@ pub(crate) fn wait_for_element(
     } = input;
     let start = Instant::now();
     let deadline = crate::Deadline::at(start, timeout_ms)?;
-    let (resolved_snapshot_id, local_ref) =
-        crate::ref_token::resolve_ref_target(&ref_id, snapshot_id.as_deref())?;
-    let store = RefStore::for_session(context.session_id())?;
-    let refmap = store.load_snapshot(&resolved_snapshot_id)?;
-    let entry = refmap.get(&local_ref).cloned().ok_or_else(|| {
-        AppError::invalid_input_with_suggestion(
-            format!("Ref {ref_id} is not present in the requested snapshot"),
-            "Use a snapshot-qualified ref returned by that snapshot, or pair a legacy @eN ref with its snapshot_id.",
-        )
-    })?;
+    let entry = if crate::ref_token::is_alias_token(&ref_id) {
+        crate::ref_alias::resolve(&ref_id, snapshot_id.as_deref(), context)?
+    } else {
+        let (resolved_snapshot_id, local_ref) =
+            crate::ref_token::resolve_ref_target(&ref_id, snapshot_id.as_deref())?;
+        let store = RefStore::for_session(context.session_id())?;
+        let refmap = store.load_snapshot(&resolved_snapshot_id)?;
+        refmap.get(&local_ref).cloned().ok_or_else(|| {
+            AppError::invalid_input_with_suggestion(
+                format!("Ref {ref_id} is not present in the requested snapshot"),
+                "Use a snapshot-qualified ref returned by that snapshot, or pair a legacy @eN ref with its snapshot_id.",
+            )
+        })?
+    };
 
     let mut last_observed = json!(null);
     let mut expected_bounds_hash = None;
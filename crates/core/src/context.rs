@@ -18,6 +18,14 @@ pub struct CommandContext {
     trace: TraceConfig,
     artifacts_full: bool,
     interaction_policy: InteractionPolicy,
+    heal: bool,
+    follow_up: ActionFollowUp,
+}
+
+/// Per-command action state that a batch item never inherits from its
+/// parent: the post-action wait and the signal baseline captured for it.
+#[derive(Debug, Clone, Default)]
+struct ActionFollowUp {
     wait_selector: Option<WaitSelector>,
     event_baseline: Option<Result<SignalBaseline, AdapterError>>,
 }
@@ -101,8 +109,8 @@ impl CommandContext {
             trace: TraceConfig::build(trace_path, segment_dir, trace_strict)?,
             artifacts_full,
             interaction_policy: InteractionPolicy::headless(),
-            wait_selector: None,
-            event_baseline: None,
+            heal: false,
+            follow_up: ActionFollowUp::default(),
         })
     }
 
@@ -120,20 +128,31 @@ impl CommandContext {
         self
     }
 
+    /// Opts ref actions into re-identifying a stale ref by its stored
+    /// identity instead of failing with `STALE_REF`.
+    pub fn with_heal(mut self, heal: bool) -> Self {
+        self.heal = heal;
+        self
+    }
+
+    pub fn heal_enabled(&self) -> bool {
+        self.heal
+    }
+
     pub fn with_wait_selector(mut self, wait_selector: Option<WaitSelector>) -> Self {
-        self.wait_selector = wait_selector;
+        self.follow_up.wait_selector = wait_selector;
         self
     }
 
     pub fn wait_selector(&self) -> Option<&WaitSelector> {
-        self.wait_selector.as_ref()
+        self.follow_up.wait_selector.as_ref()
     }
 
     pub fn with_event_baseline(
         mut self,
         baseline: Option<Result<SignalBaseline, AdapterError>>,
     ) -> Self {
-        self.event_baseline = baseline;
+        self.follow_up.event_baseline = baseline;
         self
     }
 
@@ -143,33 +162,61 @@ impl CommandContext {
     }
 
     pub fn event_baseline(&self) -> Option<&Result<SignalBaseline, AdapterError>> {
-        self.event_baseline.as_ref()
+        self.follow_up.event_baseline.as_ref()
     }
 
     pub fn command_scope(&self, command: &'static str) -> Result<CommandScope<'_>, AppError> {
-        self.command_scope_with_disposition(command, crate::DeliverySemantics::not_delivered())
+        self.open_command_scope(
+            command,
+            crate::DeliverySemantics::not_delivered(),
+            json!({ "command": command }),
+        )
     }
 
     pub fn mutating_command_scope(
         &self,
         command: &'static str,
     ) -> Result<CommandScope<'_>, AppError> {
-        self.command_scope_with_disposition(
+        self.open_command_scope(
             command,
             crate::DeliverySemantics::delivered_unverified(),
+            json!({ "command": command }),
         )
     }
 
-    fn command_scope_with_disposition(
+    /// Like [`Self::command_scope`], with `command.start` also recording the
+    /// command's batch-shaped `args` so `trace replay` can run it again.
+    /// `None` records `args_omitted` instead, for arguments too large to keep.
+    pub fn replayable_command_scope(
+        &self,
+        command: &'static str,
+        mutating: bool,
+        args: Option<Value>,
+    ) -> Result<CommandScope<'_>, AppError> {
+        let mut fields = json!({ "command": command });
+        match args {
+            Some(args) if self.trace_enabled() => fields["args"] = args,
+            Some(_) => {}
+            None => fields["args_omitted"] = json!(true),
+        }
+        let disposition = if mutating {
+            crate::DeliverySemantics::delivered_unverified()
+        } else {
+            crate::DeliverySemantics::not_delivered()
+        };
+        self.open_command_scope(command, disposition, fields)
+    }
+
+    fn open_command_scope(
         &self,
         command: &'static str,
         success_disposition: crate::DeliverySemantics,
+        start_fields: Value,
     ) -> Result<CommandScope<'_>, AppError> {
         let deadline_scope = crate::deadline::enter_scope(self.inherited_deadline);
-        self.trace("command.start", json!({ "command": command }))
-            .map_err(|error| {
-                trace_error_with_disposition(error, crate::DeliverySemantics::not_delivered())
-            })?;
+        self.trace("command.start", start_fields).map_err(|error| {
+            trace_error_with_disposition(error, crate::DeliverySemantics::not_delivered())
+        })?;
         Ok(CommandScope {
             context: self,
             command,
@@ -231,8 +278,8 @@ impl CommandContext {
             trace,
             artifacts_full,
             interaction_policy: self.interaction_policy,
-            wait_selector: None,
-            event_baseline: None,
+            heal: self.heal,
+            follow_up: ActionFollowUp::default(),
         })
     }
 
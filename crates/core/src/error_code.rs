@@ -18,6 +18,7 @@ pub enum ErrorCode {
     SnapshotNotFound,
     PolicyDenied,
     AppUnresponsive,
+    AssertionFailed,
     Internal,
 }
 
@@ -39,6 +40,7 @@ impl ErrorCode {
             Self::SnapshotNotFound => "SNAPSHOT_NOT_FOUND",
             Self::PolicyDenied => "POLICY_DENIED",
             Self::AppUnresponsive => "APP_UNRESPONSIVE",
+            Self::AssertionFailed => "ASSERTION_FAILED",
             Self::Internal => "INTERNAL",
         }
     }
@@ -8,5 +8,7 @@ pub enum IdentifierKind {
     AutomationId,
     RuntimeId,
     AtspiObjectPath,
+    /// A DOM node in a page reached over DevTools: `<target id>:<backend node id>`.
+    CdpNode,
     Unknown,
 }
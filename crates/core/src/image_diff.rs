@@ -0,0 +1,120 @@
+use crate::raster::{PixelBox, Raster};
+use serde_json::{Value, json};
+
+/// Share of differing pixels a comparison tolerates when no threshold is given.
+pub(crate) const DEFAULT_THRESHOLD: f64 = 0.001;
+
+/// Largest YIQ distance between two colors (black against white).
+const MAX_YIQ_DELTA: f64 = 35215.0;
+
+/// Per-pixel tolerance as a fraction of [`MAX_YIQ_DELTA`]'s square root.
+/// Anti-aliasing and subpixel rendering noise stays below it.
+const PIXEL_TOLERANCE: f64 = 0.1;
+
+const DIFF_COLOR: [u8; 4] = [255, 0, 0, 255];
+const IGNORED_COLOR: [u8; 4] = [170, 200, 255, 255];
+
+/// The outcome of comparing two images pixel by pixel. `score` is the share
+/// of compared pixels that differ perceptibly: `0.0` is identical and `1.0`
+/// is entirely different. `mask` paints differing pixels red, ignored ones
+/// blue, and the rest as a faded copy of the expected image.
+pub(crate) struct ImageDiff {
+    pub score: f64,
+    pub mismatched_pixels: u64,
+    pub compared_pixels: u64,
+    pub ignored_pixels: u64,
+    pub dimensions_match: bool,
+    pub mask: Raster,
+}
+
+impl ImageDiff {
+    pub(crate) fn passes(&self, threshold: f64) -> bool {
+        self.score <= threshold
+    }
+
+    pub(crate) fn summary(&self, threshold: f64) -> Value {
+        json!({
+            "passed": self.passes(threshold),
+            "score": self.score,
+            "threshold": threshold,
+            "mismatched_pixels": self.mismatched_pixels,
+            "compared_pixels": self.compared_pixels,
+            "ignored_pixels": self.ignored_pixels,
+            "dimensions_match": self.dimensions_match,
+            "width": self.mask.width,
+            "height": self.mask.height,
+        })
+    }
+}
+
+/// Compares `actual` against `expected` over the area the two cover
+/// together, so a size change counts the uncovered pixels as different
+/// rather than failing outright. Pixels inside `ignore` are not compared.
+pub(crate) fn compare(expected: &Raster, actual: &Raster, ignore: &[PixelBox]) -> ImageDiff {
+    let width = expected.width.max(actual.width);
+    let height = expected.height.max(actual.height);
+    let mut mask = Raster {
+        width,
+        height,
+        rgba: vec![0; width as usize * height as usize * 4],
+    };
+    let limit = PIXEL_TOLERANCE * PIXEL_TOLERANCE * MAX_YIQ_DELTA;
+    let (mut mismatched, mut ignored) = (0u64, 0u64);
+    for y in 0..height {
+        for x in 0..width {
+            if ignore.iter().any(|area| area.contains(x, y)) {
+                ignored += 1;
+                mask.set_pixel(x, y, IGNORED_COLOR);
+                continue;
+            }
+            let inside = |image: &Raster| x < image.width && y < image.height;
+            let left = inside(expected).then(|| over_white(expected.pixel(x, y)));
+            let right = inside(actual).then(|| over_white(actual.pixel(x, y)));
+            match (left, right) {
+                (Some(left), Some(right)) if yiq_delta(left, right) <= limit => {
+                    mask.set_pixel(x, y, faded(left));
+                }
+                _ => {
+                    mismatched += 1;
+                    mask.set_pixel(x, y, DIFF_COLOR);
+                }
+            }
+        }
+    }
+    let compared = u64::from(width) * u64::from(height) - ignored;
+    ImageDiff {
+        score: if compared == 0 {
+            0.0
+        } else {
+            mismatched as f64 / compared as f64
+        },
+        mismatched_pixels: mismatched,
+        compared_pixels: compared,
+        ignored_pixels: ignored,
+        dimensions_match: expected.width == actual.width && expected.height == actual.height,
+        mask,
+    }
+}
+
+fn over_white([r, g, b, a]: [u8; 4]) -> [f64; 3] {
+    let alpha = f64::from(a) / 255.0;
+    [r, g, b].map(|channel| 255.0 + (f64::from(channel) - 255.0) * alpha)
+}
+
+fn yiq_delta(left: [f64; 3], right: [f64; 3]) -> f64 {
+    let [r, g, b] = [0, 1, 2].map(|channel| left[channel] - right[channel]);
+    let y = r * 0.298_895_31 + g * 0.586_622_47 + b * 0.114_482_23;
+    let i = r * 0.595_977_99 - g * 0.274_176_10 - b * 0.321_801_89;
+    let q = r * 0.211_470_17 - g * 0.522_617_11 + b * 0.311_146_94;
+    0.5053 * y * y + 0.299 * i * i + 0.1957 * q * q
+}
+
+fn faded([r, g, b]: [f64; 3]) -> [u8; 4] {
+    let luma = r * 0.298_895_31 + g * 0.586_622_47 + b * 0.114_482_23;
+    let gray = (255.0 - (255.0 - luma) * 0.1).round() as u8;
+    [gray, gray, gray, 255]
+}
+
+#[cfg(test)]
+#[path = "image_diff_tests.rs"]
+mod tests;
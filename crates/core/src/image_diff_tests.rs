@@ -0,0 +1,89 @@
+use super::*;
+
+fn solid(width: u32, height: u32, color: [u8; 4]) -> Raster {
+    Raster {
+        width,
+        height,
+        rgba: color.repeat(width as usize * height as usize),
+    }
+}
+
+#[test]
+fn identical_images_score_zero_and_pass_any_threshold() {
+    let image = solid(4, 3, [10, 120, 200, 255]);
+
+    let diff = compare(&image, &image.clone(), &[]);
+
+    assert_eq!(diff.score, 0.0);
+    assert_eq!(diff.mismatched_pixels, 0);
+    assert_eq!(diff.compared_pixels, 12);
+    assert!(diff.dimensions_match);
+    assert!(diff.passes(0.0));
+}
+
+#[test]
+fn changed_pixels_are_scored_and_painted_red_in_the_mask() {
+    let expected = solid(4, 4, [255, 255, 255, 255]);
+    let mut actual = expected.clone();
+    actual.set_pixel(1, 2, [0, 0, 0, 255]);
+    actual.set_pixel(3, 3, [0, 0, 0, 255]);
+
+    let diff = compare(&expected, &actual, &[]);
+
+    assert_eq!(diff.mismatched_pixels, 2);
+    assert_eq!(diff.score, 2.0 / 16.0);
+    assert!(!diff.passes(DEFAULT_THRESHOLD));
+    assert!(diff.passes(0.125));
+    assert_eq!(diff.mask.pixel(1, 2), DIFF_COLOR);
+    assert_eq!(diff.mask.pixel(0, 0), [255, 255, 255, 255]);
+}
+
+#[test]
+fn imperceptible_color_noise_is_not_a_difference() {
+    let expected = solid(2, 2, [100, 100, 100, 255]);
+    let actual = solid(2, 2, [104, 101, 99, 255]);
+
+    assert_eq!(compare(&expected, &actual, &[]).mismatched_pixels, 0);
+}
+
+#[test]
+fn transparent_pixels_compare_as_if_drawn_over_white() {
+    let expected = solid(1, 1, [0, 0, 0, 0]);
+    let actual = solid(1, 1, [255, 255, 255, 255]);
+
+    assert_eq!(compare(&expected, &actual, &[]).score, 0.0);
+}
+
+#[test]
+fn ignored_regions_are_excluded_from_the_score() {
+    let expected = solid(4, 2, [255, 255, 255, 255]);
+    let mut actual = expected.clone();
+    actual.set_pixel(0, 0, [0, 0, 0, 255]);
+    actual.set_pixel(1, 1, [0, 0, 0, 255]);
+    let ignore = [PixelBox {
+        x: 0,
+        y: 0,
+        width: 2,
+        height: 2,
+    }];
+
+    let diff = compare(&expected, &actual, &ignore);
+
+    assert_eq!(diff.ignored_pixels, 4);
+    assert_eq!(diff.compared_pixels, 4);
+    assert_eq!(diff.score, 0.0);
+    assert_eq!(diff.mask.pixel(0, 0), IGNORED_COLOR);
+}
+
+#[test]
+fn size_changes_count_uncovered_pixels_as_different() {
+    let expected = solid(4, 2, [255, 255, 255, 255]);
+    let actual = solid(2, 2, [255, 255, 255, 255]);
+
+    let diff = compare(&expected, &actual, &[]);
+
+    assert!(!diff.dimensions_match);
+    assert_eq!((diff.mask.width, diff.mask.height), (4, 2));
+    assert_eq!(diff.score, 0.5);
+    assert_eq!(diff.summary(0.5)["passed"], true);
+}
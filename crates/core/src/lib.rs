@@ -1,5 +1,6 @@
 #![cfg_attr(not(test), deny(clippy::unwrap_used, clippy::expect_used))]
 
+mod a11y_audit;
 mod accname;
 pub mod action;
 pub mod action_request;
@@ -14,7 +15,14 @@ mod app_error;
 mod app_info;
 mod app_lookup;
 pub mod capability;
+mod cdp_action;
 pub mod cdp_endpoint;
+mod cdp_page;
+mod cdp_registry;
+mod cdp_socket;
+#[cfg(test)]
+mod cdp_test_support;
+mod cdp_tree;
 mod clipboard_content;
 mod clipboard_format;
 pub mod commands;
@@ -40,6 +48,7 @@ pub mod hit_test;
 mod identifier_kind;
 mod identity_predicate;
 mod image_buffer;
+mod image_diff;
 mod image_format;
 mod interaction_lease;
 pub mod interaction_policy;
@@ -76,6 +85,8 @@ mod process_identity;
 #[cfg(unix)]
 mod process_lease_guard;
 pub mod process_state;
+mod raster;
+mod read_text;
 mod recovery_hint;
 mod rect;
 pub mod ref_action;
@@ -87,6 +98,7 @@ mod ref_action_wait;
 mod ref_action_wait_context;
 mod ref_action_wait_evidence;
 mod ref_action_wait_support;
+mod ref_alias;
 pub mod ref_alloc;
 mod ref_alloc_config;
 mod ref_alloc_options;
@@ -97,6 +109,7 @@ mod ref_capabilities;
 mod ref_entry;
 mod ref_entry_identity;
 mod ref_geometry;
+mod ref_heal;
 pub mod ref_identity;
 mod ref_identity_match;
 mod ref_process;
@@ -112,13 +125,19 @@ mod refs_test_support;
 mod refs_validate;
 mod renderer_accessibility;
 mod renderer_kind;
+pub mod replay_refs;
 mod resolve_attempt_outcome;
 mod retry_disposition;
 mod retryability;
 mod role;
 pub mod roles;
+mod screenshot_annotate;
+mod screenshot_annotate_draw;
 pub mod screenshot_target;
+pub mod script_refs;
 pub(crate) mod search_text;
+mod secret_source;
+mod secure_mask;
 pub mod session;
 mod session_affinity;
 mod signal_baseline;
@@ -126,17 +145,28 @@ mod signal_completeness;
 mod signal_filter;
 pub(crate) mod signals;
 pub mod snapshot;
+mod snapshot_budget;
+pub mod snapshot_extras;
+mod snapshot_format;
+mod snapshot_outline;
 pub mod snapshot_ref;
+pub mod snapshot_stable;
 pub mod snapshot_surface;
+mod snapshot_yaml;
 pub mod state;
 mod state_predicate;
 pub(crate) mod state_root;
 pub mod step_mechanism;
+pub mod surface_handler;
 mod surface_info;
 mod surface_signal;
+mod table_extract;
 pub(crate) mod trace;
 mod trace_artifact_budget;
 pub(crate) mod trace_artifacts;
+pub mod trace_diff;
+mod trace_line;
+mod trace_quota;
 pub mod trace_read;
 pub mod trace_sanitize;
 mod trace_state;
@@ -251,6 +281,7 @@ pub use signal_baseline::SignalBaseline;
 pub use signal_completeness::SignalCompleteness;
 pub use signal_filter::SignalFilter;
 pub use signals::diff_signals;
+pub use snapshot_format::SnapshotFormat;
 pub use snapshot_surface::SnapshotSurface;
 pub use state_predicate::StatePredicate;
 pub use state_root::validate_state_root_env;
@@ -6,6 +6,8 @@ pub struct LocatorMatchData {
     pub role: String,
     pub name: String,
     pub value: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub value_masked: bool,
     pub states: Vec<String>,
     pub interactive: bool,
     pub path: Vec<String>,
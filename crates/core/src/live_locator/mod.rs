@@ -80,6 +80,7 @@ pub use observation_source::ObservationSource;
 pub use observed_node::ObservedNode;
 pub use observed_subtree::ObservedSubtree;
 pub use observed_tree::ObservedTree;
+pub(crate) use predicate::normalize_query;
 pub use ref_evidence_requirements::RefEvidenceRequirements;
 pub use resolve::{find_first_entry, resolve_query};
 pub use validate::{validate_query, validate_request};
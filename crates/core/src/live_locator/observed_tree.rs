@@ -53,6 +53,12 @@ impl ObservedTree {
         self.structurally_complete
     }
 
+    /// Confirms every node has one parent and siblings arrive in native
+    /// document order, so a preorder walk of the projection is reading order.
+    pub(crate) fn validate_document_order(&self) -> Result<(), AdapterError> {
+        super::tree_order::validated_postorder(self).map(|_| ())
+    }
+
     pub fn into_accessibility_tree(self) -> Result<AccessibilityNode, AdapterError> {
         if !self.structurally_complete {
             return Err(AdapterError::new(
@@ -158,6 +164,7 @@ impl ObservedTree {
                 value: node.evidence.value.meaningful_string(),
                 description: node.evidence.description.meaningful_string(),
                 native_id: node.evidence.identifiers.preferred_identifier().cloned(),
+                value_masked: false,
             },
             presentation: crate::NodePresentation {
                 hint: None,
@@ -35,11 +35,12 @@ pub(crate) fn match_data(
         .known()
         .cloned()
         .unwrap_or_else(|| "unknown".into());
+    let value = node.evidence.value.meaningful_string();
     let name = node
         .evidence
         .name
         .meaningful_string()
-        .or_else(|| node.evidence.value.meaningful_string())
+        .or_else(|| value.clone())
         .or_else(|| node.evidence.description.meaningful_string())
         .unwrap_or_else(|| format!("(unnamed {role})"));
     let mut ancestor_indices = Vec::new();
@@ -58,7 +59,8 @@ pub(crate) fn match_data(
         ref_id: node.ref_id.clone(),
         role,
         name,
-        value: node.evidence.value.meaningful_string(),
+        value,
+        value_masked: false,
         states: node.evidence.states.known().cloned().unwrap_or_default(),
         interactive: super::materialize::addressability(&node.evidence).0,
         path,
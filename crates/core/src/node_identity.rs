@@ -10,4 +10,8 @@ pub struct NodeIdentity {
     pub description: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub native_id: Option<crate::ElementIdentifier>,
+    /// Set when `value` was withheld because the node is a secure text field
+    /// or matches a configured secure locator.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub value_masked: bool,
 }
@@ -1,5 +1,6 @@
 use crate::AccessibilityNode;
 
+#[derive(Clone, Copy)]
 pub struct NodeMatchContext<'a> {
     pub role: &'a str,
     pub name: Option<&'a str>,
@@ -52,7 +52,7 @@ pub(crate) fn read_regular_bounded(path: &Path, max_bytes: u64) -> std::io::Resu
 pub(crate) fn write_atomic(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
     write_atomic_with(
         path,
-        bytes,
+        |file| file.write_all(bytes),
         crate::private_file_parent::ensure_private,
         sync_directory,
         validate_private_destination,
@@ -60,9 +60,18 @@ pub(crate) fn write_atomic(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
 }
 
 pub(crate) fn write_user_atomic(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    write_user_atomic_streamed(path, |file| file.write_all(bytes))
+}
+
+/// [`write_user_atomic`] for output too large to hold in memory: `write`
+/// streams it into the temporary file that replaces `path`.
+pub(crate) fn write_user_atomic_streamed(
+    path: &Path,
+    write: impl FnOnce(&mut File) -> std::io::Result<()>,
+) -> std::io::Result<()> {
     write_atomic_with(
         path,
-        bytes,
+        write,
         crate::private_file_parent::ensure_user,
         sync_user_directory,
         validate_user_destination,
@@ -71,7 +80,7 @@ pub(crate) fn write_user_atomic(path: &Path, bytes: &[u8]) -> std::io::Result<()
 
 fn write_atomic_with(
     path: &Path,
-    bytes: &[u8],
+    write: impl FnOnce(&mut File) -> std::io::Result<()>,
     ensure_parent: fn(&Path) -> std::io::Result<()>,
     sync_parent: fn(&Path) -> std::io::Result<()>,
     validate_destination: fn(&Path) -> std::io::Result<()>,
@@ -83,7 +92,7 @@ fn write_atomic_with(
     validate_destination(path)?;
     let (temporary, mut file) = create_temporary(path)?;
     let result = (|| {
-        file.write_all(bytes)?;
+        write(&mut file)?;
         file.sync_all()?;
         #[cfg(test)]
         crash_before_rename_if_requested(path);
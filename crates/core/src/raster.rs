@@ -0,0 +1,144 @@
+use crate::{AppError, MAX_PNG_INPUT_BYTES, Rect};
+
+/// A rectangle in image pixels, already clipped to the image it belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct PixelBox {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl PixelBox {
+    pub(crate) fn contains(&self, x: u32, y: u32) -> bool {
+        x >= self.x && y >= self.y && x - self.x < self.width && y - self.y < self.height
+    }
+}
+
+/// Where a capture sits on screen: `origin` is the top-left corner of the
+/// image in screen points and `scale` converts points to image pixels.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Placement {
+    pub origin_x: f64,
+    pub origin_y: f64,
+    pub scale: f64,
+}
+
+impl Placement {
+    pub(crate) fn new(origin: &Rect, scale: f64) -> Self {
+        Self {
+            origin_x: origin.x,
+            origin_y: origin.y,
+            scale: if scale.is_finite() && scale > 0.0 {
+                scale
+            } else {
+                1.0
+            },
+        }
+    }
+
+    /// Maps screen-point bounds into the image, clipped to its edges.
+    /// Returns `None` when nothing of the element is inside the capture.
+    pub(crate) fn pixel_box(&self, bounds: &Rect, width: u32, height: u32) -> Option<PixelBox> {
+        let to_pixels = |points: f64, origin: f64, limit: u32| {
+            ((points - origin) * self.scale)
+                .round()
+                .clamp(0.0, f64::from(limit)) as u32
+        };
+        let left = to_pixels(bounds.x, self.origin_x, width);
+        let top = to_pixels(bounds.y, self.origin_y, height);
+        let right = to_pixels(bounds.x + bounds.width, self.origin_x, width);
+        let bottom = to_pixels(bounds.y + bounds.height, self.origin_y, height);
+        (right > left && bottom > top).then_some(PixelBox {
+            x: left,
+            y: top,
+            width: right - left,
+            height: bottom - top,
+        })
+    }
+}
+
+/// A decoded RGBA8 image. Screenshots are decoded into this form before
+/// they are drawn on, cropped, or compared, and encoded back to PNG after.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Raster {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+impl Raster {
+    /// Decodes any PNG color type and bit depth into 8-bit RGBA.
+    pub(crate) fn decode_png(data: &[u8]) -> Result<Self, png::DecodingError> {
+        let mut decoder = png::Decoder::new_with_limits(
+            std::io::Cursor::new(data),
+            png::Limits {
+                bytes: MAX_PNG_INPUT_BYTES * 4,
+            },
+        );
+        decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+        let mut reader = decoder.read_info()?;
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let frame = reader.next_frame(&mut pixels)?;
+        pixels.truncate(frame.buffer_size());
+        let rgba = match frame.color_type {
+            png::ColorType::Rgb => expand(&pixels, 3, |px| [px[0], px[1], px[2], 255]),
+            png::ColorType::GrayscaleAlpha => expand(&pixels, 2, |px| [px[0], px[0], px[0], px[1]]),
+            png::ColorType::Grayscale => expand(&pixels, 1, |px| [px[0], px[0], px[0], 255]),
+            png::ColorType::Rgba | png::ColorType::Indexed => pixels,
+        };
+        Ok(Self {
+            width: frame.width,
+            height: frame.height,
+            rgba,
+        })
+    }
+
+    pub(crate) fn encode_png(&self) -> Result<Vec<u8>, AppError> {
+        let mut data = Vec::new();
+        let mut encoder = png::Encoder::new(&mut data, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(encode_error)?;
+        writer.write_image_data(&self.rgba).map_err(encode_error)?;
+        writer.finish().map_err(encode_error)?;
+        Ok(data)
+    }
+
+    pub(crate) fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let offset = (y as usize * self.width as usize + x as usize) * 4;
+        self.rgba
+            .get(offset..offset + 4)
+            .and_then(|pixel| pixel.try_into().ok())
+            .unwrap_or([0, 0, 0, 0])
+    }
+
+    pub(crate) fn set_pixel(&mut self, x: u32, y: u32, color: [u8; 4]) {
+        let offset = (y as usize * self.width as usize + x as usize) * 4;
+        if let Some(pixel) = self.rgba.get_mut(offset..offset + 4) {
+            pixel.copy_from_slice(&color);
+        }
+    }
+
+    pub(crate) fn crop(&self, area: PixelBox) -> Self {
+        let mut rgba = Vec::with_capacity(area.width as usize * area.height as usize * 4);
+        for y in area.y..area.y + area.height {
+            for x in area.x..area.x + area.width {
+                rgba.extend_from_slice(&self.pixel(x, y));
+            }
+        }
+        Self {
+            width: area.width,
+            height: area.height,
+            rgba,
+        }
+    }
+}
+
+fn expand(pixels: &[u8], channels: usize, convert: impl Fn(&[u8]) -> [u8; 4]) -> Vec<u8> {
+    pixels.chunks_exact(channels).flat_map(convert).collect()
+}
+
+fn encode_error(error: png::EncodingError) -> AppError {
+    AppError::Internal(format!("PNG could not be encoded: {error}"))
+}
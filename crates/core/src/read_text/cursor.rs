@@ -0,0 +1,82 @@
+use std::hash::Hasher;
+
+use crate::AppError;
+
+/// One size-bounded slice of rendered blocks.
+#[derive(Debug)]
+pub(crate) struct TextPage {
+    pub markdown: String,
+    pub start: usize,
+    pub end: usize,
+    pub next_cursor: Option<String>,
+}
+
+/// Takes blocks from the cursor position until the next one would push the
+/// page past `max_chars`; a page always holds at least one block so a read
+/// always makes progress. Cursors are `<index>.<hash>` where the hash is of
+/// the last block returned: when content above the cursor changed between
+/// calls, the page resumes after the block with that hash instead.
+pub(crate) fn page(
+    blocks: &[String],
+    cursor: Option<&str>,
+    max_chars: usize,
+) -> Result<TextPage, AppError> {
+    let start = match cursor {
+        Some(cursor) => resume_index(blocks, cursor)?,
+        None => 0,
+    };
+    let mut end = start;
+    let mut used = 0;
+    while let Some(block) = blocks.get(end) {
+        let cost = block.chars().count() + if end > start { 2 } else { 0 };
+        if end > start && used + cost > max_chars {
+            break;
+        }
+        used += cost;
+        end += 1;
+    }
+    let next_cursor =
+        (end < blocks.len()).then(|| format!("{end}.{}", fingerprint(&blocks[end - 1])));
+    Ok(TextPage {
+        markdown: blocks[start..end].join("\n\n"),
+        start,
+        end,
+        next_cursor,
+    })
+}
+
+fn resume_index(blocks: &[String], cursor: &str) -> Result<usize, AppError> {
+    let parsed = cursor
+        .split_once('.')
+        .and_then(|(index, hash)| Some((index.parse::<usize>().ok()?, hash)));
+    let Some((index, hash)) = parsed.filter(|(index, _)| *index > 0) else {
+        return Err(AppError::invalid_input_with_suggestion(
+            format!("Malformed --cursor '{cursor}'"),
+            "Pass the next_cursor value from a previous read-text response.",
+        ));
+    };
+    if blocks
+        .get(index - 1)
+        .is_some_and(|block| fingerprint(block) == hash)
+    {
+        return Ok(index);
+    }
+    blocks
+        .iter()
+        .enumerate()
+        .filter(|(_, block)| fingerprint(block) == hash)
+        .min_by_key(|(position, _)| position.abs_diff(index - 1))
+        .map(|(position, _)| position + 1)
+        .ok_or_else(|| {
+            AppError::invalid_input_with_suggestion(
+                "The text changed since this --cursor was issued",
+                "Run read-text without --cursor to read from the beginning.",
+            )
+        })
+}
+
+fn fingerprint(block: &str) -> String {
+    let mut hasher = rustc_hash::FxHasher::default();
+    hasher.write(block.as_bytes());
+    format!("{:08x}", hasher.finish() as u32)
+}
@@ -0,0 +1,297 @@
+mod cursor;
+
+pub(crate) use cursor::page;
+
+use crate::AccessibilityNode;
+
+const SKIPPED_ROLES: &[&str] = &[
+    "button",
+    "menubutton",
+    "menu",
+    "menuitem",
+    "toolbar",
+    "scrollbar",
+    "splitter",
+    "separator",
+    "tablist",
+    "incrementor",
+    "handle",
+    "disclosure",
+];
+const FIELD_ROLES: &[&str] = &[
+    "textfield",
+    "combobox",
+    "slider",
+    "datefield",
+    "timefield",
+    "levelindicator",
+    "progressbar",
+];
+const TOGGLE_ROLES: &[&str] = &["checkbox", "switch", "radiobutton"];
+const LIST_ROLES: &[&str] = &["list", "listbox", "outline"];
+const DOCUMENT_ROOTS: &[&str] = &["window", "sheet", "dialog", "alertdialog", "popover"];
+
+/// Renders a tree as markdown blocks in document order. The observation layer
+/// validates that children arrive in native order (see
+/// `ObservedTree::validate_document_order`), so a preorder walk is the
+/// reading order. Each block is one heading, paragraph, list item, field, or
+/// table; a block identical to its predecessor is dropped. Hidden and
+/// offscreen nodes are left out, as `is visible` would report them.
+pub(crate) fn render_blocks(root: &AccessibilityNode) -> Vec<String> {
+    let mut renderer = Renderer::default();
+    if DOCUMENT_ROOTS.contains(&root.role.as_str()) {
+        if let Some(title) = root
+            .identity
+            .name
+            .as_deref()
+            .map(str::trim)
+            .filter(|title| !title.is_empty())
+        {
+            renderer.push(format!("# {title}"));
+        }
+        renderer.children(root);
+    } else {
+        renderer.visit(root);
+    }
+    renderer.blocks
+}
+
+#[derive(Default)]
+struct Renderer {
+    blocks: Vec<String>,
+    list_depth: usize,
+}
+
+impl Renderer {
+    fn push(&mut self, block: String) {
+        let trimmed = block.trim_end();
+        if trimmed.trim().is_empty() || self.blocks.last().is_some_and(|last| last == trimmed) {
+            return;
+        }
+        self.blocks.push(trimmed.to_string());
+    }
+
+    fn children(&mut self, node: &AccessibilityNode) {
+        for child in &node.children {
+            self.visit(child);
+        }
+    }
+
+    fn visit(&mut self, node: &AccessibilityNode) {
+        let role = node.role.as_str();
+        match role {
+            _ if SKIPPED_ROLES.contains(&role) || is_hidden(node) => {}
+            "heading" => self.push(heading(node)),
+            "paragraph" | "statictext" | "link" => self.push(inline_text(node)),
+            "image" => {
+                if let Some(name) = own_text(node) {
+                    self.push(format!("![{name}]"));
+                }
+            }
+            "table" | "grid" => self.push(table(node)),
+            _ if LIST_ROLES.contains(&role) => self.list(node),
+            _ if FIELD_ROLES.contains(&role) => self.push(field(node)),
+            _ if TOGGLE_ROLES.contains(&role) => self.push(toggle(node)),
+            _ => self.children(node),
+        }
+    }
+
+    fn list(&mut self, list: &AccessibilityNode) {
+        let indent = "  ".repeat(self.list_depth);
+        for item in &list.children {
+            if SKIPPED_ROLES.contains(&item.role.as_str()) || is_hidden(item) {
+                continue;
+            }
+            let text = inline_text(item);
+            if !text.is_empty() {
+                self.push(format!("{indent}- {text}"));
+            }
+            self.list_depth += 1;
+            for nested in nested_lists(item) {
+                self.list(nested);
+            }
+            self.list_depth -= 1;
+        }
+    }
+}
+
+/// The same rule `is visible` applies, read from the observed tree: a node in
+/// the `hidden` or `offscreen` state is left out with its subtree, and so is
+/// one whose bounds were read and cover no area. A node observed without
+/// bounds is kept, since its states are the only evidence available.
+fn is_hidden(node: &AccessibilityNode) -> bool {
+    let states = &node.presentation.states;
+    match node.presentation.bounds {
+        Some(bounds) => !crate::state::is_visible(Some(bounds), states),
+        None => {
+            crate::state::has_state(states, crate::state::HIDDEN)
+                || crate::state::has_state(states, crate::state::OFFSCREEN)
+        }
+    }
+}
+
+fn nested_lists(node: &AccessibilityNode) -> Vec<&AccessibilityNode> {
+    let mut found = Vec::new();
+    for child in node.children.iter().filter(|child| !is_hidden(child)) {
+        if LIST_ROLES.contains(&child.role.as_str()) {
+            found.push(child);
+        } else {
+            found.extend(nested_lists(child));
+        }
+    }
+    found
+}
+
+/// Distinct text of one node: the name, then the value and description when
+/// they add something the earlier fields do not already say.
+fn own_text(node: &AccessibilityNode) -> Option<String> {
+    let mut parts: Vec<&str> = Vec::new();
+    for candidate in [
+        &node.identity.name,
+        &node.identity.value,
+        &node.identity.description,
+    ]
+    .into_iter()
+    .flatten()
+    {
+        let candidate = candidate.trim();
+        if !candidate.is_empty() && !parts.iter().any(|part| part.contains(candidate)) {
+            parts.push(candidate);
+        }
+    }
+    (!parts.is_empty()).then(|| parts.join(" — "))
+}
+
+/// Text of a node and its descendants on one line, with links kept as
+/// markdown links. Nested lists are rendered separately as their own items.
+fn inline_text(node: &AccessibilityNode) -> String {
+    let mut parts = Vec::new();
+    collect_inline(node, &mut parts);
+    parts.join(" ")
+}
+
+fn collect_inline(node: &AccessibilityNode, parts: &mut Vec<String>) {
+    let role = node.role.as_str();
+    if SKIPPED_ROLES.contains(&role) || LIST_ROLES.contains(&role) || is_hidden(node) {
+        return;
+    }
+    if role == "link" {
+        parts.push(link(node));
+        return;
+    }
+    let before = parts.len();
+    for child in &node.children {
+        collect_inline(child, parts);
+    }
+    if parts.len() == before {
+        parts.extend(own_text(node));
+    }
+}
+
+fn link(node: &AccessibilityNode) -> String {
+    let url = node
+        .identity
+        .value
+        .as_deref()
+        .filter(|value| value.contains("://"));
+    let mut text = node.identity.name.clone().unwrap_or_default();
+    if text.trim().is_empty() {
+        let mut parts = Vec::new();
+        for child in &node.children {
+            collect_inline(child, &mut parts);
+        }
+        text = parts.join(" ");
+    }
+    match url {
+        Some(url) => format!("[{}]({url})", text.trim()),
+        None => format!("[{}]", text.trim()),
+    }
+}
+
+/// Web headings expose their level as a numeric value.
+fn heading(node: &AccessibilityNode) -> String {
+    let level = node
+        .identity
+        .value
+        .as_deref()
+        .and_then(|value| value.trim().parse::<usize>().ok())
+        .filter(|level| (1..=6).contains(level));
+    let text = match node.identity.name.as_deref().map(str::trim) {
+        Some(name) if !name.is_empty() => name.to_string(),
+        _ if level.is_some() => node
+            .children
+            .iter()
+            .map(inline_text)
+            .collect::<Vec<_>>()
+            .join(" "),
+        _ => inline_text(node),
+    };
+    format!("{} {}", "#".repeat(level.unwrap_or(2)), text.trim())
+}
+
+fn field(node: &AccessibilityNode) -> String {
+    let label = node
+        .identity
+        .name
+        .as_deref()
+        .or(node.identity.description.as_deref())
+        .map(str::trim)
+        .filter(|label| !label.is_empty())
+        .unwrap_or(node.role.as_str());
+    let secure = node
+        .presentation
+        .states
+        .iter()
+        .any(|state| state == crate::state::SECURE);
+    let value = if secure || node.identity.value_masked {
+        "(hidden)".to_string()
+    } else {
+        node.identity.value.clone().unwrap_or_default()
+    };
+    format!("**{label}:** {}", value.trim())
+}
+
+fn toggle(node: &AccessibilityNode) -> String {
+    let checked = node
+        .presentation
+        .states
+        .iter()
+        .any(|state| state == crate::state::CHECKED);
+    let mark = if checked { "x" } else { " " };
+    format!("- [{mark}] {}", own_text(node).unwrap_or_default())
+}
+
+fn table(node: &AccessibilityNode) -> String {
+    let table = crate::table_extract::extract(node);
+    let escape = |text: &str| text.replace('|', "\\|");
+    let mut lines = vec![
+        format!(
+            "| {} |",
+            table
+                .columns()
+                .iter()
+                .map(|c| escape(c))
+                .collect::<Vec<_>>()
+                .join(" | ")
+        ),
+        format!("|{}", " --- |".repeat(table.columns().len())),
+    ];
+    for row in table.padded_rows() {
+        let cells: Vec<String> = row
+            .iter()
+            .map(|cell| {
+                escape(
+                    cell.as_ref()
+                        .and_then(|cell| cell.text.as_deref())
+                        .unwrap_or_default(),
+                )
+            })
+            .collect();
+        lines.push(format!("| {} |", cells.join(" | ")));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+#[path = "mod_tests.rs"]
+mod tests;
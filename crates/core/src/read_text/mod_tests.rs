@@ -0,0 +1,194 @@
+use super::*;
+
+fn node(role: &str, name: Option<&str>, children: Vec<AccessibilityNode>) -> AccessibilityNode {
+    AccessibilityNode {
+        ref_id: None,
+        role: role.into(),
+        identity: crate::NodeIdentity {
+            name: name.map(str::to_string),
+            ..Default::default()
+        },
+        presentation: Default::default(),
+        children_count: None,
+        subtree_truncated: false,
+        children,
+    }
+}
+
+fn with_value(mut node: AccessibilityNode, value: &str) -> AccessibilityNode {
+    node.identity.value = Some(value.into());
+    node
+}
+
+fn text(value: &str) -> AccessibilityNode {
+    with_value(node("statictext", None, vec![]), value)
+}
+
+fn blocks(size: usize) -> Vec<String> {
+    (0..size).map(|index| format!("block {index}")).collect()
+}
+
+#[test]
+fn document_renders_headings_paragraphs_links_and_lists_in_order() {
+    let link = with_value(
+        node("link", Some("docs"), vec![]),
+        "https://example.com/docs",
+    );
+    let tree = node(
+        "window",
+        Some("Mail"),
+        vec![
+            with_value(node("heading", None, vec![text("Welcome")]), "1"),
+            node(
+                "paragraph",
+                None,
+                vec![text("Read the"), link, text("first.")],
+            ),
+            node(
+                "list",
+                None,
+                vec![
+                    node("group", None, vec![text("One")]),
+                    node(
+                        "group",
+                        None,
+                        vec![text("Two"), node("list", None, vec![text("Two.a")])],
+                    ),
+                ],
+            ),
+            node("toolbar", None, vec![node("button", Some("Reply"), vec![])]),
+        ],
+    );
+
+    assert_eq!(
+        render_blocks(&tree),
+        [
+            "# Mail",
+            "# Welcome",
+            "Read the [docs](https://example.com/docs) first.",
+            "- One",
+            "- Two",
+            "  - Two.a",
+        ]
+    );
+}
+
+#[test]
+fn an_untitled_window_gets_no_heading() {
+    let window = node("window", Some("  "), vec![text("Body")]);
+
+    assert_eq!(render_blocks(&window), ["Body"]);
+}
+
+#[test]
+fn repeated_name_value_and_description_are_said_once() {
+    let mut label = with_value(node("statictext", Some("Total"), vec![]), "Total");
+    label.identity.description = Some("Total: 42".into());
+    let tree = node("group", None, vec![label, text("Same"), text("Same")]);
+
+    assert_eq!(render_blocks(&tree), ["Total — Total: 42", "Same"]);
+}
+
+#[test]
+fn form_fields_render_label_value_pairs_and_hide_secure_values() {
+    let mut password = with_value(node("textfield", Some("Password"), vec![]), "hunter2");
+    password
+        .presentation
+        .states
+        .push(crate::state::SECURE.into());
+    let mut remember = node("checkbox", Some("Remember me"), vec![]);
+    remember
+        .presentation
+        .states
+        .push(crate::state::CHECKED.into());
+    let tree = node(
+        "group",
+        None,
+        vec![
+            with_value(node("textfield", Some("Email"), vec![]), "ada@example.com"),
+            password,
+            remember,
+        ],
+    );
+
+    assert_eq!(
+        render_blocks(&tree),
+        [
+            "**Email:** ada@example.com",
+            "**Password:** (hidden)",
+            "- [x] Remember me",
+        ]
+    );
+}
+
+#[test]
+fn hidden_offscreen_and_zero_area_nodes_are_left_out() {
+    let mut offscreen = node("group", None, vec![text("Below the fold")]);
+    offscreen.presentation.states = vec![crate::state::OFFSCREEN.into()];
+    let mut hidden_item = node("listitem", None, vec![text("Stale draft")]);
+    hidden_item.presentation.states = vec![crate::state::HIDDEN.into()];
+    let mut collapsed = text("Collapsed note");
+    collapsed.presentation.bounds = Some(crate::Rect {
+        x: 10.0,
+        y: 10.0,
+        width: 0.0,
+        height: 0.0,
+    });
+    let mut shown = text("Shown note");
+    shown.presentation.bounds = Some(crate::Rect {
+        x: 10.0,
+        y: 30.0,
+        width: 120.0,
+        height: 16.0,
+    });
+    let tree = node(
+        "window",
+        None,
+        vec![
+            text("Visible"),
+            offscreen,
+            node(
+                "list",
+                None,
+                vec![node("listitem", None, vec![text("Kept item")]), hidden_item],
+            ),
+            node("paragraph", None, vec![collapsed, shown]),
+        ],
+    );
+
+    assert_eq!(
+        render_blocks(&tree),
+        vec!["Visible", "- Kept item", "Shown note"]
+    );
+}
+
+#[test]
+fn pages_respect_the_budget_and_always_make_progress() {
+    let blocks = blocks(5);
+
+    let first = page(&blocks, None, 16).unwrap();
+    assert_eq!(first.markdown, "block 0\n\nblock 1");
+    let cursor = first.next_cursor.expect("more blocks remain");
+
+    let rest = page(&blocks, Some(&cursor), 1).unwrap();
+    assert_eq!((rest.start, rest.end), (2, 3));
+
+    let last = page(&blocks, None, 10_000).unwrap();
+    assert_eq!(last.end, 5);
+    assert!(last.next_cursor.is_none());
+}
+
+#[test]
+fn cursor_realigns_after_content_shifts_and_rejects_unknown_anchors() {
+    let original = blocks(4);
+    let cursor = page(&original, None, 16).unwrap().next_cursor.unwrap();
+    let mut shifted = vec!["inserted".to_string()];
+    shifted.extend(original);
+
+    let resumed = page(&shifted, Some(&cursor), 10_000).unwrap();
+    assert_eq!(resumed.markdown, "block 2\n\nblock 3");
+
+    let err = page(&["other".to_string()], Some(&cursor), 100).unwrap_err();
+    assert_eq!(err.code(), "INVALID_ARGS");
+    assert!(page(&shifted, Some("nonsense"), 100).is_err());
+}
@@ -156,7 +156,9 @@ pub(crate) fn capture_pre_artifact(
     deadline: crate::Deadline,
 ) -> crate::trace_artifacts::ArtifactOutcome {
     let Some(capture_deadline) = pre_trace_capture_deadline(deadline) else {
-        return crate::trace_artifacts::ArtifactOutcome::Skipped("dispatch_budget".into());
+        return crate::trace_artifacts::ArtifactOutcome::Skipped {
+            reason: "dispatch_budget".into(),
+        };
     };
     crate::trace_artifacts::capture_action_screenshot(
         context,
@@ -30,6 +30,17 @@ pub(crate) fn execute_poll_loop(
 ) -> Result<RefActionPollState, AdapterError> {
     let mut state = RefActionPollState::default();
     loop {
+        if crate::cdp_action::targets(context.entry) {
+            state.preflight_attempts = state.preflight_attempts.saturating_add(1);
+            match crate::cdp_action::probe(context.entry, context.ref_id, &request.action, deadline)
+            {
+                Ok(()) => return Ok(state),
+                Err(error) => {
+                    handle_actionability_failure(&mut state, error, deadline)?;
+                    continue;
+                }
+            }
+        }
         state.resolve_attempts = state.resolve_attempts.saturating_add(1);
         match resolve_within_deadline(context.adapter, context.entry, deadline) {
             ResolveAttemptOutcome::DeadlinePassed => return Err(timeout(&state, deadline)),
@@ -0,0 +1,100 @@
+use crate::{
+    AdapterError, AppError, DeliverySemantics, ErrorCode, IdentityMatch,
+    context::CommandContext,
+    live_locator::{IdentifierEvidence, LocatorField},
+    refs::RefEntry,
+    refs_store::RefStore,
+};
+use serde_json::json;
+
+/// Resolves `@name` to one element. A unique identity match in the latest
+/// snapshot wins, so the alias follows re-snapshots. With no match there,
+/// the stored entry is returned as-is and strict resolution re-identifies it
+/// in the live tree, failing with `STALE_REF` when it is gone. Several
+/// matches fail with `STALE_REF` rather than guessing.
+pub(crate) fn resolve(
+    ref_id: &str,
+    snapshot_id: Option<&str>,
+    context: &CommandContext,
+) -> Result<RefEntry, AppError> {
+    if snapshot_id.is_some() {
+        return Err(AppError::invalid_input(
+            "--snapshot does not apply to a ref alias; it always resolves against the latest snapshot",
+        ));
+    }
+    let name = ref_id.trim_start_matches('@');
+    let store = RefStore::for_session(context.session_id())?;
+    let Some(alias) = store.load_aliases()?.remove(name) else {
+        return Err(AppError::invalid_input_with_suggestion(
+            format!("Unknown ref alias '{ref_id}'"),
+            "Create it with `alias set NAME <ref>`, or run `alias list` in the same --session.",
+        ));
+    };
+    let latest_id = store.latest_snapshot_id()?;
+    let mut candidates = Vec::new();
+    if let Some(latest_id) = &latest_id {
+        let latest = store.load_snapshot(latest_id)?;
+        candidates = latest
+            .iter()
+            .filter(|(_, entry)| same_element(&alias.entry, entry))
+            .map(|(local_ref, entry)| {
+                let qualified = crate::ref_token::qualify_ref_id(latest_id, local_ref);
+                (qualified, entry.clone())
+            })
+            .collect();
+        candidates.sort_by(|left, right| left.0.cmp(&right.0));
+    }
+    let (resolved, entry) = match candidates.len() {
+        0 => ("live".to_string(), alias.entry),
+        1 => candidates.remove(0),
+        count => {
+            let refs: Vec<String> = candidates.into_iter().map(|(id, _)| id).collect();
+            return Err(AdapterError::new(
+                ErrorCode::StaleRef,
+                format!("Ref alias '{ref_id}' matches {count} elements in the latest snapshot"),
+            )
+            .with_suggestion(
+                "Re-point the alias with `alias set NAME <ref>` using one of the candidate refs",
+            )
+            .with_details(json!({ "alias": ref_id, "candidates": refs }))
+            .with_disposition(DeliverySemantics::not_delivered())
+            .into());
+        }
+    };
+    context.trace_lazy(
+        "ref.alias",
+        || json!({ "alias": ref_id, "resolved": resolved, "source_ref": alias.source_ref }),
+    )?;
+    Ok(entry)
+}
+
+/// Same process, app, and role, and the stored native identifier or stable
+/// text identity matches.
+pub(crate) fn same_element(alias: &RefEntry, candidate: &RefEntry) -> bool {
+    alias.process == candidate.process && same_identity(alias, candidate)
+}
+
+/// [`same_element`] without the process check, for matching an element
+/// across app relaunches.
+pub(crate) fn same_identity(alias: &RefEntry, candidate: &RefEntry) -> bool {
+    let field = |value: &Option<String>| {
+        value
+            .clone()
+            .map(LocatorField::Known)
+            .unwrap_or(LocatorField::Absent)
+    };
+    let identity = &candidate.identity;
+    alias.source.source_app == candidate.source.source_app
+        && alias.identity.role == identity.role
+        && crate::ref_identity::identity_match(
+            alias,
+            &field(&identity.name),
+            &field(&identity.value),
+            &field(&identity.description),
+            &IdentifierEvidence::typed(identity.native_id.clone(), Some(0), true),
+        ) == IdentityMatch::Match
+}
+
+#[cfg(test)]
+#[path = "ref_alias_tests.rs"]
+mod tests;
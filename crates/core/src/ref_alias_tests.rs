@@ -0,0 +1,145 @@
+use super::*;
+use crate::{
+    commands::alias::{AliasAction, execute},
+    commands::helpers::load_ref_entry,
+    refs::RefMap,
+    refs_test_support::HomeGuard,
+};
+use serde_json::Value;
+
+fn button(name: &str) -> RefEntry {
+    RefEntry {
+        process: crate::RefProcess {
+            pid: crate::ProcessId::new(1),
+            process_instance: Some("test-instance".into()),
+        },
+        identity: crate::RefEntryIdentity {
+            role: "button".into(),
+            name: Some(name.into()),
+            value: None,
+            description: None,
+            native_id: None,
+        },
+        geometry: crate::RefGeometry {
+            bounds: None,
+            bounds_hash: None,
+        },
+        capabilities: crate::RefCapabilities {
+            states: vec![],
+            available_actions: vec!["Click".into()],
+        },
+        source: crate::RefSource {
+            source_app: Some("Mail".into()),
+            source_window_id: Some("w-1".into()),
+            source_window_title: Some("Inbox".into()),
+            source_window_bounds_hash: None,
+            source_surface: crate::SnapshotSurface::Window,
+        },
+        scope: crate::RefScope {
+            root_ref: None,
+            path_is_absolute: false,
+            path: smallvec::smallvec![0],
+        },
+    }
+}
+
+fn snapshot(names: &[&str]) -> String {
+    let mut refmap = RefMap::new();
+    for name in names {
+        refmap.allocate(button(name));
+    }
+    RefStore::new().unwrap().save_new_snapshot(&refmap).unwrap()
+}
+
+fn set(name: &str, ref_id: &str) -> Result<Value, AppError> {
+    execute(
+        AliasAction::Set {
+            name: name.into(),
+            ref_id: ref_id.into(),
+            snapshot_id: None,
+        },
+        &CommandContext::default(),
+    )
+}
+
+#[test]
+fn an_alias_follows_its_element_into_later_snapshots() {
+    let _guard = HomeGuard::new();
+    let first = snapshot(&["Reply", "Send"]);
+    let created = set("send", &format!("@{first}:e2")).unwrap();
+    assert_eq!(created["alias"], "@send");
+    assert_eq!(created["replaced"], false);
+
+    snapshot(&["Archive", "Forward", "Send"]);
+    let entry = load_ref_entry("@send", None, &CommandContext::default()).unwrap();
+    assert_eq!(entry.identity.name.as_deref(), Some("Send"));
+
+    let listed = execute(AliasAction::List, &CommandContext::default()).unwrap();
+    assert_eq!(listed["aliases"][0]["alias"], "@send");
+    assert_eq!(listed["aliases"][0]["source_ref"], format!("@{first}:e2"));
+}
+
+#[test]
+fn an_alias_missing_from_the_latest_snapshot_falls_back_to_its_stored_identity() {
+    let _guard = HomeGuard::new();
+    let first = snapshot(&["Send"]);
+    set("send", &format!("@{first}:e1")).unwrap();
+    snapshot(&["Reply"]);
+
+    let entry = load_ref_entry("@send", None, &CommandContext::default()).unwrap();
+
+    assert_eq!(entry, button("Send"));
+}
+
+#[test]
+fn duplicate_matches_fail_as_stale_and_removed_aliases_are_unknown() {
+    let _guard = HomeGuard::new();
+    let first = snapshot(&["Send"]);
+    set("send", &format!("@{first}:e1")).unwrap();
+    snapshot(&["Send", "Send"]);
+
+    let err = load_ref_entry("@send", None, &CommandContext::default()).unwrap_err();
+    assert_eq!(err.code(), "STALE_REF");
+    let AppError::Adapter(error) = err else {
+        panic!("expected adapter error");
+    };
+    assert_eq!(
+        error.details.unwrap()["candidates"]
+            .as_array()
+            .unwrap()
+            .len(),
+        2
+    );
+
+    let removed = execute(
+        AliasAction::Rm {
+            name: "send".into(),
+        },
+        &CommandContext::default(),
+    )
+    .unwrap();
+    assert_eq!(removed["removed"], true);
+    let err = load_ref_entry("@send", None, &CommandContext::default()).unwrap_err();
+    assert_eq!(err.code(), "INVALID_ARGS");
+}
+
+#[test]
+fn aliases_reject_bad_names_and_identity_free_elements() {
+    let _guard = HomeGuard::new();
+    let mut refmap = RefMap::new();
+    let mut unnamed = button("Send");
+    unnamed.identity.name = None;
+    refmap.allocate(unnamed);
+    let snapshot_id = RefStore::new().unwrap().save_new_snapshot(&refmap).unwrap();
+
+    assert_eq!(
+        set("e7", &format!("@{snapshot_id}:e1")).unwrap_err().code(),
+        "INVALID_ARGS"
+    );
+    assert_eq!(
+        set("blank", &format!("@{snapshot_id}:e1"))
+            .unwrap_err()
+            .code(),
+        "INVALID_ARGS"
+    );
+}
@@ -0,0 +1,268 @@
+use crate::{
+    AdapterError, AppError, DeliverySemantics, ErrorCode, IdentityPredicate, LocatorQuery,
+    adapter::PlatformAdapter,
+    context::CommandContext,
+    live_locator::{
+        LocatorMaterialization, LocatorResolveRequest, LocatorSelection, ObservationRoot,
+        resolve_query,
+    },
+    output::ErrorPayload,
+    refs::RefEntry,
+    refs_store::RefStore,
+    trace_artifacts,
+};
+use serde_json::{Value, json};
+use std::time::Duration;
+
+const HEAL_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_RAW_DEPTH: u8 = 50;
+
+/// A stale ref re-identified in the live tree and saved as a fresh snapshot.
+pub(crate) struct Healed {
+    pub(crate) entry: RefEntry,
+    pub(crate) ref_id: String,
+    pub(crate) evidence: Value,
+}
+
+/// How far the candidate moved from the stale entry's position.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Ancestry {
+    Same,
+    Sibling,
+    Moved,
+    Unknown,
+}
+
+impl Ancestry {
+    /// Paths compare only when both are measured from the window root: a
+    /// subtree-relative path (rooted at a `root_ref`) says nothing here.
+    fn between(stale: &RefEntry, found: &RefEntry) -> Self {
+        let window_rooted =
+            |scope: &crate::RefScope| scope.root_ref.is_none() || scope.path_is_absolute;
+        let (from, to) = (&stale.scope, &found.scope);
+        if !window_rooted(from) || !window_rooted(to) || from.path.is_empty() {
+            return Self::Unknown;
+        }
+        if from.path == to.path {
+            Self::Same
+        } else if from.path.len() == to.path.len()
+            && from.path[..from.path.len() - 1] == to.path[..to.path.len() - 1]
+        {
+            Self::Sibling
+        } else {
+            Self::Moved
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Same => "same",
+            Self::Sibling => "sibling",
+            Self::Moved => "moved",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+/// Re-runs the stale entry's identity as a live locator in its source
+/// window. Heals only when exactly one candidate in the same process
+/// matches with high or medium confidence: a native identifier match, or
+/// an exact role and name match that stayed at, or beside, its old
+/// position. Anything else returns the reason as a `heal` detail for the
+/// original `STALE_REF`, so ambiguity still fails. The outer `Err` is a
+/// failure to trace the attempt.
+pub(crate) fn heal(
+    adapter: &dyn PlatformAdapter,
+    stale: &RefEntry,
+    ref_id: &str,
+    context: &CommandContext,
+) -> Result<Result<Healed, Value>, AppError> {
+    let outcome = attempt(adapter, stale, context);
+    context.trace_lazy("ref.heal", || match &outcome {
+        Ok(healed) => json!({ "ref": ref_id, "outcome": "healed", "healed_to": healed.ref_id }),
+        Err(refusal) => json!({ "ref": ref_id, "outcome": refusal["outcome"] }),
+    })?;
+    Ok(outcome)
+}
+
+/// Healing applies only when opted in and the action failed as `STALE_REF`
+/// before anything reached the app.
+pub(crate) fn applies(error: &AdapterError, context: &CommandContext) -> bool {
+    context.heal_enabled()
+        && error.code == ErrorCode::StaleRef
+        && error.disposition == DeliverySemantics::not_delivered()
+}
+
+/// Heals `stale` and re-delivers the action to the healed ref, adding
+/// `healed_from`, `healed_to`, and the `heal` evidence to the result. A
+/// refusal returns the original `error` with the refusal as its `heal`
+/// detail.
+pub(crate) fn retry(
+    adapter: &dyn PlatformAdapter,
+    stale: &RefEntry,
+    ref_id: &str,
+    error: AdapterError,
+    context: &CommandContext,
+    deliver: impl FnOnce(&RefEntry, &str) -> Result<Value, AppError>,
+) -> Result<Value, AppError> {
+    let healed = match heal(adapter, stale, ref_id, context)? {
+        Ok(healed) => healed,
+        Err(refusal) => return Err(with_heal_detail(error, refusal)),
+    };
+    deliver(&healed.entry, &healed.ref_id)
+        .map_err(|error| match error {
+            AppError::Adapter(error) => with_heal_detail(
+                error,
+                json!({ "outcome": "healed", "healed_to": healed.ref_id }),
+            ),
+            other => other,
+        })
+        .map(|mut value| {
+            if let Some(body) = value.as_object_mut() {
+                body.insert("healed_from".into(), json!(ref_id));
+                body.insert("healed_to".into(), json!(healed.ref_id));
+                body.insert("heal".into(), healed.evidence);
+            }
+            value
+        })
+}
+
+/// Attaches `heal` to the error's `details`.
+pub(crate) fn with_heal_detail(mut error: AdapterError, heal: Value) -> AppError {
+    let mut details = error.details.take().unwrap_or_else(|| json!({}));
+    if let Some(object) = details.as_object_mut() {
+        object.insert("heal".into(), heal);
+    }
+    AppError::Adapter(error.with_details(details))
+}
+
+fn attempt(
+    adapter: &dyn PlatformAdapter,
+    stale: &RefEntry,
+    context: &CommandContext,
+) -> Result<Healed, Value> {
+    let Some(query) = identity_query(stale) else {
+        return Err(refusal("insufficient_identity"));
+    };
+    let deadline =
+        crate::Deadline::from_duration(HEAL_TIMEOUT).map_err(|error| failed(&error.into()))?;
+    let window = crate::snapshot::resolve_window_for_surface(
+        adapter,
+        stale.source.source_app.as_deref(),
+        stale.source.source_window_id.as_deref(),
+        stale.source.source_surface,
+        deadline,
+    )
+    .map_err(|error| failed(&error))?;
+    if window.pid != stale.process.pid {
+        return Err(refusal("process_changed"));
+    }
+    let mut resolution = resolve_query(
+        adapter,
+        &query,
+        ObservationRoot::Window(&window),
+        &LocatorResolveRequest {
+            selection: LocatorSelection::All { limit: Some(2) },
+            deadline,
+            max_raw_depth: MAX_RAW_DEPTH,
+            surface: Some(stale.source.source_surface),
+            materialization: LocatorMaterialization::SelectedMatches,
+        },
+    )
+    .map_err(|error| failed(&error))?;
+    if !resolution.meta.selection_complete {
+        return Err(refusal("incomplete_observation"));
+    }
+    let total = resolution.meta.total_matches;
+    let mut candidates = std::mem::take(&mut resolution.matches).into_iter();
+    let (Some(found), None) = (candidates.next(), candidates.next()) else {
+        let reason = if total == 0 { "no_match" } else { "ambiguous" };
+        let mut detail = refusal(reason);
+        detail["candidates"] = json!(total);
+        return Err(detail);
+    };
+    let local_ref = found.data.ref_id.clone();
+    let entry = found.into_entry();
+    let (confidence, evidence) = judge(stale, &entry);
+    if confidence == "low" {
+        let mut detail = refusal("low_confidence");
+        detail["evidence"] = evidence;
+        return Err(detail);
+    }
+    let (Some(local_ref), Some(refmap)) = (local_ref, resolution.refmap.take()) else {
+        return Err(refusal("not_materialized"));
+    };
+    let store = RefStore::for_session(context.session_id()).map_err(|error| failed(&error))?;
+    let snapshot_id = store
+        .save_new_snapshot(&refmap)
+        .map_err(|error| failed(&error))?;
+    trace_artifacts::copy_refmap_if_full(context, &store, &snapshot_id, &refmap)
+        .map_err(|error| failed(&error))?;
+    Ok(Healed {
+        entry,
+        ref_id: crate::ref_token::qualify_ref_id(&snapshot_id, &local_ref),
+        evidence,
+    })
+}
+
+/// Role plus the strongest stored identity: the native identifier when the
+/// snapshot recorded one (labels may change; identifiers rarely do),
+/// otherwise the exact accessible name. `None` when neither exists.
+fn identity_query(stale: &RefEntry) -> Option<LocatorQuery> {
+    let identity = &stale.identity;
+    let (name, native_id) = match (&identity.native_id, &identity.name) {
+        (Some(id), _) => (None, Some(id.value.clone())),
+        (None, Some(name)) if !name.trim().is_empty() => (Some(name.clone()), None),
+        _ => return None,
+    };
+    Some(LocatorQuery {
+        identity: IdentityPredicate {
+            role: Some(identity.role.clone()),
+            name,
+            native_id,
+            ..IdentityPredicate::default()
+        },
+        exact: true,
+        ..LocatorQuery::default()
+    })
+}
+
+fn judge(stale: &RefEntry, found: &RefEntry) -> (&'static str, Value) {
+    let native_id =
+        stale.identity.native_id.is_some() && stale.identity.native_id == found.identity.native_id;
+    let name = stale.identity.name.is_some() && stale.identity.name == found.identity.name;
+    let ancestry = Ancestry::between(stale, found);
+    let confidence = match ancestry {
+        _ if native_id => "high",
+        Ancestry::Same if name => "high",
+        Ancestry::Sibling | Ancestry::Unknown if name => "medium",
+        _ => "low",
+    };
+    let matched_on: Vec<&str> = [("role", true), ("native_id", native_id), ("name", name)]
+        .into_iter()
+        .filter_map(|(field, matched)| matched.then_some(field))
+        .collect();
+    let evidence = json!({
+        "confidence": confidence,
+        "matched_on": matched_on,
+        "ancestry": ancestry.as_str(),
+        "role": found.identity.role,
+        "name": found.identity.name,
+        "candidates": 1,
+    });
+    (confidence, evidence)
+}
+
+fn refusal(outcome: &str) -> Value {
+    json!({ "attempted": true, "outcome": outcome })
+}
+
+fn failed(error: &AppError) -> Value {
+    let mut detail = refusal("error");
+    detail["error"] = json!(ErrorPayload::from_app_error(error));
+    detail
+}
+
+#[cfg(test)]
+#[path = "ref_heal_tests.rs"]
+mod tests;
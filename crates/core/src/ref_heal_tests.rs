@@ -0,0 +1,244 @@
+use super::*;
+use crate::{
+    AdapterError, ElementIdentifier, IdentifierKind, ProcessId, WindowInfo,
+    adapter::{ActionOps, InputOps, NativeHandle, ObservationOps, SystemOps, WindowFilter},
+    live_locator::{
+        IdentifierEvidence, LocatorEvidence, LocatorField, LocatorRefEvidence, LocatorStats,
+        ObservationRequest, ObservationSource, ObservedSubtree, ObservedTree,
+    },
+    refs_test_support::HomeGuard,
+};
+
+/// A window whose children are `(role, name)` nodes under one group.
+struct RerenderedAdapter {
+    children: Vec<(&'static str, &'static str)>,
+}
+
+fn evidence(role: &str, name: &str) -> LocatorEvidence {
+    LocatorEvidence {
+        role: LocatorField::Known(role.into()),
+        name: LocatorField::Known(name.into()),
+        description: LocatorField::Absent,
+        value: LocatorField::Absent,
+        identifiers: IdentifierEvidence::absent(),
+        states: LocatorField::Known(Vec::new()),
+        ref_evidence: LocatorRefEvidence {
+            bounds: LocatorField::Absent,
+            available_actions: LocatorField::Known(vec!["Click".into()]),
+        },
+    }
+}
+
+impl ObservationOps for RerenderedAdapter {
+    fn observe_tree(
+        &self,
+        root: ObservationRoot<'_>,
+        request: &ObservationRequest,
+    ) -> Result<ObservedTree, AdapterError> {
+        let source = ObservationSource::from_root(&root, request.surface);
+        if let ObservationRoot::Element { entry, .. } = &root {
+            let name = entry.identity.name.as_deref().unwrap_or_default();
+            return ObservedTree::from_roots(
+                vec![ObservedSubtree::new(
+                    evidence(&entry.identity.role, name),
+                    Vec::new(),
+                    true,
+                    None,
+                )],
+                source,
+                LocatorStats::default(),
+                true,
+            );
+        }
+        let leaf = |(role, name): &(&str, &str)| {
+            ObservedSubtree::new(evidence(role, name), Vec::new(), true, None)
+        };
+        let group = ObservedSubtree::new(
+            evidence("group", "Toolbar"),
+            self.children.iter().map(leaf).collect(),
+            true,
+            None,
+        );
+        ObservedTree::from_roots(
+            vec![ObservedSubtree::new(
+                evidence("window", "Editor"),
+                vec![group],
+                true,
+                None,
+            )],
+            source,
+            LocatorStats::default(),
+            true,
+        )
+    }
+
+    fn list_windows(
+        &self,
+        _filter: &WindowFilter,
+        _deadline: crate::Deadline,
+    ) -> Result<Vec<WindowInfo>, AdapterError> {
+        Ok(vec![WindowInfo {
+            id: "w-1".into(),
+            title: "Editor".into(),
+            app: "FixtureApp".into(),
+            pid: ProcessId::new(1),
+            process_instance: Some("test-instance".into()),
+            bounds: None,
+            state: crate::WindowState {
+                is_focused: true,
+                ..Default::default()
+            },
+        }])
+    }
+
+    fn resolve_locator_anchor(
+        &self,
+        _entry: &RefEntry,
+        _deadline: crate::Deadline,
+    ) -> Result<NativeHandle, AdapterError> {
+        Ok(NativeHandle::null())
+    }
+}
+
+impl ActionOps for RerenderedAdapter {}
+
+impl InputOps for RerenderedAdapter {}
+
+impl SystemOps for RerenderedAdapter {}
+
+/// The snapshot entry for `button "Save"`, second child of the toolbar.
+fn stale_save() -> RefEntry {
+    RefEntry {
+        process: crate::RefProcess {
+            pid: ProcessId::new(1),
+            process_instance: Some("test-instance".into()),
+        },
+        identity: crate::RefEntryIdentity {
+            role: "button".into(),
+            name: Some("Save".into()),
+            value: None,
+            description: None,
+            native_id: None,
+        },
+        geometry: crate::RefGeometry {
+            bounds: None,
+            bounds_hash: None,
+        },
+        capabilities: crate::RefCapabilities {
+            states: vec![],
+            available_actions: vec!["Click".into()],
+        },
+        source: crate::RefSource {
+            source_app: Some("FixtureApp".into()),
+            source_window_id: Some("w-1".into()),
+            source_window_title: Some("Editor".into()),
+            source_window_bounds_hash: None,
+            source_surface: crate::SnapshotSurface::Window,
+        },
+        scope: crate::RefScope {
+            root_ref: None,
+            path_is_absolute: false,
+            path: smallvec::smallvec![0, 1],
+        },
+    }
+}
+
+fn heal_with(children: &[(&'static str, &'static str)], stale: &RefEntry) -> Result<Healed, Value> {
+    let adapter = RerenderedAdapter {
+        children: children.to_vec(),
+    };
+    heal(&adapter, stale, "@s1:e4", &CommandContext::default()).expect("untraced heal")
+}
+
+#[test]
+fn unique_match_at_the_same_position_heals_into_a_fresh_snapshot() {
+    let _guard = HomeGuard::new();
+    let healed = heal_with(&[("button", "Open"), ("button", "Save")], &stale_save()).unwrap();
+
+    assert!(healed.ref_id.starts_with('@') && healed.ref_id.contains(':'));
+    assert_eq!(healed.entry.identity.name.as_deref(), Some("Save"));
+    assert_eq!(healed.evidence["confidence"], "high");
+    assert_eq!(healed.evidence["ancestry"], "same");
+    assert_eq!(healed.evidence["matched_on"], json!(["role", "name"]));
+}
+
+#[test]
+fn a_reordered_sibling_heals_with_medium_confidence() {
+    let _guard = HomeGuard::new();
+    let healed = heal_with(&[("button", "Save"), ("button", "Open")], &stale_save()).unwrap();
+
+    assert_eq!(healed.evidence["confidence"], "medium");
+    assert_eq!(healed.evidence["ancestry"], "sibling");
+}
+
+#[test]
+fn ambiguity_missing_identity_and_moved_matches_refuse_to_heal() {
+    let _guard = HomeGuard::new();
+    let ambiguous = heal_with(&[("button", "Save"), ("button", "Save")], &stale_save());
+    let refusal = ambiguous.err().unwrap();
+    assert_eq!(refusal["outcome"], "ambiguous");
+    assert_eq!(refusal["candidates"], 2);
+
+    let gone = heal_with(&[("button", "Open")], &stale_save())
+        .err()
+        .unwrap();
+    assert_eq!(gone["outcome"], "no_match");
+
+    let mut deep = stale_save();
+    deep.scope.path = smallvec::smallvec![2, 0, 1];
+    let moved = heal_with(&[("button", "Save")], &deep).err().unwrap();
+    assert_eq!(moved["outcome"], "low_confidence");
+    assert_eq!(moved["evidence"]["ancestry"], "moved");
+
+    let mut unnamed = stale_save();
+    unnamed.identity.name = None;
+    let blind = heal_with(&[("button", "Save")], &unnamed).err().unwrap();
+    assert_eq!(blind["outcome"], "insufficient_identity");
+}
+
+#[test]
+fn a_native_identifier_match_heals_with_high_confidence_despite_a_move() {
+    let mut stale = stale_save();
+    stale.identity.native_id = Some(ElementIdentifier {
+        kind: IdentifierKind::AutomationId,
+        value: "save-button".into(),
+    });
+    let found = RefEntry {
+        identity: crate::RefEntryIdentity {
+            name: Some("Save As".into()),
+            ..stale.identity.clone()
+        },
+        scope: crate::RefScope {
+            path: smallvec::smallvec![3],
+            ..stale.scope.clone()
+        },
+        ..stale.clone()
+    };
+
+    let (confidence, evidence) = judge(&stale, &found);
+    assert_eq!(confidence, "high");
+    assert_eq!(evidence["matched_on"], json!(["role", "native_id"]));
+    assert_eq!(evidence["ancestry"], "moved");
+}
+
+#[test]
+fn a_strict_trace_failure_is_returned_instead_of_a_heal_outcome() {
+    let _guard = HomeGuard::new();
+    let path = std::env::temp_dir().join(format!(
+        "agent-desktop-ref-heal-trace-{}.jsonl",
+        crate::refs::new_snapshot_id()
+    ));
+    let context = CommandContext::new(None, Some(path.clone()), true).unwrap();
+    std::fs::OpenOptions::new()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_len(crate::trace::MAX_TRACE_FILE_BYTES)
+        .unwrap();
+    let adapter = RerenderedAdapter {
+        children: vec![("button", "Save")],
+    };
+
+    assert!(heal(&adapter, &stale_save(), "@s1:e4", &context).is_err());
+    std::fs::remove_file(path).unwrap();
+}
@@ -29,6 +29,26 @@ pub fn has_stable_text_identity(entry: &RefEntry) -> bool {
         || meaningful_text(entry.identity.description.as_deref()).is_some()
 }
 
+/// The one `find` filter that re-locates `entry` the way [`identity_match`]
+/// compares it: its native identifier when it has one, else its stable
+/// name, value, or description. Returns the filter's field and text.
+pub fn locator_field(entry: &RefEntry) -> Option<(&'static str, &str)> {
+    let role = entry.identity.role.as_str();
+    if let Some(identifier) = entry.identity.native_id.as_ref() {
+        return meaningful_text(Some(&identifier.value)).map(|text| ("native_id", text));
+    }
+    stable_name(
+        role,
+        entry.identity.name.as_deref(),
+        entry.identity.value.as_deref(),
+    )
+    .map(|text| ("name", text))
+    .or_else(|| stable_value(role, entry.identity.value.as_deref()).map(|text| ("value", text)))
+    .or_else(|| {
+        meaningful_text(entry.identity.description.as_deref()).map(|text| ("description", text))
+    })
+}
+
 pub fn identity_match(
     entry: &RefEntry,
     actual_name: &LocatorField<String>,
@@ -239,3 +259,7 @@ fn numeric_text(value: Option<&str>) -> Option<f64> {
 #[cfg(test)]
 #[path = "ref_identity_tests.rs"]
 mod tests;
+
+#[cfg(test)]
+#[path = "ref_identity_locator_tests.rs"]
+mod locator_tests;
@@ -0,0 +1,24 @@
+use super::tests::{entry, identifier};
+use super::*;
+
+#[test]
+fn locator_field_prefers_the_identifier_then_stable_text() {
+    let mut entry = entry();
+    assert_eq!(locator_field(&entry), None);
+
+    entry.identity.description = Some("Sends the message".into());
+    assert_eq!(
+        locator_field(&entry),
+        Some(("description", "Sends the message"))
+    );
+    entry.identity.name = Some("Send".into());
+    assert_eq!(locator_field(&entry), Some(("name", "Send")));
+    entry.identity.native_id = Some(identifier("send-button"));
+    assert_eq!(locator_field(&entry), Some(("native_id", "send-button")));
+
+    let mut field = self::entry();
+    field.identity.role = "textfield".into();
+    field.identity.name = Some("draft".into());
+    field.identity.value = Some("draft".into());
+    assert_eq!(locator_field(&field), None);
+}
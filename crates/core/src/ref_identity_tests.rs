@@ -1,6 +1,6 @@
 use super::*;
 
-fn identifier(value: &str) -> crate::ElementIdentifier {
+pub(super) fn identifier(value: &str) -> crate::ElementIdentifier {
     crate::ElementIdentifier {
         kind: crate::IdentifierKind::AxIdentifier,
         value: value.into(),
@@ -11,7 +11,7 @@ use crate::{
     live_locator::{IdentifierEvidence, LocatorField},
 };
 
-fn entry() -> RefEntry {
+pub(super) fn entry() -> RefEntry {
     RefEntry {
         process: crate::RefProcess {
             pid: crate::ProcessId::new(1),
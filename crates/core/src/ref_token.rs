@@ -1,5 +1,7 @@
 use crate::{AccessibilityNode, AppError, refs::validate_snapshot_id};
 
+const MAX_ALIAS_NAME_CHARS: usize = 32;
+
 pub(crate) fn qualify_ref_id(snapshot_id: &str, local_ref: &str) -> String {
     format!(
         "@{snapshot_id}:{}",
@@ -20,6 +22,12 @@ pub(crate) fn resolve_ref_target(
     ref_id: &str,
     explicit_snapshot_id: Option<&str>,
 ) -> Result<(String, String), AppError> {
+    if is_alias_token(ref_id) {
+        return Err(AppError::invalid_input_with_suggestion(
+            format!("Ref alias '{ref_id}' cannot be used here"),
+            "Aliases resolve for commands that act on or read one element; pass a snapshot ref instead.",
+        ));
+    }
     if is_local_ref(ref_id) {
         let snapshot_id = explicit_snapshot_id.ok_or_else(|| {
             AppError::invalid_input_with_suggestion(
@@ -52,13 +60,43 @@ pub(crate) fn resolve_ref_target(
 }
 
 pub(crate) fn validate_ref_token(ref_id: &str) -> Result<(), AppError> {
-    if is_local_ref(ref_id) {
+    if is_local_ref(ref_id) || is_alias_token(ref_id) {
         return Ok(());
     }
     resolve_ref_target(ref_id, None).map(|_| ())
 }
 
-fn is_local_ref(ref_id: &str) -> bool {
+/// `@name` where `name` is a valid alias name.
+pub(crate) fn is_alias_token(ref_id: &str) -> bool {
+    ref_id
+        .strip_prefix('@')
+        .is_some_and(|name| validate_alias_name(name).is_ok())
+}
+
+/// Alias names are 1-32 lowercase ASCII letters, digits, `-`, or `_`,
+/// starting with a letter. `e` and names starting with `e` and a digit are
+/// reserved so an alias can never be mistaken for a local ref like `@e12`.
+pub(crate) fn validate_alias_name(name: &str) -> Result<(), AppError> {
+    let well_formed = name.len() <= MAX_ALIAS_NAME_CHARS
+        && name.starts_with(|character: char| character.is_ascii_lowercase())
+        && name.chars().all(|character| {
+            character.is_ascii_lowercase()
+                || character.is_ascii_digit()
+                || character == '-'
+                || character == '_'
+        });
+    let ref_shaped = name.strip_prefix('e').is_some_and(|rest| {
+        rest.is_empty() || rest.starts_with(|character: char| character.is_ascii_digit())
+    });
+    if !well_formed || ref_shaped {
+        return Err(AppError::invalid_input(format!(
+            "Invalid alias name '{name}': expected 1-{MAX_ALIAS_NAME_CHARS} lowercase letters, digits, '-' or '_', starting with a letter, and not e or e<digit>..."
+        )));
+    }
+    Ok(())
+}
+
+pub(crate) fn is_local_ref(ref_id: &str) -> bool {
     ref_id.strip_prefix("@e").is_some_and(|digits| {
         !digits.is_empty()
             && digits.len() <= 10
@@ -1,4 +1,6 @@
-use crate::ref_token::{qualify_ref_id, resolve_ref_target, validate_ref_token};
+use crate::ref_token::{
+    is_alias_token, qualify_ref_id, resolve_ref_target, validate_alias_name, validate_ref_token,
+};
 
 #[test]
 fn qualified_ref_selects_its_own_snapshot() {
@@ -37,3 +39,21 @@ fn syntax_validation_accepts_both_forms() {
     assert!(validate_ref_token("@sabc:e1").is_ok());
     assert!(validate_ref_token("@latest:e0").is_err());
 }
+
+#[test]
+fn alias_tokens_are_distinct_from_ref_forms() {
+    assert!(is_alias_token("@search"));
+    assert!(is_alias_token("@send-button_2"));
+    assert!(validate_ref_token("@search").is_ok());
+    for not_alias in [
+        "@e12", "@e", "@e0abc", "@sabc:e1", "@Search", "@2fa", "search", "@",
+    ] {
+        assert!(!is_alias_token(not_alias), "{not_alias}");
+    }
+    assert!(validate_alias_name("edit").is_ok());
+    assert!(validate_alias_name(&"a".repeat(33)).is_err());
+    assert_eq!(
+        resolve_ref_target("@search", None).unwrap_err().code(),
+        "INVALID_ARGS"
+    );
+}
@@ -76,6 +76,48 @@ impl RefMap {
         self.inner.get(ref_id)
     }
 
+    pub(crate) fn counter(&self) -> u32 {
+        self.counter
+    }
+
+    /// Rebuilds a map from explicitly numbered entries. The counter is kept
+    /// at or above `floor` so a retired number is never handed out again.
+    pub(crate) fn from_numbered(
+        entries: impl IntoIterator<Item = (u32, RefEntry)>,
+        floor: u32,
+    ) -> Result<Self, AppError> {
+        let mut map = Self::new();
+        map.counter = floor;
+        for (number, entry) in entries {
+            let ref_id = format!("@e{number}");
+            if number == 0 || map.inner.insert(ref_id, entry).is_some() {
+                return Err(AppError::Internal(format!(
+                    "Ref number {number} is invalid or assigned twice"
+                )));
+            }
+            map.counter = map.counter.max(number);
+        }
+        Ok(map)
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&String, &RefEntry)> {
+        self.inner.iter()
+    }
+
+    /// Entries keyed by their `@eN` number, in ascending order.
+    pub(crate) fn numbered(&self) -> Vec<(u32, &RefEntry)> {
+        let mut entries: Vec<(u32, &RefEntry)> = self
+            .inner
+            .iter()
+            .filter_map(|(ref_id, entry)| {
+                let number = ref_id.strip_prefix("@e")?.parse().ok()?;
+                Some((number, entry))
+            })
+            .collect();
+        entries.sort_by_key(|(number, _)| *number);
+        entries
+    }
+
     pub fn len(&self) -> usize {
         self.inner.len()
     }
@@ -84,6 +126,15 @@ impl RefMap {
         self.inner.is_empty()
     }
 
+    /// Drops the `value` of every entry `hide` selects.
+    pub(crate) fn clear_values(&mut self, hide: impl Fn(&RefEntry) -> bool) {
+        for entry in self.inner.values_mut() {
+            if hide(entry) {
+                entry.identity.value = None;
+            }
+        }
+    }
+
     pub fn remove_by_root_ref(&mut self, root: &str) {
         self.inner
             .retain(|_, entry| entry.scope.root_ref.as_deref() != Some(root));
@@ -205,19 +256,31 @@ pub(crate) fn write_private_file(path: &Path, bytes: &[u8]) -> Result<(), AppErr
 }
 
 pub(crate) fn write_user_file(path: &Path, bytes: &[u8]) -> Result<(), AppError> {
-    crate::private_file::write_user_atomic(path, bytes).map_err(|error| {
-        if error.kind() == std::io::ErrorKind::InvalidData {
-            AppError::invalid_input_with_suggestion(
-                format!("Cannot write output file: {error}"),
-                format!(
-                    "Pass an output path that is not '{}' or remove the conflicting entry there",
-                    path.display()
-                ),
-            )
-        } else {
-            AppError::from(error)
-        }
-    })
+    crate::private_file::write_user_atomic(path, bytes)
+        .map_err(|error| user_output_error(path, error))
+}
+
+/// [`write_user_file`] for output that `write` streams into the file.
+pub(crate) fn write_user_file_streamed(
+    path: &Path,
+    write: impl FnOnce(&mut std::fs::File) -> std::io::Result<()>,
+) -> Result<(), AppError> {
+    crate::private_file::write_user_atomic_streamed(path, write)
+        .map_err(|error| user_output_error(path, error))
+}
+
+fn user_output_error(path: &Path, error: std::io::Error) -> AppError {
+    if error.kind() == std::io::ErrorKind::InvalidData {
+        AppError::invalid_input_with_suggestion(
+            format!("Cannot write output file: {error}"),
+            format!(
+                "Pass an output path that is not '{}' or remove the conflicting entry there",
+                path.display()
+            ),
+        )
+    } else {
+        AppError::from(error)
+    }
 }
 
 pub(crate) fn is_symlink(path: &Path) -> bool {
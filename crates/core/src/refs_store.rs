@@ -20,6 +20,7 @@ pub(crate) const STALE_TMP_MAX_AGE: std::time::Duration = std::time::Duration::f
 #[derive(Debug, Clone)]
 pub struct RefStore {
     base_dir: PathBuf,
+    session_id: Option<String>,
     allow_legacy_migration: bool,
 }
 
@@ -34,11 +35,13 @@ impl RefStore {
             validate_session_id(session_id)?;
             return Ok(Self {
                 base_dir: state_root.join("sessions").join(session_id),
+                session_id: Some(session_id.to_string()),
                 allow_legacy_migration: false,
             });
         }
         Ok(Self {
             base_dir: state_root,
+            session_id: None,
             allow_legacy_migration: true,
         })
     }
@@ -202,9 +205,25 @@ impl RefStore {
 
     fn with_write_lock<T>(&self, f: impl FnOnce() -> Result<T, AppError>) -> Result<T, AppError> {
         let _lock = RefStoreLock::acquire(&self.lock_path())?;
+        self.ensure_writable()?;
         f()
     }
 
+    /// Imported sessions are evidence from another machine; new snapshots
+    /// and aliases would mix this machine's refs into it.
+    fn ensure_writable(&self) -> Result<(), AppError> {
+        let Some(session_id) = self.session_id.as_deref() else {
+            return Ok(());
+        };
+        if crate::session::read_manifest(session_id)?.is_some_and(|manifest| manifest.read_only) {
+            return Err(AppError::invalid_input_with_suggestion(
+                format!("Session '{session_id}' was imported read-only"),
+                "Start a new session for live commands; imported sessions serve trace reads only.",
+            ));
+        }
+        Ok(())
+    }
+
     fn migrate_legacy_latest(&self) -> Result<Option<RefMap>, AppError> {
         if !self.allow_legacy_migration {
             return Ok(None);
@@ -236,6 +255,11 @@ impl RefStore {
 #[path = "refs_store_prune.rs"]
 pub(crate) mod prune;
 
+/// Session-scoped ref aliases live beside the snapshots they were taken
+/// from and share the store's write lock.
+#[path = "refs_store_alias.rs"]
+pub(crate) mod alias;
+
 #[cfg(test)]
 #[path = "refs_store_tests.rs"]
 mod tests;
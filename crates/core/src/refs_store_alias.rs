@@ -0,0 +1,73 @@
+use super::RefStore;
+use crate::{AppError, refs::RefEntry, refs::write_private_file};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+const ALIASES_FILE: &str = "aliases.json";
+pub(crate) const MAX_ALIASES: usize = 64;
+const MAX_ALIASES_BYTES: u64 = 1_048_576;
+
+/// The identity evidence an alias was created from, plus the ref it was
+/// copied from so `alias list` can say where it came from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct RefAlias {
+    pub(crate) source_ref: String,
+    pub(crate) entry: RefEntry,
+}
+
+impl RefStore {
+    pub(crate) fn load_aliases(&self) -> Result<BTreeMap<String, RefAlias>, AppError> {
+        let json = match crate::private_file::read_private_bounded(
+            &self.aliases_path(),
+            MAX_ALIASES_BYTES,
+        ) {
+            Ok(json) => json,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(BTreeMap::new()),
+            Err(err) => return Err(err.into()),
+        };
+        let aliases: BTreeMap<String, RefAlias> = serde_json::from_slice(&json)?;
+        for (name, alias) in &aliases {
+            crate::ref_token::validate_alias_name(name)?;
+            crate::refs_validate::validate_ref_entry(&alias.entry)?;
+        }
+        Ok(aliases)
+    }
+
+    /// Stores or replaces `name`. Returns whether an alias was replaced.
+    pub(crate) fn set_alias(&self, name: &str, alias: RefAlias) -> Result<bool, AppError> {
+        self.with_write_lock(|| {
+            let mut aliases = self.load_aliases()?;
+            if !aliases.contains_key(name) && aliases.len() >= MAX_ALIASES {
+                return Err(AppError::invalid_input_with_suggestion(
+                    format!("At most {MAX_ALIASES} ref aliases may be stored per session"),
+                    "Remove unused aliases with `alias rm NAME`",
+                ));
+            }
+            let replaced = aliases.insert(name.to_string(), alias).is_some();
+            self.save_aliases_unlocked(&aliases)?;
+            Ok(replaced)
+        })
+    }
+
+    /// Removes `name`. Returns whether it existed.
+    pub(crate) fn remove_alias(&self, name: &str) -> Result<bool, AppError> {
+        self.with_write_lock(|| {
+            let mut aliases = self.load_aliases()?;
+            if aliases.remove(name).is_none() {
+                return Ok(false);
+            }
+            self.save_aliases_unlocked(&aliases)?;
+            Ok(true)
+        })
+    }
+
+    fn save_aliases_unlocked(&self, aliases: &BTreeMap<String, RefAlias>) -> Result<(), AppError> {
+        write_private_file(&self.aliases_path(), &serde_json::to_vec(aliases)?)
+    }
+
+    fn aliases_path(&self) -> PathBuf {
+        self.base_dir.join(ALIASES_FILE)
+    }
+}
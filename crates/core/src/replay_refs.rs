@@ -0,0 +1,169 @@
+use crate::{
+    AppError,
+    context::CommandContext,
+    refs::{MAX_REFMAP_BYTES, RefEntry, RefMap},
+    refs_store::RefStore,
+};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// How `trace replay` carried one recorded ref into the replayed run.
+/// `reason` is set when the ref was not re-resolved: `ref_unmapped` refs
+/// name a snapshot no replayed step produced and are kept as recorded;
+/// for `ref_not_found` and `ref_ambiguous`, `replayed` is absent.
+#[derive(Debug, Clone, Serialize)]
+pub struct RefRewrite {
+    pub recorded: String,
+    pub replayed: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<&'static str>,
+}
+
+impl RefRewrite {
+    pub fn resolved(&self) -> bool {
+        self.replayed.is_some()
+    }
+}
+
+/// Maps the snapshots and refs of a recorded session onto the ones a
+/// replay produces. A recorded ref is re-resolved by identity: the one
+/// element of the replayed snapshot with the same app, role, and identity
+/// evidence, with the same tree path breaking ties.
+pub struct RefRemapper {
+    recorded: RefStore,
+    replayed: RefStore,
+    snapshots: HashMap<String, String>,
+}
+
+impl RefRemapper {
+    pub fn new(recorded_session: &str, context: &CommandContext) -> Result<Self, AppError> {
+        let recorded = RefStore::for_session(Some(recorded_session))?;
+        Ok(Self {
+            recorded,
+            replayed: RefStore::for_session(context.session_id())?,
+            snapshots: HashMap::new(),
+        })
+    }
+
+    /// Records that the replayed step which took `recorded` produced `replayed`.
+    pub fn map_snapshot(&mut self, recorded: &str, replayed: &str) {
+        self.snapshots
+            .insert(recorded.to_string(), replayed.to_string());
+    }
+
+    /// Rewrites every qualified ref in `args`, and every bare ref that sits
+    /// next to a `snapshot` field, to its replayed counterpart. Returns one
+    /// entry per ref seen; refs without a counterpart are left as recorded.
+    pub fn rewrite(&self, args: &mut Value) -> Vec<RefRewrite> {
+        let mut rewrites = Vec::new();
+        self.rewrite_value(args, None, &mut rewrites);
+        rewrites
+    }
+
+    fn rewrite_value(&self, value: &mut Value, snapshot: Option<&str>, out: &mut Vec<RefRewrite>) {
+        match value {
+            Value::String(text) => {
+                if let Some(rewrite) = self.rewrite_ref(text, snapshot) {
+                    if let Some(replayed) = &rewrite.replayed {
+                        *text = replayed.clone();
+                    }
+                    out.push(rewrite);
+                }
+            }
+            Value::Array(items) => {
+                for item in items {
+                    self.rewrite_value(item, snapshot, out);
+                }
+            }
+            Value::Object(map) => {
+                let snapshot = map
+                    .get("snapshot")
+                    .and_then(Value::as_str)
+                    .map(str::to_string);
+                for (key, item) in map.iter_mut() {
+                    if key != "snapshot" {
+                        self.rewrite_value(item, snapshot.as_deref(), out);
+                    }
+                }
+                if let Some(Value::String(recorded)) = map.get_mut("snapshot")
+                    && let Some(replayed) = self.snapshots.get(recorded.as_str())
+                {
+                    *recorded = replayed.clone();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn rewrite_ref(&self, text: &str, snapshot: Option<&str>) -> Option<RefRewrite> {
+        if !text.starts_with('@') || crate::ref_token::is_alias_token(text) {
+            return None;
+        }
+        let (recorded_id, local_ref) = crate::ref_token::resolve_ref_target(text, snapshot).ok()?;
+        let qualified = !crate::ref_token::is_local_ref(text);
+        let outcome = self.resolve(&recorded_id, &local_ref);
+        let replayed = match &outcome {
+            Ok((snapshot_id, local)) if qualified => {
+                Some(crate::ref_token::qualify_ref_id(snapshot_id, local))
+            }
+            Ok((_, local)) => Some(local.clone()),
+            Err("ref_unmapped") => Some(text.to_string()),
+            Err(_) => None,
+        };
+        Some(RefRewrite {
+            recorded: text.to_string(),
+            replayed,
+            reason: outcome.err(),
+        })
+    }
+
+    fn resolve(
+        &self,
+        recorded_id: &str,
+        local_ref: &str,
+    ) -> Result<(String, String), &'static str> {
+        let replayed_id = self.snapshots.get(recorded_id).ok_or("ref_unmapped")?;
+        let recorded = recorded_refmap(&self.recorded, recorded_id).ok_or("ref_not_found")?;
+        let entry = recorded.get(local_ref).ok_or("ref_not_found")?;
+        let replayed = self
+            .replayed
+            .load_snapshot(replayed_id)
+            .map_err(|_| "ref_not_found")?;
+        let local = unique_match(&replayed, entry)?;
+        Ok((replayed_id.clone(), local))
+    }
+}
+
+/// The refmap a recorded session saved for `snapshot_id`: the copy in its
+/// trace artifacts when it kept one, else the one in its ref store.
+pub fn recorded_refmap(recorded: &RefStore, snapshot_id: &str) -> Option<RefMap> {
+    crate::refs::validate_snapshot_id(snapshot_id).ok()?;
+    let artifact = crate::trace_artifacts::refmap_artifact_dir(&recorded.trace_dir())
+        .join(format!("{snapshot_id}.json"));
+    crate::private_file::read_private_bounded(&artifact, MAX_REFMAP_BYTES)
+        .ok()
+        .and_then(|json| serde_json::from_slice(&json).ok())
+        .or_else(|| recorded.load_snapshot(snapshot_id).ok())
+}
+
+fn unique_match(replayed: &RefMap, entry: &RefEntry) -> Result<String, &'static str> {
+    let matches: Vec<(&String, &RefEntry)> = replayed
+        .iter()
+        .filter(|(_, candidate)| crate::ref_alias::same_identity(entry, candidate))
+        .collect();
+    let at_same_path: Vec<&String> = matches
+        .iter()
+        .filter(|(_, candidate)| candidate.scope == entry.scope)
+        .map(|(local, _)| *local)
+        .collect();
+    match (matches.as_slice(), at_same_path.as_slice()) {
+        ([(local, _)], _) | (_, [local]) => Ok((*local).clone()),
+        ([], _) => Err("ref_not_found"),
+        _ => Err("ref_ambiguous"),
+    }
+}
+
+#[cfg(test)]
+#[path = "replay_refs_tests.rs"]
+mod tests;
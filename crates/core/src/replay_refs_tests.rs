@@ -0,0 +1,115 @@
+use super::*;
+use crate::refs_test_support::HomeGuard;
+use serde_json::json;
+
+fn button(name: &str, pid: u32) -> RefEntry {
+    RefEntry {
+        process: crate::RefProcess {
+            pid: crate::ProcessId::new(pid),
+            process_instance: Some(format!("{pid}:1")),
+        },
+        identity: crate::RefEntryIdentity {
+            role: "button".into(),
+            name: Some(name.into()),
+            value: None,
+            description: None,
+            native_id: None,
+        },
+        geometry: crate::RefGeometry {
+            bounds: None,
+            bounds_hash: None,
+        },
+        capabilities: crate::RefCapabilities {
+            states: vec![],
+            available_actions: vec!["Click".into()],
+        },
+        source: crate::RefSource {
+            source_app: Some("Mail".into()),
+            source_window_id: Some("w-1".into()),
+            source_window_title: Some("Inbox".into()),
+            source_window_bounds_hash: None,
+            source_surface: crate::SnapshotSurface::Window,
+        },
+        scope: crate::RefScope {
+            root_ref: None,
+            path_is_absolute: false,
+            path: smallvec::smallvec![0],
+        },
+    }
+}
+
+fn save(store: &RefStore, names: &[&str], pid: u32) -> String {
+    let mut refmap = RefMap::new();
+    for name in names {
+        refmap.allocate(button(name, pid));
+    }
+    store.save_new_snapshot(&refmap).unwrap()
+}
+
+fn remapper() -> (RefRemapper, String, String) {
+    let recorded = save(
+        &RefStore::for_session(Some("recorded")).unwrap(),
+        &["Reply", "Send"],
+        100,
+    );
+    let replayed = save(
+        &RefStore::new().unwrap(),
+        &["Send", "Archive", "Reply"],
+        200,
+    );
+    let mut remapper = RefRemapper::new("recorded", &CommandContext::default()).unwrap();
+    remapper.map_snapshot(&recorded, &replayed);
+    (remapper, recorded, replayed)
+}
+
+#[test]
+fn qualified_refs_follow_their_element_into_the_replayed_snapshot() {
+    let _home = HomeGuard::new();
+    let (remapper, recorded, replayed) = remapper();
+    let mut args = json!({ "ref_id": format!("@{recorded}:e2"), "timeout_ms": 5000 });
+
+    let rewrites = remapper.rewrite(&mut args);
+
+    assert_eq!(args["ref_id"], format!("@{replayed}:e1"));
+    assert_eq!(rewrites.len(), 1);
+    assert!(rewrites[0].resolved());
+}
+
+#[test]
+fn bare_refs_move_with_their_snapshot_field() {
+    let _home = HomeGuard::new();
+    let (remapper, recorded, replayed) = remapper();
+    let mut args = json!({ "ref_id": "@e1", "snapshot": recorded });
+
+    remapper.rewrite(&mut args);
+
+    assert_eq!(args, json!({ "ref_id": "@e3", "snapshot": replayed }));
+}
+
+#[test]
+fn refs_without_a_counterpart_are_reported_and_left_as_recorded() {
+    let _home = HomeGuard::new();
+    let (mut remapper, recorded, _) = remapper();
+    let mut args = json!({ "ref_id": "@s-unknown:e1", "plain": "@e1" });
+
+    let rewrites = remapper.rewrite(&mut args);
+
+    assert_eq!(
+        rewrites.len(),
+        1,
+        "bare refs without a snapshot field are kept"
+    );
+    assert_eq!(rewrites[0].reason, Some("ref_unmapped"));
+    assert!(
+        rewrites[0].resolved(),
+        "unmapped refs still run as recorded"
+    );
+    assert_eq!(args["ref_id"], "@s-unknown:e1");
+
+    let gone = save(&RefStore::new().unwrap(), &["Archive"], 200);
+    remapper.map_snapshot(&recorded, &gone);
+    let mut args = json!({ "ref_id": format!("@{recorded}:e1") });
+    let rewrites = remapper.rewrite(&mut args);
+    assert_eq!(rewrites[0].reason, Some("ref_not_found"));
+    assert!(!rewrites[0].resolved());
+}
@@ -0,0 +1,74 @@
+use crate::{
+    AdapterError, AppError, ErrorCode, ImageBuffer, ImageFormat, RefMap,
+    raster::{Placement, Raster},
+    screenshot_annotate_draw::draw_mark,
+};
+use serde_json::{Value, json};
+
+/// Draws a numbered box for every ref in `refmap` that has bounds inside the
+/// capture, and returns the annotated PNG with a legend that maps each label
+/// back to its qualified ref. Refs outside the capture are listed as
+/// `unplaced` rather than silently dropped.
+pub(crate) fn annotate(
+    image: &ImageBuffer,
+    refmap: &RefMap,
+    snapshot_id: &str,
+    placement: Placement,
+) -> Result<(ImageBuffer, Value), AppError> {
+    let mut canvas = decode(image)?;
+    let unit = (placement.scale * 2.0).round().max(2.0) as u32;
+    let mut legend = Vec::new();
+    let mut unplaced = Vec::new();
+    for (number, entry) in refmap.numbered() {
+        let ref_id = crate::ref_token::qualify_ref_id(snapshot_id, &format!("@e{number}"));
+        let area = entry
+            .geometry
+            .bounds
+            .as_ref()
+            .and_then(|bounds| placement.pixel_box(bounds, canvas.width, canvas.height));
+        let Some(area) = area else {
+            unplaced.push(ref_id);
+            continue;
+        };
+        draw_mark(&mut canvas, number, area, unit);
+        legend.push(json!({
+            "label": number,
+            "ref_id": ref_id,
+            "role": entry.identity.role,
+            "name": entry.identity.name,
+            "box": { "x": area.x, "y": area.y, "width": area.width, "height": area.height },
+        }));
+    }
+    let annotated = ImageBuffer {
+        data: canvas.encode_png()?,
+        format: ImageFormat::Png,
+        width: canvas.width,
+        height: canvas.height,
+        scale_factor: image.scale_factor,
+    };
+    Ok((
+        annotated,
+        json!({ "snapshot_id": snapshot_id, "legend": legend, "unplaced": unplaced }),
+    ))
+}
+
+fn decode(image: &ImageBuffer) -> Result<Raster, AppError> {
+    if !matches!(image.format, ImageFormat::Png) {
+        return Err(AdapterError::new(
+            ErrorCode::ActionNotSupported,
+            "Only PNG screenshots can be annotated",
+        )
+        .into());
+    }
+    Raster::decode_png(&image.data).map_err(|error| {
+        AdapterError::new(
+            ErrorCode::ActionFailed,
+            format!("Screenshot could not be decoded for annotation: {error}"),
+        )
+        .into()
+    })
+}
+
+#[cfg(test)]
+#[path = "screenshot_annotate_tests.rs"]
+mod tests;
@@ -0,0 +1,75 @@
+use crate::raster::{PixelBox, Raster};
+
+/// Box colors, cycled by label so neighbouring refs stay distinguishable.
+const PALETTE: [[u8; 3]; 6] = [
+    [230, 25, 75],
+    [0, 130, 200],
+    [60, 180, 75],
+    [245, 130, 48],
+    [145, 30, 180],
+    [0, 150, 150],
+];
+
+/// 3x5 digit glyphs, one row per byte with the leftmost pixel in bit 2.
+const DIGITS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b010, 0b010, 0b010],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+const WHITE: [u8; 3] = [255, 255, 255];
+
+/// Fills a rectangle, ignoring the parts that fall outside the image.
+fn fill(canvas: &mut Raster, [x, y, width, height]: [i64; 4], color: [u8; 3]) {
+    let left = x.clamp(0, i64::from(canvas.width)) as u32;
+    let top = y.clamp(0, i64::from(canvas.height)) as u32;
+    let right = x.saturating_add(width).clamp(0, i64::from(canvas.width)) as u32;
+    let bottom = y.saturating_add(height).clamp(0, i64::from(canvas.height)) as u32;
+    for row in top..bottom {
+        for column in left..right {
+            canvas.set_pixel(column, row, [color[0], color[1], color[2], 255]);
+        }
+    }
+}
+
+/// Outlines `area` and tags it with `label`. `unit` is the size of one font
+/// pixel, so labels stay legible on high-density captures.
+pub(crate) fn draw_mark(canvas: &mut Raster, label: u32, area: PixelBox, unit: u32) {
+    let color = PALETTE[label as usize % PALETTE.len()];
+    let unit = i64::from(unit.max(1));
+    let (x, y) = (i64::from(area.x), i64::from(area.y));
+    let (width, height) = (i64::from(area.width), i64::from(area.height));
+    let stroke = unit.min(width).min(height).max(1);
+    fill(canvas, [x, y, width, stroke], color);
+    fill(canvas, [x, y + height - stroke, width, stroke], color);
+    fill(canvas, [x, y, stroke, height], color);
+    fill(canvas, [x + width - stroke, y, stroke, height], color);
+
+    let digits: Vec<usize> = label
+        .to_string()
+        .bytes()
+        .map(|digit| usize::from(digit - b'0'))
+        .collect();
+    let tag_width = (digits.len() as i64 * 4 + 1) * unit;
+    let tag_height = 7 * unit;
+    let tag_y = if y >= tag_height { y - tag_height } else { y };
+    fill(canvas, [x, tag_y, tag_width, tag_height], color);
+    for (index, digit) in digits.into_iter().enumerate() {
+        let left = x + (index as i64 * 4 + 1) * unit;
+        for (row, bits) in DIGITS[digit].iter().enumerate() {
+            for column in 0..3 {
+                if bits & (0b100 >> column) != 0 {
+                    let top = tag_y + (row as i64 + 1) * unit;
+                    fill(canvas, [left + column * unit, top, unit, unit], WHITE);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use super::*;
+use crate::Rect;
+use crate::{
+    RefEntry,
+    raster::{PixelBox, Placement},
+};
+
+fn gray_png(width: u32, height: u32) -> ImageBuffer {
+    let mut data = Vec::new();
+    let mut encoder = png::Encoder::new(&mut data, width, height);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().unwrap();
+    writer
+        .write_image_data(&vec![128; (width * height * 3) as usize])
+        .unwrap();
+    writer.finish().unwrap();
+    ImageBuffer {
+        data,
+        format: ImageFormat::Png,
+        width,
+        height,
+        scale_factor: 2.0,
+    }
+}
+
+fn pixel(image: &ImageBuffer, x: u32, y: u32) -> [u8; 4] {
+    decode(image).unwrap().pixel(x, y)
+}
+
+fn entry(name: &str, bounds: Option<Rect>) -> RefEntry {
+    RefEntry {
+        process: crate::RefProcess {
+            pid: crate::ProcessId::new(1),
+            process_instance: Some("test-instance".into()),
+        },
+        identity: crate::RefEntryIdentity {
+            role: "button".into(),
+            name: Some(name.into()),
+            value: None,
+            description: None,
+            native_id: None,
+        },
+        geometry: crate::RefGeometry {
+            bounds,
+            bounds_hash: bounds.and_then(|bounds| bounds.bounds_hash()),
+        },
+        capabilities: crate::RefCapabilities {
+            states: vec![],
+            available_actions: vec!["Click".into()],
+        },
+        source: crate::RefSource {
+            source_app: Some("Mail".into()),
+            source_window_id: Some("w-1".into()),
+            source_window_title: None,
+            source_window_bounds_hash: None,
+            source_surface: crate::SnapshotSurface::Window,
+        },
+        scope: crate::RefScope {
+            root_ref: None,
+            path_is_absolute: false,
+            path: smallvec::smallvec![0],
+        },
+    }
+}
+
+fn rect(x: f64, y: f64, width: f64, height: f64) -> Rect {
+    Rect {
+        x,
+        y,
+        width,
+        height,
+    }
+}
+
+#[test]
+fn pixel_box_applies_window_origin_and_scale_and_clips_to_the_image() {
+    let placement = Placement::new(&rect(100.0, 50.0, 320.0, 240.0), 2.0);
+
+    assert_eq!(
+        placement.pixel_box(&rect(110.0, 60.0, 50.0, 20.0), 640, 480),
+        Some(PixelBox {
+            x: 20,
+            y: 20,
+            width: 100,
+            height: 40
+        })
+    );
+    assert_eq!(
+        placement.pixel_box(&rect(90.0, 40.0, 20.0, 20.0), 640, 480),
+        Some(PixelBox {
+            x: 0,
+            y: 0,
+            width: 20,
+            height: 20
+        })
+    );
+    assert_eq!(
+        placement.pixel_box(&rect(500.0, 60.0, 20.0, 20.0), 640, 480),
+        None
+    );
+}
+
+#[test]
+fn annotate_outlines_placed_refs_and_lists_the_rest_in_the_legend() {
+    let image = gray_png(128, 96);
+    let mut refmap = RefMap::new();
+    refmap.allocate(entry("Send", Some(rect(10.0, 20.0, 20.0, 10.0))));
+    refmap.allocate(entry("Hidden", None));
+    refmap.allocate(entry("Offscreen", Some(rect(400.0, 10.0, 10.0, 6.0))));
+    let placement = Placement::new(&rect(0.0, 0.0, 64.0, 48.0), 2.0);
+
+    let (annotated, legend) = annotate(&image, &refmap, "s1234567", placement).unwrap();
+
+    assert_eq!((annotated.width, annotated.height), (128, 96));
+    assert_eq!(annotated.scale_factor, 2.0);
+    assert_eq!(legend["snapshot_id"], "s1234567");
+    assert_eq!(legend["legend"].as_array().unwrap().len(), 1);
+    assert_eq!(legend["legend"][0]["label"], 1);
+    assert_eq!(legend["legend"][0]["ref_id"], "@s1234567:e1");
+    assert_eq!(legend["legend"][0]["name"], "Send");
+    assert_eq!(
+        legend["legend"][0]["box"],
+        serde_json::json!({"x": 20, "y": 40, "width": 40, "height": 20})
+    );
+    assert_eq!(
+        legend["unplaced"],
+        serde_json::json!(["@s1234567:e2", "@s1234567:e3"])
+    );
+    assert_eq!(pixel(&annotated, 59, 59), [0, 130, 200, 255]);
+    assert_eq!(pixel(&annotated, 40, 50), [128, 128, 128, 255]);
+    assert_eq!(pixel(&annotated, 100, 80), [128, 128, 128, 255]);
+}
+
+#[test]
+fn labels_sit_above_the_box_when_there_is_room() {
+    let image = gray_png(64, 48);
+    let mut refmap = RefMap::new();
+    refmap.allocate(entry("Send", Some(rect(5.0, 20.0, 10.0, 6.0))));
+    let placement = Placement::new(&rect(0.0, 0.0, 64.0, 48.0), 1.0);
+
+    let (annotated, _) = annotate(&image, &refmap, "s1234567", placement).unwrap();
+
+    assert_eq!(pixel(&annotated, 5, 20), [0, 130, 200, 255]);
+    assert_eq!(pixel(&annotated, 7, 8), [0, 130, 200, 255]);
+    assert_eq!(pixel(&annotated, 9, 8), [255, 255, 255, 255]);
+    assert_eq!(pixel(&annotated, 5, 4), [128, 128, 128, 255]);
+}
+
+#[test]
+fn annotate_rejects_non_png_captures() {
+    let mut image = gray_png(4, 4);
+    image.format = ImageFormat::Jpg;
+
+    let error = annotate(
+        &image,
+        &RefMap::new(),
+        "s1234567",
+        Placement::new(&rect(0.0, 0.0, 4.0, 4.0), 1.0),
+    )
+    .unwrap_err();
+
+    assert_eq!(error.code(), "ACTION_NOT_SUPPORTED");
+}
@@ -0,0 +1,133 @@
+use crate::{
+    AppError, ref_identity::locator_field, refs::RefEntry, refs_store::RefStore,
+    replay_refs::recorded_refmap,
+};
+use serde::Serialize;
+use serde_json::{Map, Value, json};
+
+/// How `trace to-script` replaced one recorded ref. A located ref becomes
+/// `${capture}`, filled by a `find` step with `locator` as its args; the
+/// others are left as recorded with a `reason`: `ref_not_found` when the
+/// session kept no refmap entry for it, `no_stable_identity` when the entry
+/// has no identifier or stable text to find it by.
+#[derive(Debug, Clone, Serialize)]
+pub struct RefLocation {
+    pub recorded: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capture: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locator: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<&'static str>,
+}
+
+/// Turns the snapshot refs of a recorded session into locator lookups, so a
+/// script derived from its trace does not depend on snapshots it never takes.
+pub struct RefLocator {
+    recorded: RefStore,
+    next_capture: usize,
+}
+
+impl RefLocator {
+    pub fn new(recorded_session: &str) -> Result<Self, AppError> {
+        Ok(Self {
+            recorded: RefStore::for_session(Some(recorded_session))?,
+            next_capture: 0,
+        })
+    }
+
+    /// Replaces every qualified ref in `args`, and every bare ref that sits
+    /// next to a `snapshot` field, with a `${refN}` capture reference. The
+    /// `snapshot` field goes once none of its refs are left. Returns one
+    /// entry per ref seen, in the order their `find` steps must run.
+    pub fn locate(&mut self, args: &mut Value) -> Vec<RefLocation> {
+        let mut locations = Vec::new();
+        self.locate_value(args, None, &mut locations);
+        locations
+    }
+
+    fn locate_value(
+        &mut self,
+        value: &mut Value,
+        snapshot: Option<&str>,
+        out: &mut Vec<RefLocation>,
+    ) {
+        match value {
+            Value::String(text) => {
+                if let Some(location) = self.locate_ref(text, snapshot) {
+                    if let Some(capture) = &location.capture {
+                        *text = format!("${{{capture}}}");
+                    }
+                    out.push(location);
+                }
+            }
+            Value::Array(items) => {
+                for item in items {
+                    self.locate_value(item, snapshot, out);
+                }
+            }
+            Value::Object(map) => {
+                let snapshot = map
+                    .get("snapshot")
+                    .and_then(Value::as_str)
+                    .map(str::to_string);
+                let before = out.len();
+                for (key, item) in map.iter_mut() {
+                    if key != "snapshot" {
+                        self.locate_value(item, snapshot.as_deref(), out);
+                    }
+                }
+                let own = &out[before..];
+                if snapshot.is_some()
+                    && !own.is_empty()
+                    && own.iter().all(|location| location.capture.is_some())
+                {
+                    map.remove("snapshot");
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn locate_ref(&mut self, text: &str, snapshot: Option<&str>) -> Option<RefLocation> {
+        if !text.starts_with('@') || crate::ref_token::is_alias_token(text) {
+            return None;
+        }
+        let (snapshot_id, local_ref) = crate::ref_token::resolve_ref_target(text, snapshot).ok()?;
+        let entry = recorded_refmap(&self.recorded, &snapshot_id)
+            .and_then(|refmap| refmap.get(&local_ref).cloned());
+        let locator = match &entry {
+            None => Err("ref_not_found"),
+            Some(entry) => find_args(entry).ok_or("no_stable_identity"),
+        };
+        let capture = locator.is_ok().then(|| {
+            self.next_capture += 1;
+            format!("ref{}", self.next_capture)
+        });
+        Some(RefLocation {
+            recorded: text.to_string(),
+            capture,
+            reason: locator.as_ref().err().copied(),
+            locator: locator.ok(),
+        })
+    }
+}
+
+/// `find` args that return the first element in the recorded app with the
+/// entry's role and identity, matched exactly.
+fn find_args(entry: &RefEntry) -> Option<Value> {
+    let (field, text) = locator_field(entry)?;
+    let mut args = Map::new();
+    if let Some(app) = &entry.source.source_app {
+        args.insert("app".into(), json!(app));
+    }
+    args.insert("role".into(), json!(entry.identity.role));
+    args.insert(field.into(), json!(text));
+    args.insert("exact".into(), json!(true));
+    args.insert("first".into(), json!(true));
+    Some(Value::Object(args))
+}
+
+#[cfg(test)]
+#[path = "script_refs_tests.rs"]
+mod tests;
@@ -0,0 +1,100 @@
+use super::*;
+use crate::refs::RefMap;
+use crate::refs_test_support::HomeGuard;
+
+fn entry(role: &str, name: Option<&str>) -> RefEntry {
+    RefEntry {
+        process: crate::RefProcess {
+            pid: crate::ProcessId::new(100),
+            process_instance: Some("100:1".into()),
+        },
+        identity: crate::RefEntryIdentity {
+            role: role.into(),
+            name: name.map(Into::into),
+            value: None,
+            description: None,
+            native_id: None,
+        },
+        geometry: crate::RefGeometry {
+            bounds: None,
+            bounds_hash: None,
+        },
+        capabilities: crate::RefCapabilities {
+            states: vec![],
+            available_actions: vec!["Click".into()],
+        },
+        source: crate::RefSource {
+            source_app: Some("Mail".into()),
+            source_window_id: Some("w-1".into()),
+            source_window_title: Some("Inbox".into()),
+            source_window_bounds_hash: None,
+            source_surface: crate::SnapshotSurface::Window,
+        },
+        scope: crate::RefScope {
+            root_ref: None,
+            path_is_absolute: false,
+            path: smallvec::smallvec![0],
+        },
+    }
+}
+
+fn recorded_snapshot() -> String {
+    let mut refmap = RefMap::new();
+    refmap.allocate(entry("button", Some("Send")));
+    refmap.allocate(entry("group", None));
+    RefStore::for_session(Some("recorded"))
+        .unwrap()
+        .save_new_snapshot(&refmap)
+        .unwrap()
+}
+
+#[test]
+fn located_refs_become_capture_references_with_find_args() {
+    let _home = HomeGuard::new();
+    let snapshot = recorded_snapshot();
+    let mut locator = RefLocator::new("recorded").unwrap();
+    let mut args = json!({ "ref_id": "@e1", "snapshot": snapshot, "timeout_ms": 5000 });
+
+    let locations = locator.locate(&mut args);
+
+    assert_eq!(args, json!({ "ref_id": "${ref1}", "timeout_ms": 5000 }));
+    assert_eq!(locations.len(), 1);
+    assert_eq!(locations[0].capture.as_deref(), Some("ref1"));
+    assert_eq!(
+        locations[0].locator,
+        Some(json!({
+            "app": "Mail", "role": "button", "name": "Send", "exact": true, "first": true
+        }))
+    );
+
+    let mut args = json!({ "ref_id": format!("@{snapshot}:e1") });
+    locator.locate(&mut args);
+    assert_eq!(args["ref_id"], "${ref2}");
+}
+
+#[test]
+fn refs_without_a_locator_are_reported_and_left_as_recorded() {
+    let _home = HomeGuard::new();
+    let snapshot = recorded_snapshot();
+    let mut locator = RefLocator::new("recorded").unwrap();
+    let mut args = json!({
+        "ref_id": "@e2",
+        "snapshot": snapshot,
+        "nested": { "other": "@s-missing:e1", "plain": "@e1", "alias": "@send" },
+    });
+    let recorded = args.clone();
+
+    let locations = locator.locate(&mut args);
+
+    assert_eq!(
+        args, recorded,
+        "bare refs without a snapshot field are text"
+    );
+    let reasons: Vec<_> = locations.iter().map(|location| location.reason).collect();
+    assert_eq!(
+        reasons,
+        vec![Some("ref_not_found"), Some("no_stable_identity")],
+        "keys are visited in order"
+    );
+    assert!(locations.iter().all(|location| location.capture.is_none()));
+}
@@ -10,6 +10,7 @@ fn node(name: Option<&str>, value: Option<&str>, description: Option<&str>) -> A
             value: value.map(String::from),
             description: description.map(String::from),
             native_id: None,
+            value_masked: false,
         },
         presentation: Default::default(),
         children_count: None,
@@ -0,0 +1,341 @@
+use crate::{AdapterError, AppError, ErrorCode, context::CommandContext};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::{
+    collections::BTreeMap,
+    io::{ErrorKind, Read},
+    path::PathBuf,
+    process::{Command, Stdio},
+    sync::{
+        Mutex, PoisonError,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+/// Maps secret names to where their values live, under the state root. It
+/// can name commands to run, so it must be private like the files it names.
+pub const SECRETS_FILE: &str = "secrets.json";
+/// Prefix of the environment variable read for a name `secrets.json` does
+/// not configure: `--secret github-token` reads `AGENT_DESKTOP_SECRET_GITHUB_TOKEN`.
+pub const SECRET_ENV_PREFIX: &str = "AGENT_DESKTOP_SECRET_";
+const MAX_SECRETS_FILE_BYTES: u64 = 64 * 1024;
+const MAX_SECRET_BYTES: u64 = 64 * 1024;
+const MAX_SECRET_NAME_LEN: usize = 64;
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(10);
+const COMMAND_POLL: Duration = Duration::from_millis(20);
+const REDACTED_TEXT: &str = "[redacted]";
+/// Secrets shorter than this are hidden only where a string equals them
+/// whole: a one-to-three character value would otherwise redact it from
+/// every unrelated word that happens to contain it.
+const MIN_SUBSTRING_SECRET_CHARS: usize = 4;
+
+/// Values of the secrets this process currently holds, so trace writers can
+/// redact them wherever they appear. `ACTIVE_COUNT` keeps the common case of
+/// no secret lock-free.
+static ACTIVE: Mutex<Vec<String>> = Mutex::new(Vec::new());
+static ACTIVE_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+enum SecretSource {
+    Env(String),
+    File(PathBuf),
+    Command(Vec<String>),
+}
+
+impl SecretSource {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Env(_) => "env",
+            Self::File(_) => "file",
+            Self::Command(_) => "command",
+        }
+    }
+}
+
+/// A secret value resolved by name for one command. It is registered with
+/// the trace sanitizer until dropped, and its `Debug` output never shows it.
+pub(crate) struct Secret {
+    value: String,
+}
+
+impl std::fmt::Debug for Secret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Secret(..)")
+    }
+}
+
+impl Secret {
+    /// Resolves `name` and records a `secret.resolved` trace event that
+    /// carries only the name and the kind of source.
+    pub(crate) fn resolve(name: &str, context: &CommandContext) -> Result<Self, AppError> {
+        validate_name(name)?;
+        let source = configured_source(name)?;
+        context.trace(
+            "secret.resolved",
+            json!({ "id": name, "source": source.kind() }),
+        )?;
+        let value = read_source(name, &source)?;
+        if value.is_empty() {
+            return Err(AppError::invalid_input(format!("Secret '{name}' is empty")));
+        }
+        Ok(Self::register(value))
+    }
+
+    fn register(value: String) -> Self {
+        ACTIVE
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(value.clone());
+        ACTIVE_COUNT.fetch_add(1, Ordering::Release);
+        Self { value }
+    }
+
+    pub(crate) fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Removes the value from a command's outcome: wherever it appears in a
+    /// response string or in error text, it is replaced by `[redacted]`.
+    pub(crate) fn scrub(&self, result: Result<Value, AppError>) -> Result<Value, AppError> {
+        match result {
+            Ok(value) => Ok(scrub_value(value, &self.value)),
+            Err(err) => Err(self.scrub_error(err)),
+        }
+    }
+
+    fn scrub_error(&self, err: AppError) -> AppError {
+        let hide = |text: String| hide_secret(text, &self.value);
+        match err {
+            AppError::Adapter(mut error) => {
+                error.message = hide(error.message);
+                error.suggestion = error.suggestion.map(hide);
+                error.platform_detail = error.platform_detail.map(hide);
+                error.details = error
+                    .details
+                    .map(|details| scrub_value(details, &self.value));
+                AppError::Adapter(error)
+            }
+            other => {
+                let text = other.to_string();
+                let hidden = hide(text.clone());
+                if hidden == text {
+                    other
+                } else {
+                    AppError::Internal(hidden)
+                }
+            }
+        }
+    }
+}
+
+impl Drop for Secret {
+    fn drop(&mut self) {
+        let mut active = ACTIVE.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(index) = active.iter().position(|value| *value == self.value) {
+            active.swap_remove(index);
+            ACTIVE_COUNT.fetch_sub(1, Ordering::Release);
+        }
+    }
+}
+
+/// `text` with the value of every secret this process holds replaced by
+/// `[redacted]`, longest first so a secret containing another is hidden
+/// whole. Short secrets are only hidden where they make up the whole string.
+pub(crate) fn hide_active_secrets(text: String) -> String {
+    if ACTIVE_COUNT.load(Ordering::Acquire) == 0 {
+        return text;
+    }
+    let mut active = ACTIVE
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    active.sort_by_key(|value| std::cmp::Reverse(value.len()));
+    active
+        .iter()
+        .fold(text, |text, value| hide_secret(text, value))
+}
+
+fn hide_secret(text: String, secret: &str) -> String {
+    if secret.chars().count() >= MIN_SUBSTRING_SECRET_CHARS {
+        text.replace(secret, REDACTED_TEXT)
+    } else if text == secret {
+        REDACTED_TEXT.to_string()
+    } else {
+        text
+    }
+}
+
+fn scrub_value(value: Value, secret: &str) -> Value {
+    match value {
+        Value::String(text) => Value::String(hide_secret(text, secret)),
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| (key, scrub_value(value, secret)))
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|value| scrub_value(value, secret))
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+fn validate_name(name: &str) -> Result<(), AppError> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_SECRET_NAME_LEN
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-');
+    if valid {
+        return Ok(());
+    }
+    Err(AppError::invalid_input_with_suggestion(
+        format!("Invalid secret name '{name}'"),
+        format!(
+            "Use 1-{MAX_SECRET_NAME_LEN} ASCII letters, digits, '_' or '-', such as github-token."
+        ),
+    ))
+}
+
+fn configured_source(name: &str) -> Result<SecretSource, AppError> {
+    let path = crate::state_root::resolve_configured_state_root()?.join(SECRETS_FILE);
+    let mut sources: BTreeMap<String, SecretSource> =
+        match crate::private_file::read_private_bounded(&path, MAX_SECRETS_FILE_BYTES) {
+            Ok(bytes) => serde_json::from_slice(&bytes).map_err(|err| {
+                AppError::invalid_input_with_suggestion(
+                    format!("Invalid {SECRETS_FILE}: {err}"),
+                    "Map each name to one source, such as {\"github\": {\"env\": \"GITHUB_TOKEN\"}}, {\"db\": {\"file\": \"/path\"}}, or {\"vault\": {\"command\": [\"op\", \"read\", \"op://...\"]}}.",
+                )
+            })?,
+            Err(err) if err.kind() == ErrorKind::NotFound => BTreeMap::new(),
+            Err(err) => {
+                return Err(AppError::invalid_input(format!(
+                    "Cannot read {SECRETS_FILE}: {err}"
+                )));
+            }
+        };
+    Ok(sources
+        .remove(name)
+        .unwrap_or_else(|| SecretSource::Env(fallback_env_var(name))))
+}
+
+fn fallback_env_var(name: &str) -> String {
+    format!(
+        "{SECRET_ENV_PREFIX}{}",
+        name.to_ascii_uppercase().replace('-', "_")
+    )
+}
+
+fn read_source(name: &str, source: &SecretSource) -> Result<String, AppError> {
+    match source {
+        SecretSource::Env(var) => std::env::var(var).map_err(|_| {
+            AppError::invalid_input_with_suggestion(
+                format!("Secret '{name}' is not available: environment variable {var} is not set"),
+                format!("Export {var}, or configure '{name}' in {SECRETS_FILE}."),
+            )
+        }),
+        SecretSource::File(path) => {
+            let bytes = crate::private_file::read_private_bounded(path, MAX_SECRET_BYTES).map_err(
+                |err| {
+                    AppError::invalid_input_with_suggestion(
+                        format!("Cannot read secret '{name}' from {}: {err}", path.display()),
+                        "The file must be a regular file owned by you with mode 0600.",
+                    )
+                },
+            )?;
+            secret_text(name, bytes)
+        }
+        SecretSource::Command(argv) => run_command(name, argv),
+    }
+}
+
+/// Secret text without the one trailing newline files and commands add.
+fn secret_text(name: &str, bytes: Vec<u8>) -> Result<String, AppError> {
+    let mut text = String::from_utf8(bytes)
+        .map_err(|_| AppError::invalid_input(format!("Secret '{name}' is not valid UTF-8")))?;
+    if text.ends_with('\n') {
+        text.pop();
+        if text.ends_with('\r') {
+            text.pop();
+        }
+    }
+    Ok(text)
+}
+
+/// Runs a configured secret command with no stdin and stderr discarded, so
+/// nothing it prints besides the value on stdout reaches the caller.
+fn run_command(name: &str, argv: &[String]) -> Result<String, AppError> {
+    let (program, rest) = argv.split_first().ok_or_else(|| {
+        AppError::invalid_input(format!(
+            "Secret '{name}' has an empty command in {SECRETS_FILE}"
+        ))
+    })?;
+    let failed = |detail: String| {
+        AppError::Adapter(
+            AdapterError::new(
+                ErrorCode::ActionFailed,
+                format!("Secret command for '{name}' {detail}"),
+            )
+            .with_disposition(crate::DeliverySemantics::not_delivered()),
+        )
+    };
+    let mut child = Command::new(program)
+        .args(rest)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|err| failed(format!("could not start: {err}")))?;
+    let Some(stdout) = child.stdout.take() else {
+        return Err(failed("has no stdout".into()));
+    };
+    let reader = std::thread::spawn(move || {
+        let mut bytes = Vec::new();
+        stdout
+            .take(MAX_SECRET_BYTES + 1)
+            .read_to_end(&mut bytes)
+            .map(|_| bytes)
+    });
+    let deadline = Instant::now() + COMMAND_TIMEOUT;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(AppError::Adapter(
+                AdapterError::new(
+                    ErrorCode::Timeout,
+                    format!(
+                        "Secret command for '{name}' did not finish within {}s",
+                        COMMAND_TIMEOUT.as_secs()
+                    ),
+                )
+                .with_disposition(crate::DeliverySemantics::not_delivered()),
+            ));
+        }
+        std::thread::sleep(COMMAND_POLL);
+    };
+    if !status.success() {
+        return Err(failed(format!("exited with {status}")));
+    }
+    let bytes = reader
+        .join()
+        .map_err(|_| failed("output could not be read".into()))??;
+    if bytes.len() as u64 > MAX_SECRET_BYTES {
+        return Err(failed(format!(
+            "printed more than {MAX_SECRET_BYTES} bytes"
+        )));
+    }
+    secret_text(name, bytes)
+}
+
+#[cfg(test)]
+#[path = "secret_source_tests.rs"]
+mod tests;
@@ -0,0 +1,119 @@
+use super::*;
+use crate::refs_test_support::HomeGuard;
+
+fn configure(sources: Value) {
+    let root = crate::state_root::resolve_configured_state_root().unwrap();
+    crate::private_file::write_atomic(&root.join(SECRETS_FILE), sources.to_string().as_bytes())
+        .unwrap();
+}
+
+fn resolve(name: &str) -> Result<Secret, AppError> {
+    Secret::resolve(name, &CommandContext::default())
+}
+
+#[test]
+fn a_configured_file_is_read_only_when_private() {
+    let _guard = HomeGuard::new();
+    let path = crate::state_root::resolve_configured_state_root()
+        .unwrap()
+        .join("password");
+    crate::private_file::write_atomic(&path, b"file-secret-1\n").unwrap();
+    configure(json!({ "login": { "file": path } }));
+
+    assert_eq!(resolve("login").unwrap().value(), "file-secret-1");
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        let err = resolve("login").unwrap_err();
+        assert_eq!(err.code(), "INVALID_ARGS");
+        assert!(!err.to_string().contains("file-secret-1"));
+    }
+}
+
+#[test]
+fn a_configured_environment_variable_is_read() {
+    let _guard = HomeGuard::new();
+    configure(json!({ "path": { "env": "PATH" } }));
+
+    let expected = std::env::var("PATH").unwrap();
+    assert_eq!(resolve("path").unwrap().value(), expected);
+}
+
+#[test]
+fn an_unconfigured_name_falls_back_to_a_prefixed_environment_variable() {
+    let _guard = HomeGuard::new();
+
+    assert_eq!(
+        fallback_env_var("github-token"),
+        "AGENT_DESKTOP_SECRET_GITHUB_TOKEN"
+    );
+    let err = resolve("not-set-anywhere").unwrap_err();
+    assert_eq!(err.code(), "INVALID_ARGS");
+    assert!(
+        err.to_string()
+            .contains("AGENT_DESKTOP_SECRET_NOT_SET_ANYWHERE")
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn a_configured_command_prints_the_value_and_its_failures_are_reported() {
+    let _guard = HomeGuard::new();
+    configure(json!({
+        "vault": { "command": ["sh", "-c", "printf 'command-secret-1\\n'; echo noise >&2"] },
+        "broken": { "command": ["sh", "-c", "exit 3"] },
+    }));
+
+    assert_eq!(resolve("vault").unwrap().value(), "command-secret-1");
+    assert_eq!(resolve("broken").unwrap_err().code(), "ACTION_FAILED");
+}
+
+#[test]
+fn names_outside_the_allowed_alphabet_are_rejected() {
+    let _guard = HomeGuard::new();
+
+    for name in ["", "../etc", "has space", &"x".repeat(65)] {
+        assert_eq!(resolve(name).unwrap_err().code(), "INVALID_ARGS");
+    }
+}
+
+#[test]
+fn a_held_secret_is_scrubbed_from_results_errors_and_traces() {
+    let secret = Secret::register("held-secret-1".into());
+
+    let scrubbed = secret
+        .scrub(Ok(
+            json!({ "post_state": { "role": "textfield", "note": "typed held-secret-1 in" } }),
+        ))
+        .unwrap();
+    assert_eq!(scrubbed["post_state"]["note"], "typed [redacted] in");
+    assert_eq!(scrubbed["post_state"]["role"], "textfield");
+
+    let err = secret
+        .scrub(Err(AppError::invalid_input("typed held-secret-1 too fast")))
+        .unwrap_err();
+    assert_eq!(err.to_string(), "typed [redacted] too fast");
+
+    let traced = crate::sanitize_trace_value(json!({ "detail": "held-secret-1 rejected" }));
+    assert_eq!(traced["detail"], "[redacted] rejected");
+
+    drop(secret);
+    assert_eq!(hide_active_secrets("held-secret-1".into()), "held-secret-1");
+}
+
+#[test]
+fn a_short_secret_is_only_hidden_where_it_is_the_whole_string() {
+    let secret = Secret::register("k9".into());
+
+    let scrubbed = secret
+        .scrub(Ok(json!({ "typed": "k9", "note": "bank92 statement" })))
+        .unwrap();
+    assert_eq!(scrubbed["typed"], "[redacted]");
+    assert_eq!(scrubbed["note"], "bank92 statement");
+
+    let traced = crate::sanitize_trace_value(json!({ "detail": "k9", "message": "Desk9 k9x" }));
+    assert_eq!(traced["detail"], "[redacted]");
+    assert_eq!(traced["message"], "Desk9 k9x");
+}
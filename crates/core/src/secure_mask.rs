@@ -0,0 +1,121 @@
+use crate::{
+    AccessibilityNode, AppError, NodeMatchContext,
+    live_locator::normalize_query,
+    locator::{LocatorQuery, node_context, node_matches},
+    refs::{RefEntry, RefMap},
+    state,
+};
+use std::io::ErrorKind;
+
+/// User-authored list of locators (the `find` filter keys) whose values
+/// are masked like secure text fields, under the state root.
+pub const SECURE_FIELDS_FILE: &str = "secure-fields.json";
+const MAX_SECURE_FIELDS_BYTES: u64 = 64 * 1024;
+
+/// Decides which values never leave the process. A node is masked when the
+/// platform reports it `secure` or it matches one of the configured
+/// locators; masking drops its value and sets `value_masked`, so callers can
+/// tell a hidden value from an empty one.
+#[derive(Debug, Default)]
+pub(crate) struct SecureMask {
+    rules: Vec<LocatorQuery>,
+}
+
+impl SecureMask {
+    /// Loads the configured locators. A missing file means secure roles
+    /// only; an unreadable or malformed one fails the command rather than
+    /// silently showing the values it was meant to hide.
+    pub(crate) fn load() -> Result<Self, AppError> {
+        let path = crate::state_root::resolve_configured_state_root()?.join(SECURE_FIELDS_FILE);
+        let bytes = match crate::private_file::read_regular_bounded(&path, MAX_SECURE_FIELDS_BYTES)
+        {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(err.into()),
+        };
+        let rules: Vec<LocatorQuery> = serde_json::from_slice(&bytes).map_err(|err| {
+            AppError::invalid_input_with_suggestion(
+                format!("Invalid {SECURE_FIELDS_FILE}: {err}"),
+                "Write a JSON array of locators such as [{\"role\": \"textfield\", \"name\": \"API key\"}].",
+            )
+        })?;
+        Self::new(rules)
+    }
+
+    pub(crate) fn new(rules: Vec<LocatorQuery>) -> Result<Self, AppError> {
+        for rule in &rules {
+            if rule.is_empty() {
+                return Err(AppError::invalid_input(format!(
+                    "{SECURE_FIELDS_FILE} contains an empty locator, which would mask every value"
+                )));
+            }
+            rule.validate_states()?;
+        }
+        Ok(Self {
+            rules: rules.iter().map(normalize_query).collect(),
+        })
+    }
+
+    fn masks(&self, ctx: NodeMatchContext<'_>) -> bool {
+        state::has_state(ctx.states, state::SECURE)
+            || self.rules.iter().any(|rule| node_matches(rule, ctx))
+    }
+
+    /// Masks every matching node of `tree` and returns how many were masked.
+    pub(crate) fn mask_tree(&self, tree: &mut AccessibilityNode) -> usize {
+        let mut masked = 0;
+        if self.masks(node_context(tree)) {
+            tree.identity.value = None;
+            tree.identity.value_masked = true;
+            masked += 1;
+        }
+        for child in &mut tree.children {
+            masked += self.mask_tree(child);
+        }
+        masked
+    }
+
+    pub(crate) fn masks_entry(&self, entry: &RefEntry) -> bool {
+        self.masks(NodeMatchContext {
+            role: &entry.identity.role,
+            name: entry.identity.name.as_deref(),
+            description: entry.identity.description.as_deref(),
+            native_id: entry
+                .identity
+                .native_id
+                .as_ref()
+                .map(|identifier| identifier.value.as_str()),
+            value: entry.identity.value.as_deref(),
+            states: &entry.capabilities.states,
+            children: &[],
+        })
+    }
+
+    /// A copy of `refmap` without the values this mask covers, for writing
+    /// outside the ref store (trace artifacts).
+    pub(crate) fn mask_refmap(&self, refmap: &RefMap) -> RefMap {
+        let mut masked = refmap.clone();
+        masked.clear_values(|entry| self.masks_entry(entry));
+        masked
+    }
+}
+
+/// Masks a freshly observed tree unless the caller passed `--reveal-secure`.
+pub(crate) fn mask_observed_tree(
+    tree: &mut AccessibilityNode,
+    opts: &crate::adapter::TreeOptions,
+) -> Result<(), AppError> {
+    if !opts.reveal_secure {
+        SecureMask::load()?.mask_tree(tree);
+    }
+    Ok(())
+}
+
+/// Number of nodes in `tree` whose value was masked.
+pub(crate) fn count_masked(tree: &AccessibilityNode) -> usize {
+    usize::from(tree.identity.value_masked) + tree.children.iter().map(count_masked).sum::<usize>()
+}
+
+#[cfg(test)]
+#[path = "secure_mask_tests.rs"]
+mod tests;
@@ -0,0 +1,87 @@
+use super::*;
+use crate::refs_test_support::HomeGuard;
+
+fn node(role: &str, name: &str, value: &str, states: &[&str]) -> AccessibilityNode {
+    AccessibilityNode {
+        ref_id: None,
+        role: role.into(),
+        identity: crate::NodeIdentity {
+            name: Some(name.into()),
+            value: Some(value.into()),
+            ..Default::default()
+        },
+        presentation: crate::NodePresentation {
+            states: states.iter().map(|state| state.to_string()).collect(),
+            ..Default::default()
+        },
+        children_count: None,
+        subtree_truncated: false,
+        children: vec![],
+    }
+}
+
+fn form() -> AccessibilityNode {
+    let mut window = node("window", "Login", "", &[]);
+    window.children = vec![
+        node("textfield", "Password", "hunter2", &[state::SECURE]),
+        node("textfield", "API key", "sk-123", &[]),
+        node("textfield", "User", "ada", &[]),
+    ];
+    window
+}
+
+fn rule(json: serde_json::Value) -> LocatorQuery {
+    serde_json::from_value(json).unwrap()
+}
+
+#[test]
+fn secure_fields_are_masked_without_any_configured_locator() {
+    let mut tree = form();
+
+    let masked = SecureMask::default().mask_tree(&mut tree);
+
+    assert_eq!(masked, 1);
+    assert_eq!(tree.children[0].identity.value, None);
+    assert!(tree.children[0].identity.value_masked);
+    assert_eq!(tree.children[1].identity.value.as_deref(), Some("sk-123"));
+    assert_eq!(count_masked(&tree), 1);
+}
+
+#[test]
+fn configured_locators_mask_matching_fields_too() {
+    let mut tree = form();
+    let mask = SecureMask::new(vec![rule(
+        serde_json::json!({ "role": "textfield", "name": "API key" }),
+    )])
+    .unwrap();
+
+    assert_eq!(mask.mask_tree(&mut tree), 2);
+    assert!(tree.children[1].identity.value_masked);
+    assert_eq!(tree.children[2].identity.value.as_deref(), Some("ada"));
+}
+
+#[test]
+fn an_empty_locator_is_rejected_instead_of_masking_everything() {
+    let err = SecureMask::new(vec![LocatorQuery::default()]).unwrap_err();
+
+    assert_eq!(err.code(), "INVALID_ARGS");
+}
+
+#[test]
+fn the_locator_list_is_read_from_the_state_root() {
+    let _guard = HomeGuard::new();
+    let path = crate::state_root::resolve_configured_state_root()
+        .unwrap()
+        .join(SECURE_FIELDS_FILE);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, r#"[{"role": "textfield", "name": "User"}]"#).unwrap();
+    let mut tree = form();
+
+    assert_eq!(SecureMask::load().unwrap().mask_tree(&mut tree), 2);
+    assert!(tree.children[2].identity.value_masked);
+
+    std::fs::write(&path, "{not json").unwrap();
+    let err = SecureMask::load().unwrap_err();
+    assert_eq!(err.code(), "INVALID_ARGS");
+    assert!(err.to_string().contains(SECURE_FIELDS_FILE));
+}
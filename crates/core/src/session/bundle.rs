@@ -0,0 +1,307 @@
+use super::bundle_extract::{bundle_path, create_staging_dir, extract_files, read_manifest_entry};
+use super::bundle_sanitize::sanitize_for_export;
+use super::{SESSION_MANIFEST_FILE, SessionManifest, now_millis, session_dir};
+use crate::{
+    AppError, context::validate_session_id, refs::write_private_file, refs_lock::RefStoreLock,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+pub const SESSION_BUNDLE_FORMAT: &str = "agent-desktop-session-bundle";
+pub(super) const SESSION_BUNDLE_VERSION: u32 = 1;
+pub(super) const BUNDLE_MANIFEST_ENTRY: &str = "bundle.json";
+pub(super) const BUNDLE_FILES_PREFIX: &str = "session/";
+pub(super) const MAX_BUNDLE_FILE_BYTES: u64 = 256 * 1024 * 1024;
+pub(super) const MAX_BUNDLE_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+pub(super) const MAX_BUNDLE_FILES: usize = 20_000;
+const BUNDLE_ZSTD_LEVEL: i32 = 3;
+
+/// Content manifest stored as the first entry of a bundle. Every other
+/// entry is a file of the session, listed here with its size and SHA-256.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: u64,
+    pub session: SessionManifest,
+    pub files: Vec<BundleFile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleFile {
+    /// Path relative to the session directory, `/`-separated.
+    pub path: String,
+    pub bytes: u64,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportReport {
+    pub session_id: String,
+    pub path: PathBuf,
+    pub files: usize,
+    /// Size of the compressed bundle.
+    pub bytes: u64,
+    /// Trace lines dropped because they were not complete JSON events.
+    pub dropped_lines: usize,
+    /// Session entries left out because they were not regular files.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub skipped: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportReport {
+    pub session_id: String,
+    pub imported_from: String,
+    pub files: usize,
+    pub bytes: u64,
+}
+
+/// Packs a session into a zstd-compressed tar at `out`: a `bundle.json`
+/// content manifest, then the session's snapshots, aliases, and trace with
+/// its screenshots and refmap copies, each redacted by
+/// [`sanitize_for_export`]. Lock files, liveness leases, temp files, and
+/// symlinks are left out.
+pub fn export_session(session_id: &str, out: &Path) -> Result<ExportReport, AppError> {
+    validate_session_id(session_id)?;
+    let session = super::read_manifest(session_id)?.ok_or_else(|| {
+        AppError::invalid_input_with_suggestion(
+            format!("Session '{session_id}' has no manifest"),
+            "Use `session list` to see known sessions.",
+        )
+    })?;
+    let dir = session_dir(session_id)?;
+    let _lock = RefStoreLock::acquire(&dir.join("refstore.lock"))?;
+
+    let mut skipped = Vec::new();
+    let mut paths = Vec::new();
+    collect_files(&dir, "", &mut paths, &mut skipped)?;
+    let mut dropped_lines = 0;
+    let mut files = Vec::with_capacity(paths.len());
+    let mut sources = Vec::with_capacity(paths.len());
+    let mut total = 0u64;
+    for (relative, path) in paths {
+        let (raw_len, bytes) = export_bytes(&relative, &path, None, &mut dropped_lines)?;
+        total += bytes.len() as u64;
+        if total > MAX_BUNDLE_BYTES || files.len() >= MAX_BUNDLE_FILES {
+            return Err(AppError::invalid_input_with_suggestion(
+                format!("Session '{session_id}' is too large to bundle"),
+                "Export a session recorded without --screenshots, or prune its trace first.",
+            ));
+        }
+        files.push(BundleFile {
+            path: relative,
+            bytes: bytes.len() as u64,
+            sha256: sha256_hex(&bytes),
+        });
+        sources.push((path, raw_len));
+    }
+
+    let exported_at = now_millis();
+    let manifest = BundleManifest {
+        format: SESSION_BUNDLE_FORMAT.into(),
+        version: SESSION_BUNDLE_VERSION,
+        exported_at,
+        session,
+        files,
+    };
+    crate::refs::write_user_file_streamed(out, |file| {
+        write_archive(file, &manifest, &sources, exported_at / 1000)
+    })?;
+    Ok(ExportReport {
+        session_id: session_id.to_string(),
+        path: out.to_path_buf(),
+        files: manifest.files.len(),
+        bytes: std::fs::metadata(out)?.len(),
+        dropped_lines,
+        skipped,
+    })
+}
+
+/// A session file as it goes into the bundle: read, cut to `raw_len`
+/// bytes when given, then sanitized. Returns the length read with the
+/// sanitized bytes, so the archive pass can re-read exactly what the
+/// manifest pass hashed even while a live trace segment grows.
+fn export_bytes(
+    relative: &str,
+    path: &Path,
+    raw_len: Option<u64>,
+    dropped_lines: &mut usize,
+) -> Result<(u64, Vec<u8>), AppError> {
+    let mut raw = crate::private_file::read_regular_bounded(path, MAX_BUNDLE_FILE_BYTES)?;
+    if let Some(len) = raw_len {
+        raw.truncate(usize::try_from(len).unwrap_or(usize::MAX));
+    }
+    let raw_len = raw.len() as u64;
+    Ok((raw_len, sanitize_for_export(relative, raw, dropped_lines)?))
+}
+
+fn collect_files(
+    dir: &Path,
+    prefix: &str,
+    files: &mut Vec<(String, PathBuf)>,
+    skipped: &mut Vec<String>,
+) -> Result<(), AppError> {
+    let mut entries: Vec<_> = std::fs::read_dir(dir)?.flatten().collect();
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let name = entry.file_name();
+        let Some(name) = name.to_str() else {
+            skipped.push(format!("{prefix}{}", name.to_string_lossy()));
+            continue;
+        };
+        let relative = format!("{prefix}{name}");
+        if is_transient(prefix, name) {
+            continue;
+        }
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            collect_files(&entry.path(), &format!("{relative}/"), files, skipped)?;
+        } else if file_type.is_file() && bundle_path(&relative).is_some() {
+            files.push((relative, entry.path()));
+        } else {
+            skipped.push(relative);
+        }
+    }
+    Ok(())
+}
+
+/// State that belongs to the live session on this machine, not its record.
+pub(super) fn is_transient(prefix: &str, name: &str) -> bool {
+    let top_level =
+        prefix.is_empty() && matches!(name, SESSION_MANIFEST_FILE | "refstore.lock" | "liveness");
+    top_level || name.ends_with(".tmp") || name.ends_with(".lock")
+}
+
+/// Streams the tar through zstd into `out`, one file in memory at a time.
+/// Each file is read and sanitized again and must match the manifest entry
+/// hashed before; a file that changed in between fails the export.
+fn write_archive(
+    out: &mut std::fs::File,
+    manifest: &BundleManifest,
+    sources: &[(PathBuf, u64)],
+    mtime: u64,
+) -> std::io::Result<()> {
+    let mut builder = tar::Builder::new(zstd::Encoder::new(out, BUNDLE_ZSTD_LEVEL)?);
+    let manifest_json = serde_json::to_vec_pretty(manifest)?;
+    append_entry(&mut builder, BUNDLE_MANIFEST_ENTRY, &manifest_json, mtime)?;
+    for (listed, (path, raw_len)) in manifest.files.iter().zip(sources) {
+        let (_, bytes) = export_bytes(&listed.path, path, Some(*raw_len), &mut 0)
+            .map_err(std::io::Error::other)?;
+        if bytes.len() as u64 != listed.bytes || sha256_hex(&bytes) != listed.sha256 {
+            return Err(std::io::Error::other(format!(
+                "{} changed while the session was being exported",
+                listed.path
+            )));
+        }
+        append_entry(
+            &mut builder,
+            &format!("{BUNDLE_FILES_PREFIX}{}", listed.path),
+            &bytes,
+            mtime,
+        )?;
+    }
+    builder.into_inner()?.finish()?;
+    Ok(())
+}
+
+fn append_entry<W: std::io::Write>(
+    builder: &mut tar::Builder<W>,
+    path: &str,
+    bytes: &[u8],
+    mtime: u64,
+) -> std::io::Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_entry_type(tar::EntryType::Regular);
+    header.set_size(bytes.len() as u64);
+    header.set_mode(0o600);
+    header.set_mtime(mtime);
+    builder.append_data(&mut header, path, bytes)
+}
+
+/// Registers the session carried by a bundle, under `as_id` or the id it
+/// was exported with. Every entry must be a regular file at a plain
+/// relative path that the content manifest lists with a matching SHA-256;
+/// links, absolute paths, `..`, and unlisted or missing files reject the
+/// whole bundle. Files are written with the same private-file checks as
+/// the ref store into a staging directory that is renamed into place only
+/// once all of them verified. The session is registered ended and
+/// read-only.
+pub fn import_session(path: &Path, as_id: Option<&str>) -> Result<ImportReport, AppError> {
+    let file = crate::refs::open_nofollow(path).map_err(|err| {
+        AppError::invalid_input_with_suggestion(
+            format!("Cannot open session bundle '{}': {err}", path.display()),
+            "Pass the .tar.zst file written by `session export`.",
+        )
+    })?;
+    crate::private_file::validate_regular(&file)?;
+    let decoder = zstd::Decoder::new(file).map_err(invalid_bundle)?;
+    let mut archive = tar::Archive::new(decoder);
+    let mut entries = archive.entries().map_err(invalid_bundle)?;
+
+    let manifest = match entries.next() {
+        Some(entry) => read_manifest_entry(entry.map_err(invalid_bundle)?)?,
+        None => return Err(invalid_bundle("archive is empty")),
+    };
+    let session_id = as_id.unwrap_or(&manifest.session.id).to_string();
+    validate_session_id(&session_id)?;
+    let target = session_dir(&session_id)?;
+    if std::fs::symlink_metadata(&target).is_ok() {
+        return Err(AppError::invalid_input_with_suggestion(
+            format!("Session '{session_id}' already exists"),
+            "Pass --as <id> to import the bundle under another session id.",
+        ));
+    }
+
+    let staging = create_staging_dir()?;
+    let result = (|| {
+        let bytes = extract_files(&mut entries, &manifest, &staging)?;
+        let session = SessionManifest {
+            id: session_id.clone(),
+            ended_at: Some(manifest.session.ended_at.unwrap_or(manifest.exported_at)),
+            read_only: true,
+            imported_from: Some(
+                manifest
+                    .session
+                    .imported_from
+                    .clone()
+                    .unwrap_or_else(|| manifest.session.id.clone()),
+            ),
+            ..manifest.session.clone()
+        };
+        let json = serde_json::to_string_pretty(&session)?;
+        write_private_file(&staging.join(SESSION_MANIFEST_FILE), json.as_bytes())?;
+        if std::fs::symlink_metadata(&target).is_ok() {
+            return Err(AppError::invalid_input_with_suggestion(
+                format!("Session '{session_id}' already exists"),
+                "Pass --as <id> to import the bundle under another session id.",
+            ));
+        }
+        std::fs::rename(&staging, &target)?;
+        Ok(ImportReport {
+            session_id: session_id.clone(),
+            imported_from: session.imported_from.unwrap_or_default(),
+            files: manifest.files.len(),
+            bytes,
+        })
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_dir_all(&staging);
+    }
+    result
+}
+
+pub(super) fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+pub(super) fn invalid_bundle(detail: impl std::fmt::Display) -> AppError {
+    AppError::invalid_input_with_suggestion(
+        format!("Invalid session bundle: {detail}"),
+        "Re-export the session with `session export` and import the new bundle.",
+    )
+}
@@ -0,0 +1,146 @@
+use super::bundle::{
+    BUNDLE_FILES_PREFIX, BUNDLE_MANIFEST_ENTRY, BundleFile, BundleManifest, MAX_BUNDLE_BYTES,
+    MAX_BUNDLE_FILE_BYTES, MAX_BUNDLE_FILES, SESSION_BUNDLE_FORMAT, SESSION_BUNDLE_VERSION,
+    invalid_bundle, is_transient, sha256_hex,
+};
+use super::{agent_desktop_dir, now_millis};
+use crate::{AppError, context::validate_session_id, refs::write_private_file};
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+const MAX_BUNDLE_MANIFEST_BYTES: u64 = 16 * 1024 * 1024;
+static IMPORT_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+pub(super) fn read_manifest_entry<R: Read>(
+    mut entry: tar::Entry<'_, R>,
+) -> Result<BundleManifest, AppError> {
+    if entry.path_bytes().as_ref() != BUNDLE_MANIFEST_ENTRY.as_bytes() {
+        return Err(invalid_bundle(format!(
+            "first entry must be {BUNDLE_MANIFEST_ENTRY}"
+        )));
+    }
+    let bytes = read_entry(&mut entry, MAX_BUNDLE_MANIFEST_BYTES)?;
+    let manifest: BundleManifest = serde_json::from_slice(&bytes).map_err(invalid_bundle)?;
+    if manifest.format != SESSION_BUNDLE_FORMAT || manifest.version != SESSION_BUNDLE_VERSION {
+        return Err(invalid_bundle(format!(
+            "unsupported format {} version {}",
+            manifest.format, manifest.version
+        )));
+    }
+    if manifest.files.len() > MAX_BUNDLE_FILES {
+        return Err(invalid_bundle("too many files"));
+    }
+    validate_session_id(&manifest.session.id)?;
+    Ok(manifest)
+}
+
+pub(super) fn extract_files<R: Read>(
+    entries: &mut tar::Entries<'_, R>,
+    manifest: &BundleManifest,
+    staging: &Path,
+) -> Result<u64, AppError> {
+    let mut expected: BTreeMap<&str, &BundleFile> = BTreeMap::new();
+    for file in &manifest.files {
+        if bundle_path(&file.path).is_none() || expected.insert(&file.path, file).is_some() {
+            return Err(invalid_bundle(format!(
+                "manifest lists an invalid or duplicate path {:?}",
+                file.path
+            )));
+        }
+    }
+    let mut total = 0u64;
+    for entry in entries {
+        let mut entry = entry.map_err(invalid_bundle)?;
+        let name = String::from_utf8(entry.path_bytes().into_owned())
+            .map_err(|_| invalid_bundle("entry path is not UTF-8"))?;
+        if entry.header().entry_type() != tar::EntryType::Regular {
+            return Err(invalid_bundle(format!("{name:?} is not a regular file")));
+        }
+        let relative = name
+            .strip_prefix(BUNDLE_FILES_PREFIX)
+            .ok_or_else(|| invalid_bundle(format!("unexpected entry {name:?}")))?;
+        let destination =
+            bundle_path(relative).ok_or_else(|| invalid_bundle(format!("unsafe path {name:?}")))?;
+        let listed = expected
+            .remove(relative)
+            .ok_or_else(|| invalid_bundle(format!("{name:?} is not in the content manifest")))?;
+        total += listed.bytes;
+        if listed.bytes > MAX_BUNDLE_FILE_BYTES || total > MAX_BUNDLE_BYTES {
+            return Err(invalid_bundle("bundle is too large"));
+        }
+        let bytes = read_entry(&mut entry, listed.bytes)?;
+        if bytes.len() as u64 != listed.bytes || sha256_hex(&bytes) != listed.sha256 {
+            return Err(invalid_bundle(format!(
+                "{name:?} does not match its checksum"
+            )));
+        }
+        write_private_file(&staging.join(destination), &bytes)?;
+    }
+    if let Some(missing) = expected.keys().next() {
+        return Err(invalid_bundle(format!("{missing:?} is missing")));
+    }
+    Ok(total)
+}
+
+fn read_entry<R: Read>(entry: &mut R, max_bytes: u64) -> Result<Vec<u8>, AppError> {
+    let mut bytes = Vec::new();
+    entry
+        .take(max_bytes + 1)
+        .read_to_end(&mut bytes)
+        .map_err(invalid_bundle)?;
+    if bytes.len() as u64 > max_bytes {
+        return Err(invalid_bundle("entry is larger than its manifest size"));
+    }
+    Ok(bytes)
+}
+
+/// A bundle path as a relative path under the session directory: plain
+/// `/`-separated names only, never the manifest or transient state.
+pub(super) fn bundle_path(relative: &str) -> Option<PathBuf> {
+    let mut path = PathBuf::new();
+    let mut prefix = String::new();
+    for part in relative.split('/') {
+        let plain = !part.is_empty()
+            && part != "."
+            && part != ".."
+            && !part.contains(['\\', ':', '\0'])
+            && !is_transient(&prefix, part);
+        if !plain {
+            return None;
+        }
+        path.push(part);
+        prefix.push_str(part);
+        prefix.push('/');
+    }
+    Some(path)
+}
+
+/// Staging lives beside the sessions so the final rename stays on one
+/// filesystem; its leading dot is not a valid session id, so `session
+/// list` and `session gc` never see a half-imported session.
+pub(super) fn create_staging_dir() -> Result<PathBuf, AppError> {
+    let sessions = agent_desktop_dir()?.join("sessions");
+    let n = IMPORT_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let staging = sessions.join(format!(
+        ".import-{}-{n}-{}",
+        std::process::id(),
+        now_millis()
+    ));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        std::fs::DirBuilder::new()
+            .recursive(true)
+            .mode(0o700)
+            .create(&sessions)?;
+        std::fs::DirBuilder::new().mode(0o700).create(&staging)?;
+    }
+    #[cfg(not(unix))]
+    {
+        std::fs::create_dir_all(&sessions)?;
+        std::fs::create_dir(&staging)?;
+    }
+    Ok(staging)
+}
@@ -0,0 +1,60 @@
+use crate::{
+    AppError, refs::RefMap, refs_store::alias::RefAlias, trace_sanitize::sanitize_trace_value,
+};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Rewrites a session file on its way into a bundle. Trace events pass
+/// through `sanitize_trace_value` again, so a bundle never carries values
+/// an older writer failed to redact; incomplete lines are dropped and
+/// counted. Snapshot refmaps, their trace copies, and aliases lose every
+/// element value, which covers secure values a `--reveal-secure` snapshot
+/// kept in the ref store. Roles, names, and identifiers stay, since those
+/// are what a ref is re-identified by. Other files are copied as recorded.
+pub(super) fn sanitize_for_export(
+    relative: &str,
+    bytes: Vec<u8>,
+    dropped_lines: &mut usize,
+) -> Result<Vec<u8>, AppError> {
+    if relative == "aliases.json" {
+        return strip_alias_values(&bytes);
+    }
+    let parts: Vec<&str> = relative.split('/').collect();
+    match parts.as_slice() {
+        ["snapshots", _, "refmap.json"] | ["trace", "refmaps", _] => strip_refmap_values(&bytes),
+        ["trace", segment] if segment.ends_with(".jsonl") => {
+            Ok(sanitize_trace_segment(&bytes, dropped_lines)?)
+        }
+        _ => Ok(bytes),
+    }
+}
+
+fn sanitize_trace_segment(bytes: &[u8], dropped_lines: &mut usize) -> serde_json::Result<Vec<u8>> {
+    let mut sanitized = Vec::with_capacity(bytes.len());
+    for line in bytes.split_inclusive(|byte| *byte == b'\n') {
+        let complete = line.last() == Some(&b'\n');
+        match serde_json::from_slice::<Value>(line.trim_ascii()) {
+            Ok(event) if complete => {
+                serde_json::to_writer(&mut sanitized, &sanitize_trace_value(event))?;
+                sanitized.push(b'\n');
+            }
+            _ if line.trim_ascii().is_empty() => {}
+            _ => *dropped_lines += 1,
+        }
+    }
+    Ok(sanitized)
+}
+
+fn strip_refmap_values(bytes: &[u8]) -> Result<Vec<u8>, AppError> {
+    let mut refmap: RefMap = serde_json::from_slice(bytes)?;
+    refmap.clear_values(|_| true);
+    Ok(refmap.serialize_with_size_check()?.into_bytes())
+}
+
+fn strip_alias_values(bytes: &[u8]) -> Result<Vec<u8>, AppError> {
+    let mut aliases: BTreeMap<String, RefAlias> = serde_json::from_slice(bytes)?;
+    for alias in aliases.values_mut() {
+        alias.entry.identity.value = None;
+    }
+    Ok(serde_json::to_vec(&aliases)?)
+}
@@ -61,25 +61,55 @@ pub fn gc(options: GcOptions) -> Result<GcReport, AppError> {
         } else if manifest.ended_at.is_none() {
             continue;
         }
-        let store = RefStore::for_session(Some(&manifest.id))?;
-        let dir = store.base_dir().to_path_buf();
-        let lock = match RefStoreLock::acquire(&dir.join("refstore.lock")) {
-            Ok(lock) => lock,
-            Err(_) => continue,
+        if remove_if_idle(&manifest)? {
+            removed.push(manifest.id);
+        }
+    }
+    Ok(GcReport { removed })
+}
+
+/// Collects every ended session whose `ended_at` is older than its quota's
+/// `max_age_secs` (its own, else the global one). Runs opportunistically on
+/// `session start`, so it applies the same liveness checks as `gc`: a
+/// session with a held lease, a live ref-store lock, or recent activity is
+/// never collected, and `except` (the session being started) is skipped
+/// outright. A session that never ended may just be idle between commands,
+/// so only an explicit `session gc` removes it.
+pub fn enforce_retention(except: &str) -> Result<GcReport, AppError> {
+    let global = super::read_global_quota()?;
+    let mut removed = Vec::new();
+    for manifest in list_sessions()? {
+        let Some(max_age) = manifest.quota.or(global).max_age_secs else {
+            continue;
         };
-        if super::liveness::any_held(&dir) || has_recent_activity(&store, Some(&manifest)) {
-            drop(lock);
+        let Some(ended_at) = manifest.ended_at else {
+            continue;
+        };
+        let age_ms = now_millis().saturating_sub(ended_at);
+        if manifest.id == except || Duration::from_millis(age_ms) < Duration::from_secs(max_age) {
             continue;
         }
-        let did_remove = remove_session_dir(&dir)?;
-        drop(lock);
-        if did_remove {
+        if !is_live(&manifest.id)? && remove_if_idle(&manifest)? {
             removed.push(manifest.id);
         }
     }
     Ok(GcReport { removed })
 }
 
+fn remove_if_idle(manifest: &SessionManifest) -> Result<bool, AppError> {
+    let store = RefStore::for_session(Some(&manifest.id))?;
+    let dir = store.base_dir().to_path_buf();
+    let Ok(lock) = RefStoreLock::acquire(&dir.join("refstore.lock")) else {
+        return Ok(false);
+    };
+    if super::liveness::any_held(&dir) || has_recent_activity(&store, Some(manifest)) {
+        return Ok(false);
+    }
+    let did_remove = remove_session_dir(&dir)?;
+    drop(lock);
+    Ok(did_remove)
+}
+
 fn path_recently_modified(path: &Path) -> bool {
     let cutoff = SystemTime::now()
         .checked_sub(TRACE_LIVENESS_WINDOW)
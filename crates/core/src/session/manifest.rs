@@ -1,3 +1,4 @@
+use super::SessionQuota;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -12,6 +13,15 @@ pub struct SessionManifest {
     pub trace: SessionTraceMode,
     #[serde(default)]
     pub artifacts: ArtifactsMode,
+    #[serde(default, skip_serializing_if = "SessionQuota::is_empty")]
+    pub quota: SessionQuota,
+    /// Set on sessions registered by `session import`: their snapshots,
+    /// aliases, and trace are kept exactly as the bundle carried them.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub read_only: bool,
+    /// Id the session had where its bundle was exported.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub imported_from: Option<String>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
@@ -31,11 +41,18 @@ pub enum SessionTraceMode {
 }
 
 impl SessionManifest {
+    /// Whether commands run under this session append to its trace: never
+    /// once it ended, and never for an imported session, whose trace is the
+    /// record of another machine's run.
     pub fn trace_enabled(&self) -> bool {
-        matches!(self.trace, SessionTraceMode::On) && self.ended_at.is_none()
+        matches!(self.trace, SessionTraceMode::On) && self.is_live()
     }
 
     pub fn artifacts_full(&self) -> bool {
-        matches!(self.artifacts, ArtifactsMode::Full) && self.ended_at.is_none()
+        matches!(self.artifacts, ArtifactsMode::Full) && self.is_live()
+    }
+
+    fn is_live(&self) -> bool {
+        self.ended_at.is_none() && !self.read_only
     }
 }
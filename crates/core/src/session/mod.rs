@@ -1,10 +1,20 @@
+mod bundle;
+mod bundle_extract;
+mod bundle_sanitize;
 mod gc;
 mod liveness;
 mod manifest;
+mod quota;
 
-pub use gc::{GcOptions, GcReport, gc, is_live};
+pub use bundle::{
+    BundleFile, BundleManifest, ExportReport, ImportReport, SESSION_BUNDLE_FORMAT, export_session,
+    import_session,
+};
+pub use gc::{GcOptions, GcReport, enforce_retention, gc, is_live};
 pub use liveness::SessionLivenessLease;
 pub use manifest::{ArtifactsMode, SessionManifest, SessionTraceMode};
+pub(crate) use quota::quota_for_trace_dir;
+pub use quota::{SessionQuota, read_global_quota, write_global_quota};
 
 use crate::{
     AppError, context::validate_session_id, refs::write_private_file, refs_store::RefStore,
@@ -24,6 +34,7 @@ pub struct StartSessionOptions {
     pub name: Option<String>,
     pub trace: SessionTraceMode,
     pub artifacts: ArtifactsMode,
+    pub quota: SessionQuota,
 }
 
 impl Default for StartSessionOptions {
@@ -32,6 +43,7 @@ impl Default for StartSessionOptions {
             name: None,
             trace: SessionTraceMode::On,
             artifacts: ArtifactsMode::Events,
+            quota: SessionQuota::default(),
         }
     }
 }
@@ -183,6 +195,9 @@ pub fn start_session(options: StartSessionOptions) -> Result<SessionManifest, Ap
         ended_at: None,
         trace: options.trace,
         artifacts: options.artifacts,
+        quota: options.quota,
+        read_only: false,
+        imported_from: None,
     };
     write_manifest(&manifest)?;
     Ok(manifest)
@@ -260,3 +275,11 @@ mod tests;
 #[cfg(test)]
 #[path = "session_gc_tests.rs"]
 mod gc_tests;
+
+#[cfg(test)]
+#[path = "session_bundle_tests.rs"]
+mod bundle_tests;
+
+#[cfg(test)]
+#[path = "session_quota_tests.rs"]
+mod quota_tests;
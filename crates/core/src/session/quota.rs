@@ -0,0 +1,83 @@
+use super::{
+    MAX_SESSION_MANIFEST_BYTES, SESSION_MANIFEST_FILE, SessionManifest, agent_desktop_dir,
+};
+use crate::{AppError, refs::write_private_file};
+use serde::{Deserialize, Serialize};
+use std::io::ErrorKind;
+use std::path::Path;
+
+/// Under `sessions/`, beside the session directories `list_sessions` reads.
+const GLOBAL_QUOTA_FILE: &str = "quota.json";
+const MAX_GLOBAL_QUOTA_BYTES: u64 = 4096;
+
+/// Resource limits for one session. An unset field falls back to the
+/// global quota, and an unset global field to the built-in budget (no
+/// limit for trace bytes and age).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionQuota {
+    /// Bytes across all of the session's trace segments.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_trace_bytes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_screenshots: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_screenshot_bytes: Option<u64>,
+    /// Seconds since the session ended (or started, if it never ended)
+    /// after which retention collects it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age_secs: Option<u64>,
+}
+
+impl SessionQuota {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// This quota, with each unset field taken from `fallback`.
+    pub fn or(self, fallback: Self) -> Self {
+        Self {
+            max_trace_bytes: self.max_trace_bytes.or(fallback.max_trace_bytes),
+            max_screenshots: self.max_screenshots.or(fallback.max_screenshots),
+            max_screenshot_bytes: self.max_screenshot_bytes.or(fallback.max_screenshot_bytes),
+            max_age_secs: self.max_age_secs.or(fallback.max_age_secs),
+        }
+    }
+}
+
+pub fn read_global_quota() -> Result<SessionQuota, AppError> {
+    let path = agent_desktop_dir()?
+        .join("sessions")
+        .join(GLOBAL_QUOTA_FILE);
+    match crate::private_file::read_private_bounded(&path, MAX_GLOBAL_QUOTA_BYTES) {
+        Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(SessionQuota::default()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+pub fn write_global_quota(quota: &SessionQuota) -> Result<(), AppError> {
+    let path = agent_desktop_dir()?
+        .join("sessions")
+        .join(GLOBAL_QUOTA_FILE);
+    write_private_file(&path, &serde_json::to_vec_pretty(quota)?)
+}
+
+/// Effective quota for the session that owns `trace_dir`: its manifest's
+/// quota over the global one. Writers call this with the trace directory
+/// they already hold, so an unreadable manifest or global file degrades to
+/// the built-in budgets instead of failing the write.
+pub(crate) fn quota_for_trace_dir(trace_dir: &Path) -> SessionQuota {
+    let session = trace_dir
+        .parent()
+        .and_then(|dir| {
+            crate::private_file::read_private_bounded(
+                &dir.join(SESSION_MANIFEST_FILE),
+                MAX_SESSION_MANIFEST_BYTES,
+            )
+            .ok()
+        })
+        .and_then(|bytes| serde_json::from_slice::<SessionManifest>(&bytes).ok())
+        .map(|manifest| manifest.quota)
+        .unwrap_or_default();
+    session.or(read_global_quota().unwrap_or_default())
+}
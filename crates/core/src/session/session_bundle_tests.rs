@@ -0,0 +1,245 @@
+use super::*;
+use crate::refs::RefMap;
+use crate::refs_test_support::HomeGuard;
+use std::path::Path;
+
+fn traced_session() -> SessionManifest {
+    let manifest = start_session(StartSessionOptions::default()).unwrap();
+    let dir = session_dir(&manifest.id).unwrap();
+    write_private_file(
+        &dir.join("trace").join("100-1.jsonl"),
+        concat!(
+            "{\"event\":\"command.start\",\"ts_ms\":1,\"command\":\"type\",\"password\":\"hunter2\"}\n",
+            "{\"event\":\"command.end\",\"ts_ms\":2,",
+        )
+        .as_bytes(),
+    )
+    .unwrap();
+    RefStore::for_session(Some(&manifest.id))
+        .unwrap()
+        .save_new_snapshot(&RefMap::new())
+        .unwrap();
+    manifest
+}
+
+fn read(path: &Path) -> String {
+    String::from_utf8(std::fs::read(path).unwrap()).unwrap()
+}
+
+#[test]
+fn export_then_import_registers_a_sanitized_read_only_copy() {
+    let guard = HomeGuard::new();
+    let source = traced_session();
+    let out = guard.path().join("run.tar.zst");
+
+    let exported = export_session(&source.id, &out).unwrap();
+    assert_eq!(exported.dropped_lines, 1, "the partial trailing line");
+    let imported = import_session(&out, Some("shared-run")).unwrap();
+
+    assert_eq!(imported.files, exported.files);
+    assert_eq!(imported.imported_from, source.id);
+    let manifest = read_manifest("shared-run").unwrap().unwrap();
+    assert!(manifest.read_only);
+    assert!(manifest.ended_at.is_some());
+    assert!(!manifest.trace_enabled());
+    let reopened = SessionManifest {
+        ended_at: None,
+        ..manifest
+    };
+    assert!(!reopened.trace_enabled() && !reopened.artifacts_full());
+
+    let trace = session_dir("shared-run").unwrap().join("trace");
+    let events = read(&trace.join("100-1.jsonl"));
+    assert_eq!(events.lines().count(), 1);
+    assert!(!events.contains("hunter2"));
+    let store = RefStore::for_session(Some("shared-run")).unwrap();
+    assert!(
+        store.load_latest().is_ok(),
+        "snapshots travel with the bundle"
+    );
+}
+
+#[test]
+fn an_imported_session_refuses_new_refs() {
+    let guard = HomeGuard::new();
+    let source = traced_session();
+    let out = guard.path().join("run.tar.zst");
+    export_session(&source.id, &out).unwrap();
+    import_session(&out, Some("shared-run")).unwrap();
+
+    let err = RefStore::for_session(Some("shared-run"))
+        .unwrap()
+        .save_new_snapshot(&RefMap::new())
+        .unwrap_err();
+
+    assert_eq!(err.code(), "INVALID_ARGS");
+    assert!(err.to_string().contains("read-only"));
+}
+
+#[test]
+fn import_never_replaces_an_existing_session() {
+    let guard = HomeGuard::new();
+    let source = traced_session();
+    let out = guard.path().join("run.tar.zst");
+    export_session(&source.id, &out).unwrap();
+
+    let err = import_session(&out, None).unwrap_err();
+
+    assert!(err.to_string().contains("already exists"));
+    assert!(!read_manifest(&source.id).unwrap().unwrap().read_only);
+}
+
+fn bundle_with(files: Vec<BundleFile>, entries: &[(&str, tar::EntryType, &[u8])]) -> Vec<u8> {
+    let manifest = BundleManifest {
+        format: SESSION_BUNDLE_FORMAT.into(),
+        version: 1,
+        exported_at: 1,
+        session: SessionManifest {
+            id: "origin".into(),
+            name: None,
+            created_at: 1,
+            ended_at: Some(2),
+            trace: SessionTraceMode::On,
+            artifacts: ArtifactsMode::Events,
+            quota: SessionQuota::default(),
+            read_only: false,
+            imported_from: None,
+        },
+        files,
+    };
+    let mut builder = tar::Builder::new(Vec::new());
+    let manifest = serde_json::to_vec(&manifest).unwrap();
+    let mut all = vec![("bundle.json", tar::EntryType::Regular, manifest.as_slice())];
+    all.extend_from_slice(entries);
+    for (name, kind, bytes) in all {
+        let mut header = tar::Header::new_old();
+        header.as_old_mut().name[..name.len()].copy_from_slice(name.as_bytes());
+        header.set_entry_type(kind);
+        header.set_size(bytes.len() as u64);
+        header.set_mode(0o600);
+        header.set_cksum();
+        builder.append(&header, bytes).unwrap();
+    }
+    zstd::encode_all(builder.into_inner().unwrap().as_slice(), 0).unwrap()
+}
+
+fn import_bytes(guard: &HomeGuard, bytes: Vec<u8>) -> Result<ImportReport, AppError> {
+    let path = guard.path().join("crafted.tar.zst");
+    std::fs::write(&path, bytes).unwrap();
+    import_session(&path, None)
+}
+
+fn assert_nothing_registered() {
+    let sessions = agent_desktop_dir().unwrap().join("sessions");
+    let leftovers: Vec<_> = std::fs::read_dir(&sessions)
+        .map(|entries| entries.flatten().map(|entry| entry.file_name()).collect())
+        .unwrap_or_default();
+    assert!(leftovers.is_empty(), "left behind {leftovers:?}");
+}
+
+#[test]
+fn import_rejects_traversal_and_links() {
+    let guard = HomeGuard::new();
+    for (name, kind) in [
+        ("session/../escape", tar::EntryType::Regular),
+        ("session//etc/passwd", tar::EntryType::Regular),
+        ("session/trace/link", tar::EntryType::Symlink),
+        ("session/session.json", tar::EntryType::Regular),
+    ] {
+        let err = import_bytes(&guard, bundle_with(Vec::new(), &[(name, kind, b"x")])).unwrap_err();
+        assert!(err.to_string().contains("Invalid session bundle"), "{name}");
+    }
+    assert!(!agent_desktop_dir().unwrap().join("escape").exists());
+    assert_nothing_registered();
+}
+
+#[test]
+fn import_rejects_checksum_mismatches_and_missing_files() {
+    let guard = HomeGuard::new();
+    let listed = |sha256: &str| BundleFile {
+        path: "latest_snapshot_id".into(),
+        bytes: 1,
+        sha256: sha256.into(),
+    };
+
+    let tampered = bundle_with(
+        vec![listed(&"0".repeat(64))],
+        &[("session/latest_snapshot_id", tar::EntryType::Regular, b"x")],
+    );
+    let err = import_bytes(&guard, tampered).unwrap_err();
+    assert!(err.to_string().contains("checksum"));
+
+    let missing = bundle_with(vec![listed(&"0".repeat(64))], &[]);
+    let err = import_bytes(&guard, missing).unwrap_err();
+    assert!(err.to_string().contains("missing"));
+    assert_nothing_registered();
+}
+
+fn revealed_password_field() -> crate::refs::RefEntry {
+    crate::refs::RefEntry {
+        process: crate::RefProcess {
+            pid: crate::ProcessId::new(100),
+            process_instance: Some("100:1".into()),
+        },
+        identity: crate::RefEntryIdentity {
+            role: "textfield".into(),
+            name: Some("Password".into()),
+            value: Some("hunter2".into()),
+            description: None,
+            native_id: None,
+        },
+        geometry: crate::RefGeometry {
+            bounds: None,
+            bounds_hash: None,
+        },
+        capabilities: crate::RefCapabilities {
+            states: vec![crate::state::SECURE.into()],
+            available_actions: vec![],
+        },
+        source: crate::RefSource {
+            source_app: Some("Login".into()),
+            source_window_id: None,
+            source_window_title: None,
+            source_window_bounds_hash: None,
+            source_surface: crate::adapter::SnapshotSurface::Window,
+        },
+        scope: crate::RefScope {
+            root_ref: None,
+            path_is_absolute: false,
+            path: smallvec::SmallVec::new(),
+        },
+    }
+}
+
+#[test]
+fn export_drops_values_a_revealed_snapshot_kept() {
+    let guard = HomeGuard::new();
+    let source = start_session(StartSessionOptions::default()).unwrap();
+    let store = RefStore::for_session(Some(&source.id)).unwrap();
+    let mut refmap = RefMap::new();
+    refmap.allocate(revealed_password_field());
+    store.save_new_snapshot(&refmap).unwrap();
+    let alias = crate::refs_store::alias::RefAlias {
+        source_ref: "@e1".into(),
+        entry: revealed_password_field(),
+    };
+    store.set_alias("login", alias).unwrap();
+    let out = guard.path().join("run.tar.zst");
+
+    export_session(&source.id, &out).unwrap();
+
+    let archive = zstd::decode_all(std::fs::File::open(&out).unwrap()).unwrap();
+    assert!(!String::from_utf8_lossy(&archive).contains("hunter2"));
+    import_session(&out, Some("shared-run")).unwrap();
+    let imported = RefStore::for_session(Some("shared-run")).unwrap();
+    let field = imported.load_latest().unwrap().get("@e1").cloned().unwrap();
+    assert_eq!(field.identity.name.as_deref(), Some("Password"));
+    assert_eq!(field.identity.value, None);
+    assert_eq!(
+        imported.load_aliases().unwrap()["login"]
+            .entry
+            .identity
+            .value,
+        None
+    );
+}
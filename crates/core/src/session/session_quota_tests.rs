@@ -0,0 +1,97 @@
+use super::*;
+use crate::refs_test_support::HomeGuard;
+
+fn aged_session(quota: SessionQuota) -> SessionManifest {
+    let mut manifest = start_session(StartSessionOptions {
+        trace: SessionTraceMode::Off,
+        quota,
+        ..Default::default()
+    })
+    .unwrap();
+    manifest.created_at = 0;
+    manifest.ended_at = Some(1);
+    write_manifest(&manifest).unwrap();
+    manifest
+}
+
+fn max_age(secs: u64) -> SessionQuota {
+    SessionQuota {
+        max_age_secs: Some(secs),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn a_session_quota_overrides_the_global_one_field_by_field() {
+    let _guard = HomeGuard::new();
+    write_global_quota(&SessionQuota {
+        max_screenshots: Some(5),
+        max_trace_bytes: Some(1000),
+        ..Default::default()
+    })
+    .unwrap();
+    let manifest = aged_session(SessionQuota {
+        max_trace_bytes: Some(10),
+        ..Default::default()
+    });
+
+    let quota = quota_for_trace_dir(&session_dir(&manifest.id).unwrap().join("trace"));
+
+    assert_eq!(quota.max_trace_bytes, Some(10));
+    assert_eq!(quota.max_screenshots, Some(5));
+    assert_eq!(quota.max_age_secs, None);
+    assert_eq!(
+        read_manifest(&manifest.id)
+            .unwrap()
+            .unwrap()
+            .quota
+            .max_trace_bytes,
+        Some(10)
+    );
+}
+
+#[test]
+fn retention_collects_only_sessions_past_their_max_age() {
+    let _guard = HomeGuard::new();
+    let expired = aged_session(max_age(60));
+    let unlimited = aged_session(SessionQuota::default());
+    let starting = aged_session(max_age(60));
+
+    let report = enforce_retention(&starting.id).unwrap();
+
+    assert_eq!(report.removed, std::slice::from_ref(&expired.id));
+    assert!(!session_dir(&expired.id).unwrap().exists());
+    assert!(session_dir(&unlimited.id).unwrap().is_dir());
+    assert!(session_dir(&starting.id).unwrap().is_dir());
+}
+
+#[test]
+fn retention_applies_the_global_max_age_and_never_collects_live_sessions() {
+    let _guard = HomeGuard::new();
+    write_global_quota(&max_age(60)).unwrap();
+    let expired = aged_session(SessionQuota::default());
+    let held = aged_session(SessionQuota::default());
+    let kept = aged_session(max_age(u64::MAX));
+    let lease = acquire_liveness_lease(&held.id).unwrap().unwrap();
+
+    let report = enforce_retention("").unwrap();
+
+    assert_eq!(report.removed, [expired.id]);
+    assert!(session_dir(&held.id).unwrap().is_dir());
+    assert!(session_dir(&kept.id).unwrap().is_dir());
+    drop(lease);
+    assert_eq!(enforce_retention("").unwrap().removed, [held.id]);
+}
+
+#[test]
+fn retention_never_collects_a_session_that_has_not_ended() {
+    let _guard = HomeGuard::new();
+    let mut idle = aged_session(max_age(60));
+    idle.ended_at = None;
+    write_manifest(&idle).unwrap();
+
+    let report = enforce_retention("").unwrap();
+
+    assert!(report.removed.is_empty());
+    assert!(session_dir(&idle.id).unwrap().is_dir());
+}
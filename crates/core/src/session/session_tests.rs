@@ -45,6 +45,9 @@ fn manifest_round_trips_with_optional_fields() {
         ended_at: None,
         trace: SessionTraceMode::On,
         artifacts: ArtifactsMode::Events,
+        quota: SessionQuota::default(),
+        read_only: false,
+        imported_from: None,
     };
     write_manifest(&manifest).unwrap();
     let loaded = read_manifest("run-1").unwrap().expect("manifest");
@@ -316,6 +319,7 @@ fn ending_a_full_artifacts_session_drops_scaffolding_but_keeps_the_recording() {
         name: Some("sealed-full".into()),
         trace: SessionTraceMode::On,
         artifacts: crate::session::manifest::ArtifactsMode::Full,
+        ..Default::default()
     })
     .unwrap();
     let (dir, snapshot_id) = seed_session_refs(&manifest.id);
@@ -335,6 +339,7 @@ fn a_refmap_the_trace_never_copied_survives_ending_a_full_session() {
         name: Some("sealed-partial".into()),
         trace: SessionTraceMode::On,
         artifacts: crate::session::manifest::ArtifactsMode::Full,
+        ..Default::default()
     })
     .unwrap();
 
@@ -21,6 +21,7 @@ pub struct SnapshotResult {
     pub snapshot_id: Option<String>,
     pub complete: bool,
     pub nodes_observed: usize,
+    pub extras: crate::snapshot_extras::SnapshotExtras,
 }
 
 impl SnapshotResult {
@@ -39,12 +40,15 @@ pub fn build(
 ) -> Result<SnapshotResult, AppError> {
     let window = resolve_window_for_surface(adapter, app_name, window_id, opts.surface, deadline)?;
     let observation_options = opts.with_ref_identity_bounds();
-    let (raw_tree, complete, nodes_observed) = crate::renderer_accessibility::observe_tree(
+    let (mut raw_tree, complete, nodes_observed) = crate::renderer_accessibility::observe_tree(
         adapter,
         ObservationRoot::Window(&window),
         &ObservationRequest::snapshot(&observation_options, deadline).validate()?,
     )?
     .into_accessibility_tree_partial()?;
+    let web_content = crate::cdp_tree::graft(&mut raw_tree, &window, opts.max_depth, deadline);
+    let mut raw_tree = opts.budget_tree(raw_tree);
+    crate::secure_mask::mask_observed_tree(&mut raw_tree, opts)?;
 
     let mut refmap = RefMap::new();
     let config = RefAllocConfig {
@@ -78,6 +82,10 @@ pub fn build(
         snapshot_id: None,
         complete,
         nodes_observed,
+        extras: crate::snapshot_extras::SnapshotExtras {
+            stable_refs: None,
+            web_content,
+        },
     })
 }
 
@@ -196,13 +204,21 @@ pub fn run_with_context(
     window_id: Option<&str>,
     context: &CommandContext,
 ) -> Result<SnapshotResult, AppError> {
-    let mut result = build(
+    let result = build(
         adapter,
         opts,
         app_name,
         window_id,
         crate::Deadline::after(3_000)?,
     )?;
+    persist(result, context)
+}
+
+/// Saves a built snapshot as the namespace's latest and binds its id.
+pub(crate) fn persist(
+    mut result: SnapshotResult,
+    context: &CommandContext,
+) -> Result<SnapshotResult, AppError> {
     let store = RefStore::for_session(context.session_id())?;
     let snapshot_id = store.save_new_snapshot(&result.refmap)?;
     trace_artifacts::copy_refmap_if_full(context, &store, &snapshot_id, &result.refmap)?;
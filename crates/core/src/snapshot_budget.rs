@@ -0,0 +1,209 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use serde_json::Value;
+
+use crate::AccessibilityNode;
+
+/// Serialized JSON bytes per estimated LLM token. A coarse, model-agnostic
+/// ratio: it only has to rank subtrees and keep the total near the budget.
+pub(crate) const BYTES_PER_TOKEN: usize = 4;
+
+/// Serialized overhead a qualified `"ref_id":"@<snapshot>:eN",` adds to a
+/// node once refs are allocated after budgeting.
+const REF_ID_BYTES: usize = 28;
+
+const FOCUSED_WEIGHT: u64 = 8;
+const INTERACTIVE_WEIGHT: u64 = 4;
+const NAMED_WEIGHT: u64 = 2;
+
+/// Cost-model inputs that mirror the ref-allocation options applied after
+/// budgeting, so pruned bounds and non-interactive leaves are not charged.
+#[derive(Clone, Copy)]
+pub(crate) struct BudgetCostModel {
+    pub(crate) include_bounds: bool,
+    pub(crate) interactive_only: bool,
+}
+
+struct Slot {
+    node: AccessibilityNode,
+    children: Vec<usize>,
+    own_bytes: usize,
+    subtree_bytes: usize,
+    subtree_info: u64,
+    holds_focus: bool,
+}
+
+/// Greedily keeps the most informative nodes of a raw tree until the
+/// estimated serialized size reaches `max_tokens`. The root is always kept.
+/// Candidates are ranked by whether they lead to the focused element, then by
+/// information density (interactive, named, focused nodes per byte), so a
+/// cheap toolbar full of buttons wins over a large unnamed layout subtree.
+/// Every container that loses children carries `children_count` and
+/// `subtree_truncated`, making it a `--root` drill-down target.
+pub(crate) fn apply_token_budget(
+    tree: AccessibilityNode,
+    max_tokens: u32,
+    model: BudgetCostModel,
+) -> AccessibilityNode {
+    let mut slots = Vec::new();
+    flatten(tree, &model, &mut slots);
+    aggregate(&mut slots);
+
+    let budget = (max_tokens as usize).saturating_mul(BYTES_PER_TOKEN);
+    let mut included = vec![false; slots.len()];
+    included[0] = true;
+    let mut used = slots[0].own_bytes;
+    let mut frontier = BinaryHeap::new();
+    push_children(&slots, 0, &mut frontier);
+
+    while let Some((_, _, Reverse(index))) = frontier.pop() {
+        let cost = slots[index].own_bytes;
+        if used.saturating_add(cost) > budget {
+            continue;
+        }
+        used += cost;
+        included[index] = true;
+        push_children(&slots, index, &mut frontier);
+    }
+
+    rebuild(&mut slots, &included, 0)
+}
+
+/// Estimated token cost of an already-serialized snapshot tree.
+pub(crate) fn estimate_tokens(tree: &Value) -> usize {
+    serde_json::to_vec(tree)
+        .map(|bytes| bytes.len().div_ceil(BYTES_PER_TOKEN))
+        .unwrap_or(0)
+}
+
+/// Number of containers in a serialized tree whose descendants were cut short.
+pub(crate) fn count_truncated(tree: &Value) -> usize {
+    let own = tree.get("subtree_truncated").and_then(Value::as_bool) == Some(true);
+    let children = tree
+        .get("children")
+        .and_then(Value::as_array)
+        .map_or(0, |children| children.iter().map(count_truncated).sum());
+    usize::from(own) + children
+}
+
+fn flatten(mut node: AccessibilityNode, model: &BudgetCostModel, slots: &mut Vec<Slot>) -> usize {
+    let children = std::mem::take(&mut node.children);
+    let index = slots.len();
+    let ref_able = crate::ref_alloc::is_ref_able(&node);
+    let focused = node
+        .presentation
+        .states
+        .iter()
+        .any(|state| state == crate::state::FOCUSED);
+    let named = node
+        .identity
+        .name
+        .as_deref()
+        .is_some_and(|name| !name.trim().is_empty());
+    let pruned_leaf = model.interactive_only && !ref_able && children.is_empty();
+    let own_bytes = if pruned_leaf {
+        0
+    } else {
+        own_bytes(&mut node, model.include_bounds, ref_able)
+    };
+    let own_info = u64::from(focused) * FOCUSED_WEIGHT
+        + u64::from(ref_able) * INTERACTIVE_WEIGHT
+        + u64::from(named) * NAMED_WEIGHT;
+    slots.push(Slot {
+        node,
+        children: Vec::with_capacity(children.len()),
+        own_bytes,
+        subtree_bytes: own_bytes,
+        subtree_info: own_info,
+        holds_focus: focused,
+    });
+    for child in children {
+        let child_index = flatten(child, model, slots);
+        slots[index].children.push(child_index);
+    }
+    index
+}
+
+fn own_bytes(node: &mut AccessibilityNode, include_bounds: bool, ref_able: bool) -> usize {
+    let bounds = if include_bounds {
+        None
+    } else {
+        node.presentation.bounds.take()
+    };
+    let bytes = serde_json::to_vec(&*node).map_or(0, |bytes| bytes.len());
+    if bounds.is_some() {
+        node.presentation.bounds = bounds;
+    }
+    bytes + if ref_able { REF_ID_BYTES } else { 0 }
+}
+
+/// Slots are in preorder, so every child index is greater than its parent's
+/// and a reverse sweep folds each subtree before its parent reads it.
+fn aggregate(slots: &mut [Slot]) {
+    for index in (0..slots.len()).rev() {
+        let (bytes, info, focus) = slots[index].children.iter().fold(
+            (0usize, 0u64, false),
+            |(bytes, info, focus), &child| {
+                let child = &slots[child];
+                (
+                    bytes.saturating_add(child.subtree_bytes),
+                    info.saturating_add(child.subtree_info),
+                    focus || child.holds_focus,
+                )
+            },
+        );
+        let slot = &mut slots[index];
+        slot.subtree_bytes = slot.subtree_bytes.saturating_add(bytes);
+        slot.subtree_info = slot.subtree_info.saturating_add(info);
+        slot.holds_focus |= focus;
+    }
+}
+
+fn push_children(
+    slots: &[Slot],
+    parent: usize,
+    frontier: &mut BinaryHeap<(bool, u64, Reverse<usize>)>,
+) {
+    for &child in &slots[parent].children {
+        let slot = &slots[child];
+        let density = slot.subtree_info.saturating_mul(1024) / slot.subtree_bytes.max(1) as u64;
+        frontier.push((slot.holds_focus, density, Reverse(child)));
+    }
+}
+
+fn rebuild(slots: &mut [Slot], included: &[bool], index: usize) -> AccessibilityNode {
+    let children = std::mem::take(&mut slots[index].children);
+    let total = children.len();
+    let mut kept = Vec::with_capacity(total);
+    for child in children {
+        if included[child] {
+            kept.push(rebuild(slots, included, child));
+        }
+    }
+    let mut node = std::mem::replace(&mut slots[index].node, placeholder());
+    if kept.len() < total {
+        node.subtree_truncated = true;
+        if node.children_count.is_none() {
+            node.children_count = Some(u32::try_from(total).unwrap_or(u32::MAX));
+        }
+    }
+    node.children = kept;
+    node
+}
+
+fn placeholder() -> AccessibilityNode {
+    AccessibilityNode {
+        ref_id: None,
+        role: String::new(),
+        identity: Default::default(),
+        presentation: Default::default(),
+        children_count: None,
+        subtree_truncated: false,
+        children: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+#[path = "snapshot_budget_tests.rs"]
+mod tests;
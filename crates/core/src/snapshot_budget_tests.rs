@@ -0,0 +1,162 @@
+use super::*;
+
+const FULL: BudgetCostModel = BudgetCostModel {
+    include_bounds: false,
+    interactive_only: false,
+};
+
+fn node(role: &str, name: Option<&str>, children: Vec<AccessibilityNode>) -> AccessibilityNode {
+    AccessibilityNode {
+        ref_id: None,
+        role: role.into(),
+        identity: crate::NodeIdentity {
+            name: name.map(str::to_string),
+            ..Default::default()
+        },
+        presentation: Default::default(),
+        children_count: None,
+        subtree_truncated: false,
+        children,
+    }
+}
+
+fn focused(mut node: AccessibilityNode) -> AccessibilityNode {
+    node.presentation.states.push(crate::state::FOCUSED.into());
+    node
+}
+
+fn layout_noise(count: usize) -> AccessibilityNode {
+    node(
+        "group",
+        None,
+        (0..count)
+            .map(|_| node("statictext", None, vec![]))
+            .collect(),
+    )
+}
+
+fn toolbar() -> AccessibilityNode {
+    node(
+        "toolbar",
+        Some("Main"),
+        vec![
+            node("button", Some("Back"), vec![]),
+            node("button", Some("Forward"), vec![]),
+        ],
+    )
+}
+
+fn names(node: &AccessibilityNode) -> Vec<String> {
+    let mut out = node.identity.name.clone().into_iter().collect::<Vec<_>>();
+    for child in &node.children {
+        out.extend(names(child));
+    }
+    out
+}
+
+#[test]
+fn generous_budget_keeps_the_whole_tree_unmarked() {
+    let tree = node("window", Some("Doc"), vec![toolbar(), layout_noise(3)]);
+
+    let shaped = apply_token_budget(tree, 100_000, FULL);
+
+    assert_eq!(shaped.children.len(), 2);
+    assert!(!shaped.subtree_truncated);
+    assert_eq!(shaped.children[1].children.len(), 3);
+}
+
+#[test]
+fn tight_budget_prefers_interactive_named_branch_over_layout_noise() {
+    let tree = node("window", Some("Doc"), vec![layout_noise(40), toolbar()]);
+
+    let shaped = apply_token_budget(tree, 60, FULL);
+
+    let kept = names(&shaped);
+    assert!(kept.contains(&"Back".to_string()), "kept: {kept:?}");
+    assert!(kept.contains(&"Forward".to_string()), "kept: {kept:?}");
+    let noise = &shaped.children[0];
+    assert_eq!(noise.role, "group");
+    assert!(noise.subtree_truncated);
+    assert_eq!(noise.children_count, Some(40));
+}
+
+#[test]
+fn focused_branch_is_expanded_before_denser_siblings() {
+    let editor = node(
+        "group",
+        None,
+        vec![node(
+            "group",
+            None,
+            vec![focused(node("textfield", None, vec![]))],
+        )],
+    );
+    let tree = node("window", Some("Doc"), vec![toolbar(), editor]);
+
+    let shaped = apply_token_budget(tree, 40, FULL);
+
+    let editor = shaped
+        .children
+        .iter()
+        .find(|child| child.role == "group")
+        .expect("editor branch kept");
+    assert_eq!(editor.children[0].children[0].role, "textfield");
+}
+
+#[test]
+fn root_survives_a_budget_smaller_than_itself() {
+    let tree = node("window", Some("Doc"), vec![toolbar()]);
+
+    let shaped = apply_token_budget(tree, 1, FULL);
+
+    assert_eq!(shaped.role, "window");
+    assert!(shaped.children.is_empty());
+    assert!(shaped.subtree_truncated);
+    assert_eq!(shaped.children_count, Some(1));
+}
+
+#[test]
+fn document_order_is_preserved_for_kept_children() {
+    let tree = node(
+        "window",
+        None,
+        vec![
+            node("button", Some("One"), vec![]),
+            focused(node("button", Some("Two"), vec![])),
+            node("button", Some("Three"), vec![]),
+        ],
+    );
+
+    let shaped = apply_token_budget(tree, 100_000, FULL);
+
+    assert_eq!(names(&shaped), ["One", "Two", "Three"]);
+}
+
+#[test]
+fn excluded_bounds_are_not_charged_but_are_restored_for_ref_identity() {
+    let mut button = node("button", Some("Go"), vec![]);
+    button.presentation.bounds = Some(crate::Rect {
+        x: 1.0,
+        y: 2.0,
+        width: 3.0,
+        height: 4.0,
+    });
+    let tree = node("window", None, vec![button]);
+
+    let shaped = apply_token_budget(tree, 100_000, FULL);
+
+    assert!(shaped.children[0].presentation.bounds.is_some());
+}
+
+#[test]
+fn token_estimate_and_truncation_count_read_serialized_trees() {
+    let tree = serde_json::json!({
+        "role": "window",
+        "subtree_truncated": true,
+        "children": [{ "role": "group", "subtree_truncated": true }, { "role": "button" }]
+    });
+
+    assert_eq!(count_truncated(&tree), 2);
+    let bytes = serde_json::to_vec(&tree).unwrap().len();
+    assert_eq!(estimate_tokens(&tree), bytes.div_ceil(BYTES_PER_TOKEN));
+}
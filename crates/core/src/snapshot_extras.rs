@@ -0,0 +1,7 @@
+/// Optional reports a snapshot carries beside its tree, each emitted as its
+/// own top-level key when present.
+#[derive(Clone, Default)]
+pub struct SnapshotExtras {
+    pub stable_refs: Option<crate::snapshot_stable::StableRefs>,
+    pub web_content: Option<serde_json::Value>,
+}
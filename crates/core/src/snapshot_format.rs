@@ -0,0 +1,59 @@
+use serde_json::{Value, json};
+
+use crate::{AccessibilityNode, AppError};
+
+/// Rendering of a snapshot tree inside the response envelope. Every format is
+/// produced from the same allocated tree, so refs are identical across them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SnapshotFormat {
+    #[default]
+    Json,
+    Outline,
+    Yaml,
+}
+
+impl SnapshotFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Outline => "outline",
+            Self::Yaml => "yaml",
+        }
+    }
+
+    pub fn render(self, tree: &AccessibilityNode) -> Option<String> {
+        match self {
+            Self::Json => None,
+            Self::Outline => Some(crate::snapshot_outline::render(tree)),
+            Self::Yaml => Some(crate::snapshot_yaml::render(tree)),
+        }
+    }
+
+    /// Replaces the `tree` object of a formatted snapshot body with a
+    /// line-oriented `content` string. A reported token budget is re-measured
+    /// against the text actually emitted.
+    pub(crate) fn apply(self, mut body: Value) -> Result<Value, AppError> {
+        if self == Self::Json {
+            return Ok(body);
+        }
+        let Some(tree) = body.as_object_mut().and_then(|body| body.remove("tree")) else {
+            return Ok(body);
+        };
+        let tree: AccessibilityNode = serde_json::from_value(tree)?;
+        let content = self.render(&tree).unwrap_or_default();
+        if let Some(budget) = body.get_mut("token_budget") {
+            budget["estimated_tokens"] = json!(
+                content
+                    .len()
+                    .div_ceil(crate::snapshot_budget::BYTES_PER_TOKEN)
+            );
+        }
+        body["format"] = json!(self.as_str());
+        body["content"] = json!(content);
+        Ok(body)
+    }
+}
+
+#[cfg(test)]
+#[path = "snapshot_format_tests.rs"]
+mod tests;
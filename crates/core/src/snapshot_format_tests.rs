@@ -0,0 +1,122 @@
+use super::*;
+
+fn node(role: &str, name: Option<&str>, children: Vec<AccessibilityNode>) -> AccessibilityNode {
+    AccessibilityNode {
+        ref_id: None,
+        role: role.into(),
+        identity: crate::NodeIdentity {
+            name: name.map(str::to_string),
+            ..Default::default()
+        },
+        presentation: Default::default(),
+        children_count: None,
+        subtree_truncated: false,
+        children,
+    }
+}
+
+fn fixture() -> AccessibilityNode {
+    let mut field = node("textfield", Some("Search \"all\""), vec![]);
+    field.ref_id = Some("@s1:e2".into());
+    field.identity.value = Some("cats\ndogs".into());
+    field.presentation.states = vec!["focused".into(), "required".into()];
+    let mut submit = node("button", Some("Go"), vec![]);
+    submit.ref_id = Some("@s1:e3".into());
+    let mut sidebar = node("group", Some("Sidebar"), vec![]);
+    sidebar.ref_id = Some("@s1:e1".into());
+    sidebar.children_count = Some(12);
+    sidebar.subtree_truncated = true;
+    node(
+        "window",
+        Some("Doc"),
+        vec![sidebar, node("group", None, vec![field, submit])],
+    )
+}
+
+#[test]
+fn outline_emits_one_indented_line_per_node_with_refs_and_states() {
+    let outline = SnapshotFormat::Outline.render(&fixture()).unwrap();
+
+    assert_eq!(
+        outline,
+        "window \"Doc\"\n\
+         \x20 group \"Sidebar\" [@s1:e1] (+12 children)\n\
+         \x20 group\n\
+         \x20   textfield \"Search \\\"all\\\"\" value=\"cats\\ndogs\" [focused,required] [@s1:e2]\n\
+         \x20   button \"Go\" [@s1:e3]\n"
+    );
+}
+
+#[test]
+fn outline_counts_only_the_children_a_truncated_container_left_out() {
+    let mut list = node(
+        "list",
+        Some("Results"),
+        vec![
+            node("cell", Some("One"), vec![]),
+            node("cell", Some("Two"), vec![]),
+        ],
+    );
+    list.children_count = Some(5);
+    list.subtree_truncated = true;
+
+    let outline = SnapshotFormat::Outline.render(&list).unwrap();
+
+    assert!(outline.starts_with("list \"Results\" (+3 children)\n"));
+}
+
+#[test]
+fn yaml_nests_children_and_quotes_every_scalar() {
+    let yaml = SnapshotFormat::Yaml.render(&fixture()).unwrap();
+
+    assert!(yaml.starts_with("- role: \"window\"\n  name: \"Doc\"\n  children:\n"));
+    assert!(yaml.contains("    - ref: \"@s1:e1\"\n      role: \"group\"\n"));
+    assert!(yaml.contains("      children_count: 12\n      subtree_truncated: true\n"));
+    assert!(yaml.contains("        - ref: \"@s1:e2\"\n"));
+    assert!(yaml.contains("          value: \"cats\\ndogs\"\n"));
+    assert!(yaml.contains("          states: [\"focused\", \"required\"]\n"));
+}
+
+#[test]
+fn every_format_exposes_the_same_refs() {
+    let tree = fixture();
+    let outline = SnapshotFormat::Outline.render(&tree).unwrap();
+    let yaml = SnapshotFormat::Yaml.render(&tree).unwrap();
+    let json = serde_json::to_string(&tree).unwrap();
+
+    for ref_id in ["@s1:e1", "@s1:e2", "@s1:e3"] {
+        assert!(outline.contains(ref_id));
+        assert!(yaml.contains(ref_id));
+        assert!(json.contains(ref_id));
+    }
+}
+
+#[test]
+fn apply_swaps_tree_for_content_and_remeasures_the_budget() {
+    let body = json!({
+        "snapshot_id": "s1",
+        "tree": serde_json::to_value(fixture()).unwrap(),
+        "token_budget": { "max_tokens": 500, "estimated_tokens": 999 },
+    });
+
+    let formatted = SnapshotFormat::Outline.apply(body).unwrap();
+
+    assert!(formatted.get("tree").is_none());
+    assert_eq!(formatted["format"], "outline");
+    let content = formatted["content"].as_str().unwrap();
+    assert_eq!(
+        formatted["token_budget"]["estimated_tokens"].as_u64(),
+        Some(
+            content
+                .len()
+                .div_ceil(crate::snapshot_budget::BYTES_PER_TOKEN) as u64
+        )
+    );
+}
+
+#[test]
+fn json_format_leaves_the_body_untouched() {
+    let body = json!({ "tree": { "role": "window" } });
+
+    assert_eq!(SnapshotFormat::Json.apply(body.clone()).unwrap(), body);
+}
@@ -0,0 +1,60 @@
+use std::fmt::Write;
+
+use crate::AccessibilityNode;
+
+const INDENT: &str = "  ";
+
+/// One line per node: `role "name" value="…" [state,…] [@ref]`, indented two
+/// spaces per depth. Truncated containers end with `(+N children)`, N being
+/// the children left out rather than the total, so a reader knows to drill
+/// down with `--root`.
+pub(crate) fn render(tree: &AccessibilityNode) -> String {
+    let mut out = String::new();
+    write_node(&mut out, tree, 0);
+    out
+}
+
+fn write_node(out: &mut String, node: &AccessibilityNode, depth: usize) {
+    for _ in 0..depth {
+        out.push_str(INDENT);
+    }
+    out.push_str(&node.role);
+    if let Some(name) = non_empty(node.identity.name.as_deref()) {
+        let _ = write!(out, " {}", quoted(name));
+    }
+    if let Some(value) = non_empty(node.identity.value.as_deref()) {
+        let _ = write!(out, " value={}", quoted(value));
+    } else if node.identity.value_masked {
+        out.push_str(" value=<masked>");
+    }
+    if !node.presentation.states.is_empty() {
+        let _ = write!(out, " [{}]", node.presentation.states.join(","));
+    }
+    if let Some(ref_id) = node.ref_id.as_deref() {
+        let _ = write!(out, " [{ref_id}]");
+    }
+    if node.subtree_truncated || (node.children.is_empty() && node.children_count.is_some()) {
+        let omitted = node
+            .children_count
+            .map(|count| (count as usize).saturating_sub(node.children.len()));
+        match omitted {
+            Some(omitted) if omitted > 0 => {
+                let _ = write!(out, " (+{omitted} children)");
+            }
+            _ => out.push_str(" (+truncated)"),
+        }
+    }
+    out.push('\n');
+    for child in &node.children {
+        write_node(out, child, depth + 1);
+    }
+}
+
+fn non_empty(text: Option<&str>) -> Option<&str> {
+    text.filter(|text| !text.trim().is_empty())
+}
+
+/// JSON string escaping keeps embedded quotes and newlines on one line.
+pub(crate) fn quoted(text: &str) -> String {
+    serde_json::to_string(text).unwrap_or_else(|_| format!("\"{text}\""))
+}
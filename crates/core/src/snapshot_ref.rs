@@ -59,6 +59,8 @@ pub fn run_from_ref_with_context(
     )?
     .into_accessibility_tree()?;
     let nodes_observed = count_nodes(&raw_tree);
+    let mut raw_tree = opts.budget_tree(raw_tree);
+    crate::secure_mask::mask_observed_tree(&mut raw_tree, opts)?;
 
     let source_app = entry.source.source_app.as_deref();
     let source_window_id = entry.source.source_window_id.as_deref();
@@ -124,6 +126,7 @@ pub fn run_from_ref_with_context(
         snapshot_id: Some(active_snapshot_id),
         complete: true,
         nodes_observed,
+        extras: Default::default(),
     })
 }
 
@@ -0,0 +1,152 @@
+use crate::{
+    AccessibilityNode, AppError, ErrorCode,
+    adapter::{PlatformAdapter, TreeOptions},
+    context::CommandContext,
+    refs::{RefEntry, RefMap},
+    refs_store::RefStore,
+    snapshot::{self, SnapshotResult},
+};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// How `snapshot --stable-refs` numbered a snapshot against the previous one.
+/// `added` refs belong to the new snapshot; `retired` refs name elements of
+/// the previous snapshot that no longer matched anything.
+#[derive(Debug, Clone, Serialize)]
+pub struct StableRefs {
+    pub previous_snapshot_id: Option<String>,
+    pub kept: usize,
+    pub added: Vec<String>,
+    pub retired: Vec<String>,
+}
+
+/// Builds a window snapshot whose refs keep the previous snapshot's numbers
+/// for every element whose identity still matches, then saves it like any
+/// other snapshot.
+pub fn run_with_context(
+    adapter: &dyn PlatformAdapter,
+    opts: &TreeOptions,
+    app_name: Option<&str>,
+    window_id: Option<&str>,
+    context: &CommandContext,
+) -> Result<SnapshotResult, AppError> {
+    let mut result = snapshot::build(
+        adapter,
+        opts,
+        app_name,
+        window_id,
+        crate::Deadline::after(3_000)?,
+    )?;
+    let store = RefStore::for_session(context.session_id())?;
+    let previous_id = store.latest_snapshot_id()?;
+    let previous = match previous_id.as_deref().map(|id| store.load_snapshot(id)) {
+        Some(Ok(refmap)) => previous_id.zip(Some(refmap)),
+        Some(Err(AppError::Adapter(error))) if error.code == ErrorCode::SnapshotNotFound => None,
+        Some(Err(error)) => return Err(error),
+        None => None,
+    };
+    let previous = previous.as_ref().map(|(id, refmap)| (id.as_str(), refmap));
+    let mut stable = renumber(&mut result, previous)?;
+    let mut result = snapshot::persist(result, context)?;
+    if let Some(snapshot_id) = &result.snapshot_id {
+        for added in &mut stable.added {
+            *added = crate::ref_token::qualify_ref_id(snapshot_id, added);
+        }
+    }
+    result.extras.stable_refs = Some(stable);
+    Ok(result)
+}
+
+/// Gives each new entry the number of the one previous entry it pairs with,
+/// and numbers the rest after the previous counter. A pair needs a matching
+/// identity in both directions; when several previous entries match, the one
+/// at the same tree path wins, and a tie that remains gets a fresh number.
+fn renumber(
+    result: &mut SnapshotResult,
+    previous: Option<(&str, &RefMap)>,
+) -> Result<StableRefs, AppError> {
+    let old = previous
+        .map(|(_, refmap)| refmap.numbered())
+        .unwrap_or_default();
+    let new = result.refmap.numbered();
+    let choices: Vec<Option<u32>> = new
+        .iter()
+        .map(|(_, entry)| unique_match(&old, entry))
+        .collect();
+    let mut claims: HashMap<u32, usize> = HashMap::new();
+    for choice in choices.iter().flatten() {
+        *claims.entry(*choice).or_default() += 1;
+    }
+    let mut next = previous.map_or(0, |(_, refmap)| refmap.counter());
+    let mut renamed = HashMap::new();
+    let mut entries = Vec::with_capacity(new.len());
+    let mut added = Vec::new();
+    for ((number, entry), choice) in new.into_iter().zip(choices) {
+        let reused = choice.filter(|old_number| claims.get(old_number) == Some(&1));
+        let final_number = match reused {
+            Some(old_number) => old_number,
+            None => {
+                next = next.checked_add(1).ok_or_else(|| {
+                    AppError::invalid_input("RefMap exhausted its identifier space")
+                })?;
+                added.push(format!("@e{next}"));
+                next
+            }
+        };
+        renamed.insert(format!("@e{number}"), format!("@e{final_number}"));
+        entries.push((final_number, entry.clone()));
+    }
+    let kept = entries.len() - added.len();
+    let retired = old
+        .iter()
+        .filter(|(number, _)| !entries.iter().any(|(reused, _)| reused == number))
+        .filter_map(|(number, _)| {
+            previous.map(|(id, _)| crate::ref_token::qualify_ref_id(id, &format!("@e{number}")))
+        })
+        .collect();
+    result.refmap = RefMap::from_numbered(entries, next)?;
+    rename_tree_refs(&mut result.tree, &renamed);
+    Ok(StableRefs {
+        previous_snapshot_id: previous.map(|(id, _)| id.to_string()),
+        kept,
+        added,
+        retired,
+    })
+}
+
+fn unique_match(old: &[(u32, &RefEntry)], entry: &RefEntry) -> Option<u32> {
+    let matches: Vec<&(u32, &RefEntry)> = old
+        .iter()
+        .filter(|(_, candidate)| {
+            candidate.source.source_window_id == entry.source.source_window_id
+                && crate::ref_alias::same_element(candidate, entry)
+        })
+        .collect();
+    let at_same_path = || {
+        matches
+            .iter()
+            .filter(|(_, candidate)| candidate.scope == entry.scope)
+            .collect::<Vec<_>>()
+    };
+    match matches.as_slice() {
+        [(number, _)] => Some(*number),
+        [] => None,
+        _ => match at_same_path().as_slice() {
+            [(number, _)] => Some(*number),
+            _ => None,
+        },
+    }
+}
+
+fn rename_tree_refs(node: &mut AccessibilityNode, renamed: &HashMap<String, String>) {
+    if let Some(renumbered) = node.ref_id.as_ref().and_then(|ref_id| renamed.get(ref_id)) {
+        node.ref_id = Some(renumbered.clone());
+    }
+    for child in &mut node.children {
+        rename_tree_refs(child, renamed);
+    }
+}
+
+#[cfg(test)]
+#[path = "snapshot_stable_tests.rs"]
+mod tests;
@@ -0,0 +1,149 @@
+use super::*;
+use crate::{
+    WindowInfo,
+    ref_alloc::{self, RefAllocConfig},
+    ref_alloc_options::RefAllocOptions,
+    ref_alloc_scope::RefAllocScope,
+    ref_alloc_source::RefAllocSource,
+};
+
+fn button(name: &str) -> AccessibilityNode {
+    AccessibilityNode {
+        ref_id: None,
+        role: "button".into(),
+        identity: crate::NodeIdentity {
+            name: Some(name.into()),
+            ..Default::default()
+        },
+        presentation: Default::default(),
+        children_count: None,
+        subtree_truncated: false,
+        children: vec![],
+    }
+}
+
+/// A window snapshot whose buttons are the window root's direct children.
+fn snapshot_of(names: &[&str]) -> SnapshotResult {
+    let mut root = button("unused");
+    root.role = "window".into();
+    root.identity.name = None;
+    root.children = names.iter().map(|name| button(name)).collect();
+    let config = RefAllocConfig {
+        options: RefAllocOptions {
+            include_bounds: false,
+            interactive_only: false,
+            compact: false,
+        },
+        source: RefAllocSource {
+            pid: crate::ProcessId::new(1),
+            app: Some("Mail"),
+            window_id: Some("w-1"),
+            window_title: Some("Inbox"),
+            window_bounds_hash: None,
+            process_instance: Some("test-instance"),
+            surface: crate::SnapshotSurface::Window,
+        },
+        scope: RefAllocScope {
+            root_ref_id: None,
+            path_prefix: &[],
+        },
+    };
+    let mut refmap = RefMap::new();
+    let tree = ref_alloc::allocate_refs(root, &mut refmap, &config).unwrap();
+    SnapshotResult {
+        tree,
+        refmap,
+        window: WindowInfo {
+            id: "w-1".into(),
+            title: "Inbox".into(),
+            app: "Mail".into(),
+            pid: crate::ProcessId::new(1),
+            process_instance: Some("test-instance".into()),
+            bounds: None,
+            state: Default::default(),
+        },
+        snapshot_id: None,
+        complete: true,
+        nodes_observed: names.len() + 1,
+        extras: Default::default(),
+    }
+}
+
+fn tree_refs(result: &SnapshotResult) -> Vec<(String, String)> {
+    result
+        .tree
+        .children
+        .iter()
+        .map(|child| {
+            (
+                child.identity.name.clone().unwrap_or_default(),
+                child.ref_id.clone().unwrap_or_default(),
+            )
+        })
+        .collect()
+}
+
+fn pairs(expected: &[(&str, &str)]) -> Vec<(String, String)> {
+    expected
+        .iter()
+        .map(|(name, ref_id)| (name.to_string(), ref_id.to_string()))
+        .collect()
+}
+
+#[test]
+fn matching_elements_keep_their_numbers_and_new_ones_continue_the_sequence() {
+    let previous = snapshot_of(&["Reply", "Send", "Archive"]).refmap;
+    let mut result = snapshot_of(&["Send", "Forward", "Reply"]);
+
+    let stable = renumber(&mut result, Some(("sprev", &previous))).unwrap();
+
+    assert_eq!(
+        tree_refs(&result),
+        pairs(&[("Send", "@e2"), ("Forward", "@e4"), ("Reply", "@e1")])
+    );
+    assert_eq!(
+        result.refmap.get("@e2").unwrap().identity.name.as_deref(),
+        Some("Send")
+    );
+    assert_eq!(result.refmap.counter(), 4);
+    assert_eq!(stable.previous_snapshot_id.as_deref(), Some("sprev"));
+    assert_eq!(stable.kept, 2);
+    assert_eq!(stable.added, ["@e4"]);
+    assert_eq!(stable.retired, ["@sprev:e3"]);
+}
+
+#[test]
+fn duplicate_identities_pair_by_tree_path_or_get_fresh_numbers() {
+    let previous = snapshot_of(&["Delete", "Delete"]).refmap;
+
+    let mut same_layout = snapshot_of(&["Delete", "Delete"]);
+    let stable = renumber(&mut same_layout, Some(("sprev", &previous))).unwrap();
+    assert_eq!(
+        tree_refs(&same_layout),
+        pairs(&[("Delete", "@e1"), ("Delete", "@e2")])
+    );
+    assert!(stable.retired.is_empty());
+
+    let mut shifted = snapshot_of(&["Undo", "Delete", "Delete"]);
+    let stable = renumber(&mut shifted, Some(("sprev", &previous))).unwrap();
+    assert_eq!(
+        tree_refs(&shifted),
+        pairs(&[("Undo", "@e3"), ("Delete", "@e2"), ("Delete", "@e4")])
+    );
+    assert_eq!(stable.retired, ["@sprev:e1"]);
+}
+
+#[test]
+fn without_a_previous_snapshot_every_ref_is_added() {
+    let mut result = snapshot_of(&["Send", "Reply"]);
+
+    let stable = renumber(&mut result, None).unwrap();
+
+    assert_eq!(
+        tree_refs(&result),
+        pairs(&[("Send", "@e1"), ("Reply", "@e2")])
+    );
+    assert_eq!(stable.previous_snapshot_id, None);
+    assert_eq!(stable.kept, 0);
+    assert_eq!(stable.added, ["@e1", "@e2"]);
+}
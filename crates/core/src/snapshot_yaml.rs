@@ -0,0 +1,68 @@
+use std::fmt::Write;
+
+use crate::AccessibilityNode;
+use crate::snapshot_outline::quoted;
+
+/// Block-style YAML with one mapping per node. Scalars use double-quoted
+/// (JSON-compatible) escaping, so any name or value round-trips safely.
+pub(crate) fn render(tree: &AccessibilityNode) -> String {
+    let mut out = String::new();
+    write_node(&mut out, tree, 0);
+    out
+}
+
+fn write_node(out: &mut String, node: &AccessibilityNode, depth: usize) {
+    let pad = "  ".repeat(depth);
+    let mut first = true;
+    let mut field = |out: &mut String, key: &str, value: &str| {
+        let lead = if first { "- " } else { "  " };
+        first = false;
+        if value.is_empty() {
+            let _ = writeln!(out, "{pad}{lead}{key}:");
+        } else {
+            let _ = writeln!(out, "{pad}{lead}{key}: {value}");
+        }
+    };
+    if let Some(ref_id) = node.ref_id.as_deref() {
+        field(out, "ref", &quoted(ref_id));
+    }
+    field(out, "role", &quoted(&node.role));
+    if let Some(name) = node.identity.name.as_deref() {
+        field(out, "name", &quoted(name));
+    }
+    if let Some(value) = node.identity.value.as_deref() {
+        field(out, "value", &quoted(value));
+    }
+    if node.identity.value_masked {
+        field(out, "value_masked", "true");
+    }
+    if let Some(description) = node.identity.description.as_deref() {
+        field(out, "description", &quoted(description));
+    }
+    if !node.presentation.states.is_empty() {
+        let states: Vec<String> = node.presentation.states.iter().map(|s| quoted(s)).collect();
+        field(out, "states", &format!("[{}]", states.join(", ")));
+    }
+    if let Some(bounds) = node.presentation.bounds {
+        field(
+            out,
+            "bounds",
+            &format!(
+                "{{x: {}, y: {}, width: {}, height: {}}}",
+                bounds.x, bounds.y, bounds.width, bounds.height
+            ),
+        );
+    }
+    if let Some(count) = node.children_count {
+        field(out, "children_count", &count.to_string());
+    }
+    if node.subtree_truncated {
+        field(out, "subtree_truncated", "true");
+    }
+    if !node.children.is_empty() {
+        field(out, "children", "");
+        for child in &node.children {
+            write_node(out, child, depth + 2);
+        }
+    }
+}
@@ -0,0 +1,268 @@
+use crate::{
+    AdapterError, AppError, DeliverySemantics, ErrorCode, IdentityPredicate, LocatorQuery,
+    SnapshotSurface, WindowInfo,
+    adapter::PlatformAdapter,
+    app_lookup::{process_identity, resolve_app},
+    commands::{click, helpers::RefArgs},
+    context::CommandContext,
+    live_locator::{
+        LocatorMaterialization, LocatorResolveRequest, LocatorSelection, ObservationRoot,
+        resolve_query,
+    },
+    output::ErrorPayload,
+    refs_store::RefStore,
+    trace_artifacts,
+};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::time::Duration;
+
+pub const MAX_SURFACE_HANDLERS: usize = 8;
+const MAX_HANDLER_TEXT_CHARS: usize = 256;
+/// Wall-clock slice one pre-step sweep may spend probing and clicking, so
+/// registered handlers cannot eat the step's own budget.
+const SWEEP_BUDGET: Duration = Duration::from_secs(5);
+const MAX_RAW_DEPTH: u8 = 50;
+
+/// The blocking surfaces a handler may dismiss.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HandledSurface {
+    Alert,
+    Sheet,
+}
+
+impl HandledSurface {
+    pub fn as_str(self) -> &'static str {
+        self.surface().as_str()
+    }
+
+    fn surface(self) -> SnapshotSurface {
+        match self {
+            Self::Alert => SnapshotSurface::Alert,
+            Self::Sheet => SnapshotSurface::Sheet,
+        }
+    }
+}
+
+/// `on-surface alert --text "Update available" --click "Later"`: when an
+/// alert or sheet containing `text` is open in `app` (the focused app when
+/// omitted), press the button named exactly `click`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct SurfaceHandler {
+    pub on_surface: HandledSurface,
+    pub app: Option<String>,
+    pub text: Option<String>,
+    pub click: String,
+}
+
+/// Parses a `--handlers` JSON array.
+pub fn parse_handlers(json: &str) -> Result<Vec<SurfaceHandler>, AppError> {
+    let handlers: Vec<SurfaceHandler> = serde_json::from_str(json).map_err(|error| {
+        AppError::invalid_input_with_suggestion(
+            format!("Invalid surface handlers: {error}"),
+            r#"Pass a JSON array like [{"on-surface":"alert","text":"Update available","click":"Later"}]"#,
+        )
+    })?;
+    validate_handlers(&handlers)?;
+    Ok(handlers)
+}
+
+pub fn validate_handlers(handlers: &[SurfaceHandler]) -> Result<(), AppError> {
+    if handlers.len() > MAX_SURFACE_HANDLERS {
+        return Err(AppError::invalid_input(format!(
+            "At most {MAX_SURFACE_HANDLERS} surface handlers may be registered, found {}",
+            handlers.len()
+        )));
+    }
+    for (index, handler) in handlers.iter().enumerate() {
+        let fields = [
+            ("click", Some(handler.click.as_str())),
+            ("text", handler.text.as_deref()),
+            ("app", handler.app.as_deref()),
+        ];
+        for (field, value) in fields {
+            let Some(value) = value else { continue };
+            if value.trim().is_empty() || value.chars().count() > MAX_HANDLER_TEXT_CHARS {
+                return Err(AppError::invalid_input(format!(
+                    "Surface handler {index} '{field}' must be 1 to {MAX_HANDLER_TEXT_CHARS} non-blank characters"
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Checks every handler once, in order, and presses the button of each one
+/// whose surface is open. Returns one record per handler that fired; each is
+/// also traced as `surface.handler`. A handler whose surface is absent is
+/// not recorded. Only a trace failure is returned as an error — a failed
+/// press is part of the record so the caller's own step still runs.
+pub fn run_handlers(
+    handlers: &[SurfaceHandler],
+    adapter: &dyn PlatformAdapter,
+    context: &CommandContext,
+    deadline: crate::Deadline,
+) -> Result<Vec<Value>, AppError> {
+    let sweep = deadline.capped(SWEEP_BUDGET);
+    let mut fired = Vec::new();
+    for (index, handler) in handlers.iter().enumerate() {
+        if sweep.is_expired() {
+            break;
+        }
+        let Some(window) = open_surface_owner(handler, adapter, sweep) else {
+            continue;
+        };
+        let mut record = json!({
+            "handler": index,
+            "surface": handler.on_surface.as_str(),
+            "app": window.app,
+            "click": handler.click,
+        });
+        if let Some(text) = &handler.text {
+            record["text"] = json!(text);
+        }
+        match press(handler, &window, adapter, context, sweep) {
+            Ok(ref_id) => {
+                record["outcome"] = json!("clicked");
+                record["ref"] = json!(ref_id);
+            }
+            Err(error) => {
+                record["outcome"] = json!("failed");
+                record["error"] = json!(ErrorPayload::from_app_error(&error));
+            }
+        }
+        context.trace_lazy("surface.handler", || record.clone())?;
+        fired.push(record);
+    }
+    Ok(fired)
+}
+
+/// The window owning a matching open surface, or `None` when the surface is
+/// absent or cannot be probed. Adapters with a surface inventory are asked
+/// first so the common no-dialog case stays one cheap call. Without `text`,
+/// the surface matches when it holds the button to press.
+fn open_surface_owner(
+    handler: &SurfaceHandler,
+    adapter: &dyn PlatformAdapter,
+    deadline: crate::Deadline,
+) -> Option<WindowInfo> {
+    let surface = handler.on_surface.surface();
+    let app = resolve_app(handler.app.as_deref(), adapter, deadline).ok()?;
+    match adapter.list_surfaces(process_identity(&app).ok()?, deadline) {
+        Ok(surfaces) if !surfaces.iter().any(|open| open.kind == surface.as_str()) => {
+            return None;
+        }
+        Err(error) if error.code != ErrorCode::ActionNotSupported => return None,
+        _ => {}
+    }
+    let window = crate::snapshot::resolve_window_for_surface(
+        adapter,
+        Some(&app.name),
+        None,
+        surface,
+        deadline,
+    )
+    .ok()?;
+    let probe = match &handler.text {
+        Some(text) => LocatorQuery {
+            has_text: Some(text.clone()),
+            ..LocatorQuery::default()
+        },
+        None => button_query(handler),
+    };
+    let resolution = resolve_query(
+        adapter,
+        &probe,
+        ObservationRoot::Window(&window),
+        &probe_request(surface, deadline, LocatorSelection::Count),
+    )
+    .ok()?;
+    (resolution.meta.total_matches > 0).then_some(window)
+}
+
+/// Materializes the exactly-named button and clicks it through the normal
+/// ref action path, so the press gets the same revalidation and delivery
+/// evidence as a `click` command.
+fn press(
+    handler: &SurfaceHandler,
+    window: &WindowInfo,
+    adapter: &dyn PlatformAdapter,
+    context: &CommandContext,
+    deadline: crate::Deadline,
+) -> Result<String, AppError> {
+    let mut resolution = resolve_query(
+        adapter,
+        &button_query(handler),
+        ObservationRoot::Window(window),
+        &probe_request(
+            handler.on_surface.surface(),
+            deadline,
+            LocatorSelection::First,
+        ),
+    )?;
+    let (Some(found), Some(refmap)) = (resolution.matches.pop(), resolution.refmap.take()) else {
+        return Err(AdapterError::new(
+            ErrorCode::ElementNotFound,
+            format!(
+                "No button named '{}' on the open {}",
+                handler.click,
+                handler.on_surface.as_str()
+            ),
+        )
+        .with_disposition(DeliverySemantics::not_delivered())
+        .into());
+    };
+    let local_ref = found
+        .data
+        .ref_id
+        .ok_or_else(|| AdapterError::internal("Materialized handler button has no ref"))?;
+    let store = RefStore::for_session(context.session_id())?;
+    let snapshot_id = store.save_new_snapshot(&refmap)?;
+    trace_artifacts::copy_refmap_if_full(context, &store, &snapshot_id, &refmap)?;
+    let ref_id = crate::ref_token::qualify_ref_id(&snapshot_id, &local_ref);
+    click::execute(
+        RefArgs {
+            ref_id: ref_id.clone(),
+            snapshot_id: None,
+            timeout_ms: Some(deadline.remaining_ms()),
+        },
+        adapter,
+        &context.clone().with_inherited_deadline(deadline),
+    )?;
+    Ok(ref_id)
+}
+
+fn button_query(handler: &SurfaceHandler) -> LocatorQuery {
+    LocatorQuery {
+        identity: IdentityPredicate {
+            role: Some("button".into()),
+            name: Some(handler.click.clone()),
+            ..IdentityPredicate::default()
+        },
+        exact: true,
+        ..LocatorQuery::default()
+    }
+}
+
+fn probe_request(
+    surface: SnapshotSurface,
+    deadline: crate::Deadline,
+    selection: LocatorSelection,
+) -> LocatorResolveRequest {
+    LocatorResolveRequest {
+        materialization: match selection {
+            LocatorSelection::Count => LocatorMaterialization::None,
+            _ => LocatorMaterialization::SelectedMatches,
+        },
+        selection,
+        deadline,
+        max_raw_depth: MAX_RAW_DEPTH,
+        surface: Some(surface),
+    }
+}
+
+#[cfg(test)]
+#[path = "surface_handler_tests.rs"]
+mod tests;
@@ -0,0 +1,191 @@
+use super::*;
+use crate::{
+    AppInfo, ProcessId, SurfaceInfo,
+    adapter::{ActionOps, InputOps, ObservationOps, SystemOps, WindowFilter},
+    live_locator::{
+        IdentifierEvidence, LocatorEvidence, LocatorField, LocatorRefEvidence, LocatorStats,
+        ObservationRequest, ObservationSource, ObservedSubtree, ObservedTree,
+    },
+};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// One focused app whose open surfaces and surface contents are scripted.
+struct DialogAdapter {
+    open: Vec<&'static str>,
+    nodes: Vec<(&'static str, &'static str)>,
+    observations: AtomicU32,
+}
+
+impl DialogAdapter {
+    fn new(open: &[&'static str], nodes: &[(&'static str, &'static str)]) -> Self {
+        Self {
+            open: open.to_vec(),
+            nodes: nodes.to_vec(),
+            observations: AtomicU32::new(0),
+        }
+    }
+}
+
+fn evidence(role: &str, name: &str) -> LocatorEvidence {
+    LocatorEvidence {
+        role: LocatorField::Known(role.into()),
+        name: LocatorField::Known(name.into()),
+        description: LocatorField::Absent,
+        value: LocatorField::Absent,
+        identifiers: IdentifierEvidence::absent(),
+        states: LocatorField::Known(Vec::new()),
+        ref_evidence: LocatorRefEvidence {
+            bounds: LocatorField::Absent,
+            available_actions: LocatorField::Known(Vec::new()),
+        },
+    }
+}
+
+impl ObservationOps for DialogAdapter {
+    fn observe_tree(
+        &self,
+        root: ObservationRoot<'_>,
+        request: &ObservationRequest,
+    ) -> Result<ObservedTree, AdapterError> {
+        self.observations.fetch_add(1, Ordering::SeqCst);
+        let children = self
+            .nodes
+            .iter()
+            .map(|(role, name)| ObservedSubtree::new(evidence(role, name), Vec::new(), true, None))
+            .collect();
+        ObservedTree::from_roots(
+            vec![ObservedSubtree::new(
+                evidence("alert", "Notice"),
+                children,
+                true,
+                None,
+            )],
+            ObservationSource::from_root(&root, request.surface),
+            LocatorStats::default(),
+            true,
+        )
+    }
+
+    fn list_windows(
+        &self,
+        _filter: &WindowFilter,
+        _deadline: crate::Deadline,
+    ) -> Result<Vec<WindowInfo>, AdapterError> {
+        Ok(vec![WindowInfo {
+            id: "w-1".into(),
+            title: "Editor".into(),
+            app: "FixtureApp".into(),
+            pid: ProcessId::new(101),
+            process_instance: Some("test-instance".into()),
+            bounds: None,
+            state: crate::WindowState {
+                is_focused: true,
+                ..Default::default()
+            },
+        }])
+    }
+
+    fn list_apps_scoped(
+        &self,
+        _name: &str,
+        _bundle_id: Option<&str>,
+        _deadline: crate::Deadline,
+    ) -> Result<Vec<AppInfo>, AdapterError> {
+        Ok(vec![AppInfo {
+            name: "FixtureApp".into(),
+            pid: ProcessId::new(101),
+            bundle_id: None,
+            process_instance: Some("test-instance".into()),
+            presentation: None,
+        }])
+    }
+
+    fn list_surfaces(
+        &self,
+        _process: crate::ProcessIdentity,
+        _deadline: crate::Deadline,
+    ) -> Result<Vec<SurfaceInfo>, AdapterError> {
+        Ok(self
+            .open
+            .iter()
+            .map(|kind| SurfaceInfo {
+                id: format!("{kind}-1"),
+                kind: kind.to_string(),
+                title: None,
+                item_count: None,
+            })
+            .collect())
+    }
+}
+
+impl ActionOps for DialogAdapter {}
+
+impl InputOps for DialogAdapter {}
+
+impl SystemOps for DialogAdapter {}
+
+fn handlers(json: &str) -> Vec<SurfaceHandler> {
+    parse_handlers(json).unwrap()
+}
+
+#[test]
+fn handler_specs_are_validated_before_any_step_runs() {
+    let parsed = handlers(r#"[{"on-surface":"sheet","app":"Mail","click":"Don't Save"}]"#);
+    assert_eq!(parsed[0].on_surface, HandledSurface::Sheet);
+    assert_eq!(parsed[0].app.as_deref(), Some("Mail"));
+
+    let too_many = format!(
+        "[{}]",
+        [r#"{"on-surface":"alert","click":"OK"}"#; MAX_SURFACE_HANDLERS + 1].join(",")
+    );
+    for invalid in [
+        r#"[{"on-surface":"popover","click":"OK"}]"#,
+        r#"[{"on-surface":"alert","click":"OK","press":true}]"#,
+        r#"[{"on-surface":"alert","click":"  "}]"#,
+        r#"[{"on-surface":"alert","text":"","click":"OK"}]"#,
+        r#"{"on-surface":"alert","click":"OK"}"#,
+        too_many.as_str(),
+    ] {
+        let err = parse_handlers(invalid).unwrap_err();
+        assert_eq!(err.code(), "INVALID_ARGS", "{invalid}");
+    }
+}
+
+#[test]
+fn absent_surfaces_are_skipped_without_observing_the_tree() {
+    let adapter = DialogAdapter::new(&["sheet"], &[("button", "Later")]);
+    let fired = run_handlers(
+        &handlers(r#"[{"on-surface":"alert","click":"Later"}]"#),
+        &adapter,
+        &CommandContext::default(),
+        crate::Deadline::after(5_000).unwrap(),
+    )
+    .unwrap();
+
+    assert!(fired.is_empty());
+    assert_eq!(adapter.observations.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn a_matching_surface_is_recorded_even_when_its_button_is_missing() {
+    let adapter = DialogAdapter::new(&["alert"], &[("text", "Update available")]);
+    let fired = run_handlers(
+        &handlers(
+            r#"[
+                {"on-surface":"alert","text":"Disk full","click":"OK"},
+                {"on-surface":"alert","text":"update available","click":"Later"}
+            ]"#,
+        ),
+        &adapter,
+        &CommandContext::default(),
+        crate::Deadline::after(5_000).unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(fired.len(), 1, "{fired:?}");
+    assert_eq!(fired[0]["handler"], 1);
+    assert_eq!(fired[0]["surface"], "alert");
+    assert_eq!(fired[0]["app"], "FixtureApp");
+    assert_eq!(fired[0]["outcome"], "failed");
+    assert_eq!(fired[0]["error"]["code"], "ELEMENT_NOT_FOUND");
+}
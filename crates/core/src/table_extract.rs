@@ -0,0 +1,277 @@
+use serde::Serialize;
+
+use crate::AccessibilityNode;
+
+const TABLE_ROLES: &[&str] = &["table", "grid", "outline", "list", "listbox"];
+const ROW_ROLES: &[&str] = &["row", "treeitem"];
+const HEADER_CELL_ROLES: &[&str] = &["button", "heading", "column", "statictext"];
+
+/// One cell of an extracted table: the visible text plus the snapshot ref an
+/// agent can act on, when the cell or one of its descendants carries one.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct TableCell {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(rename = "ref", skip_serializing_if = "Option::is_none")]
+    pub ref_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum HeaderSource {
+    Columns,
+    HeaderGroup,
+    FirstRow,
+    Generated,
+}
+
+/// Rows are not yet padded: [`ExtractedTable::columns`] is computed from the
+/// final row set so that pages merged during scrolling share one width.
+#[derive(Debug, Clone)]
+pub(crate) struct ExtractedTable {
+    pub kind: String,
+    pub headers: Vec<String>,
+    pub header_source: HeaderSource,
+    pub rows: Vec<Vec<TableCell>>,
+    pub subtree_truncated: bool,
+}
+
+impl ExtractedTable {
+    pub(crate) fn columns(&self) -> Vec<String> {
+        let width = self
+            .rows
+            .iter()
+            .map(Vec::len)
+            .max()
+            .unwrap_or(0)
+            .max(self.headers.len());
+        (0..width)
+            .map(|index| {
+                self.headers
+                    .get(index)
+                    .filter(|name| !name.is_empty())
+                    .cloned()
+                    .unwrap_or_else(|| format!("column_{}", index + 1))
+            })
+            .collect()
+    }
+
+    /// Rectangular rows: every row is padded with `None` to the column count.
+    pub(crate) fn padded_rows(&self) -> Vec<Vec<Option<TableCell>>> {
+        let width = self.columns().len();
+        self.rows
+            .iter()
+            .map(|row| {
+                let mut cells: Vec<Option<TableCell>> = row.iter().cloned().map(Some).collect();
+                cells.resize(width, None);
+                cells
+            })
+            .collect()
+    }
+
+    /// Appends a later page, dropping the rows it shares with the tail of the
+    /// rows collected so far. `anchor` is the page position of the last row
+    /// collected so far, located by geometry after scrolling it into view;
+    /// every page row up to it is a repeat, whatever its text. Without an
+    /// anchor the shortest text overlap is assumed, so identical rows are kept
+    /// rather than collapsed. The page was observed last, so its refs are the
+    /// live ones; refs on earlier rows were replaced by the re-observation and
+    /// are removed. Returns how many rows were new.
+    pub(crate) fn merge_page(&mut self, page: ExtractedTable, anchor: Option<usize>) -> usize {
+        let overlap = anchor
+            .map(|index| (index + 1).min(self.rows.len()).min(page.rows.len()))
+            .unwrap_or_else(|| overlap_len(&self.rows, &page.rows));
+        let added = page.rows.len() - overlap;
+        self.rows.truncate(self.rows.len() - overlap);
+        for cell in self.rows.iter_mut().flatten() {
+            cell.ref_id = None;
+        }
+        self.rows.extend(page.rows);
+        self.subtree_truncated |= page.subtree_truncated;
+        if self.headers.is_empty() {
+            self.headers = page.headers;
+            self.header_source = page.header_source;
+        }
+        added
+    }
+}
+
+/// Finds the first table-like node at or below `root`.
+pub(crate) fn find_table(root: &AccessibilityNode) -> Option<&AccessibilityNode> {
+    if TABLE_ROLES.contains(&root.role.as_str()) {
+        return Some(root);
+    }
+    root.children.iter().find_map(find_table)
+}
+
+/// Reads a table, grid, outline, or list subtree into headers and rows.
+/// Row nodes (`row`, `treeitem`) are collected in document order, including
+/// nested outline rows; lists without row nodes use their direct children.
+pub(crate) fn extract(table: &AccessibilityNode) -> ExtractedTable {
+    let mut row_nodes = Vec::new();
+    collect_rows(table, &mut row_nodes);
+    if row_nodes.is_empty() {
+        row_nodes = table
+            .children
+            .iter()
+            .filter(|child| child.role != "scrollbar")
+            .collect();
+    }
+    let mut rows: Vec<Vec<TableCell>> = row_nodes.iter().map(|row| row_cells(row)).collect();
+    let (headers, header_source) = match column_headers(table) {
+        Some(headers) => (headers, HeaderSource::Columns),
+        None => match header_group(table) {
+            Some(headers) => (headers, HeaderSource::HeaderGroup),
+            None if row_nodes.first().is_some_and(|row| is_header_row(row)) => {
+                let header = rows.remove(0);
+                let names = header.into_iter().map(|c| c.text.unwrap_or_default());
+                (names.collect(), HeaderSource::FirstRow)
+            }
+            None => (Vec::new(), HeaderSource::Generated),
+        },
+    };
+    ExtractedTable {
+        kind: table.role.clone(),
+        headers,
+        header_source,
+        rows,
+        subtree_truncated: any_truncated(table),
+    }
+}
+
+/// Text-only identity used to line up overlapping pages.
+fn row_key(row: &[TableCell]) -> Vec<Option<&str>> {
+    row.iter().map(|cell| cell.text.as_deref()).collect()
+}
+
+fn overlap_len(existing: &[Vec<TableCell>], page: &[Vec<TableCell>]) -> usize {
+    (1..=existing.len().min(page.len()))
+        .find(|&len| {
+            existing[existing.len() - len..]
+                .iter()
+                .zip(&page[..len])
+                .all(|(a, b)| row_key(a) == row_key(b))
+        })
+        .unwrap_or(0)
+}
+
+fn collect_rows<'a>(node: &'a AccessibilityNode, rows: &mut Vec<&'a AccessibilityNode>) {
+    for child in &node.children {
+        if ROW_ROLES.contains(&child.role.as_str()) {
+            rows.push(child);
+        }
+        collect_rows(child, rows);
+    }
+}
+
+fn row_cells(row: &AccessibilityNode) -> Vec<TableCell> {
+    let cells: Vec<&AccessibilityNode> = row
+        .children
+        .iter()
+        .filter(|child| child.role == "cell")
+        .collect();
+    if !cells.is_empty() {
+        return cells.into_iter().map(cell).collect();
+    }
+    let content: Vec<&AccessibilityNode> = row
+        .children
+        .iter()
+        .filter(|child| !ROW_ROLES.contains(&child.role.as_str()) && child.role != "disclosure")
+        .collect();
+    if content.is_empty() || row.identity.name.is_some() {
+        return vec![cell(row)];
+    }
+    content.into_iter().map(cell).collect()
+}
+
+fn cell(node: &AccessibilityNode) -> TableCell {
+    let text = own_text(node).or_else(|| {
+        let mut parts = Vec::new();
+        descendant_text(node, &mut parts);
+        (!parts.is_empty()).then(|| parts.join(" "))
+    });
+    TableCell {
+        text,
+        ref_id: first_ref(node),
+    }
+}
+
+fn own_text(node: &AccessibilityNode) -> Option<String> {
+    [&node.identity.name, &node.identity.value]
+        .into_iter()
+        .flatten()
+        .map(|text| text.trim())
+        .find(|text| !text.is_empty())
+        .map(str::to_string)
+}
+
+fn descendant_text(node: &AccessibilityNode, parts: &mut Vec<String>) {
+    for child in &node.children {
+        if ROW_ROLES.contains(&child.role.as_str()) {
+            continue;
+        }
+        match own_text(child) {
+            Some(text) => parts.push(text),
+            None => descendant_text(child, parts),
+        }
+    }
+}
+
+fn first_ref(node: &AccessibilityNode) -> Option<String> {
+    node.ref_id.clone().or_else(|| {
+        node.children
+            .iter()
+            .filter(|child| !ROW_ROLES.contains(&child.role.as_str()))
+            .find_map(first_ref)
+    })
+}
+
+fn column_headers(table: &AccessibilityNode) -> Option<Vec<String>> {
+    let headers: Vec<String> = table
+        .children
+        .iter()
+        .filter(|child| child.role == "column")
+        .map(|column| cell(column).text.unwrap_or_default())
+        .collect();
+    headers
+        .iter()
+        .any(|name| !name.is_empty())
+        .then_some(headers)
+}
+
+/// A non-row container whose children are all named header-like controls,
+/// as with the sortable header buttons of native table views.
+fn header_group(table: &AccessibilityNode) -> Option<Vec<String>> {
+    table
+        .children
+        .iter()
+        .filter(|child| child.role == "group" && child.children.len() >= 2)
+        .find_map(|group| {
+            group
+                .children
+                .iter()
+                .map(|header| {
+                    HEADER_CELL_ROLES
+                        .contains(&header.role.as_str())
+                        .then(|| own_text(header))
+                        .flatten()
+                })
+                .collect()
+        })
+}
+
+fn is_header_row(row: &AccessibilityNode) -> bool {
+    !row.children.is_empty()
+        && row
+            .children
+            .iter()
+            .all(|cell| matches!(cell.role.as_str(), "heading" | "column"))
+}
+
+fn any_truncated(node: &AccessibilityNode) -> bool {
+    node.subtree_truncated || node.children.iter().any(any_truncated)
+}
+
+#[cfg(test)]
+#[path = "table_extract_tests.rs"]
+mod tests;
@@ -0,0 +1,242 @@
+use super::*;
+
+fn node(role: &str, name: Option<&str>, children: Vec<AccessibilityNode>) -> AccessibilityNode {
+    AccessibilityNode {
+        ref_id: None,
+        role: role.into(),
+        identity: crate::NodeIdentity {
+            name: name.map(str::to_string),
+            ..Default::default()
+        },
+        presentation: Default::default(),
+        children_count: None,
+        subtree_truncated: false,
+        children,
+    }
+}
+
+fn reffed(mut node: AccessibilityNode, ref_id: &str) -> AccessibilityNode {
+    node.ref_id = Some(ref_id.into());
+    node
+}
+
+fn text_cell(text: &str, ref_id: &str) -> AccessibilityNode {
+    reffed(
+        node("cell", None, vec![node("statictext", Some(text), vec![])]),
+        ref_id,
+    )
+}
+
+fn row(cells: &[(&str, &str)]) -> AccessibilityNode {
+    node(
+        "row",
+        None,
+        cells
+            .iter()
+            .map(|(text, ref_id)| text_cell(text, ref_id))
+            .collect(),
+    )
+}
+
+fn texts(table: &ExtractedTable) -> Vec<Vec<Option<String>>> {
+    table
+        .rows
+        .iter()
+        .map(|row| row.iter().map(|cell| cell.text.clone()).collect())
+        .collect()
+}
+
+#[test]
+fn native_table_reads_column_headers_and_cell_refs() {
+    let table = node(
+        "table",
+        Some("Inbox"),
+        vec![
+            row(&[("Ada", "@e2"), ("Hello", "@e3")]),
+            row(&[("Grace", "@e4"), ("Re: Hello", "@e5")]),
+            node("column", Some("From"), vec![]),
+            node("column", Some("Subject"), vec![]),
+        ],
+    );
+
+    let extracted = extract(&table);
+
+    assert_eq!(extracted.header_source, HeaderSource::Columns);
+    assert_eq!(extracted.columns(), ["From", "Subject"]);
+    assert_eq!(extracted.rows[1][1].text.as_deref(), Some("Re: Hello"));
+    assert_eq!(extracted.rows[1][1].ref_id.as_deref(), Some("@e5"));
+}
+
+#[test]
+fn header_group_of_buttons_names_the_columns() {
+    let header = node(
+        "group",
+        None,
+        vec![
+            node("button", Some("Name"), vec![]),
+            node("button", Some("Size"), vec![]),
+        ],
+    );
+    let table = node(
+        "table",
+        None,
+        vec![header, row(&[("a.txt", "@e2"), ("1 KB", "@e3")])],
+    );
+
+    let extracted = extract(&table);
+
+    assert_eq!(extracted.header_source, HeaderSource::HeaderGroup);
+    assert_eq!(extracted.columns(), ["Name", "Size"]);
+    assert_eq!(extracted.rows.len(), 1);
+}
+
+#[test]
+fn heading_first_row_becomes_the_header_and_short_rows_are_padded() {
+    let header = node(
+        "row",
+        None,
+        vec![
+            node("heading", Some("Key"), vec![]),
+            node("heading", Some("Value"), vec![]),
+        ],
+    );
+    let table = node(
+        "grid",
+        None,
+        vec![
+            header,
+            row(&[("a", "@e2"), ("1", "@e3")]),
+            row(&[("b", "@e4")]),
+        ],
+    );
+
+    let extracted = extract(&table);
+
+    assert_eq!(extracted.header_source, HeaderSource::FirstRow);
+    assert_eq!(extracted.columns(), ["Key", "Value"]);
+    let padded = extracted.padded_rows();
+    assert_eq!(padded[1].len(), 2);
+    assert!(padded[1][1].is_none());
+}
+
+#[test]
+fn list_without_rows_uses_children_and_generated_column_names() {
+    let list = node(
+        "list",
+        None,
+        vec![
+            reffed(node("button", Some("Inbox"), vec![]), "@e2"),
+            reffed(node("button", Some("Sent"), vec![]), "@e3"),
+            node("scrollbar", None, vec![]),
+        ],
+    );
+
+    let extracted = extract(find_table(&node("scrollarea", None, vec![list])).unwrap());
+
+    assert_eq!(extracted.kind, "list");
+    assert_eq!(extracted.header_source, HeaderSource::Generated);
+    assert_eq!(extracted.columns(), ["column_1"]);
+    assert_eq!(
+        texts(&extracted),
+        [
+            vec![Some("Inbox".to_string())],
+            vec![Some("Sent".to_string())]
+        ]
+    );
+}
+
+#[test]
+fn outline_rows_are_collected_in_document_order() {
+    let mut parent = reffed(node("treeitem", Some("Projects"), vec![]), "@e2");
+    parent.children = vec![reffed(node("treeitem", Some("agent"), vec![]), "@e3")];
+    let outline = node(
+        "outline",
+        None,
+        vec![
+            parent,
+            reffed(node("treeitem", Some("Notes"), vec![]), "@e4"),
+        ],
+    );
+
+    let extracted = extract(&outline);
+
+    let names: Vec<_> = texts(&extracted).into_iter().flatten().flatten().collect();
+    assert_eq!(names, ["Projects", "agent", "Notes"]);
+    assert_eq!(extracted.rows[0][0].ref_id.as_deref(), Some("@e2"));
+}
+
+#[test]
+fn merging_a_page_skips_the_overlap_and_keeps_only_live_refs() {
+    let table = |rows: Vec<AccessibilityNode>| extract(&node("table", None, rows));
+    let mut merged = table(vec![
+        row(&[("1", "@e2")]),
+        row(&[("2", "@e3")]),
+        row(&[("3", "@e4")]),
+    ]);
+
+    let added = merged.merge_page(
+        table(vec![
+            row(&[("2", "@e7")]),
+            row(&[("3", "@e8")]),
+            row(&[("4", "@e9")]),
+        ]),
+        None,
+    );
+
+    assert_eq!(added, 1);
+    let refs: Vec<_> = merged.rows.iter().map(|r| r[0].ref_id.clone()).collect();
+    assert_eq!(
+        refs,
+        [
+            None,
+            Some("@e7".into()),
+            Some("@e8".into()),
+            Some("@e9".into())
+        ]
+    );
+    assert_eq!(
+        merged.merge_page(table(vec![row(&[("4", "@e10")])]), None),
+        0
+    );
+}
+
+#[test]
+fn identical_rows_are_kept_when_the_anchor_position_is_known() {
+    let table = |rows: Vec<AccessibilityNode>| extract(&node("table", None, rows));
+    let mut merged = table(vec![row(&[("same", "@e2")]), row(&[("same", "@e3")])]);
+
+    let added = merged.merge_page(
+        table(vec![
+            row(&[("same", "@e7")]),
+            row(&[("same", "@e8")]),
+            row(&[("other", "@e9")]),
+        ]),
+        Some(0),
+    );
+
+    assert_eq!(added, 2);
+    let names: Vec<_> = merged
+        .rows
+        .iter()
+        .map(|r| r[0].text.clone().unwrap())
+        .collect();
+    assert_eq!(names, ["same", "same", "same", "other"]);
+}
+
+#[test]
+fn without_an_anchor_the_shortest_overlap_keeps_repeated_rows() {
+    let table = |rows: Vec<AccessibilityNode>| extract(&node("table", None, rows));
+    let mut merged = table(vec![row(&[("same", "@e2")]), row(&[("same", "@e3")])]);
+
+    let added = merged.merge_page(
+        table(vec![
+            row(&[("same", "@e7")]),
+            row(&[("same", "@e8")]),
+            row(&[("other", "@e9")]),
+        ]),
+        None,
+    );
+
+    assert_eq!(added, 2);
+    assert_eq!(merged.rows.len(), 4);
+}
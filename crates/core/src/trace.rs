@@ -1,15 +1,14 @@
 use crate::AppError;
-use crate::trace_sanitize::sanitize_trace_value;
+use crate::trace_line::{encode_event_line, write_line_locked};
+use crate::trace_quota;
 use crate::trace_state::{TracePending, TraceState, TraceWriterState};
-use serde_json::{Map, Value, json};
-use std::io::Write;
+use serde_json::{Value, json};
 use std::path::{Path, PathBuf};
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex, OnceLock};
 
 pub(crate) const MAX_TRACE_FILE_BYTES: u64 = 64 * 1024 * 1024;
-const MAX_TRACE_EVENT_BYTES: usize = 1024 * 1024;
-static EVENT_SEQ: AtomicU64 = AtomicU64::new(0);
+pub(crate) const MAX_TRACE_EVENT_BYTES: usize = 1024 * 1024;
 
 #[derive(Debug, Clone, Default)]
 pub struct TraceConfig {
@@ -52,6 +51,7 @@ impl TraceConfig {
                 pending,
                 writer: Arc::new(Mutex::new(writer)),
                 meta_written: AtomicBool::new(false),
+                ..TraceState::default()
             }),
         })
     }
@@ -80,8 +80,16 @@ impl TraceConfig {
             .map_err(|_| AppError::Internal("trace writer lock poisoned".into()))
             .and_then(|mut file| {
                 with_exclusive_file(&mut file, |file| {
+                    if self.state.quota_exceeded.load(Ordering::Relaxed) {
+                        return trace_quota::dropped_over_quota(self.strict);
+                    }
                     self.ensure_meta_if_needed(file, session_id)?;
-                    write_event_locked(file, event, session_id, fields())
+                    let line = encode_event_line(event, session_id, fields())?;
+                    let incoming = line.len() as u64;
+                    if !trace_quota::within_quota(&self.state, file, event, session_id, incoming)? {
+                        return trace_quota::dropped_over_quota(self.strict);
+                    }
+                    write_line_locked(file, &line)
                 })
             }) {
             Ok(()) => Ok(()),
@@ -110,7 +118,8 @@ impl TraceConfig {
                 return Ok(None);
             }
             TracePending::File(path) => open_trace_file(path),
-            TracePending::SegmentDir(dir) => open_segment_trace_file(dir),
+            TracePending::SegmentDir(dir) => open_segment_trace_file(dir)
+                .inspect(|_| trace_quota::size_segment(&self.state, dir)),
         };
         match open_result {
             Ok(file) => {
@@ -282,100 +291,8 @@ fn write_event_locked(
     session_id: Option<&str>,
     fields: Value,
 ) -> Result<(), AppError> {
-    let envelope_bytes = event
-        .len()
-        .saturating_mul(6)
-        .saturating_add(session_id.map_or(0, |session| session.len().saturating_mul(6)))
-        .saturating_add(512);
-    if envelope_bytes >= MAX_TRACE_EVENT_BYTES
-        || !value_fits(&fields, MAX_TRACE_EVENT_BYTES - envelope_bytes)
-    {
-        return Err(AppError::invalid_input_with_suggestion(
-            "Trace event exceeds the maximum supported event size",
-            "Emit bounded metadata and omit raw application content from trace fields.",
-        ));
-    }
-    let mut body = match sanitize_trace_value(fields) {
-        Value::Object(fields) => fields,
-        _ => Map::new(),
-    };
-    body.insert("event".to_string(), json!(event));
-    body.insert(
-        "ts_ms".to_string(),
-        json!(
-            std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .map_err(|err| AppError::Internal(err.to_string()))?
-                .as_millis()
-        ),
-    );
-    body.insert(
-        "seq".to_string(),
-        json!(EVENT_SEQ.fetch_add(1, Ordering::Relaxed)),
-    );
-    body.insert("writer_pid".to_string(), json!(std::process::id()));
-    body.insert(
-        "writer_proc_start_ms".to_string(),
-        json!(process_start_ms()),
-    );
-    if let Some(sid) = session_id {
-        body.insert("session_id".to_string(), json!(sid));
-    }
-    let mut line = Vec::new();
-    serde_json::to_writer(&mut line, &Value::Object(body))?;
-    line.push(b'\n');
-    reject_oversized_trace(file, line.len() as u64)?;
-    file.write_all(&line).map_err(AppError::from)
-}
-
-fn value_fits(value: &Value, max_bytes: usize) -> bool {
-    fn visit(value: &Value, remaining: &mut usize) -> bool {
-        let fixed = match value {
-            Value::Null => 4,
-            Value::Bool(_) => 5,
-            Value::Number(_) => 32,
-            Value::String(string) => string.len().saturating_mul(6),
-            Value::Array(values) => {
-                if !take(remaining, values.len()) {
-                    return false;
-                }
-                return values.iter().all(|value| visit(value, remaining));
-            }
-            Value::Object(values) => {
-                for (key, value) in values {
-                    if !take(remaining, key.len().saturating_mul(6).saturating_add(4))
-                        || !visit(value, remaining)
-                    {
-                        return false;
-                    }
-                }
-                return true;
-            }
-        };
-        take(remaining, fixed)
-    }
-
-    fn take(remaining: &mut usize, amount: usize) -> bool {
-        let Some(next) = remaining.checked_sub(amount) else {
-            return false;
-        };
-        *remaining = next;
-        true
-    }
-
-    let mut remaining = max_bytes;
-    visit(value, &mut remaining)
-}
-
-fn reject_oversized_trace(file: &std::fs::File, incoming: u64) -> Result<(), AppError> {
-    let len = file.metadata()?.len();
-    if len.saturating_add(incoming) <= MAX_TRACE_FILE_BYTES {
-        return Ok(());
-    }
-    Err(AppError::invalid_input_with_suggestion(
-        "Trace file reached the maximum supported size",
-        "Start a new --trace file or rotate the existing trace before retrying.",
-    ))
+    let line = encode_event_line(event, session_id, fields)?;
+    write_line_locked(file, &line)
 }
 
 fn with_exclusive_file<T>(
@@ -26,7 +26,7 @@ pub(crate) fn clear_test_budgets() {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) enum ArtifactOutcome {
     Captured(String),
-    Skipped(String),
+    Skipped { reason: String },
 }
 
 fn artifacts_enabled(context: &CommandContext) -> bool {
@@ -65,10 +65,14 @@ pub(crate) fn capture_action_screenshot(
     deadline: crate::Deadline,
 ) -> ArtifactOutcome {
     if !artifacts_enabled(context) {
-        return ArtifactOutcome::Skipped("disabled".into());
+        return ArtifactOutcome::Skipped {
+            reason: "disabled".into(),
+        };
     }
     if deadline.is_expired() {
-        return ArtifactOutcome::Skipped("deadline".into());
+        return ArtifactOutcome::Skipped {
+            reason: "deadline".into(),
+        };
     }
     let Some(window_id) = entry
         .source
@@ -76,7 +80,9 @@ pub(crate) fn capture_action_screenshot(
         .as_deref()
         .filter(|window_id| !window_id.is_empty())
     else {
-        return ArtifactOutcome::Skipped("exact_target_unavailable".into());
+        return ArtifactOutcome::Skipped {
+            reason: "exact_target_unavailable".into(),
+        };
     };
     let Some(process_instance) = entry
         .process
@@ -84,14 +90,20 @@ pub(crate) fn capture_action_screenshot(
         .as_deref()
         .filter(|instance| !instance.is_empty())
     else {
-        return ArtifactOutcome::Skipped("exact_target_unavailable".into());
+        return ArtifactOutcome::Skipped {
+            reason: "exact_target_unavailable".into(),
+        };
     };
     let Some(trace_dir) = session_trace_dir(context) else {
-        return ArtifactOutcome::Skipped("no_session".into());
+        return ArtifactOutcome::Skipped {
+            reason: "no_session".into(),
+        };
     };
     let screens = screens_dir(&trace_dir);
     if let Err(err) = ensure_trace_dir(&screens) {
-        return ArtifactOutcome::Skipped(format!("dir: {err}"));
+        return ArtifactOutcome::Skipped {
+            reason: format!("dir: {err}"),
+        };
     }
 
     let target = crate::WindowInfo {
@@ -109,7 +121,9 @@ pub(crate) fn capture_action_screenshot(
     let buf = match adapter.screenshot(ScreenshotTarget::ExactWindow(target), deadline) {
         Ok(buf) => buf,
         Err(err) => {
-            return ArtifactOutcome::Skipped(format!("adapter: {}", err.code.as_str()));
+            return ArtifactOutcome::Skipped {
+                reason: format!("adapter: {}", err.code.as_str()),
+            };
         }
     };
     let seq = CAPTURE_SEQ.fetch_add(1, Ordering::Relaxed);
@@ -124,11 +138,52 @@ pub(crate) fn capture_action_screenshot(
     if let Err(reason) =
         crate::trace_artifact_budget::write_screenshot(&trace_dir, &path, &buf.data, deadline)
     {
-        return ArtifactOutcome::Skipped(reason.into());
+        return ArtifactOutcome::Skipped {
+            reason: reason.into(),
+        };
     }
     ArtifactOutcome::Captured(relative_to_trace(&trace_dir, &path))
 }
 
+/// Stores the images behind a failed screenshot assertion beside the action
+/// screenshots and records them as an `action.artifacts` event, so the HTML
+/// viewer shows them. Each `(phase, png)` pair lands in `screenshot_<phase>`.
+/// Returns the recorded fields, or the reason nothing was stored.
+pub(crate) fn store_comparison_artifacts(
+    context: &CommandContext,
+    ref_id: &str,
+    pid: crate::ProcessId,
+    images: &[(&str, &[u8])],
+    deadline: crate::Deadline,
+) -> serde_json::Value {
+    if !artifacts_enabled(context) {
+        return json!({ "skipped": "disabled" });
+    }
+    let Some(trace_dir) = session_trace_dir(context) else {
+        return json!({ "skipped": "no_session" });
+    };
+    let screens = screens_dir(&trace_dir);
+    if let Err(err) = ensure_trace_dir(&screens) {
+        return json!({ "skipped": format!("dir: {err}") });
+    }
+    let mut fields = json!({ "ref": ref_id });
+    for (phase, png) in images {
+        let seq = CAPTURE_SEQ.fetch_add(1, Ordering::Relaxed);
+        let path = screens.join(format!("{pid}-{}-{seq}-{phase}.png", process_start_ms()));
+        if let Err(reason) =
+            crate::trace_artifact_budget::write_screenshot(&trace_dir, &path, png, deadline)
+        {
+            return json!({ "skipped": reason });
+        }
+        fields[format!("screenshot_{phase}")] = json!(relative_to_trace(&trace_dir, &path));
+    }
+    let _ = context.trace("action.artifacts", fields.clone());
+    fields
+}
+
+/// Copies a snapshot's refmap into a full-artifacts session trace. A
+/// `--reveal-secure` snapshot keeps secure values in the ref store; the
+/// trace copy is always masked, and is skipped if the mask cannot load.
 pub(crate) fn copy_refmap_if_full(
     context: &CommandContext,
     store: &RefStore,
@@ -145,6 +200,13 @@ pub(crate) fn copy_refmap_if_full(
         return Ok(());
     }
     let dest = refmaps.join(format!("{snapshot_id}.json"));
+    let refmap = match crate::secure_mask::SecureMask::load() {
+        Ok(mask) => mask.mask_refmap(refmap),
+        Err(err) => {
+            tracing::warn!("refmap artifact skipped, secure fields unreadable: {err}");
+            return Ok(());
+        }
+    };
     let json = match refmap.serialize_with_size_check() {
         Ok(json) => json,
         Err(err) => {
@@ -152,12 +214,12 @@ pub(crate) fn copy_refmap_if_full(
             return Ok(());
         }
     };
-    if crate::trace_artifact_budget::write_refmap_if_absent(&trace_dir, &dest, json.as_bytes())
-        .is_err()
+    if let Err(reason) =
+        crate::trace_artifact_budget::write_refmap_if_absent(&trace_dir, &dest, json.as_bytes())
     {
         let _ = context.trace_lazy(
             "action.artifacts.refmap_skipped",
-            || json!({ "snapshot_id": snapshot_id }),
+            || json!({ "snapshot_id": snapshot_id, "reason": reason }),
         );
         return Ok(());
     }
@@ -174,7 +236,9 @@ pub(crate) fn emit_action_artifacts(
         return Ok(());
     }
     let same_skip = match (pre, post) {
-        (ArtifactOutcome::Skipped(a), ArtifactOutcome::Skipped(b)) if a == b && a != "disabled" => {
+        (ArtifactOutcome::Skipped { reason: a }, ArtifactOutcome::Skipped { reason: b })
+            if a == b && a != "disabled" =>
+        {
             Some(a.as_str())
         }
         _ => None,
@@ -188,14 +252,14 @@ pub(crate) fn emit_action_artifacts(
     let mut fields = json!({ "ref": ref_id });
     match pre {
         ArtifactOutcome::Captured(path) => fields["screenshot_pre"] = json!(path),
-        ArtifactOutcome::Skipped(reason) if reason != "disabled" => {
+        ArtifactOutcome::Skipped { reason } if reason != "disabled" => {
             fields["skipped_pre"] = json!(reason);
         }
         _ => {}
     }
     match post {
         ArtifactOutcome::Captured(path) => fields["screenshot_post"] = json!(path),
-        ArtifactOutcome::Skipped(reason) if reason != "disabled" => {
+        ArtifactOutcome::Skipped { reason } if reason != "disabled" => {
             fields["skipped_post"] = json!(reason);
         }
         _ => {}
@@ -36,3 +36,43 @@ fn capture_targets_window_for_pid() {
         _ => panic!("expected exact window screenshot target"),
     }
 }
+
+#[test]
+fn comparison_artifacts_land_in_screens_and_the_trace() {
+    let (_home, _lock) = setup_artifacts_test();
+    let manifest = artifacts_session();
+    let context = CommandContext::new(Some(manifest.id.clone()), None, false).unwrap();
+
+    let fields = store_comparison_artifacts(
+        &context,
+        "@s1:e1",
+        crate::ProcessId::new(7),
+        &[("actual", MINI_PNG), ("diff", MINI_PNG)],
+        crate::Deadline::standard().unwrap(),
+    );
+
+    let trace_dir = RefStore::for_session(Some(&manifest.id))
+        .unwrap()
+        .trace_dir();
+    for key in ["screenshot_actual", "screenshot_diff"] {
+        let relative = fields[key].as_str().expect("artifact path");
+        assert!(relative.starts_with("screens/7-"));
+        assert_eq!(
+            read_screenshot_for_embed(&trace_dir, relative).as_deref(),
+            Some(MINI_PNG)
+        );
+    }
+}
+
+#[test]
+fn comparison_artifacts_are_skipped_without_full_artifacts() {
+    let fields = store_comparison_artifacts(
+        &CommandContext::default(),
+        "@s1:e1",
+        crate::ProcessId::new(7),
+        &[("actual", MINI_PNG)],
+        crate::Deadline::standard().unwrap(),
+    );
+
+    assert_eq!(fields, serde_json::json!({ "skipped": "disabled" }));
+}
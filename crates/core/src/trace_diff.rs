@@ -0,0 +1,290 @@
+use crate::{
+    AppError, refs_store::RefStore, replay_refs::recorded_refmap, trace_read::RecordedCommand,
+};
+use serde::Serialize;
+use serde_json::{Value, json};
+
+pub const DEFAULT_LATENCY_SHIFT_MS: u64 = 500;
+
+/// Steps aligned after the shared prefix. Alignment is quadratic, so a
+/// longer tail is cut here and the report marked `truncated`.
+const MAX_ALIGNED_STEPS: usize = 2000;
+
+/// The element a step acted on, as its run's refmap recorded it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiffTarget {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app: Option<String>,
+    pub role: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiffStep {
+    pub index: usize,
+    pub command: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<DiffTarget>,
+    pub ok: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub surfaces: Vec<String>,
+}
+
+impl DiffStep {
+    fn key(&self) -> (&str, Option<&DiffTarget>) {
+        (&self.command, self.target.as_ref())
+    }
+}
+
+/// One line of the alignment. `same` and `changed` rows pair a step of
+/// each run; `missing` steps ran only in A and `added` steps only in B.
+#[derive(Debug, Clone, Serialize)]
+pub struct DiffRow {
+    pub status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub a: Option<DiffStep>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub b: Option<DiffStep>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub differences: Vec<Value>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct DiffSummary {
+    pub same: usize,
+    pub changed: usize,
+    pub missing: usize,
+    pub added: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TraceDiff {
+    pub summary: DiffSummary,
+    /// Index into `rows` of the first row that is not `same`.
+    pub first_divergence: Option<usize>,
+    pub truncated: bool,
+    pub rows: Vec<DiffRow>,
+}
+
+/// The steps of a recorded session, each with the identity of the element
+/// its first ref named in that session's refmaps.
+pub fn load_steps(
+    session: &str,
+    commands: Vec<RecordedCommand>,
+) -> Result<Vec<DiffStep>, AppError> {
+    let store = RefStore::for_session(Some(session))?;
+    Ok(commands
+        .into_iter()
+        .map(|command| DiffStep {
+            target: command
+                .args
+                .as_ref()
+                .and_then(|args| first_ref(args, None))
+                .and_then(|(ref_id, snapshot)| target(&store, &ref_id, snapshot.as_deref())),
+            index: command.index,
+            command: command.command,
+            ok: command.ok,
+            code: command.code,
+            duration_ms: command.duration_ms,
+            surfaces: command.surfaces,
+        })
+        .collect())
+}
+
+fn first_ref(value: &Value, snapshot: Option<&str>) -> Option<(String, Option<String>)> {
+    match value {
+        Value::String(text) if text.starts_with('@') => {
+            Some((text.clone(), snapshot.map(str::to_string)))
+        }
+        Value::Array(items) => items.iter().find_map(|item| first_ref(item, snapshot)),
+        Value::Object(map) => {
+            let snapshot = map.get("snapshot").and_then(Value::as_str).or(snapshot);
+            map.iter()
+                .filter(|(key, _)| key.as_str() != "snapshot")
+                .find_map(|(_, item)| first_ref(item, snapshot))
+        }
+        _ => None,
+    }
+}
+
+fn target(store: &RefStore, ref_id: &str, snapshot: Option<&str>) -> Option<DiffTarget> {
+    if crate::ref_token::is_alias_token(ref_id) {
+        return None;
+    }
+    let (snapshot_id, local) = crate::ref_token::resolve_ref_target(ref_id, snapshot).ok()?;
+    let refmap = recorded_refmap(store, &snapshot_id)?;
+    let entry = refmap.get(&local)?;
+    Some(DiffTarget {
+        app: entry.source.source_app.clone(),
+        role: entry.identity.role.clone(),
+        name: entry.identity.name.clone(),
+    })
+}
+
+/// Aligns run A against run B by command name and target identity, the
+/// longest common subsequence after their shared prefix. Within each gap,
+/// a step missing from B and one added in B with the same command are
+/// paired as a changed target. Paired steps are then compared on outcome
+/// code, overlays opened, and latency: a shift counts when it is at least
+/// `latency_shift_ms` and the slower run took at least twice as long.
+pub fn diff(a: Vec<DiffStep>, b: Vec<DiffStep>, latency_shift_ms: u64) -> TraceDiff {
+    let prefix = a
+        .iter()
+        .zip(&b)
+        .take_while(|(left, right)| left.key() == right.key())
+        .count();
+    let mut a = a.into_iter();
+    let mut b = b.into_iter();
+    let mut rows: Vec<DiffRow> = a
+        .by_ref()
+        .take(prefix)
+        .zip(b.by_ref().take(prefix))
+        .map(|(left, right)| paired(left, right, latency_shift_ms))
+        .collect();
+    let rest_a: Vec<DiffStep> = a.collect();
+    let rest_b: Vec<DiffStep> = b.collect();
+    let truncated = rest_a.len() > MAX_ALIGNED_STEPS || rest_b.len() > MAX_ALIGNED_STEPS;
+    let rest_a: Vec<DiffStep> = rest_a.into_iter().take(MAX_ALIGNED_STEPS).collect();
+    let rest_b: Vec<DiffStep> = rest_b.into_iter().take(MAX_ALIGNED_STEPS).collect();
+    align(rest_a, rest_b, latency_shift_ms, &mut rows);
+
+    let mut summary = DiffSummary::default();
+    for row in &rows {
+        match row.status {
+            "same" => summary.same += 1,
+            "changed" => summary.changed += 1,
+            "missing" => summary.missing += 1,
+            _ => summary.added += 1,
+        }
+    }
+    TraceDiff {
+        summary,
+        first_divergence: rows.iter().position(|row| row.status != "same"),
+        truncated,
+        rows,
+    }
+}
+
+fn align(a: Vec<DiffStep>, b: Vec<DiffStep>, latency_shift_ms: u64, rows: &mut Vec<DiffRow>) {
+    let (n, m) = (a.len(), b.len());
+    let width = m + 1;
+    let mut lcs = vec![0u16; (n + 1) * width];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i * width + j] = if a[i].key() == b[j].key() {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut a = a.into_iter().map(Some).collect::<Vec<_>>();
+    let mut b = b.into_iter().map(Some).collect::<Vec<_>>();
+    let (mut i, mut j) = (0, 0);
+    let mut missing = Vec::new();
+    let mut added = Vec::new();
+    while i < n || j < m {
+        let matched =
+            i < n && j < m && a[i].as_ref().map(DiffStep::key) == b[j].as_ref().map(DiffStep::key);
+        if matched {
+            flush_gap(&mut missing, &mut added, latency_shift_ms, rows);
+            if let (Some(left), Some(right)) = (a[i].take(), b[j].take()) {
+                rows.push(paired(left, right, latency_shift_ms));
+            }
+            i += 1;
+            j += 1;
+        } else if j == m || (i < n && lcs[(i + 1) * width + j] >= lcs[i * width + j + 1]) {
+            missing.extend(a[i].take());
+            i += 1;
+        } else {
+            added.extend(b[j].take());
+            j += 1;
+        }
+    }
+    flush_gap(&mut missing, &mut added, latency_shift_ms, rows);
+}
+
+/// Emits the unmatched steps between two aligned pairs, pairing each step
+/// missing from B with the next added step of the same command.
+fn flush_gap(
+    missing: &mut Vec<DiffStep>,
+    added: &mut Vec<DiffStep>,
+    latency_shift_ms: u64,
+    rows: &mut Vec<DiffRow>,
+) {
+    let mut added: Vec<Option<DiffStep>> = added.drain(..).map(Some).collect();
+    for left in missing.drain(..) {
+        let partner = added
+            .iter_mut()
+            .find(|right| right.as_ref().is_some_and(|r| r.command == left.command))
+            .and_then(Option::take);
+        rows.push(match partner {
+            Some(right) => paired(left, right, latency_shift_ms),
+            None => DiffRow {
+                status: "missing",
+                a: Some(left),
+                b: None,
+                differences: Vec::new(),
+            },
+        });
+    }
+    rows.extend(added.into_iter().flatten().map(|right| DiffRow {
+        status: "added",
+        a: None,
+        b: Some(right),
+        differences: Vec::new(),
+    }));
+}
+
+fn paired(a: DiffStep, b: DiffStep, latency_shift_ms: u64) -> DiffRow {
+    let mut differences = Vec::new();
+    if a.target != b.target {
+        differences.push(json!({ "kind": "target", "a": a.target, "b": b.target }));
+    }
+    if a.ok != b.ok || a.code != b.code {
+        differences.push(json!({ "kind": "error_code", "a": a.code, "b": b.code }));
+    }
+    let appeared: Vec<&String> = b
+        .surfaces
+        .iter()
+        .filter(|s| !a.surfaces.contains(s))
+        .collect();
+    let disappeared: Vec<&String> = a
+        .surfaces
+        .iter()
+        .filter(|s| !b.surfaces.contains(s))
+        .collect();
+    if !appeared.is_empty() || !disappeared.is_empty() {
+        differences.push(json!({
+            "kind": "surface",
+            "appeared": appeared,
+            "disappeared": disappeared,
+        }));
+    }
+    if let (Some(a_ms), Some(b_ms)) = (a.duration_ms, b.duration_ms) {
+        let (fast, slow) = (a_ms.min(b_ms), a_ms.max(b_ms));
+        if slow - fast >= latency_shift_ms && slow >= fast.saturating_mul(2) {
+            differences.push(json!({ "kind": "latency", "a_ms": a_ms, "b_ms": b_ms }));
+        }
+    }
+    DiffRow {
+        status: if differences.is_empty() {
+            "same"
+        } else {
+            "changed"
+        },
+        a: Some(a),
+        b: Some(b),
+        differences,
+    }
+}
+
+#[cfg(test)]
+#[path = "trace_diff_tests.rs"]
+mod tests;
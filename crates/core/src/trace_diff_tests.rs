@@ -0,0 +1,109 @@
+use super::*;
+
+fn step(index: usize, command: &str, name: Option<&str>) -> DiffStep {
+    DiffStep {
+        index,
+        command: command.into(),
+        target: name.map(|name| DiffTarget {
+            app: Some("Mail".into()),
+            role: "button".into(),
+            name: Some(name.into()),
+        }),
+        ok: Some(true),
+        code: None,
+        duration_ms: Some(100),
+        surfaces: Vec::new(),
+    }
+}
+
+fn statuses(report: &TraceDiff) -> Vec<&str> {
+    report.rows.iter().map(|row| row.status).collect()
+}
+
+#[test]
+fn identical_runs_have_no_divergence() {
+    let run = || vec![step(1, "click", Some("Send")), step(2, "wait", None)];
+
+    let report = diff(run(), run(), DEFAULT_LATENCY_SHIFT_MS);
+
+    assert_eq!(statuses(&report), ["same", "same"]);
+    assert_eq!(report.first_divergence, None);
+}
+
+#[test]
+fn the_first_divergence_is_a_different_error_code() {
+    let a = vec![
+        step(1, "click", Some("Reply")),
+        step(2, "click", Some("Send")),
+    ];
+    let mut b = a.clone();
+    b[1].ok = Some(false);
+    b[1].code = Some("STALE_REF".into());
+
+    let report = diff(a, b, DEFAULT_LATENCY_SHIFT_MS);
+
+    assert_eq!(report.first_divergence, Some(1));
+    let row = &report.rows[1];
+    assert_eq!(row.status, "changed");
+    assert_eq!(row.differences[0]["kind"], "error_code");
+    assert_eq!(row.differences[0]["b"], "STALE_REF");
+}
+
+#[test]
+fn steps_are_realigned_around_inserted_and_dropped_ones() {
+    let a = vec![
+        step(1, "click", Some("Reply")),
+        step(2, "type", Some("Body")),
+        step(3, "click", Some("Send")),
+    ];
+    let b = vec![
+        step(1, "click", Some("Reply")),
+        step(2, "press", None),
+        step(3, "click", Some("Send")),
+    ];
+
+    let report = diff(a, b, DEFAULT_LATENCY_SHIFT_MS);
+
+    assert_eq!(statuses(&report), ["same", "missing", "added", "same"]);
+    assert_eq!(report.first_divergence, Some(1));
+    assert_eq!(report.summary.missing, 1);
+    assert_eq!(report.summary.added, 1);
+}
+
+#[test]
+fn a_retargeted_command_is_one_changed_row() {
+    let a = vec![step(1, "click", Some("Send")), step(2, "wait", None)];
+    let b = vec![step(1, "click", Some("Send Later")), step(2, "wait", None)];
+
+    let report = diff(a, b, DEFAULT_LATENCY_SHIFT_MS);
+
+    assert_eq!(statuses(&report), ["changed", "same"]);
+    assert_eq!(report.rows[0].differences[0]["kind"], "target");
+    assert_eq!(report.rows[0].differences[0]["b"]["name"], "Send Later");
+}
+
+#[test]
+fn new_surfaces_and_large_latency_shifts_are_reported() {
+    let a = vec![step(1, "click", Some("Send"))];
+    let mut b = a.clone();
+    b[0].surfaces = vec!["sheet".into()];
+    b[0].duration_ms = Some(1200);
+
+    let report = diff(a.clone(), b, DEFAULT_LATENCY_SHIFT_MS);
+
+    let kinds: Vec<&str> = report.rows[0]
+        .differences
+        .iter()
+        .map(|difference| difference["kind"].as_str().unwrap())
+        .collect();
+    assert_eq!(kinds, ["surface", "latency"]);
+    assert_eq!(report.rows[0].differences[0]["appeared"][0], "sheet");
+
+    let mut slower = a.clone();
+    slower[0].duration_ms = Some(180);
+    assert_eq!(
+        diff(a, slower, DEFAULT_LATENCY_SHIFT_MS).rows[0].status,
+        "same",
+        "small shifts are noise"
+    );
+}
@@ -0,0 +1,115 @@
+use crate::AppError;
+use crate::trace::{MAX_TRACE_EVENT_BYTES, MAX_TRACE_FILE_BYTES, process_start_ms};
+use crate::trace_sanitize::sanitize_trace_value;
+use serde_json::{Map, Value, json};
+use std::io::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static EVENT_SEQ: AtomicU64 = AtomicU64::new(0);
+
+/// Encodes one trace event as a sanitized JSONL line, rejecting fields too
+/// large to fit under `MAX_TRACE_EVENT_BYTES` before building it.
+pub(crate) fn encode_event_line(
+    event: &str,
+    session_id: Option<&str>,
+    fields: Value,
+) -> Result<Vec<u8>, AppError> {
+    let envelope_bytes = event
+        .len()
+        .saturating_mul(6)
+        .saturating_add(session_id.map_or(0, |session| session.len().saturating_mul(6)))
+        .saturating_add(512);
+    if envelope_bytes >= MAX_TRACE_EVENT_BYTES
+        || !value_fits(&fields, MAX_TRACE_EVENT_BYTES - envelope_bytes)
+    {
+        return Err(AppError::invalid_input_with_suggestion(
+            "Trace event exceeds the maximum supported event size",
+            "Emit bounded metadata and omit raw application content from trace fields.",
+        ));
+    }
+    let mut body = match sanitize_trace_value(fields) {
+        Value::Object(fields) => fields,
+        _ => Map::new(),
+    };
+    body.insert("event".to_string(), json!(event));
+    body.insert(
+        "ts_ms".to_string(),
+        json!(
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_err(|err| AppError::Internal(err.to_string()))?
+                .as_millis()
+        ),
+    );
+    body.insert(
+        "seq".to_string(),
+        json!(EVENT_SEQ.fetch_add(1, Ordering::Relaxed)),
+    );
+    body.insert("writer_pid".to_string(), json!(std::process::id()));
+    body.insert(
+        "writer_proc_start_ms".to_string(),
+        json!(process_start_ms()),
+    );
+    if let Some(sid) = session_id {
+        body.insert("session_id".to_string(), json!(sid));
+    }
+    let mut line = Vec::new();
+    serde_json::to_writer(&mut line, &Value::Object(body))?;
+    line.push(b'\n');
+    Ok(line)
+}
+
+pub(crate) fn write_line_locked(file: &mut std::fs::File, line: &[u8]) -> Result<(), AppError> {
+    reject_oversized_trace(file, line.len() as u64)?;
+    file.write_all(line).map_err(AppError::from)
+}
+
+fn value_fits(value: &Value, max_bytes: usize) -> bool {
+    fn visit(value: &Value, remaining: &mut usize) -> bool {
+        let fixed = match value {
+            Value::Null => 4,
+            Value::Bool(_) => 5,
+            Value::Number(_) => 32,
+            Value::String(string) => string.len().saturating_mul(6),
+            Value::Array(values) => {
+                if !take(remaining, values.len()) {
+                    return false;
+                }
+                return values.iter().all(|value| visit(value, remaining));
+            }
+            Value::Object(values) => {
+                for (key, value) in values {
+                    if !take(remaining, key.len().saturating_mul(6).saturating_add(4))
+                        || !visit(value, remaining)
+                    {
+                        return false;
+                    }
+                }
+                return true;
+            }
+        };
+        take(remaining, fixed)
+    }
+
+    fn take(remaining: &mut usize, amount: usize) -> bool {
+        let Some(next) = remaining.checked_sub(amount) else {
+            return false;
+        };
+        *remaining = next;
+        true
+    }
+
+    let mut remaining = max_bytes;
+    visit(value, &mut remaining)
+}
+
+fn reject_oversized_trace(file: &std::fs::File, incoming: u64) -> Result<(), AppError> {
+    let len = file.metadata()?.len();
+    if len.saturating_add(incoming) <= MAX_TRACE_FILE_BYTES {
+        return Ok(());
+    }
+    Err(AppError::invalid_input_with_suggestion(
+        "Trace file reached the maximum supported size",
+        "Start a new --trace file or rotate the existing trace before retrying.",
+    ))
+}
@@ -0,0 +1,91 @@
+use crate::AppError;
+use crate::trace::segment_path_for_dir;
+use crate::trace_line::{encode_event_line, write_line_locked};
+use crate::trace_state::{SegmentQuota, TraceState};
+use serde_json::json;
+use std::path::Path;
+use std::sync::atomic::Ordering;
+
+/// Sizes the quota of the session owning segment `dir` as the segment
+/// opens. If the other segments already fill it, every event is dropped.
+pub(crate) fn size_segment(state: &TraceState, dir: &Path) {
+    let quota = segment_quota(dir);
+    if quota.is_some_and(|quota| quota.other_segments_bytes >= quota.max_bytes) {
+        state.quota_exceeded.store(true, Ordering::Relaxed);
+    }
+    let _ = state.segment_quota.set(quota);
+}
+
+/// Whether `incoming` more bytes keep the session under its trace
+/// quota. The first event that would cross it is replaced by a single
+/// `trace.quota_exceeded` marker, and every later event is dropped.
+pub(crate) fn within_quota(
+    state: &TraceState,
+    file: &mut std::fs::File,
+    event: &str,
+    session_id: Option<&str>,
+    incoming: u64,
+) -> Result<bool, AppError> {
+    let Some(Some(quota)) = state.segment_quota.get() else {
+        return Ok(true);
+    };
+    let used = quota
+        .other_segments_bytes
+        .saturating_add(file.metadata()?.len());
+    if used.saturating_add(incoming) <= quota.max_bytes {
+        return Ok(true);
+    }
+    state.quota_exceeded.store(true, Ordering::Relaxed);
+    tracing::warn!("session trace quota of {} bytes reached", quota.max_bytes);
+    let marker = encode_event_line(
+        "trace.quota_exceeded",
+        session_id,
+        json!({
+            "reason": "trace_quota",
+            "max_trace_bytes": quota.max_bytes,
+            "used_bytes": used,
+            "dropped_event": event,
+        }),
+    )?;
+    write_line_locked(file, &marker)?;
+    Ok(false)
+}
+
+/// What a write the quota dropped returns: nothing, unless the writer is
+/// strict.
+pub(crate) fn dropped_over_quota(strict: bool) -> Result<(), AppError> {
+    if !strict {
+        return Ok(());
+    }
+    Err(AppError::invalid_input_with_suggestion(
+        "Session trace reached its max_trace_bytes quota",
+        "Raise the quota with `session start --max-trace-bytes` or `session quota`, or start a new session.",
+    ))
+}
+
+/// The trace byte quota of the session owning segment `dir`, with the
+/// sizes of the segments other processes wrote before this one opened.
+fn segment_quota(dir: &Path) -> Option<SegmentQuota> {
+    let max_bytes = crate::session::quota_for_trace_dir(dir).max_trace_bytes?;
+    let own = segment_path_for_dir(dir);
+    let other_segments_bytes = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| {
+            let path = entry.path();
+            path != own && path.extension().is_some_and(|ext| ext == "jsonl")
+        })
+        .filter_map(|entry| entry.metadata().ok())
+        .filter(std::fs::Metadata::is_file)
+        .map(|metadata| metadata.len())
+        .sum();
+    Some(SegmentQuota {
+        max_bytes,
+        other_segments_bytes,
+    })
+}
+
+#[cfg(test)]
+#[path = "trace_quota_tests.rs"]
+mod tests;
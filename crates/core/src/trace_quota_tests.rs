@@ -0,0 +1,73 @@
+use crate::refs_test_support::HomeGuard;
+use crate::trace::{TraceConfig, ensure_trace_dir, segment_path_for_dir};
+use serde_json::json;
+use std::fs;
+use std::path::PathBuf;
+
+fn session_with_trace_quota(max_trace_bytes: u64) -> (String, PathBuf) {
+    let manifest = crate::session::start_session(crate::session::StartSessionOptions {
+        quota: crate::session::SessionQuota {
+            max_trace_bytes: Some(max_trace_bytes),
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+    .unwrap();
+    let trace_dir = crate::refs_store::RefStore::for_session(Some(&manifest.id))
+        .unwrap()
+        .trace_dir();
+    (manifest.id, trace_dir)
+}
+
+#[test]
+fn trace_quota_records_one_marker_then_drops_events() {
+    let _guard = HomeGuard::new();
+    let (session, trace_dir) = session_with_trace_quota(1024);
+    let config = TraceConfig::build(None, Some(trace_dir.clone()), false).unwrap();
+
+    for index in 0..20 {
+        config
+            .emit("command.end", Some(&session), json!({ "index": index }))
+            .unwrap();
+    }
+
+    let body = fs::read_to_string(segment_path_for_dir(&trace_dir)).unwrap();
+    let events: Vec<serde_json::Value> = body
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let marker = events.last().unwrap();
+    assert_eq!(marker["event"], "trace.quota_exceeded");
+    assert_eq!(marker["reason"], "trace_quota");
+    assert_eq!(marker["dropped_event"], "command.end");
+    assert!(marker["used_bytes"].as_u64().unwrap() <= 1024);
+    assert!(events.len() < 20);
+    assert_eq!(
+        events
+            .iter()
+            .filter(|event| event["event"] == "trace.quota_exceeded")
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn trace_quota_counts_other_segments_and_fails_strict_writers() {
+    let _guard = HomeGuard::new();
+    let (session, trace_dir) = session_with_trace_quota(64);
+    ensure_trace_dir(&trace_dir).unwrap();
+    fs::write(trace_dir.join("1-1.jsonl"), [b'x'; 64]).unwrap();
+
+    let lenient = TraceConfig::build(None, Some(trace_dir.clone()), false).unwrap();
+    lenient
+        .emit("command.end", Some(&session), json!({}))
+        .unwrap();
+    let strict = TraceConfig::build(None, Some(trace_dir.clone()), true).unwrap();
+    let err = strict
+        .emit("command.end", Some(&session), json!({}))
+        .unwrap_err();
+
+    assert!(err.to_string().contains("max_trace_bytes"));
+    let segment = segment_path_for_dir(&trace_dir);
+    assert_eq!(segment.metadata().map(|meta| meta.len()).unwrap_or(0), 0);
+}
@@ -0,0 +1,113 @@
+(function () {
+  "use strict";
+
+  var rows = [];
+  var selectedIndex = -1;
+
+  function parsePayload() {
+    var node = document.getElementById("trace-data");
+    try {
+      return JSON.parse(node.textContent || "{}");
+    } catch (e) {
+      return { rows: [], parse_error: String(e) };
+    }
+  }
+
+  var STATUS_CLASS = { same: "ok", changed: "open-incomplete", missing: "err", added: "err" };
+
+  function stepLabel(step) {
+    if (!step) return "—";
+    var target = step.target ? " " + step.target.role + (step.target.name ? " “" + step.target.name + "”" : "") : "";
+    var outcome = step.ok === false ? " ✗ " + (step.code || "error") : "";
+    return "#" + step.index + " " + step.command + target + outcome;
+  }
+
+  function differenceLabel(d) {
+    if (d.kind === "error_code") return "code " + (d.a || "ok") + " → " + (d.b || "ok");
+    if (d.kind === "latency") return d.a_ms + "ms → " + d.b_ms + "ms";
+    if (d.kind === "surface") {
+      return (d.appeared || []).map(function (s) { return "+" + s; })
+        .concat((d.disappeared || []).map(function (s) { return "−" + s; })).join(" ");
+    }
+    return d.kind;
+  }
+
+  function makeRow(row, index, first) {
+    var el = document.createElement("div");
+    el.className = "group-header diff-row " + (STATUS_CLASS[row.status] || "");
+    if (index === first) el.classList.add("first-divergence");
+    var a = document.createElement("span");
+    a.className = "diff-side";
+    a.textContent = stepLabel(row.a);
+    var b = document.createElement("span");
+    b.className = "diff-side";
+    b.textContent = stepLabel(row.b);
+    var badge = document.createElement("span");
+    badge.className = "status " + (STATUS_CLASS[row.status] || "");
+    badge.textContent = (row.differences || []).map(differenceLabel).join(" · ") || row.status;
+    el.appendChild(a);
+    el.appendChild(b);
+    el.appendChild(badge);
+    var i = rows.length;
+    el.addEventListener("click", function () { select(i); });
+    rows.push({ el: el, row: row, index: index });
+    return el;
+  }
+
+  function select(i) {
+    if (i < 0 || i >= rows.length) return;
+    if (selectedIndex >= 0) rows[selectedIndex].el.classList.remove("selected");
+    selectedIndex = i;
+    var entry = rows[i];
+    entry.el.classList.add("selected");
+    entry.el.scrollIntoView({ block: "nearest" });
+    document.getElementById("detail-title").textContent = "row " + entry.index + " · " + entry.row.status;
+    document.getElementById("detail-body").textContent = JSON.stringify(entry.row, null, 2);
+  }
+
+  function pill(label, value) {
+    var el = document.createElement("span");
+    el.className = "pill";
+    var k = document.createElement("span");
+    k.className = "k";
+    k.textContent = label;
+    var v = document.createElement("b");
+    v.textContent = value;
+    el.appendChild(k);
+    el.appendChild(v);
+    return el;
+  }
+
+  var payload = parsePayload();
+  var summary = payload.summary || {};
+  var meta = document.getElementById("meta");
+  meta.appendChild(pill("a", payload.a || "?"));
+  meta.appendChild(pill("b", payload.b || "?"));
+  ["same", "changed", "missing", "added"].forEach(function (key) {
+    meta.appendChild(pill(key, String(summary[key] || 0)));
+  });
+
+  var notes = [];
+  if (payload.parse_error) notes.push("parse_error: " + payload.parse_error);
+  if (payload.truncated) notes.push("Alignment stopped after the step limit; later steps are not compared.");
+  if (payload.first_divergence == null) notes.push("The runs did not diverge.");
+  if (notes.length) {
+    var banner = document.getElementById("warnings");
+    banner.className = "banner";
+    banner.textContent = notes.join("\n");
+  }
+  document.documentElement.style.setProperty("--header-h", (document.querySelector("header").offsetHeight + 3) + "px");
+
+  var timeline = document.getElementById("timeline");
+  var first = payload.first_divergence;
+  (payload.rows || []).forEach(function (row, index) {
+    timeline.appendChild(makeRow(row, index, first));
+  });
+
+  document.addEventListener("keydown", function (e) {
+    if (e.key === "ArrowDown" || e.key === "j") { e.preventDefault(); select(Math.min(rows.length - 1, selectedIndex + 1)); }
+    else if (e.key === "ArrowUp" || e.key === "k") { e.preventDefault(); select(Math.max(0, selectedIndex - 1)); }
+  });
+
+  if (rows.length) select(first == null ? 0 : first);
+})();
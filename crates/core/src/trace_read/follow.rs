@@ -0,0 +1,178 @@
+use super::is_managed_artifact_dir;
+use super::merge::{annotate_provenance, apply_tail_limit, filter_by_event_prefix, merge_segments};
+use super::segment::{ParsedEvent, SegmentReadStats, parse_event_line, parse_segment_filename};
+use crate::AppError;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::Path;
+use std::time::Duration;
+
+pub const TRACE_FOLLOW_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone)]
+pub struct FollowOptions {
+    /// Events already in the trace to replay before following (0 for all).
+    pub limit: usize,
+    pub event_prefix: Option<String>,
+    pub poll_interval: Duration,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct FollowStats {
+    pub streamed_events: usize,
+    pub segments: usize,
+    pub rotated_segments: usize,
+}
+
+#[derive(Debug, Default)]
+struct SegmentCursor {
+    pid: u32,
+    offset: u64,
+    position: u64,
+}
+
+/// Read positions into every segment of one trace directory. Each poll
+/// returns only the complete lines appended since the previous one; a
+/// partial trailing line stays unread until its writer finishes it.
+#[derive(Debug, Default)]
+pub(crate) struct TraceTail {
+    cursors: BTreeMap<String, SegmentCursor>,
+    rotated: usize,
+}
+
+impl TraceTail {
+    /// New events across all segments, merged by timestamp. A segment that
+    /// is shorter than the last read position was rotated (truncated or
+    /// replaced, e.g. after reaching `MAX_TRACE_FILE_BYTES`) and is read
+    /// again from its start; a segment that disappeared, even between
+    /// listing and opening, is forgotten.
+    pub(crate) fn poll(&mut self, trace_dir: &Path) -> Result<Vec<Value>, AppError> {
+        let mut seen = Vec::new();
+        let mut sources = Vec::new();
+        for entry in std::fs::read_dir(trace_dir)? {
+            let entry = entry?;
+            let name = entry.file_name();
+            let Some(name) = name.to_str() else {
+                continue;
+            };
+            if is_managed_artifact_dir(&entry, name) {
+                continue;
+            }
+            let Some(parsed_name) = parse_segment_filename(name) else {
+                continue;
+            };
+            let path = entry.path();
+            if crate::refs::is_symlink(&path) {
+                continue;
+            }
+            let cursor = self
+                .cursors
+                .entry(parsed_name.stem.clone())
+                .or_insert_with(|| SegmentCursor {
+                    pid: parsed_name.pid,
+                    ..SegmentCursor::default()
+                });
+            let Ok(events) = read_appended(&path, cursor, &mut self.rotated) else {
+                continue;
+            };
+            seen.push(parsed_name.stem.clone());
+            if !events.is_empty() {
+                sources.push((events, cursor.pid, parsed_name.stem));
+            }
+        }
+        self.cursors.retain(|stem, _| seen.contains(stem));
+        sources.sort_by(|a, b| a.2.cmp(&b.2));
+        Ok(merge_segments(sources)
+            .iter()
+            .map(annotate_provenance)
+            .collect())
+    }
+}
+
+fn read_appended(
+    path: &Path,
+    cursor: &mut SegmentCursor,
+    rotated: &mut usize,
+) -> Result<Vec<ParsedEvent>, AppError> {
+    let mut file = crate::refs::open_nofollow(path).map_err(AppError::from)?;
+    let len = file.metadata()?.len();
+    if len < cursor.offset {
+        cursor.offset = 0;
+        cursor.position = 0;
+        *rotated += 1;
+    }
+    if len == cursor.offset {
+        return Ok(Vec::new());
+    }
+    file.seek(SeekFrom::Start(cursor.offset))?;
+    let mut reader = BufReader::new(file);
+    let mut raw = Vec::new();
+    let mut stats = SegmentReadStats::default();
+    let mut events = Vec::new();
+    loop {
+        raw.clear();
+        let bytes_read = reader.read_until(b'\n', &mut raw)?;
+        if bytes_read == 0 || raw.last() != Some(&b'\n') {
+            break;
+        }
+        cursor.offset += bytes_read as u64;
+        cursor.position += 1;
+        let line_bytes = &raw[..raw.len() - 1];
+        if line_bytes.is_empty() {
+            continue;
+        }
+        if let Some((value, ts_ms)) = parse_event_line(line_bytes, &mut stats) {
+            events.push(ParsedEvent {
+                value,
+                ts_ms,
+                position: cursor.position,
+            });
+        }
+    }
+    Ok(events)
+}
+
+/// Streams a trace directory to `emit` as it grows: first the last
+/// `options.limit` events already recorded, then every new event, each
+/// poll merged in timestamp order and filtered by `options.event_prefix`.
+/// `ended` is asked before every poll; once it reports the session over,
+/// one last poll drains what the final commands wrote and following stops.
+pub fn follow(
+    trace_dir: &Path,
+    options: &FollowOptions,
+    ended: &mut dyn FnMut() -> Result<bool, AppError>,
+    emit: &mut dyn FnMut(&Value) -> Result<(), AppError>,
+) -> Result<FollowStats, AppError> {
+    if !trace_dir.is_dir() {
+        return Err(AppError::invalid_input_with_suggestion(
+            "Trace directory does not exist",
+            "Run `session start` with tracing enabled, or pass `--session <id>`.",
+        ));
+    }
+    let mut tail = TraceTail::default();
+    let mut stats = FollowStats::default();
+    let mut backlog = true;
+    loop {
+        let over = ended()?;
+        let events = filter_by_event_prefix(tail.poll(trace_dir)?, options.event_prefix.as_deref());
+        let events = if backlog {
+            apply_tail_limit(events, options.limit).0
+        } else {
+            events
+        };
+        backlog = false;
+        for event in &events {
+            emit(event)?;
+            stats.streamed_events += 1;
+        }
+        if over {
+            break;
+        }
+        std::thread::sleep(options.poll_interval);
+    }
+    stats.segments = tail.cursors.len();
+    stats.rotated_segments = tail.rotated;
+    Ok(stats)
+}
@@ -0,0 +1,134 @@
+use super::follow::{FollowOptions, TraceTail, follow};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+fn temp_dir(prefix: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let dir = std::env::temp_dir().join(format!("agent-desktop-{prefix}-{nanos}"));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn append(dir: &Path, name: &str, text: &str) {
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join(name))
+        .unwrap();
+    file.write_all(text.as_bytes()).unwrap();
+}
+
+fn event(name: &str, ts_ms: u64) -> String {
+    format!("{{\"event\":\"{name}\",\"ts_ms\":{ts_ms}}}\n")
+}
+
+fn names(events: &[serde_json::Value]) -> Vec<&str> {
+    events
+        .iter()
+        .map(|event| event["event"].as_str().unwrap())
+        .collect()
+}
+
+#[test]
+fn polls_return_only_complete_lines_appended_since_the_last_poll() {
+    let dir = temp_dir("trace-follow-append");
+    let mut tail = TraceTail::default();
+    append(&dir, "100-1.jsonl", &event("command.start", 10));
+
+    assert_eq!(names(&tail.poll(&dir).unwrap()), ["command.start"]);
+    assert!(tail.poll(&dir).unwrap().is_empty());
+
+    append(&dir, "100-1.jsonl", "{\"event\":\"command.end\",");
+    assert!(
+        tail.poll(&dir).unwrap().is_empty(),
+        "a partial line waits for its writer"
+    );
+    append(&dir, "100-1.jsonl", "\"ts_ms\":20}\n");
+    let events = tail.poll(&dir).unwrap();
+    assert_eq!(names(&events), ["command.end"]);
+    assert_eq!(events[0]["writer_pid"], 100);
+}
+
+#[test]
+fn new_segments_are_merged_by_timestamp() {
+    let dir = temp_dir("trace-follow-merge");
+    let mut tail = TraceTail::default();
+    append(&dir, "100-1.jsonl", &event("a", 10));
+    tail.poll(&dir).unwrap();
+
+    append(&dir, "100-1.jsonl", &event("c", 30));
+    append(&dir, "200-2.jsonl", &event("b", 20));
+
+    assert_eq!(names(&tail.poll(&dir).unwrap()), ["b", "c"]);
+}
+
+#[test]
+fn a_rotated_segment_is_read_again_from_its_start() {
+    let dir = temp_dir("trace-follow-rotate");
+    let mut tail = TraceTail::default();
+    append(&dir, "100-1.jsonl", &event("old.one", 10));
+    append(&dir, "100-1.jsonl", &event("old.two", 20));
+    tail.poll(&dir).unwrap();
+
+    fs::write(dir.join("100-1.jsonl"), event("new", 30)).unwrap();
+
+    assert_eq!(names(&tail.poll(&dir).unwrap()), ["new"]);
+}
+
+#[test]
+fn follow_replays_the_filtered_tail_and_stops_once_the_session_ended() {
+    let dir = temp_dir("trace-follow-loop");
+    for (index, name) in ["command.start", "snapshot.saved", "command.end"]
+        .iter()
+        .enumerate()
+    {
+        append(&dir, "100-1.jsonl", &event(name, index as u64));
+    }
+    let mut polls = 0;
+    let mut streamed = Vec::new();
+    let stats = follow(
+        &dir,
+        &FollowOptions {
+            limit: 1,
+            event_prefix: Some("command.".into()),
+            poll_interval: Duration::ZERO,
+        },
+        &mut || {
+            polls += 1;
+            if polls == 2 {
+                append(&dir, "100-1.jsonl", &event("command.start", 5));
+            }
+            Ok(polls == 2)
+        },
+        &mut |event| {
+            streamed.push(event["event"].as_str().unwrap().to_string());
+            Ok(())
+        },
+    )
+    .unwrap();
+
+    assert_eq!(streamed, ["command.end", "command.start"]);
+    assert_eq!(stats.streamed_events, 2);
+    assert_eq!(stats.segments, 1);
+}
+
+#[test]
+fn follow_requires_a_trace_directory() {
+    let dir = temp_dir("trace-follow-missing").join("absent");
+    let result = follow(
+        &dir,
+        &FollowOptions {
+            limit: 0,
+            event_prefix: None,
+            poll_interval: Duration::ZERO,
+        },
+        &mut || Ok(true),
+        &mut |_| Ok(()),
+    );
+    assert!(result.is_err());
+}
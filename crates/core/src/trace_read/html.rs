@@ -1,4 +1,5 @@
-use super::{ReadOptions, read_merged};
+use super::merge::apply_tail_limit;
+use super::{ReadOptions, read_merged, summarize};
 use crate::AppError;
 use crate::trace_artifacts::read_screenshot_for_embed;
 use base64::Engine;
@@ -10,6 +11,8 @@ use std::sync::atomic::{AtomicU64, Ordering};
 const VIEWER_HTML: &str = include_str!("viewer.html");
 const VIEWER_CSS: &str = include_str!("viewer.css");
 const VIEWER_JS: &str = include_str!("viewer.js");
+const DIFF_VIEWER_HTML: &str = include_str!("diff_viewer.html");
+const DIFF_VIEWER_JS: &str = include_str!("diff_viewer.js");
 
 pub const TRACE_EXPORT_DEFAULT_LIMIT: usize = 5000;
 const MAX_EMBED_SCREENSHOT_BYTES: u64 = 100 * 1024 * 1024;
@@ -65,16 +68,15 @@ pub fn export_html(
     session_id: &str,
     options: &ExportOptions,
 ) -> Result<(String, ExportStats), AppError> {
-    let merged = read_merged(
-        trace_dir,
-        &ReadOptions {
-            limit: options.limit,
-            event_prefix: None,
-        },
-    )?;
+    let mut merged = read_merged(trace_dir, &ReadOptions::default())?;
     if merged.segments.is_empty() {
         return Err(empty_trace_dir_error(session_id));
     }
+    let stats = summarize(&merged.events);
+    let (events, truncated) = apply_tail_limit(std::mem::take(&mut merged.events), options.limit);
+    merged.returned_events = events.len();
+    merged.truncated = truncated;
+    merged.events = events;
 
     let (screenshots, embedded, skipped) = embed_screenshots(trace_dir, &merged.events)?;
 
@@ -84,6 +86,7 @@ pub fn export_html(
         "returned_events": merged.returned_events,
         "truncated": merged.truncated,
         "warnings": merged.warnings,
+        "stats": stats,
         "screenshots": screenshots,
         "screenshots_embedded": embedded,
         "screenshots_skipped": skipped,
@@ -143,6 +146,23 @@ pub fn export_html(
     ))
 }
 
+/// Writes `trace diff`'s report as a self-contained page on the trace
+/// viewer's styles, returning its size in bytes.
+pub fn export_diff_html(report: &Value, path: &Path) -> Result<usize, AppError> {
+    let island_json = serde_json::to_string(report)?;
+    if island_json.len() as u64 > max_json_bytes() {
+        return Err(AppError::invalid_input(
+            "Trace diff exceeds the maximum embedded JSON size",
+        ));
+    }
+    let html = DIFF_VIEWER_HTML
+        .replace("{{CSS}}", VIEWER_CSS)
+        .replace("{{JS}}", DIFF_VIEWER_JS)
+        .replace("{{DATA}}", &escape_for_json_island(&island_json));
+    write_export_file(path, html.as_bytes())?;
+    Ok(html.len())
+}
+
 fn empty_trace_dir_error(session_id: &str) -> AppError {
     AppError::invalid_input_with_suggestion(
         format!("Session '{session_id}' has an empty trace directory"),
@@ -150,6 +170,15 @@ fn empty_trace_dir_error(session_id: &str) -> AppError {
     )
 }
 
+/// Artifact fields that name a screenshot: before and after an action, and
+/// the captured crop and diff mask of a failed visual comparison.
+const SCREENSHOT_KEYS: [&str; 4] = [
+    "screenshot_pre",
+    "screenshot_post",
+    "screenshot_actual",
+    "screenshot_diff",
+];
+
 fn embed_screenshots(
     trace_dir: &Path,
     events: &[Value],
@@ -160,7 +189,7 @@ fn embed_screenshots(
         if event.get("event").and_then(Value::as_str) != Some("action.artifacts") {
             continue;
         }
-        for key in ["screenshot_pre", "screenshot_post"] {
+        for key in SCREENSHOT_KEYS {
             let Some(path) = event.get(key).and_then(Value::as_str) else {
                 continue;
             };
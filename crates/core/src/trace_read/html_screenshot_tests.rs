@@ -146,3 +146,40 @@ fn symlinked_screenshot_path_is_not_embedded() {
     assert!(island["screenshots"].as_object().unwrap().is_empty());
     assert!(!html.contains(marker));
 }
+
+#[test]
+fn comparison_actual_and_diff_screenshots_are_embedded() {
+    let (_home, _lock, session_id, trace_dir) = setup_trace_session();
+    fs::create_dir_all(trace_dir.join("screens")).unwrap();
+    let png = [0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a];
+    for name in ["actual.png", "diff.png"] {
+        let path = trace_dir.join("screens").join(name);
+        fs::write(&path, png).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+        }
+    }
+
+    write_segment(
+        &trace_dir,
+        "100-1000.jsonl",
+        &[
+            r#"{"event":"action.artifacts","screenshot_actual":"screens/actual.png","screenshot_diff":"screens/diff.png","ts_ms":1,"seq":1}"#,
+        ],
+    );
+    let (html, stats) = export_html(
+        &trace_dir,
+        &session_id,
+        &ExportOptions {
+            limit: 0,
+            out: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(stats.screenshots_embedded, 2);
+    let island = parse_trace_data_island(&html);
+    assert!(island["screenshots"]["screens/actual.png"].is_string());
+    assert!(island["screenshots"]["screens/diff.png"].is_string());
+}
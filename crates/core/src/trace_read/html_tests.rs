@@ -263,6 +263,35 @@ fn export_honors_limit_in_metadata() {
     assert!(html.contains("\"truncated\":true"));
 }
 
+#[test]
+fn summary_stats_cover_the_whole_trace_beyond_the_limit() {
+    let (_home, _lock, session_id, trace_dir) = setup_trace_session();
+    write_segment(
+        &trace_dir,
+        "100-1000.jsonl",
+        &[
+            r#"{"event":"command.start","command":"click","ts_ms":1}"#,
+            r#"{"event":"command.end","command":"click","ok":false,"code":"TIMEOUT","duration_ms":40,"ts_ms":41}"#,
+            r#"{"event":"command.start","command":"click","ts_ms":50}"#,
+            r#"{"event":"command.end","command":"click","ok":true,"duration_ms":10,"ts_ms":60}"#,
+        ],
+    );
+    let (html, _) = export_html(
+        &trace_dir,
+        &session_id,
+        &ExportOptions {
+            limit: 1,
+            out: None,
+        },
+    )
+    .unwrap();
+    let island = parse_trace_data_island(&html);
+    assert_eq!(island["events"].as_array().unwrap().len(), 1);
+    assert_eq!(island["stats"]["total_commands"], 2);
+    assert_eq!(island["stats"]["errors"]["TIMEOUT"], 1);
+    assert!(html.contains("id=\"summary\""));
+}
+
 #[test]
 fn redacted_field_survives_in_island_json() {
     let (_home, _lock, session_id, trace_dir) = setup_trace_session();
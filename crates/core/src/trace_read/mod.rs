@@ -1,8 +1,18 @@
+mod follow;
 mod html;
 mod merge;
+mod recorded;
 mod segment;
+mod stats;
 
-pub use html::{ExportOptions, ExportStats, TRACE_EXPORT_DEFAULT_LIMIT, export_html};
+pub use follow::{FollowOptions, FollowStats, TRACE_FOLLOW_POLL_INTERVAL, follow};
+pub use html::{
+    ExportOptions, ExportStats, TRACE_EXPORT_DEFAULT_LIMIT, export_diff_html, export_html,
+};
+pub use recorded::{RecordedCommand, args_redacted, read_recorded_commands};
+pub use stats::{
+    AutoWaitStats, CommandStats, DeliveryStats, GateWait, RetryStats, TraceStats, summarize,
+};
 
 use crate::AppError;
 use merge::{
@@ -209,3 +219,11 @@ mod merge_tests;
 #[cfg(test)]
 #[path = "mod_tests.rs"]
 mod mod_tests;
+
+#[cfg(test)]
+#[path = "stats_tests.rs"]
+mod stats_tests;
+
+#[cfg(test)]
+#[path = "follow_tests.rs"]
+mod follow_tests;
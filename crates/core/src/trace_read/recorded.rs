@@ -0,0 +1,102 @@
+use super::{ReadOptions, read_merged};
+use crate::AppError;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// One top-level or batch-entry command reconstructed from a session trace:
+/// what ran, with which args, and how it ended the first time. `surfaces`
+/// lists the kinds of overlay (sheet, menu, alert, …) its actions left open.
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordedCommand {
+    pub index: usize,
+    pub command: String,
+    pub args: Option<Value>,
+    pub ok: Option<bool>,
+    pub code: Option<String>,
+    pub duration_ms: Option<u64>,
+    pub snapshot_ids: Vec<String>,
+    pub surfaces: Vec<String>,
+}
+
+/// Rebuilds the replayable command sequence of a trace directory in
+/// timeline order. Commands whose `command.start` carries neither `args`
+/// nor `args_omitted` (runners such as `batch`, `run`, and `session`) are
+/// not listed, though their nested entries are. Args are the sanitized copy
+/// inline in the event; see [`args_redacted`].
+pub fn read_recorded_commands(trace_dir: &Path) -> Result<Vec<RecordedCommand>, AppError> {
+    let merged = read_merged(trace_dir, &ReadOptions::default())?;
+    let mut stacks: HashMap<String, Vec<Option<usize>>> = HashMap::new();
+    let mut commands: Vec<RecordedCommand> = Vec::new();
+    for event in &merged.events {
+        let writer = event["segment"].as_str().unwrap_or_default().to_string();
+        let stack = stacks.entry(writer).or_default();
+        match event["event"].as_str().unwrap_or_default() {
+            "command.start" => stack.push(start(event, &mut commands)),
+            "command.end" => {
+                if let Some(Some(index)) = stack.pop() {
+                    commands[index].ok = event["ok"].as_bool();
+                    commands[index].code = event["code"].as_str().map(str::to_string);
+                    commands[index].duration_ms = event["duration_ms"].as_u64();
+                }
+            }
+            "action.dispatch.ok" => {
+                let open = stack.iter().rev().flatten().next().copied();
+                let surfaces = event["result"]["surfaces"].as_array();
+                if let (Some(index), Some(surfaces)) = (open, surfaces) {
+                    commands[index].surfaces.extend(
+                        surfaces
+                            .iter()
+                            .filter_map(|surface| surface["type"].as_str())
+                            .map(str::to_string),
+                    );
+                }
+            }
+            "snapshot.saved" | "snapshot.root.saved" => {
+                let open = stack.iter().rev().flatten().next().copied();
+                if let (Some(index), Some(id)) = (open, event["snapshot_id"].as_str()) {
+                    commands[index].snapshot_ids.push(id.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(commands)
+}
+
+fn start(event: &Value, commands: &mut Vec<RecordedCommand>) -> Option<usize> {
+    let omitted = event["args_omitted"].as_bool().unwrap_or(false);
+    if event.get("args").is_none() && !omitted {
+        return None;
+    }
+    let args = event.get("args").cloned();
+    commands.push(RecordedCommand {
+        index: commands.len() + 1,
+        command: event["command"].as_str().unwrap_or_default().to_string(),
+        args,
+        ok: None,
+        code: None,
+        duration_ms: None,
+        snapshot_ids: Vec::new(),
+        surfaces: Vec::new(),
+    });
+    Some(commands.len() - 1)
+}
+
+/// True when the trace writer replaced part of `args` with its redaction
+/// marker, so the recorded copy cannot be run as it was.
+pub fn args_redacted(args: &Value) -> bool {
+    match args {
+        Value::Object(map) => {
+            (map.len() == 1 && map.get("redacted") == Some(&Value::Bool(true)))
+                || map.values().any(args_redacted)
+        }
+        Value::Array(items) => items.iter().any(args_redacted),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+#[path = "recorded_tests.rs"]
+mod tests;
@@ -0,0 +1,126 @@
+use super::{args_redacted, read_recorded_commands};
+use serde_json::json;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn temp_dir(prefix: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    std::env::temp_dir().join(format!("agent-desktop-{prefix}-{nanos}"))
+}
+
+fn write_segment(dir: &Path, name: &str, lines: &[&str]) {
+    fs::create_dir_all(dir).unwrap();
+    let mut file = fs::File::create(dir.join(name)).unwrap();
+    for line in lines {
+        writeln!(file, "{line}").unwrap();
+    }
+}
+
+#[test]
+fn batch_entries_are_listed_and_runners_are_not() {
+    let dir = temp_dir("trace-recorded-batch");
+    write_segment(
+        &dir,
+        "100-1000.jsonl",
+        &[
+            r#"{"event":"command.start","ts_ms":1,"seq":1,"command":"batch"}"#,
+            r#"{"event":"command.start","ts_ms":2,"seq":2,"command":"snapshot","args":{"app":null}}"#,
+            r#"{"event":"snapshot.saved","ts_ms":3,"seq":3,"snapshot_id":"s1"}"#,
+            r#"{"event":"command.end","ts_ms":4,"seq":4,"command":"snapshot","ok":true}"#,
+            r#"{"event":"command.start","ts_ms":5,"seq":5,"command":"click","args":{"ref_id":"@s1:e2"}}"#,
+            r#"{"event":"command.end","ts_ms":6,"seq":6,"command":"click","ok":false,"code":"STALE_REF"}"#,
+            r#"{"event":"command.end","ts_ms":7,"seq":7,"command":"batch","ok":true}"#,
+        ],
+    );
+
+    let commands = read_recorded_commands(&dir).unwrap();
+
+    assert_eq!(commands.len(), 2);
+    assert_eq!(commands[0].index, 1);
+    assert_eq!(commands[0].command, "snapshot");
+    assert_eq!(commands[0].snapshot_ids, ["s1"]);
+    assert_eq!(commands[0].ok, Some(true));
+    assert_eq!(commands[1].args, Some(json!({ "ref_id": "@s1:e2" })));
+    assert_eq!(commands[1].ok, Some(false));
+    assert_eq!(commands[1].code.as_deref(), Some("STALE_REF"));
+}
+
+#[test]
+fn omitted_args_and_unfinished_commands_are_kept_without_outcome() {
+    let dir = temp_dir("trace-recorded-omitted");
+    write_segment(
+        &dir,
+        "100-1000.jsonl",
+        &[r#"{"event":"command.start","ts_ms":1,"seq":1,"command":"type","args_omitted":true}"#],
+    );
+
+    let commands = read_recorded_commands(&dir).unwrap();
+
+    assert_eq!(commands.len(), 1);
+    assert!(commands[0].args.is_none());
+    assert!(commands[0].ok.is_none());
+}
+
+#[test]
+fn a_stored_args_file_is_never_read_over_the_sanitized_inline_copy() {
+    let dir = temp_dir("trace-recorded-artifact");
+    write_segment(
+        &dir,
+        "100-1000.jsonl",
+        &[
+            r#"{"event":"command.start","ts_ms":1,"seq":1,"command":"type","args":{"text":{"redacted":true}},"args_artifact":"commands/1-1-0.json"}"#,
+        ],
+    );
+    fs::create_dir_all(dir.join("commands")).unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        for private in [dir.clone(), dir.join("commands")] {
+            fs::set_permissions(private, fs::Permissions::from_mode(0o700)).unwrap();
+        }
+    }
+    crate::private_file::write_atomic(&dir.join("commands/1-1-0.json"), br#"{"text":"hello"}"#)
+        .unwrap();
+
+    let commands = read_recorded_commands(&dir).unwrap();
+
+    assert_eq!(
+        commands[0].args,
+        Some(json!({ "text": { "redacted": true } }))
+    );
+}
+
+#[test]
+fn redaction_markers_are_found_at_any_depth() {
+    assert!(args_redacted(&json!({ "text": { "redacted": true } })));
+    assert!(args_redacted(
+        &json!({ "steps": [{ "query": { "redacted": true } }] })
+    ));
+    assert!(!args_redacted(
+        &json!({ "ref_id": "@e1", "redacted": true, "n": 1 })
+    ));
+}
+
+#[test]
+fn durations_and_opened_surfaces_are_kept_per_command() {
+    let dir = temp_dir("trace-recorded-surfaces");
+    write_segment(
+        &dir,
+        "100-1000.jsonl",
+        &[
+            r#"{"event":"command.start","ts_ms":1,"command":"click","args":{"ref_id":"@s1:e2"}}"#,
+            r#"{"event":"action.dispatch.ok","ts_ms":2,"result":{"action":"click","surfaces":[{"id":"w1","type":"sheet"}]}}"#,
+            r#"{"event":"command.end","ts_ms":9,"command":"click","ok":true,"duration_ms":8}"#,
+        ],
+    );
+
+    let commands = read_recorded_commands(&dir).unwrap();
+
+    assert_eq!(commands[0].duration_ms, Some(8));
+    assert_eq!(commands[0].surfaces, ["sheet"]);
+}
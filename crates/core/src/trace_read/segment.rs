@@ -84,48 +84,16 @@ pub(crate) fn read_segment_events(
             continue;
         }
 
-        if line_bytes.len() > MAX_LINE_BYTES {
-            stats.skipped_lines += 1;
-            continue;
-        }
-
         if !has_trailing_newline {
             stats.skipped_lines += 1;
             continue;
         }
 
-        let Ok(line_body) = std::str::from_utf8(line_bytes) else {
-            stats.skipped_lines += 1;
-            continue;
-        };
-
-        let parsed: Value = match serde_json::from_str(line_body) {
-            Ok(v) => v,
-            Err(_) => {
-                stats.skipped_lines += 1;
-                continue;
-            }
-        };
-
-        let Some(obj) = parsed.as_object() else {
+        let Some((parsed, ts_ms)) = parse_event_line(line_bytes, &mut stats) else {
             stats.skipped_lines += 1;
             continue;
         };
 
-        if obj.get("event").and_then(Value::as_str) == Some("trace.meta") && !stats.meta_seen {
-            stats.meta_seen = true;
-            if let Some(schema) = obj.get("schema").and_then(Value::as_u64) {
-                stats.schema = schema as u32;
-                if stats.schema > KNOWN_SCHEMA_MAX {
-                    stats.schema_warning = Some(format!(
-                        "Segment schema {schema} exceeds reader maximum {KNOWN_SCHEMA_MAX}"
-                    ));
-                }
-            }
-        }
-
-        let ts_ms = obj.get("ts_ms").and_then(Value::as_u64).unwrap_or(0);
-
         events.push(ParsedEvent {
             value: parsed,
             ts_ms,
@@ -140,3 +108,33 @@ pub(crate) fn read_segment_events(
 
     Ok((events, stats))
 }
+
+/// Parses one complete segment line into an event and its timestamp,
+/// recording the segment schema from its first `trace.meta` event. `None`
+/// means the line is oversized, not UTF-8, or not a JSON object.
+pub(crate) fn parse_event_line(
+    line_bytes: &[u8],
+    stats: &mut SegmentReadStats,
+) -> Option<(Value, u64)> {
+    if line_bytes.len() > MAX_LINE_BYTES {
+        return None;
+    }
+    let line_body = std::str::from_utf8(line_bytes).ok()?;
+    let parsed: Value = serde_json::from_str(line_body).ok()?;
+    let obj = parsed.as_object()?;
+
+    if obj.get("event").and_then(Value::as_str) == Some("trace.meta") && !stats.meta_seen {
+        stats.meta_seen = true;
+        if let Some(schema) = obj.get("schema").and_then(Value::as_u64) {
+            stats.schema = schema as u32;
+            if stats.schema > KNOWN_SCHEMA_MAX {
+                stats.schema_warning = Some(format!(
+                    "Segment schema {schema} exceeds reader maximum {KNOWN_SCHEMA_MAX}"
+                ));
+            }
+        }
+    }
+
+    let ts_ms = obj.get("ts_ms").and_then(Value::as_u64).unwrap_or(0);
+    Some((parsed, ts_ms))
+}
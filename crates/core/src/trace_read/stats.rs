@@ -0,0 +1,258 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+/// Aggregate view of a merged trace: where commands spent their time, how
+/// they failed, and how actions reached the app.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TraceStats {
+    pub total_commands: usize,
+    pub failed_commands: usize,
+    pub total_duration_ms: u64,
+    /// Per command name, slowest total first.
+    pub commands: Vec<CommandStats>,
+    /// `command.end` error codes across every command.
+    pub errors: BTreeMap<String, usize>,
+    pub auto_wait: AutoWaitStats,
+    pub retries: RetryStats,
+    pub delivery: DeliveryStats,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CommandStats {
+    pub command: String,
+    pub count: usize,
+    pub failed: usize,
+    pub total_ms: u64,
+    pub p50_ms: u64,
+    pub p95_ms: u64,
+    pub max_ms: u64,
+    pub retries: usize,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub errors: BTreeMap<String, usize>,
+    #[serde(skip)]
+    durations: Vec<u64>,
+}
+
+/// Time ref actions spent waiting on actionability. A failed check charges
+/// the interval until the next check (or the end of its command) to every
+/// gate that blocked it, so one poll blocked by two gates counts for both.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AutoWaitStats {
+    pub total_ms: u64,
+    pub gates: Vec<GateWait>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GateWait {
+    pub gate: String,
+    pub blocked_checks: usize,
+    pub total_ms: u64,
+    pub max_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct RetryStats {
+    /// Actionability checks repeated after a blocked one.
+    pub actionability: usize,
+    /// `--heal` attempts on a stale ref.
+    pub heal: usize,
+}
+
+/// Delivery mechanism of every action step the trace recorded.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct DeliveryStats {
+    pub semantic_api: usize,
+    pub physical_synthetic: usize,
+    /// Share of mechanism-tagged steps delivered as synthetic input.
+    pub physical_share: Option<f64>,
+}
+
+#[derive(Default)]
+struct SegmentState<'a> {
+    open: Vec<&'a str>,
+    check_started: Option<u64>,
+    blocked: Option<(u64, Vec<&'a str>)>,
+}
+
+/// Summarizes merged events, in timeline order. Events are grouped by
+/// their `segment` so concurrent writers do not charge each other's waits.
+pub fn summarize(events: &[Value]) -> TraceStats {
+    let mut stats = TraceStats::default();
+    let mut commands: HashMap<String, CommandStats> = HashMap::new();
+    let mut gates: BTreeMap<&str, GateWait> = BTreeMap::new();
+    let mut segments: HashMap<&str, SegmentState> = HashMap::new();
+
+    for event in events {
+        let name = event.get("event").and_then(Value::as_str).unwrap_or("");
+        let ts = event.get("ts_ms").and_then(Value::as_u64).unwrap_or(0);
+        let segment = event.get("segment").and_then(Value::as_str).unwrap_or("");
+        let state = segments.entry(segment).or_default();
+        match name {
+            "command.start" => {
+                state
+                    .open
+                    .push(event.get("command").and_then(Value::as_str).unwrap_or(""));
+            }
+            "command.end" => {
+                charge_wait(state, ts, &mut gates, &mut stats.auto_wait);
+                state.check_started = None;
+                state.open.pop();
+                record_command(event, &mut stats, &mut commands);
+            }
+            "actionability.check.start" => {
+                if state.blocked.is_some() {
+                    charge_wait(state, ts, &mut gates, &mut stats.auto_wait);
+                    stats.retries.actionability += 1;
+                    count_retry(state, &mut commands);
+                }
+                state.check_started = Some(ts);
+            }
+            "actionability.check.error" => {
+                let blocking = blocking_gates(event);
+                if !blocking.is_empty() {
+                    state.blocked = Some((state.check_started.unwrap_or(ts), blocking));
+                }
+            }
+            "ref.heal" => {
+                stats.retries.heal += 1;
+                count_retry(state, &mut commands);
+            }
+            "action.dispatch.ok" => count_delivery(event, &mut stats.delivery),
+            _ => {}
+        }
+    }
+
+    let mut commands: Vec<CommandStats> = commands.into_values().map(finish_command).collect();
+    commands.sort_by(|a, b| b.total_ms.cmp(&a.total_ms).then(a.command.cmp(&b.command)));
+    stats.commands = commands;
+    stats.auto_wait.gates = gates.into_values().collect();
+    stats
+        .auto_wait
+        .gates
+        .sort_by(|a, b| b.total_ms.cmp(&a.total_ms).then(a.gate.cmp(&b.gate)));
+    let tagged = stats.delivery.semantic_api + stats.delivery.physical_synthetic;
+    stats.delivery.physical_share =
+        (tagged > 0).then(|| stats.delivery.physical_synthetic as f64 / tagged as f64);
+    stats
+}
+
+fn record_command(
+    event: &Value,
+    stats: &mut TraceStats,
+    commands: &mut HashMap<String, CommandStats>,
+) {
+    let command = event
+        .get("command")
+        .and_then(Value::as_str)
+        .unwrap_or("unknown");
+    let duration = event
+        .get("duration_ms")
+        .and_then(Value::as_u64)
+        .unwrap_or(0);
+    let entry = commands
+        .entry(command.to_string())
+        .or_insert_with(|| CommandStats {
+            command: command.to_string(),
+            ..CommandStats::default()
+        });
+    entry.count += 1;
+    entry.durations.push(duration);
+    stats.total_commands += 1;
+    stats.total_duration_ms += duration;
+    if event.get("ok").and_then(Value::as_bool) == Some(false) {
+        entry.failed += 1;
+        stats.failed_commands += 1;
+        let code = event
+            .get("code")
+            .and_then(Value::as_str)
+            .unwrap_or("UNKNOWN");
+        *entry.errors.entry(code.to_string()).or_default() += 1;
+        *stats.errors.entry(code.to_string()).or_default() += 1;
+    }
+}
+
+fn finish_command(mut stats: CommandStats) -> CommandStats {
+    stats.durations.sort_unstable();
+    stats.total_ms = stats.durations.iter().sum();
+    stats.p50_ms = percentile(&stats.durations, 50);
+    stats.p95_ms = percentile(&stats.durations, 95);
+    stats.max_ms = stats.durations.last().copied().unwrap_or(0);
+    stats
+}
+
+/// Nearest-rank percentile of an ascending slice.
+fn percentile(sorted: &[u64], pct: usize) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (sorted.len() * pct).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+fn count_retry(state: &SegmentState, commands: &mut HashMap<String, CommandStats>) {
+    let Some(command) = state.open.last() else {
+        return;
+    };
+    commands
+        .entry(command.to_string())
+        .or_insert_with(|| CommandStats {
+            command: command.to_string(),
+            ..CommandStats::default()
+        })
+        .retries += 1;
+}
+
+fn charge_wait<'a>(
+    state: &mut SegmentState<'a>,
+    until: u64,
+    gates: &mut BTreeMap<&'a str, GateWait>,
+    auto_wait: &mut AutoWaitStats,
+) {
+    let Some((since, blocking)) = state.blocked.take() else {
+        return;
+    };
+    let waited = until.saturating_sub(since);
+    auto_wait.total_ms += waited;
+    for gate in blocking {
+        let wait = gates.entry(gate).or_insert_with(|| GateWait {
+            gate: gate.to_string(),
+            ..GateWait::default()
+        });
+        wait.blocked_checks += 1;
+        wait.total_ms += waited;
+        wait.max_ms = wait.max_ms.max(waited);
+    }
+}
+
+/// Gates that blocked a failed actionability check: failed ones, and
+/// unknown ones other than the advisory `receives_events` hit test.
+fn blocking_gates(event: &Value) -> Vec<&str> {
+    let Some(checks) = event.pointer("/details/checks").and_then(Value::as_array) else {
+        return Vec::new();
+    };
+    checks
+        .iter()
+        .filter_map(|check| {
+            let gate = check.get("check").and_then(Value::as_str)?;
+            match check.get("status").and_then(Value::as_str)? {
+                "fail" => Some(gate),
+                "unknown" if gate != "receives_events" => Some(gate),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+fn count_delivery(event: &Value, delivery: &mut DeliveryStats) {
+    let Some(steps) = event.pointer("/result/steps").and_then(Value::as_array) else {
+        return;
+    };
+    for step in steps {
+        match step.get("mechanism").and_then(Value::as_str) {
+            Some("semantic_api") => delivery.semantic_api += 1,
+            Some("physical_synthetic") => delivery.physical_synthetic += 1,
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use super::stats::summarize;
+use serde_json::{Value, json};
+
+fn command(segment: &str, name: &str, ts: u64, ok: bool, duration: u64) -> [Value; 2] {
+    let mut end = json!({
+        "event": "command.end", "segment": segment, "command": name,
+        "ts_ms": ts + duration, "ok": ok, "duration_ms": duration,
+    });
+    if !ok {
+        end["code"] = json!("TIMEOUT");
+    }
+    [
+        json!({ "event": "command.start", "segment": segment, "command": name, "ts_ms": ts }),
+        end,
+    ]
+}
+
+fn check(name: &str, ts: u64, failing: &[(&str, &str)]) -> Value {
+    let checks: Vec<Value> = failing
+        .iter()
+        .map(|(check, status)| json!({ "check": check, "status": status }))
+        .collect();
+    json!({
+        "event": name, "segment": "100-1", "ts_ms": ts,
+        "details": { "checks": checks },
+    })
+}
+
+#[test]
+fn commands_report_counts_percentiles_and_error_codes() {
+    let mut events = Vec::new();
+    for (index, duration) in [10, 20, 30, 40].iter().enumerate() {
+        events.extend(command(
+            "100-1",
+            "click",
+            index as u64 * 100,
+            true,
+            *duration,
+        ));
+    }
+    events.extend(command("100-1", "click", 500, false, 900));
+    events.extend(command("100-1", "wait", 2000, true, 5));
+
+    let stats = summarize(&events);
+
+    assert_eq!(stats.total_commands, 6);
+    assert_eq!(stats.failed_commands, 1);
+    let click = &stats.commands[0];
+    assert_eq!(click.command, "click");
+    assert_eq!((click.count, click.failed), (5, 1));
+    assert_eq!((click.p50_ms, click.p95_ms, click.max_ms), (30, 900, 900));
+    assert_eq!(click.errors["TIMEOUT"], 1);
+    assert_eq!(stats.errors["TIMEOUT"], 1);
+    assert_eq!(stats.commands[1].command, "wait");
+}
+
+#[test]
+fn blocked_checks_charge_their_gates_until_the_next_check() {
+    let [start, mut end] = command("100-1", "click", 0, true, 200);
+    end["ts_ms"] = json!(200);
+    let events = vec![
+        start,
+        check("actionability.check.start", 10, &[]),
+        check(
+            "actionability.check.error",
+            12,
+            &[
+                ("visible", "fail"),
+                ("stable", "unknown"),
+                ("receives_events", "unknown"),
+            ],
+        ),
+        check("actionability.check.start", 60, &[]),
+        check("actionability.check.error", 61, &[("stable", "fail")]),
+        check("actionability.check.start", 90, &[]),
+        check("actionability.check.ok", 95, &[]),
+        json!({ "event": "ref.heal", "segment": "100-1", "ts_ms": 96, "outcome": "healed" }),
+        end,
+    ];
+
+    let stats = summarize(&events);
+
+    assert_eq!(stats.auto_wait.total_ms, 80);
+    let gates: Vec<(&str, usize, u64)> = stats
+        .auto_wait
+        .gates
+        .iter()
+        .map(|gate| (gate.gate.as_str(), gate.blocked_checks, gate.total_ms))
+        .collect();
+    assert_eq!(gates, [("stable", 2, 80), ("visible", 1, 50)]);
+    assert_eq!(stats.retries.actionability, 2);
+    assert_eq!(stats.retries.heal, 1);
+    assert_eq!(stats.commands[0].retries, 3);
+}
+
+#[test]
+fn a_wait_that_ends_its_command_is_charged_until_the_command_ends() {
+    let [start, end] = command("100-1", "click", 0, false, 100);
+    let events = vec![
+        start,
+        check("actionability.check.start", 20, &[]),
+        check("actionability.check.error", 25, &[("visible", "fail")]),
+        end,
+    ];
+
+    let stats = summarize(&events);
+
+    assert_eq!(stats.auto_wait.gates[0].total_ms, 80);
+    assert_eq!(stats.retries.actionability, 0);
+}
+
+#[test]
+fn delivery_counts_step_mechanisms() {
+    let events = vec![json!({
+        "event": "action.dispatch.ok",
+        "result": { "steps": [
+            { "mechanism": "semantic_api" },
+            { "mechanism": "physical_synthetic" },
+            { "mechanism": "semantic_api" },
+            { "outcome": "attempted" },
+        ] },
+    })];
+
+    let stats = summarize(&events);
+
+    assert_eq!(stats.delivery.semantic_api, 2);
+    assert_eq!(stats.delivery.physical_synthetic, 1);
+    assert!((stats.delivery.physical_share.unwrap() - 1.0 / 3.0).abs() < 1e-9);
+    assert_eq!(summarize(&[]).delivery.physical_share, None);
+}
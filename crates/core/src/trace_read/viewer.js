@@ -231,18 +231,28 @@
     return events.filter(function (e) { return e && e.event === "action.artifacts"; });
   }
 
+  var SHOT_LABELS = {
+    screenshot_pre: "before",
+    screenshot_post: "after",
+    screenshot_actual: "actual",
+    screenshot_diff: "diff",
+  };
+
   function setDetail(title, body, artifacts) {
     document.getElementById("detail-title").textContent = title;
     document.getElementById("detail-body").textContent = body;
     var shotsEl = document.getElementById("shots");
     shotsEl.textContent = "";
     artifacts.forEach(function (ev) {
-      ["screenshot_pre", "screenshot_post"].forEach(function (key) {
+      var keys = ev.screenshot_actual || ev.screenshot_diff
+        ? ["screenshot_actual", "screenshot_diff"]
+        : ["screenshot_pre", "screenshot_post"];
+      keys.forEach(function (key) {
         var wrap = document.createElement("div");
         wrap.className = "shot-wrap";
         var caption = document.createElement("div");
         caption.className = "shot-label";
-        caption.textContent = key === "screenshot_pre" ? "before" : "after";
+        caption.textContent = SHOT_LABELS[key];
         wrap.appendChild(caption);
         var rel = ev[key];
         var uri = rel && payload.screenshots ? safeDataUri(payload.screenshots[rel]) : null;
@@ -299,6 +309,63 @@
     if (p.truncated) meta.appendChild(pill("view", "tail"));
   }
 
+  function summaryTable(caption, headers, rowsData) {
+    var table = document.createElement("table");
+    var cap = document.createElement("caption");
+    cap.textContent = caption;
+    table.appendChild(cap);
+    var head = document.createElement("tr");
+    headers.forEach(function (h) {
+      var th = document.createElement("th");
+      th.textContent = h;
+      head.appendChild(th);
+    });
+    table.appendChild(head);
+    rowsData.forEach(function (cells) {
+      var tr = document.createElement("tr");
+      cells.forEach(function (cell) {
+        var td = document.createElement("td");
+        td.textContent = cell == null ? "—" : String(cell);
+        tr.appendChild(td);
+      });
+      table.appendChild(tr);
+    });
+    return table;
+  }
+
+  function renderSummary(p) {
+    var s = p.stats;
+    var node = document.getElementById("summary");
+    var body = document.getElementById("summary-body");
+    body.textContent = "";
+    if (!s || !s.total_commands) { node.classList.add("hidden"); return; }
+    node.classList.remove("hidden");
+    body.appendChild(summaryTable("commands", ["command", "n", "failed", "p50", "p95", "max", "retries"],
+      (s.commands || []).map(function (c) {
+        return [c.command, c.count, c.failed, c.p50_ms + "ms", c.p95_ms + "ms", c.max_ms + "ms", c.retries];
+      })));
+    var errors = Object.keys(s.errors || {});
+    if (errors.length) {
+      body.appendChild(summaryTable("errors", ["code", "n"], errors.map(function (code) {
+        return [code, s.errors[code]];
+      })));
+    }
+    var wait = s.auto_wait || {};
+    if ((wait.gates || []).length) {
+      body.appendChild(summaryTable("auto-wait " + wait.total_ms + "ms", ["gate", "blocked", "total", "max"],
+        wait.gates.map(function (g) { return [g.gate, g.blocked_checks, g.total_ms + "ms", g.max_ms + "ms"]; })));
+    }
+    var d = s.delivery || {};
+    var r = s.retries || {};
+    body.appendChild(summaryTable("delivery", ["", "n"], [
+      ["semantic_api", d.semantic_api || 0],
+      ["physical_synthetic", d.physical_synthetic || 0],
+      ["physical share", d.physical_share == null ? null : Math.round(d.physical_share * 100) + "%"],
+      ["actionability retries", r.actionability || 0],
+      ["heal retries", r.heal || 0],
+    ]));
+  }
+
   function renderWarnings(p) {
     var node = document.getElementById("warnings");
     var lines = [];
@@ -325,7 +392,9 @@
 
   renderMeta(payload);
   renderWarnings(payload);
+  renderSummary(payload);
   syncHeaderHeight();
+  document.getElementById("summary").addEventListener("toggle", syncHeaderHeight);
   window.addEventListener("resize", syncHeaderHeight);
 
   function rerender() {
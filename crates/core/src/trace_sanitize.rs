@@ -1,9 +1,11 @@
 use serde_json::{Value, json};
 
-/// Recursively redacts fields whose keys match `SENSITIVE_KEYS`. Non-sensitive
-/// fields and non-object values are left unchanged. Array elements are
-/// recursively scanned. Used by both the file-trace writer and the FFI log
-/// callback layer so that sensitive values never reach a consumer.
+/// Recursively redacts fields whose keys match `SENSITIVE_KEYS`, and
+/// replaces each secret this process holds for `--secret` with `[redacted]`
+/// wherever it appears in a string. Other values are left unchanged. Array
+/// elements are recursively scanned. Used by both the file-trace writer and
+/// the FFI log callback layer so that sensitive values never reach a
+/// consumer.
 pub fn sanitize_trace_value(value: Value) -> Value {
     match value {
         Value::Object(map) => Value::Object(
@@ -18,6 +20,7 @@ pub fn sanitize_trace_value(value: Value) -> Value {
                 .collect(),
         ),
         Value::Array(items) => Value::Array(items.into_iter().map(sanitize_trace_value).collect()),
+        Value::String(text) => Value::String(crate::secret_source::hide_active_secrets(text)),
         other => other,
     }
 }
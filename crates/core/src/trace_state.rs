@@ -1,6 +1,6 @@
 use std::{
     path::PathBuf,
-    sync::{Arc, Mutex, atomic::AtomicBool},
+    sync::{Arc, Mutex, OnceLock, atomic::AtomicBool},
 };
 
 #[derive(Debug, Clone, Default)]
@@ -19,9 +19,19 @@ pub(crate) enum TraceWriterState {
     Failed,
 }
 
+/// A session's trace byte quota, sized when its segment opens: the limit
+/// and the bytes its other segments held at that point.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SegmentQuota {
+    pub(crate) max_bytes: u64,
+    pub(crate) other_segments_bytes: u64,
+}
+
 #[derive(Debug, Default)]
 pub(crate) struct TraceState {
     pub(crate) pending: TracePending,
     pub(crate) writer: Arc<Mutex<TraceWriterState>>,
     pub(crate) meta_written: AtomicBool,
+    pub(crate) segment_quota: OnceLock<Option<SegmentQuota>>,
+    pub(crate) quota_exceeded: AtomicBool,
 }
@@ -8,6 +8,12 @@ pub struct TreeOptions {
     pub compact: bool,
     pub surface: SnapshotSurface,
     pub skeleton: bool,
+    /// Estimated token budget for the serialized snapshot tree. When set, the
+    /// observed tree is greedily truncated before refs are allocated.
+    pub max_tokens: Option<u32>,
+    /// Keeps the values of secure text fields and configured secure
+    /// locators, which are otherwise masked before refs are allocated.
+    pub reveal_secure: bool,
 }
 
 impl Default for TreeOptions {
@@ -19,6 +25,8 @@ impl Default for TreeOptions {
             compact: false,
             surface: SnapshotSurface::Window,
             skeleton: false,
+            max_tokens: None,
+            reveal_secure: false,
         }
     }
 }
@@ -28,4 +36,18 @@ impl TreeOptions {
         self.include_bounds = true;
         self
     }
+
+    pub(crate) fn budget_tree(&self, tree: crate::AccessibilityNode) -> crate::AccessibilityNode {
+        match self.max_tokens {
+            Some(max_tokens) => crate::snapshot_budget::apply_token_budget(
+                tree,
+                max_tokens,
+                crate::snapshot_budget::BudgetCostModel {
+                    include_bounds: self.include_bounds,
+                    interactive_only: self.interactive_only,
+                },
+            ),
+            None => tree,
+        }
+    }
 }
@@ -197,6 +197,7 @@ enum AdResult
   AD_RESULT_ERR_POLICY_DENIED = -14,
   AD_RESULT_ERR_AMBIGUOUS_TARGET = -15,
   AD_RESULT_ERR_APP_UNRESPONSIVE = -16,
+  AD_RESULT_ERR_ASSERTION_FAILED = -17,
 };
 #ifndef __cplusplus
 #if __STDC_VERSION__ >= 202311L
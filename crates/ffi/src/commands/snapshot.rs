@@ -98,6 +98,10 @@ pub unsafe extern "C" fn ad_snapshot(
             skeleton: false,
             root_ref: None,
             snapshot_id: None,
+            max_tokens: None,
+            format: agent_desktop_core::SnapshotFormat::Json,
+            stable_refs: false,
+            reveal_secure: false,
         };
 
         let scope = crate::commands::command_scope!(context, "snapshot");
@@ -25,6 +25,7 @@ pub enum AdResult {
     ErrPolicyDenied = -14,
     ErrAmbiguousTarget = -15,
     ErrAppUnresponsive = -16,
+    ErrAssertionFailed = -17,
 }
 
 const _: () = assert!(AdResult::ErrPermDenied as i32 == -1);
@@ -43,6 +44,7 @@ const _: () = assert!(AdResult::ErrSnapshotNotFound as i32 == -13);
 const _: () = assert!(AdResult::ErrPolicyDenied as i32 == -14);
 const _: () = assert!(AdResult::ErrAmbiguousTarget as i32 == -15);
 const _: () = assert!(AdResult::ErrAppUnresponsive as i32 == -16);
+const _: () = assert!(AdResult::ErrAssertionFailed as i32 == -17);
 
 enum MessageSource {
     Owned(CString),
@@ -114,6 +116,7 @@ fn error_code_to_result(code: &ErrorCode) -> AdResult {
         ErrorCode::SnapshotNotFound => AdResult::ErrSnapshotNotFound,
         ErrorCode::PolicyDenied => AdResult::ErrPolicyDenied,
         ErrorCode::AppUnresponsive => AdResult::ErrAppUnresponsive,
+        ErrorCode::AssertionFailed => AdResult::ErrAssertionFailed,
     }
 }
 
@@ -99,6 +99,8 @@ unsafe fn get_core_tree(
         compact: options.compact,
         surface,
         skeleton: false,
+        max_tokens: None,
+        reveal_secure: false,
     };
     let adapter = crate::adapter::acquire_adapter!(adapter);
     let deadline = crate::operation::operation_deadline!();
@@ -41,6 +41,10 @@ mod tests {
                 skeleton: false,
                 root_ref: None,
                 snapshot_id: None,
+                max_tokens: None,
+                format: agent_desktop_core::SnapshotFormat::Json,
+                stable_refs: false,
+                reveal_secure: false,
             },
             &adapter,
             &CommandContext::default(),
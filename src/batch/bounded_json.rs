@@ -8,7 +8,7 @@ pub(super) fn serialized_size(value: &Value) -> usize {
     writer.written
 }
 
-pub(super) fn serialized_fits(value: &Value, limit: usize) -> bool {
+pub(crate) fn serialized_fits(value: &Value, limit: usize) -> bool {
     serde_json::to_writer(CountingWriter::new(limit), value).is_ok()
 }
 
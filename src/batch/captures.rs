@@ -0,0 +1,312 @@
+use std::collections::BTreeMap;
+
+use agent_desktop_core::AppError;
+use serde_json::{Map, Value, json};
+
+use super::bounded_json::serialized_size;
+
+pub(super) const MAX_CAPTURES_PER_ENTRY: usize = 8;
+pub(super) const MAX_CAPTURE_VALUE_BYTES: usize = 512;
+const MAX_CAPTURE_NAME_CHARS: usize = 64;
+const REFERENCE_OPEN: &str = "${";
+const ESCAPED_OPEN: &str = "$${";
+
+/// Declared JSON type of a captured value. Captures are scalars so that a
+/// `${name}` embedded in a longer string always has a textual form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CaptureType {
+    String,
+    Integer,
+    Number,
+    Boolean,
+}
+
+impl CaptureType {
+    fn parse(label: &str) -> Option<Self> {
+        match label {
+            "string" => Some(Self::String),
+            "integer" => Some(Self::Integer),
+            "number" => Some(Self::Number),
+            "boolean" => Some(Self::Boolean),
+            _ => None,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::String => "string",
+            Self::Integer => "integer",
+            Self::Number => "number",
+            Self::Boolean => "boolean",
+        }
+    }
+
+    fn matches(self, value: &Value) -> bool {
+        match self {
+            Self::String => value.is_string(),
+            Self::Integer => value.is_i64() || value.is_u64(),
+            Self::Number => value.is_number(),
+            Self::Boolean => value.is_boolean(),
+        }
+    }
+
+    /// Stand-in used to type-check templated args before anything runs. The
+    /// string form is a well-formed ref so ref-consuming fields pass policy.
+    pub(crate) fn placeholder(self) -> Value {
+        match self {
+            Self::String => json!("@e1"),
+            Self::Integer | Self::Number => json!(1),
+            Self::Boolean => json!(false),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct CaptureSpec {
+    pub name: String,
+    pub pointer: String,
+    pub kind: CaptureType,
+}
+
+pub(crate) type DeclaredCaptures = BTreeMap<String, CaptureType>;
+pub(crate) type CapturedValues = BTreeMap<String, Value>;
+
+pub(crate) fn parse_specs(raw: BTreeMap<String, Value>) -> Result<Vec<CaptureSpec>, AppError> {
+    if raw.len() > MAX_CAPTURES_PER_ENTRY {
+        return Err(AppError::invalid_input(format!(
+            "Batch entry declares {} captures; the limit is {MAX_CAPTURES_PER_ENTRY}",
+            raw.len()
+        )));
+    }
+    raw.into_iter()
+        .map(|(name, spec)| parse_spec(name, spec))
+        .collect()
+}
+
+fn parse_spec(name: String, spec: Value) -> Result<CaptureSpec, AppError> {
+    validate_name(&name)?;
+    let (pointer, kind) = match spec {
+        Value::String(pointer) => (pointer, CaptureType::String),
+        Value::Object(mut fields) => {
+            let pointer = match fields.remove("pointer") {
+                Some(Value::String(pointer)) => pointer,
+                _ => return Err(spec_error(&name, "requires a string 'pointer'")),
+            };
+            let kind = match fields.remove("type") {
+                None => CaptureType::String,
+                Some(Value::String(label)) => CaptureType::parse(&label).ok_or_else(|| {
+                    spec_error(
+                        &name,
+                        "has an unknown 'type'; use string, integer, number, or boolean",
+                    )
+                })?,
+                Some(_) => return Err(spec_error(&name, "requires 'type' to be a string")),
+            };
+            if let Some(field) = fields.keys().next() {
+                return Err(spec_error(
+                    &name,
+                    &format!(
+                        "has unknown field {}",
+                        crate::diagnostic::token_label(field)
+                    ),
+                ));
+            }
+            (pointer, kind)
+        }
+        _ => {
+            return Err(spec_error(
+                &name,
+                "must be a JSON pointer string or {\"pointer\", \"type\"}",
+            ));
+        }
+    };
+    if !pointer.is_empty() && !pointer.starts_with('/') {
+        return Err(spec_error(&name, "pointer must be empty or start with '/'"));
+    }
+    Ok(CaptureSpec {
+        name,
+        pointer,
+        kind,
+    })
+}
+
+fn spec_error(name: &str, problem: &str) -> AppError {
+    AppError::invalid_input_with_suggestion(
+        format!("Capture '{name}' {problem}"),
+        "Write captures as {\"name\": \"/match/ref_id\"} or {\"name\": {\"pointer\": \"/count\", \"type\": \"integer\"}}",
+    )
+}
+
+fn validate_name(name: &str) -> Result<(), AppError> {
+    let mut chars = name.chars();
+    let valid_start = chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_');
+    let valid_rest = chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '_');
+    if valid_start && valid_rest && name.len() <= MAX_CAPTURE_NAME_CHARS {
+        return Ok(());
+    }
+    Err(AppError::invalid_input_with_suggestion(
+        format!(
+            "Invalid capture name {}",
+            crate::diagnostic::token_label(name)
+        ),
+        "Capture names start with a letter or '_' and contain only ASCII letters, digits, and '_'",
+    ))
+}
+
+/// True when any string inside `args` holds a `${name}` reference or a `$${`
+/// escape, so the args must be rebuilt from captured values before dispatch.
+pub(crate) fn is_template(args: &Value) -> bool {
+    match args {
+        Value::String(text) => text.contains(REFERENCE_OPEN),
+        Value::Array(items) => items.iter().any(is_template),
+        Value::Object(fields) => fields.values().any(is_template),
+        _ => false,
+    }
+}
+
+/// Rebuilds `args` with every `${name}` resolved. A string that is exactly
+/// one reference takes the captured value with its JSON type; references
+/// embedded in longer text are interpolated as text. `$${` yields a literal
+/// `${`. Object keys are never substituted.
+pub(crate) fn substitute(
+    args: &Value,
+    resolve: &mut dyn FnMut(&str) -> Result<Value, AppError>,
+) -> Result<Value, AppError> {
+    match args {
+        Value::String(text) if text.contains(REFERENCE_OPEN) => substitute_text(text, resolve),
+        Value::Array(items) => items
+            .iter()
+            .map(|item| substitute(item, resolve))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::Array),
+        Value::Object(fields) => fields
+            .iter()
+            .map(|(key, value)| Ok((key.clone(), substitute(value, resolve)?)))
+            .collect::<Result<Map<_, _>, AppError>>()
+            .map(Value::Object),
+        other => Ok(other.clone()),
+    }
+}
+
+enum Segment<'a> {
+    Literal(String),
+    Reference(&'a str),
+}
+
+fn substitute_text(
+    text: &str,
+    resolve: &mut dyn FnMut(&str) -> Result<Value, AppError>,
+) -> Result<Value, AppError> {
+    let segments = segments(text)?;
+    if let [Segment::Reference(name)] = segments.as_slice() {
+        return resolve(name);
+    }
+    let mut out = String::new();
+    for segment in segments {
+        match segment {
+            Segment::Literal(literal) => out.push_str(&literal),
+            Segment::Reference(name) => match resolve(name)? {
+                Value::String(value) => out.push_str(&value),
+                other => out.push_str(&other.to_string()),
+            },
+        }
+    }
+    Ok(Value::String(out))
+}
+
+fn segments(text: &str) -> Result<Vec<Segment<'_>>, AppError> {
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('$') {
+        literal.push_str(&rest[..start]);
+        let tail = &rest[start..];
+        if let Some(after) = tail.strip_prefix(ESCAPED_OPEN) {
+            literal.push_str(REFERENCE_OPEN);
+            rest = after;
+        } else if let Some(after) = tail.strip_prefix(REFERENCE_OPEN) {
+            let end = after.find('}').ok_or_else(|| {
+                AppError::invalid_input_with_suggestion(
+                    format!(
+                        "Unterminated capture reference in {}",
+                        crate::diagnostic::token_label(text)
+                    ),
+                    "Close references as ${name}, or write $${ for a literal '${'",
+                )
+            })?;
+            let name = &after[..end];
+            validate_name(name)?;
+            if !literal.is_empty() {
+                segments.push(Segment::Literal(std::mem::take(&mut literal)));
+            }
+            segments.push(Segment::Reference(name));
+            rest = &after[end + 1..];
+        } else {
+            literal.push('$');
+            rest = &tail[1..];
+        }
+    }
+    literal.push_str(rest);
+    if !literal.is_empty() || segments.is_empty() {
+        segments.push(Segment::Literal(literal));
+    }
+    Ok(segments)
+}
+
+pub(super) fn undeclared(name: &str) -> AppError {
+    AppError::invalid_input_with_suggestion(
+        format!("Capture reference '${{{name}}}' is not declared by an earlier entry"),
+        "Declare the capture on an earlier entry with \"capture\": {\"name\": \"/pointer\"}",
+    )
+}
+
+/// Reads every capture from a successful entry's `data`. Captures are
+/// all-or-nothing: the first missing, mistyped, or oversized value fails the
+/// set and nothing from this entry is stored.
+pub(crate) fn capture(
+    specs: &[CaptureSpec],
+    data: &Value,
+    values: &mut CapturedValues,
+) -> Result<Map<String, Value>, Value> {
+    let mut captured = Map::new();
+    for spec in specs {
+        let failure =
+            |reason: &str| json!({ "name": spec.name, "pointer": spec.pointer, "reason": reason });
+        let value = data
+            .pointer(&spec.pointer)
+            .ok_or_else(|| failure("missing"))?;
+        if !spec.kind.matches(value) {
+            let mut detail = failure("type_mismatch");
+            detail["expected"] = json!(spec.kind.label());
+            detail["actual"] = json!(json_type(value));
+            return Err(detail);
+        }
+        if serialized_size(value) > MAX_CAPTURE_VALUE_BYTES {
+            let mut detail = failure("too_large");
+            detail["max_bytes"] = json!(MAX_CAPTURE_VALUE_BYTES);
+            return Err(detail);
+        }
+        captured.insert(spec.name.clone(), value.clone());
+    }
+    for (name, value) in &captured {
+        values.insert(name.clone(), value.clone());
+    }
+    Ok(captured)
+}
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+#[path = "captures_tests.rs"]
+mod tests;
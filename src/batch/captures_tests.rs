@@ -0,0 +1,112 @@
+use super::*;
+
+fn specs(raw: Value) -> Result<Vec<CaptureSpec>, AppError> {
+    let Value::Object(fields) = raw else {
+        panic!("capture map must be an object");
+    };
+    parse_specs(fields.into_iter().collect())
+}
+
+fn resolve_from(values: &CapturedValues) -> impl FnMut(&str) -> Result<Value, AppError> + '_ {
+    |name| values.get(name).cloned().ok_or_else(|| undeclared(name))
+}
+
+#[test]
+fn bare_pointer_declares_a_string_capture() {
+    let parsed = specs(json!({ "target": "/match/ref_id" })).unwrap();
+
+    assert_eq!(parsed[0].name, "target");
+    assert_eq!(parsed[0].pointer, "/match/ref_id");
+    assert_eq!(parsed[0].kind, CaptureType::String);
+}
+
+#[test]
+fn typed_spec_rejects_bad_names_pointers_types_and_fields() {
+    let typed = specs(json!({ "total": { "pointer": "/count", "type": "integer" } })).unwrap();
+    assert_eq!(typed[0].kind, CaptureType::Integer);
+
+    for raw in [
+        json!({ "1st": "/ref" }),
+        json!({ "target": "match/ref_id" }),
+        json!({ "target": { "pointer": "/ref", "type": "array" } }),
+        json!({ "target": { "pointer": "/ref", "kind": "string" } }),
+        json!({ "target": 3 }),
+    ] {
+        let error = specs(raw).expect_err("invalid capture spec is rejected");
+        assert_eq!(error.code(), "INVALID_ARGS");
+    }
+}
+
+#[test]
+fn whole_string_reference_keeps_the_captured_json_type() {
+    let values = CapturedValues::from([("count".to_string(), json!(3))]);
+
+    let args = substitute(&json!({ "amount": "${count}" }), &mut resolve_from(&values)).unwrap();
+
+    assert_eq!(args, json!({ "amount": 3 }));
+}
+
+#[test]
+fn embedded_references_interpolate_as_text_and_escapes_stay_literal() {
+    let values = CapturedValues::from([
+        ("name".to_string(), json!("Save")),
+        ("count".to_string(), json!(2)),
+    ]);
+
+    let args = substitute(
+        &json!({ "text": "${name} x${count} costs $5, not $${name}", "keep": ["${name}"] }),
+        &mut resolve_from(&values),
+    )
+    .unwrap();
+
+    assert_eq!(args["text"], "Save x2 costs $5, not ${name}");
+    assert_eq!(args["keep"], json!(["Save"]));
+}
+
+#[test]
+fn malformed_and_unknown_references_fail() {
+    let values = CapturedValues::new();
+
+    for text in ["${open", "${bad-name}", "${missing}"] {
+        let error = substitute(&json!({ "text": text }), &mut resolve_from(&values))
+            .expect_err("reference must be well-formed and declared");
+        assert_eq!(error.code(), "INVALID_ARGS");
+    }
+    assert!(!is_template(&json!({ "text": "plain $5" })));
+    assert!(is_template(&json!({ "list": ["$${literal}"] })));
+}
+
+#[test]
+fn capture_is_all_or_nothing_and_reports_the_failing_pointer() {
+    let parsed = specs(json!({
+        "target": "/match/ref_id",
+        "total": { "pointer": "/match/name", "type": "integer" }
+    }))
+    .unwrap();
+    let data = json!({ "match": { "ref_id": "@s1:e4", "name": "Save" } });
+    let mut values = CapturedValues::new();
+
+    let failure = capture(&parsed, &data, &mut values).expect_err("name is not an integer");
+
+    assert_eq!(failure["name"], "total");
+    assert_eq!(failure["reason"], "type_mismatch");
+    assert_eq!(failure["actual"], "string");
+    assert!(values.is_empty());
+
+    let captured = capture(&parsed[..1], &data, &mut values).unwrap();
+    assert_eq!(captured["target"], "@s1:e4");
+    assert_eq!(values["target"], "@s1:e4");
+}
+
+#[test]
+fn missing_and_oversized_values_are_capture_failures() {
+    let parsed = specs(json!({ "text": "/text" })).unwrap();
+    let mut values = CapturedValues::new();
+
+    let missing = capture(&parsed, &json!({}), &mut values).unwrap_err();
+    assert_eq!(missing["reason"], "missing");
+
+    let huge = json!({ "text": "x".repeat(MAX_CAPTURE_VALUE_BYTES) });
+    let oversized = capture(&parsed, &huge, &mut values).unwrap_err();
+    assert_eq!(oversized["reason"], "too_large");
+}
@@ -0,0 +1,190 @@
+use std::time::Duration;
+
+use serde_json::{Value, json};
+
+use super::{
+    runner::{Flow, Frame, Runner},
+    steps::{CaptureTest, CompareOp, IfBlock, Predicate, RepeatBlock, RetryBlock},
+};
+
+impl Runner<'_> {
+    pub(super) fn run_if(&mut self, block: &IfBlock, more: bool) -> Flow {
+        let holds = match self.evaluate(&block.predicate, block.block) {
+            Ok(holds) => holds,
+            Err(flow) => {
+                return self.finish_block(flow, json!({ "block": block.block, "kind": "if" }));
+            }
+        };
+        let (branch, steps) = if holds {
+            ("then", &block.then)
+        } else {
+            ("else", &block.otherwise)
+        };
+        let flow = self.run_sequence(steps, more);
+        self.finish_block(
+            flow,
+            json!({ "block": block.block, "kind": "if", "branch": branch }),
+        )
+    }
+
+    /// Runs the body up to `max` times. With `until`, the predicate is
+    /// checked after each successful iteration and the block fails when it
+    /// never holds; without it the body simply runs `max` times.
+    pub(super) fn run_repeat(&mut self, block: &RepeatBlock) -> Flow {
+        let mut iterations = 0;
+        let mut until_met = false;
+        let mut flow = Flow::Ok;
+        for iteration in 0..block.max {
+            if let Err(halt) = self.enter(block.block, "repeat", iteration) {
+                return halt;
+            }
+            iterations = iteration + 1;
+            flow = self.run_sequence(&block.body, true);
+            if flow == Flow::Ok
+                && let Some(until) = &block.until
+            {
+                match self.evaluate(until, block.block) {
+                    Ok(holds) => until_met = holds,
+                    Err(stop) => flow = stop,
+                }
+            }
+            self.frames.pop();
+            if flow != Flow::Ok || until_met {
+                break;
+            }
+        }
+        let mut record =
+            json!({ "block": block.block, "kind": "repeat", "iterations": iterations });
+        if block.until.is_some() && flow == Flow::Ok {
+            record["until_met"] = json!(until_met);
+            if !until_met {
+                self.output.last_failure = json!({ "block": block.block });
+                flow = Flow::Failed;
+            }
+        }
+        self.finish_block(flow, record)
+    }
+
+    /// Re-runs the body from its first step after a failure, waiting
+    /// `backoff_ms` between attempts within the batch deadline. A failed
+    /// entry whose error disposition says a retry is unsafe ends the block.
+    pub(super) fn run_retry(&mut self, block: &RetryBlock) -> Flow {
+        let mut attempts = 0;
+        let mut flow = Flow::Failed;
+        let mut retry_unsafe = false;
+        for attempt in 0..block.attempts {
+            if attempt > 0 && block.backoff_ms > 0 {
+                std::thread::sleep(
+                    Duration::from_millis(block.backoff_ms).min(self.env.deadline.remaining()),
+                );
+            }
+            if let Err(halt) = self.enter(block.block, "retry", attempt) {
+                return halt;
+            }
+            attempts = attempt + 1;
+            self.retry_depth += 1;
+            flow = self.run_sequence(&block.body, true);
+            self.retry_depth -= 1;
+            self.frames.pop();
+            if flow != Flow::Failed {
+                break;
+            }
+            retry_unsafe = self.last_failure_is_retry_unsafe();
+            if retry_unsafe {
+                break;
+            }
+        }
+        let mut record = json!({ "block": block.block, "kind": "retry", "attempts": attempts });
+        if retry_unsafe {
+            record["retry_unsafe"] = json!(true);
+        }
+        self.finish_block(flow, record)
+    }
+
+    fn evaluate(&mut self, predicate: &Predicate, block: usize) -> Result<bool, Flow> {
+        match predicate {
+            Predicate::Not(inner) => self.evaluate(inner, block).map(|holds| !holds),
+            Predicate::Capture { name, test } => Ok(capture_holds(self.captured.get(name), test)),
+            Predicate::Is(command) => {
+                match self.run_command(command, None, &mut None, true) {
+                    Flow::Ok => {}
+                    other => return Err(other),
+                }
+                let entry = self.output.results.last_mut();
+                Ok(entry.is_some_and(|entry| {
+                    entry["predicate_of"] = json!(block);
+                    entry["data"]["result"] == true
+                }))
+            }
+        }
+    }
+
+    fn enter(&mut self, block: usize, kind: &'static str, iteration: u32) -> Result<(), Flow> {
+        let frames = self.frames_json();
+        let traced = self.env.context.trace_lazy(
+            "batch.iteration",
+            || json!({ "block": block, "kind": kind, "iteration": iteration, "frames": frames }),
+        );
+        if let Err(error) = traced {
+            return Err(self.trace_failed(&error));
+        }
+        self.frames.push(Frame {
+            block,
+            kind,
+            iteration,
+        });
+        Ok(())
+    }
+
+    /// Records the block in the batch `control` list and the trace, then
+    /// passes its flow through.
+    fn finish_block(&mut self, flow: Flow, mut record: Value) -> Flow {
+        record["outcome"] = json!(match flow {
+            Flow::Ok => "ok",
+            Flow::Failed => "failed",
+            Flow::Halt => "halted",
+        });
+        if !self.frames.is_empty() {
+            record["frames"] = self.frames_json();
+        }
+        let traced = self
+            .env
+            .context
+            .trace_lazy("batch.block", || record.clone());
+        self.output.control.push(record);
+        match traced {
+            Err(error) if flow != Flow::Halt => self.trace_failed(&error),
+            _ => flow,
+        }
+    }
+
+    fn last_failure_is_retry_unsafe(&self) -> bool {
+        self.output.last_failure.get("index").is_some()
+            && self
+                .output
+                .results
+                .last()
+                .is_some_and(|entry| entry["error"]["disposition"]["retry"] == "unsafe")
+    }
+}
+
+fn capture_holds(value: Option<&Value>, test: &CaptureTest) -> bool {
+    match (test, value) {
+        (CaptureTest::Exists(expected), value) => value.is_some() == *expected,
+        (CaptureTest::Equals(expected), Some(value)) => value == expected,
+        (CaptureTest::NotEquals(expected), value) => value != Some(expected),
+        (CaptureTest::Compare(op, bound), Some(value)) => {
+            value.as_f64().is_some_and(|value| match op {
+                CompareOp::Gt => value > *bound,
+                CompareOp::Gte => value >= *bound,
+                CompareOp::Lt => value < *bound,
+                CompareOp::Lte => value <= *bound,
+            })
+        }
+        (_, None) => false,
+    }
+}
+
+#[cfg(test)]
+#[path = "control_flow_tests.rs"]
+mod tests;
@@ -0,0 +1,280 @@
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use agent_desktop_core::{
+    AdapterError, ClipboardContent, ClipboardFormat, CommandContext, Deadline, ErrorCode,
+    InteractionLease, PermissionReport,
+};
+use serde_json::{Value, json};
+
+use crate::cli_args::batch::BatchArgs;
+
+struct ScriptedAdapter {
+    reads: AtomicUsize,
+    clears: AtomicUsize,
+    failing_clears: usize,
+    text: Mutex<Option<String>>,
+}
+
+impl agent_desktop_core::ObservationOps for ScriptedAdapter {}
+impl agent_desktop_core::ActionOps for ScriptedAdapter {}
+
+impl agent_desktop_core::InputOps for ScriptedAdapter {
+    fn clear_clipboard(&self, _lease: &InteractionLease) -> Result<(), AdapterError> {
+        let clears = self.clears.fetch_add(1, Ordering::SeqCst) + 1;
+        if clears <= self.failing_clears {
+            return Err(AdapterError::new(
+                ErrorCode::ActionFailed,
+                "pasteboard busy",
+            ));
+        }
+        Ok(())
+    }
+
+    fn get_clipboard_content(
+        &self,
+        _format: ClipboardFormat,
+        _deadline: Deadline,
+    ) -> Result<Option<ClipboardContent>, AdapterError> {
+        let reads = self.reads.fetch_add(1, Ordering::SeqCst) + 1;
+        Ok(Some(ClipboardContent::Text(reads.to_string())))
+    }
+
+    fn set_clipboard_content(
+        &self,
+        content: &ClipboardContent,
+        _lease: &InteractionLease,
+    ) -> Result<(), AdapterError> {
+        if let ClipboardContent::Text(text) = content {
+            *self.text.lock().unwrap() = Some(text.clone());
+        }
+        Ok(())
+    }
+}
+
+impl agent_desktop_core::SystemOps for ScriptedAdapter {
+    fn acquire_interaction_lease(
+        &self,
+        deadline: Deadline,
+    ) -> Result<InteractionLease, AdapterError> {
+        InteractionLease::guarded(deadline, ())
+    }
+}
+
+fn adapter(failing_clears: usize) -> ScriptedAdapter {
+    ScriptedAdapter {
+        reads: AtomicUsize::new(0),
+        clears: AtomicUsize::new(0),
+        failing_clears,
+        text: Mutex::new(None),
+    }
+}
+
+fn run(
+    adapter: &ScriptedAdapter,
+    commands: Value,
+    stop_on_error: bool,
+    context: &CommandContext,
+) -> Result<Value, agent_desktop_core::AppError> {
+    super::super::execute(
+        BatchArgs {
+            commands_json: commands.to_string(),
+            stop_on_error,
+            handlers_json: None,
+            timeout_ms: 60_000,
+        },
+        adapter,
+        &PermissionReport::default(),
+        context,
+    )
+}
+
+fn read(capture: &str) -> Value {
+    json!({ "command": "clipboard-get", "capture": { capture: "/text" } })
+}
+
+fn write(text: &str) -> Value {
+    json!({ "command": "clipboard-set", "args": { "text": text } })
+}
+
+#[test]
+fn if_block_runs_the_branch_chosen_by_a_captured_value() {
+    let adapter = adapter(0);
+    let output = run(
+        &adapter,
+        json!([
+            read("first"),
+            {
+                "if": { "capture": "first", "equals": "1" },
+                "then": [write("then ${first}")],
+                "else": [write("else")]
+            }
+        ]),
+        false,
+        &CommandContext::default(),
+    )
+    .unwrap();
+
+    assert_eq!(adapter.text.lock().unwrap().as_deref(), Some("then 1"));
+    assert_eq!(output["control"][0]["branch"], "then");
+    assert_eq!(output["control"][0]["outcome"], "ok");
+    assert_eq!(output["total_entries"], 3);
+    assert_eq!(output["not_started_entries"], 1);
+}
+
+#[test]
+fn repeat_until_stops_when_the_predicate_holds_and_tags_iterations() {
+    let adapter = adapter(0);
+    let output = run(
+        &adapter,
+        json!([{
+            "repeat": { "max": 5, "until": { "capture": "seen", "equals": "3" } },
+            "do": [read("seen")]
+        }]),
+        false,
+        &CommandContext::default(),
+    )
+    .unwrap();
+
+    assert_eq!(adapter.reads.load(Ordering::SeqCst), 3);
+    let results = output["results"].as_array().unwrap();
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[2]["index"], 0);
+    assert_eq!(results[2]["frames"][0]["kind"], "repeat");
+    assert_eq!(results[2]["frames"][0]["iteration"], 2);
+    assert_eq!(output["control"][0]["iterations"], 3);
+    assert_eq!(output["control"][0]["until_met"], true);
+    assert_eq!(output["completed_entries"], 3);
+}
+
+#[test]
+fn unmet_repeat_fails_the_block_and_honors_stop_on_error() {
+    let adapter = adapter(0);
+    let output = run(
+        &adapter,
+        json!([
+            {
+                "repeat": { "max": 2, "until": { "not": { "capture": "seen", "exists": true } } },
+                "do": [read("seen")]
+            },
+            write("after")
+        ]),
+        true,
+        &CommandContext::default(),
+    )
+    .unwrap();
+
+    assert_eq!(output["control"][0]["until_met"], false);
+    assert_eq!(output["control"][0]["outcome"], "failed");
+    assert_eq!(output["stopped"]["reason"], "stop_on_error");
+    assert_eq!(output["stopped"]["block"], 0);
+    assert!(adapter.text.lock().unwrap().is_none());
+}
+
+#[test]
+fn retry_reruns_the_body_until_an_attempt_succeeds() {
+    let adapter = adapter(2);
+    let output = run(
+        &adapter,
+        json!([
+            {
+                "retry": { "attempts": 3, "backoff_ms": 1 },
+                "do": [{ "command": "clipboard-clear" }, write("cleared")]
+            }
+        ]),
+        true,
+        &CommandContext::default(),
+    )
+    .unwrap();
+
+    assert_eq!(adapter.clears.load(Ordering::SeqCst), 3);
+    assert_eq!(adapter.text.lock().unwrap().as_deref(), Some("cleared"));
+    let results = output["results"].as_array().unwrap();
+    assert_eq!(results.len(), 4);
+    assert_eq!(results[0]["ok"], false);
+    assert_eq!(results[2]["frames"][0]["iteration"], 2);
+    assert_eq!(output["control"][0]["attempts"], 3);
+    assert_eq!(output["control"][0]["outcome"], "ok");
+    assert!(output.get("stopped").is_none());
+}
+
+#[test]
+fn exhausted_retry_fails_without_running_the_rest_of_its_body() {
+    let adapter = adapter(10);
+    let output = run(
+        &adapter,
+        json!([
+            {
+                "retry": { "attempts": 2 },
+                "do": [{ "command": "clipboard-clear" }, write("cleared")]
+            },
+            write("after")
+        ]),
+        false,
+        &CommandContext::default(),
+    )
+    .unwrap();
+
+    assert_eq!(adapter.clears.load(Ordering::SeqCst), 2);
+    assert_eq!(output["control"][0]["outcome"], "failed");
+    assert_eq!(adapter.text.lock().unwrap().as_deref(), Some("after"));
+}
+
+#[test]
+fn malformed_blocks_reject_the_batch_before_side_effects() {
+    for commands in [
+        json!([write("x"), { "repeat": { "max": 0 }, "do": [] }]),
+        json!([write("x"), { "retry": { "attempts": 2 }, "do": [], "else": [] }]),
+        json!([write("x"), { "if": { "capture": "never", "exists": true }, "then": [] }]),
+        json!([write("x"), { "if": { "capture": "x" }, "then": [] }]),
+        json!([write("x"), { "while": true }]),
+        json!([write("x"), { "repeat": { "max": 2 } }]),
+    ] {
+        let adapter = adapter(0);
+        let error = run(&adapter, commands, false, &CommandContext::default())
+            .expect_err("malformed control block is rejected during preparation");
+        assert_eq!(error.code(), "INVALID_ARGS");
+        assert!(adapter.text.lock().unwrap().is_none());
+    }
+}
+
+#[test]
+fn trace_records_iterations_and_entry_frames() {
+    let path = std::env::temp_dir().join(format!(
+        "agent-desktop-batch-control-{}-{}.jsonl",
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0)
+    ));
+    let context = CommandContext::new(None, Some(path.clone()), false).unwrap();
+    let adapter = adapter(0);
+    run(
+        &adapter,
+        json!([{ "repeat": { "max": 2 }, "do": [read("seen")] }]),
+        false,
+        &context,
+    )
+    .unwrap();
+
+    let events: Vec<Value> = std::fs::read_to_string(&path)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let _ = std::fs::remove_file(&path);
+    let named = |name: &str| {
+        events
+            .iter()
+            .filter(|event| event["event"] == name)
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+    let iterations = named("batch.iteration");
+    assert_eq!(iterations.len(), 2);
+    assert_eq!(iterations[1]["iteration"], 1);
+    let entries = named("batch.entry");
+    assert_eq!(entries[1]["frames"][0]["iteration"], 1);
+    assert_eq!(named("batch.block")[0]["iterations"], 2);
+}
@@ -4,6 +4,7 @@ use std::time::{Duration, Instant};
 use agent_desktop_core::{CommandContext, InteractionLease, PermissionReport, PermissionState};
 use serde_json::json;
 
+use super::super::result_entry::bounded_entry;
 use super::*;
 
 struct CountingAdapter {
@@ -33,6 +34,7 @@ fn args(commands: Value, timeout_ms: u64) -> BatchArgs {
     BatchArgs {
         commands_json: commands.to_string(),
         stop_on_error: false,
+        handlers_json: None,
         timeout_ms,
     }
 }
@@ -166,6 +168,7 @@ fn entry_count_and_output_are_bounded() {
         BatchArgs {
             commands_json: " ".repeat(MAX_BATCH_JSON_BYTES + 1),
             stop_on_error: false,
+            handlers_json: None,
             timeout_ms: 60_000,
         },
         &adapter,
@@ -253,3 +256,123 @@ fn inherited_batch_deadline_caps_sleep_without_variant_rewriting() {
     assert!(started.elapsed() < Duration::from_millis(500));
     assert_eq!(output["results"][0]["error"]["code"], "TIMEOUT");
 }
+
+struct ClipboardAdapter {
+    text: std::sync::Mutex<Option<String>>,
+}
+
+impl agent_desktop_core::ObservationOps for ClipboardAdapter {}
+impl agent_desktop_core::ActionOps for ClipboardAdapter {}
+
+impl agent_desktop_core::InputOps for ClipboardAdapter {
+    fn get_clipboard_content(
+        &self,
+        _format: agent_desktop_core::ClipboardFormat,
+        _deadline: agent_desktop_core::Deadline,
+    ) -> Result<Option<agent_desktop_core::ClipboardContent>, AdapterError> {
+        let text = self.text.lock().unwrap().clone();
+        Ok(text.map(agent_desktop_core::ClipboardContent::Text))
+    }
+
+    fn set_clipboard_content(
+        &self,
+        content: &agent_desktop_core::ClipboardContent,
+        _lease: &InteractionLease,
+    ) -> Result<(), AdapterError> {
+        if let agent_desktop_core::ClipboardContent::Text(text) = content {
+            *self.text.lock().unwrap() = Some(text.clone());
+        }
+        Ok(())
+    }
+}
+
+impl agent_desktop_core::SystemOps for ClipboardAdapter {
+    fn acquire_interaction_lease(
+        &self,
+        deadline: agent_desktop_core::Deadline,
+    ) -> Result<agent_desktop_core::InteractionLease, AdapterError> {
+        agent_desktop_core::InteractionLease::guarded(deadline, ())
+    }
+}
+
+fn clipboard(text: Option<&str>) -> ClipboardAdapter {
+    ClipboardAdapter {
+        text: std::sync::Mutex::new(text.map(str::to_string)),
+    }
+}
+
+#[test]
+fn captured_value_is_substituted_into_a_later_entry() {
+    let adapter = clipboard(Some("Save"));
+    let output = execute(
+        args(
+            json!([
+                {"command": "clipboard-get", "capture": {"label": "/text"}},
+                {"command": "clipboard-set", "args": {"text": "Clicked ${label}"}},
+                {"command": "clipboard-get"}
+            ]),
+            60_000,
+        ),
+        &adapter,
+        &PermissionReport::default(),
+        &CommandContext::default(),
+    )
+    .expect("batch with captures executes");
+
+    assert_eq!(output["results"][0]["captures"]["label"], "Save");
+    assert_eq!(output["results"][2]["data"]["text"], "Clicked Save");
+    assert_eq!(output["captures"]["label"], "Save");
+}
+
+#[test]
+fn undeclared_or_mistyped_references_reject_the_batch_before_side_effects() {
+    for commands in [
+        json!([
+            {"command": "clipboard-set", "args": {"text": "first"}},
+            {"command": "clipboard-set", "args": {"text": "${later}"}},
+            {"command": "clipboard-get", "capture": {"later": "/text"}}
+        ]),
+        json!([
+            {"command": "clipboard-get", "capture": {"flag": {"pointer": "/found", "type": "boolean"}}},
+            {"command": "clipboard-set", "args": {"text": "${flag}"}}
+        ]),
+    ] {
+        let adapter = clipboard(None);
+        let error = execute(
+            args(commands, 60_000),
+            &adapter,
+            &PermissionReport::default(),
+            &CommandContext::default(),
+        )
+        .expect_err("references are validated during preparation");
+        assert_eq!(error.code(), "INVALID_ARGS");
+        assert!(adapter.text.lock().unwrap().is_none());
+    }
+}
+
+#[test]
+fn failed_capture_leaves_dependent_entries_not_started() {
+    let adapter = clipboard(None);
+    let output = execute(
+        args(
+            json!([
+                {"command": "clipboard-get", "capture": {"label": "/text"}},
+                {"command": "clipboard-set", "args": {"text": "${label}"}},
+                {"command": "clipboard-set", "args": {"text": "independent"}}
+            ]),
+            60_000,
+        ),
+        &adapter,
+        &PermissionReport::default(),
+        &CommandContext::default(),
+    )
+    .expect("capture failure remains a structured batch result");
+
+    assert_eq!(output["results"][0]["capture_error"]["reason"], "missing");
+    assert_eq!(output["results"][1]["execution"], "not_started");
+    assert_eq!(
+        output["results"][1]["not_started_reason"],
+        "capture_unavailable"
+    );
+    assert_eq!(adapter.text.lock().unwrap().as_deref(), Some("independent"));
+}
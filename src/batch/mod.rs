@@ -9,17 +9,28 @@ use serde_json::{Map, Value};
 use crate::{
     cli::Commands,
     cli_args::{
+        alias::{AliasAction, AliasArgs, AliasRmArgs, AliasSetArgs},
         batch::BatchArgs,
-        session::{SessionAction, SessionArgs, SessionEndArgs, SessionGcArgs, SessionStartArgs},
+        session::{
+            SessionAction, SessionArgs, SessionEndArgs, SessionGcArgs, SessionQuotaArgs,
+            SessionStartArgs,
+        },
         skills::{SkillsAction, SkillsArgs, SkillsGetArgs},
         trace::{TraceAction, TraceArgs, TraceExportArgs, TraceShowArgs},
     },
 };
 
 mod bounded_json;
+pub(crate) mod captures;
+mod control_flow;
 mod execution;
 mod preparation;
 mod result_entry;
+mod runner;
+mod steps;
+
+pub(crate) use bounded_json::serialized_fits;
+pub(crate) use captures::substitute;
 
 #[cfg(test)]
 #[path = "baseline_tests.rs"]
@@ -40,8 +51,13 @@ pub(crate) fn parse_command(item: BatchCommand) -> Result<Commands, AppError> {
         "snapshot" => decode(command, item.args).map(Commands::Snapshot),
         "find" => decode(command, item.args).map(Commands::Find),
         "screenshot" => decode(command, item.args).map(Commands::Screenshot),
+        "screenshot-compare" => decode(command, item.args).map(Commands::ScreenshotCompare),
         "get" => decode(command, item.args).map(Commands::Get),
         "is" => decode(command, item.args).map(Commands::Is),
+        "expect" => decode(command, item.args).map(Commands::Expect),
+        "audit-a11y" => decode(command, item.args).map(Commands::AuditA11y),
+        "extract-table" => decode(command, item.args).map(Commands::ExtractTable),
+        "read-text" => decode(command, item.args).map(Commands::ReadText),
         "click" => decode(command, item.args).map(Commands::Click),
         "double-click" => decode(command, item.args).map(Commands::DoubleClick),
         "triple-click" => decode(command, item.args).map(Commands::TripleClick),
@@ -96,10 +112,15 @@ pub(crate) fn parse_command(item: BatchCommand) -> Result<Commands, AppError> {
         "skills" => parse_skills(item.args).map(Commands::Skills),
         "session" => parse_session(item.args).map(Commands::Session),
         "trace" => parse_trace(item.args).map(Commands::Trace),
+        "alias" => parse_alias(item.args).map(Commands::Alias),
         "batch" => Err(AppError::invalid_input_with_suggestion(
             "Batch commands cannot be nested",
             "Flatten nested batches into one top-level batch array",
         )),
+        "run" => Err(AppError::invalid_input_with_suggestion(
+            "Scenario runs cannot be nested inside a batch or another scenario",
+            "Inline the other scenario's steps instead",
+        )),
         other => Err(AppError::invalid_input(format!(
             "Unknown batch command {}",
             crate::diagnostic::token_label(other)
@@ -202,6 +223,10 @@ struct BatchSessionStartArgs {
     no_trace: bool,
     #[serde(default)]
     screenshots: bool,
+    max_trace_bytes: Option<u64>,
+    max_screenshots: Option<u32>,
+    max_screenshot_bytes: Option<u64>,
+    max_age: Option<u64>,
 }
 
 #[derive(Deserialize)]
@@ -232,6 +257,12 @@ fn parse_session(args: Value) -> Result<SessionArgs, AppError> {
                 name: args.name,
                 no_trace: args.no_trace,
                 screenshots: args.screenshots,
+                quota: SessionQuotaArgs {
+                    max_trace_bytes: args.max_trace_bytes,
+                    max_screenshots: args.max_screenshots,
+                    max_screenshot_bytes: args.max_screenshot_bytes,
+                    max_age_secs: args.max_age,
+                },
             })
         }
         Some("end") => {
@@ -245,6 +276,12 @@ fn parse_session(args: Value) -> Result<SessionArgs, AppError> {
                 ended: args.ended,
             })
         }
+        Some(action @ ("export" | "import" | "quota")) => {
+            return Err(AppError::invalid_input_with_suggestion(
+                format!("Session {action} cannot run inside a batch or scenario"),
+                format!("Run `session {action}` as a top-level command"),
+            ));
+        }
         Some(other) => {
             return Err(AppError::invalid_input(format!(
                 "Unknown session action {}",
@@ -279,6 +316,7 @@ fn parse_trace(args: Value) -> Result<TraceArgs, AppError> {
                     .limit
                     .unwrap_or(agent_desktop_core::commands::trace::TRACE_SHOW_DEFAULT_LIMIT),
                 event: args.event,
+                follow: false,
             })
         }
         "export" => {
@@ -294,6 +332,12 @@ fn parse_trace(args: Value) -> Result<TraceArgs, AppError> {
                 out: args.out,
             })
         }
+        action @ ("stats" | "diff" | "replay" | "to-script") => {
+            return Err(AppError::invalid_input_with_suggestion(
+                format!("Trace {action} cannot run inside a batch or scenario"),
+                format!("Run `trace {action}` as a top-level command"),
+            ));
+        }
         other => {
             return Err(AppError::invalid_input(format!(
                 "Unknown trace action {}",
@@ -306,3 +350,39 @@ fn parse_trace(args: Value) -> Result<TraceArgs, AppError> {
 
 #[cfg(test)]
 mod tests;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BatchAliasArgs {
+    action: String,
+    name: Option<String>,
+    ref_id: Option<String>,
+    #[serde(rename = "snapshot", alias = "snapshot_id")]
+    snapshot: Option<String>,
+}
+
+fn parse_alias(args: Value) -> Result<AliasArgs, AppError> {
+    let args: BatchAliasArgs = decode("alias", args)?;
+    let action = match (args.action.as_str(), args.name, args.ref_id, args.snapshot) {
+        ("set", Some(name), Some(ref_id), snapshot) => AliasAction::Set(AliasSetArgs {
+            name,
+            ref_id,
+            snapshot,
+        }),
+        ("list", None, None, None) => AliasAction::List,
+        ("rm", Some(name), None, None) => AliasAction::Rm(AliasRmArgs { name }),
+        ("set" | "list" | "rm", ..) => {
+            return Err(AppError::invalid_input(format!(
+                "Batch alias {} expects set {{name, ref_id, snapshot?}}, list {{}}, or rm {{name}}",
+                args.action
+            )));
+        }
+        (other, ..) => {
+            return Err(AppError::invalid_input(format!(
+                "Unknown alias action {}",
+                crate::diagnostic::token_label(other)
+            )));
+        }
+    };
+    Ok(AliasArgs { action })
+}
@@ -1,32 +1,57 @@
 use agent_desktop_core::{
-    AdapterError, AppError, DeliverySemantics, ErrorCode, PermissionReport,
+    AdapterError, AppError, DeliverySemantics, ErrorCode, PermissionReport, SignalFilter,
     commands::batch::BatchCommand, context::CommandContext,
 };
 use serde_json::{Value, json};
 
 use crate::cli::Commands;
 
+use super::captures::{self, CaptureSpec, CapturedValues, DeclaredCaptures};
+use super::steps::{ProgramBuilder, Step};
+
 pub(super) const MAX_BATCH_JSON_BYTES: usize = 1024 * 1024;
 pub(super) const MAX_BATCH_ENTRIES: usize = 64;
 
+/// Args of a prepared entry. `Template` args still hold `${name}`
+/// references and were validated with typed placeholders, except fields fed
+/// string text that no placeholder can stand for; they are rebuilt by
+/// [`bind_args`] and decoded again before every dispatch.
+pub(super) enum EntryArgs {
+    Fixed(Value),
+    Template(Value),
+}
+
 pub(super) struct PreparedCommand {
+    /// Static position in preorder over every command of the batch program,
+    /// including `is` predicates; repeated executions share it.
+    pub index: usize,
     pub name: String,
-    pub command: Commands,
+    pub args: EntryArgs,
+    pub session: Option<String>,
     pub context: CommandContext,
+    pub captures: Vec<CaptureSpec>,
+    pub signal_filter: Option<SignalFilter>,
+}
+
+pub(super) struct Program {
+    pub steps: Vec<Step>,
+    pub commands: usize,
+    pub blocks: usize,
 }
 
 pub(super) fn prepare(
     input: &str,
     permission_report: &PermissionReport,
     context: &CommandContext,
-) -> Result<Vec<PreparedCommand>, AppError> {
+) -> Result<Program, AppError> {
     if input.len() > MAX_BATCH_JSON_BYTES {
         return Err(limit_error(
             "Batch JSON exceeds the input limit",
             json!({ "actual_bytes": input.len(), "max_bytes": MAX_BATCH_JSON_BYTES }),
         ));
     }
-    let items = agent_desktop_core::commands::batch::parse_commands(input)?;
+    let items: Vec<Value> = serde_json::from_str(input)
+        .map_err(|e| AppError::invalid_input(format!("Invalid batch JSON: {e}")))?;
     if items.len() > MAX_BATCH_ENTRIES {
         return Err(limit_error(
             "Batch contains too many entries",
@@ -34,41 +59,191 @@ pub(super) fn prepare(
         ));
     }
 
-    let parsed = items
-        .into_iter()
-        .enumerate()
-        .map(|(index, item)| parse_one(index, item, permission_report))
-        .collect::<Result<Vec<_>, _>>()?;
-    parsed
-        .into_iter()
-        .map(|(index, name, command, session)| {
-            let item_context = context
-                .for_batch_item(session)
-                .map_err(|error| located_error(index, &name, error))?;
-            Ok(PreparedCommand {
-                name,
-                command,
-                context: item_context,
-            })
-        })
-        .collect()
+    let mut program = ProgramBuilder::new(permission_report).build(items)?;
+    attach_contexts(&mut program.steps, context)?;
+    Ok(program)
 }
 
-fn parse_one(
+fn attach_contexts(steps: &mut [Step], context: &CommandContext) -> Result<(), AppError> {
+    for step in steps {
+        for command in step.commands_mut() {
+            command.context = context
+                .for_batch_item(command.session.take())
+                .map_err(|error| located_error(command.index, &command.name, error))?;
+        }
+        for body in step.bodies_mut() {
+            attach_contexts(body, context)?;
+        }
+    }
+    Ok(())
+}
+
+/// Decodes and policy-checks one entry before anything runs, declaring its
+/// captures for the entries after it in preorder.
+pub(super) fn prepare_command(
     index: usize,
-    item: BatchCommand,
+    mut item: BatchCommand,
     permission_report: &PermissionReport,
-) -> Result<(usize, String, Commands, Option<String>), AppError> {
+    declared: &mut DeclaredCaptures,
+) -> Result<PreparedCommand, AppError> {
     let name = item.command.clone();
     let session = item.session.clone();
+    let locate = |error| located_error(index, &name, error);
     if let Some(session) = session.as_deref() {
-        agent_desktop_core::context::validate_session_id(session)
-            .map_err(|error| located_error(index, &name, error))?;
+        agent_desktop_core::context::validate_session_id(session).map_err(locate)?;
+    }
+    let captures = captures::parse_specs(std::mem::take(&mut item.capture)).map_err(locate)?;
+    let raw_args = item.args.clone();
+    let templated = captures::is_template(&raw_args);
+    if templated {
+        item.args = with_placeholders(&raw_args, declared).map_err(locate)?;
+    }
+    let command = match super::parse_command(item) {
+        Ok(command) => Some(command),
+        Err(error) if templated => {
+            decode_without_free_fields(&name, &raw_args, declared).ok_or_else(|| locate(error))?
+        }
+        Err(error) => return Err(locate(error)),
+    };
+    if let Some(command) = &command {
+        crate::command_policy::preflight(command, permission_report).map_err(locate)?;
+    }
+    for spec in &captures {
+        declared.insert(spec.name.clone(), spec.kind);
     }
-    let command = super::parse_command(item).map_err(|error| located_error(index, &name, error))?;
-    crate::command_policy::preflight(&command, permission_report)
-        .map_err(|error| located_error(index, &name, error))?;
-    Ok((index, name, command, session))
+    Ok(PreparedCommand {
+        index,
+        signal_filter: command
+            .as_ref()
+            .and_then(|command| event_filter(command, templated)),
+        name,
+        args: if templated {
+            EntryArgs::Template(raw_args)
+        } else {
+            EntryArgs::Fixed(raw_args)
+        },
+        session,
+        context: CommandContext::default(),
+        captures,
+    })
+}
+
+fn with_placeholders(args: &Value, declared: &DeclaredCaptures) -> Result<Value, AppError> {
+    captures::substitute(args, &mut |reference| {
+        declared
+            .get(reference)
+            .map(|kind| kind.placeholder())
+            .ok_or_else(|| captures::undeclared(reference))
+    })
+}
+
+/// Retries a templated entry whose placeholder args did not decode, leaving
+/// out its free fields: those that take string text from a capture. A
+/// string can hold any enum variant or format the placeholder does not, so
+/// such a field is only checked once bound. `Some(None)` means a free field
+/// the command requires is left out, so the rest cannot be decoded yet; the
+/// bound args are decoded and policy-checked before dispatch either way.
+/// `None` means a free field is not one of the command's, or the failure
+/// lies in the other fields, and the original error stands.
+fn decode_without_free_fields(
+    name: &str,
+    template: &Value,
+    declared: &DeclaredCaptures,
+) -> Option<Option<Commands>> {
+    let Value::Object(fields) = template else {
+        return None;
+    };
+    let mut kept = serde_json::Map::new();
+    let mut free = Vec::new();
+    for (key, value) in fields {
+        let mut takes_string = false;
+        let bound = captures::substitute(value, &mut |reference| {
+            let kind = declared
+                .get(reference)
+                .copied()
+                .ok_or_else(|| captures::undeclared(reference))?;
+            takes_string |= kind == captures::CaptureType::String;
+            Ok(kind.placeholder())
+        })
+        .ok()?;
+        if captures::is_template(value) && (takes_string || bound.is_string()) {
+            free.push(key.as_str());
+        } else {
+            kept.insert(key.clone(), bound);
+        }
+    }
+    if free.is_empty() {
+        return None;
+    }
+    let cli = <crate::cli::Cli as clap::CommandFactory>::command();
+    let command = cli.find_subcommand(name)?;
+    let mut requires_free = false;
+    for key in free {
+        requires_free |= field_required(command, key)?;
+    }
+    let item = BatchCommand {
+        command: name.to_string(),
+        session: None,
+        args: Value::Object(kept),
+        capture: Default::default(),
+    };
+    match super::parse_command(item) {
+        Ok(command) => Some(Some(command)),
+        Err(_) => requires_free.then_some(None),
+    }
+}
+
+/// Whether batch arg `key` is required by the CLI `command`, or `None` when
+/// the command has no such arg. Batch args carry the CLI's field names, or
+/// the long flag where the two differ.
+fn field_required(command: &clap::Command, key: &str) -> Option<bool> {
+    command
+        .get_arguments()
+        .find(|arg| arg.get_id() == key || arg.get_long() == Some(key))
+        .map(clap::Arg::is_required_set)
+}
+
+/// A templated wait may take its app from a capture that does not exist
+/// yet when its baseline is taken, so its baseline covers every app.
+fn event_filter(command: &Commands, templated: bool) -> Option<SignalFilter> {
+    match command {
+        Commands::Wait(args) if args.event.event.is_some() => Some(SignalFilter {
+            app: args.app.clone().filter(|_| !templated),
+            process: None,
+        }),
+        _ => None,
+    }
+}
+
+/// Substitutes captured values into a templated entry's args. Fails when
+/// a referenced capture was never stored.
+pub(super) fn bind_args(template: &Value, values: &CapturedValues) -> Result<Value, AppError> {
+    captures::substitute(template, &mut |reference| {
+        values.get(reference).cloned().ok_or_else(|| {
+            AppError::invalid_input_with_suggestion(
+                format!("Capture '{reference}' has no value because its entry did not capture it"),
+                "Check the producing entry's error or capture_error",
+            )
+        })
+    })
+}
+
+/// Decodes and policy-checks an entry's concrete args for one execution.
+/// Fixed args cannot fail here; they passed the same checks in [`prepare`].
+pub(super) fn decode_bound(
+    name: &str,
+    args: Value,
+    permission_report: &PermissionReport,
+) -> Result<Commands, AppError> {
+    let item = BatchCommand {
+        command: name.to_string(),
+        session: None,
+        args,
+        capture: Default::default(),
+    };
+    let command = super::parse_command(item)?;
+    crate::command_policy::preflight(&command, permission_report)?;
+    Ok(command)
 }
 
 fn located_error(index: usize, command: &str, error: AppError) -> AppError {
@@ -94,10 +269,14 @@ fn located_error(index: usize, command: &str, error: AppError) -> AppError {
     }
 }
 
-fn limit_error(message: &str, details: Value) -> AppError {
+pub(super) fn limit_error(message: &str, details: Value) -> AppError {
     AdapterError::new(ErrorCode::InvalidArgs, message)
         .with_suggestion("Split the batch or narrow commands that return large payloads")
         .with_details(details)
         .with_disposition(DeliverySemantics::not_delivered())
         .into()
 }
+
+#[cfg(test)]
+#[path = "preparation_tests.rs"]
+mod tests;
@@ -0,0 +1,89 @@
+use serde_json::json;
+
+use super::captures::CaptureType;
+use super::*;
+
+fn prepare_with(
+    declared: &[(&str, CaptureType)],
+    item: Value,
+) -> Result<PreparedCommand, AppError> {
+    let mut declared = declared
+        .iter()
+        .map(|(name, kind)| (name.to_string(), *kind))
+        .collect();
+    prepare_command(
+        0,
+        serde_json::from_value(item).unwrap(),
+        &PermissionReport::default(),
+        &mut declared,
+    )
+}
+
+#[test]
+fn string_captures_in_enum_and_number_fields_are_checked_once_bound() {
+    for args in [
+        json!({"format": "${format}"}),
+        json!({"ref_id": "@e1", "direction": "down", "amount": "${amount}"}),
+    ] {
+        let command = if args.get("format").is_some() {
+            "snapshot"
+        } else {
+            "scroll"
+        };
+        let prepared = prepare_with(
+            &[
+                ("format", CaptureType::String),
+                ("amount", CaptureType::String),
+            ],
+            json!({"command": command, "args": args}),
+        )
+        .expect("a string capture may hold any variant");
+        assert!(matches!(prepared.args, EntryArgs::Template(_)));
+    }
+}
+
+#[test]
+fn a_required_field_fed_a_string_capture_is_checked_once_bound() {
+    let prepared = prepare_with(
+        &[("width", CaptureType::String)],
+        json!({"command": "resize-window", "args": {"width": "${width}", "height": 300}}),
+    )
+    .expect("the width is only known once bound");
+
+    assert!(matches!(prepared.args, EntryArgs::Template(_)));
+}
+
+#[test]
+fn mistyped_captures_and_other_field_errors_still_fail_preparation() {
+    for (declared, item) in [
+        (
+            ("flag", CaptureType::Boolean),
+            json!({"command": "clipboard-set", "args": {"text": "${flag}"}}),
+        ),
+        (
+            ("format", CaptureType::String),
+            json!({"command": "snapshot", "args": {"format": "${format}", "surface": "nowhere"}}),
+        ),
+        (
+            ("format", CaptureType::String),
+            json!({"command": "snapshot", "args": {"shape": "${format}"}}),
+        ),
+    ] {
+        let error = prepare_with(&[declared], item)
+            .err()
+            .expect("entry is rejected");
+        assert_eq!(error.code(), "INVALID_ARGS");
+    }
+}
+
+#[test]
+fn a_bound_value_that_does_not_fit_is_rejected_before_dispatch() {
+    let template = json!({"format": "${format}"});
+    let values = [("format".to_string(), json!("sideways"))]
+        .into_iter()
+        .collect();
+    let bound = bind_args(&template, &values).unwrap();
+
+    let error = decode_bound("snapshot", bound, &PermissionReport::default()).unwrap_err();
+    assert_eq!(error.code(), "INVALID_ARGS");
+}
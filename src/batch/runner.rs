@@ -0,0 +1,345 @@
+use agent_desktop_core::{
+    AdapterError, AppError, Deadline, DeliverySemantics, PermissionReport, PlatformAdapter,
+    SignalBaseline,
+    context::CommandContext,
+    output::ErrorPayload,
+    surface_handler::{self, SurfaceHandler},
+};
+use serde_json::{Value, json};
+
+use super::{
+    bounded_json::serialized_size,
+    captures::{self, CapturedValues},
+    preparation::{EntryArgs, PreparedCommand, bind_args, decode_bound},
+    result_entry::{bounded_entry, not_started_entry},
+    steps::Step,
+};
+
+/// Upper bound on command executions in one batch, counting every loop
+/// iteration and retry attempt, independent of the wall-clock budget.
+pub(super) const MAX_BATCH_EXECUTIONS: usize = 512;
+
+type Baseline = Result<SignalBaseline, AdapterError>;
+
+/// Outcome of a step or sequence. `Failed` lets the enclosing sequence or
+/// retry block decide; `Halt` ends the whole batch with `stopped` recorded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Flow {
+    Ok,
+    Failed,
+    Halt,
+}
+
+pub(super) struct RunEnv<'a> {
+    pub adapter: &'a dyn PlatformAdapter,
+    pub permission_report: &'a PermissionReport,
+    pub context: &'a CommandContext,
+    pub deadline: Deadline,
+    pub timeout_ms: u64,
+    pub stop_on_error: bool,
+    pub handlers: &'a [SurfaceHandler],
+}
+
+#[derive(Default)]
+pub(super) struct RunOutput {
+    pub results: Vec<Value>,
+    pub results_bytes: usize,
+    pub control: Vec<Value>,
+    pub completed: usize,
+    pub stopped: Option<Value>,
+    pub last_failure: Value,
+    pub handled_surfaces: usize,
+}
+
+/// One enclosing loop of the step being run: which block, and which
+/// iteration or attempt of it (zero-based).
+#[derive(Clone, Copy)]
+pub(super) struct Frame {
+    pub block: usize,
+    pub kind: &'static str,
+    pub iteration: u32,
+}
+
+pub(super) struct Runner<'a> {
+    pub(super) env: RunEnv<'a>,
+    pub(super) output: RunOutput,
+    pub(super) captured: CapturedValues,
+    pub(super) ran: Vec<bool>,
+    pub(super) frames: Vec<Frame>,
+    pub(super) retry_depth: usize,
+    executions: usize,
+}
+
+impl<'a> Runner<'a> {
+    pub(super) fn new(env: RunEnv<'a>, commands: usize) -> Self {
+        Self {
+            env,
+            output: RunOutput::default(),
+            captured: CapturedValues::new(),
+            ran: vec![false; commands],
+            frames: Vec::new(),
+            retry_depth: 0,
+            executions: 0,
+        }
+    }
+
+    /// Runs `steps` in order. Inside a retry body the first failure returns
+    /// at once so the attempt can restart; elsewhere `--stop-on-error` halts
+    /// the batch and otherwise later steps still run. `more_after` says
+    /// whether anything can run after this sequence ends.
+    pub(super) fn run_sequence(&mut self, steps: &[Step], more_after: bool) -> Flow {
+        let mut pending: Option<Baseline> = None;
+        let mut failed = false;
+        for (position, step) in steps.iter().enumerate() {
+            let more = position + 1 < steps.len() || more_after;
+            let flow = match step {
+                Step::Command(command) => {
+                    self.run_command(command, steps.get(position + 1), &mut pending, more)
+                }
+                Step::If(block) => self.run_if(block, more),
+                Step::Repeat(block) => self.run_repeat(block),
+                Step::Retry(block) => self.run_retry(block),
+            };
+            match flow {
+                Flow::Ok => {}
+                Flow::Halt => return Flow::Halt,
+                Flow::Failed if self.retry_depth > 0 => return Flow::Failed,
+                Flow::Failed if self.env.stop_on_error => {
+                    let mut stopped = json!({ "reason": "stop_on_error" });
+                    merge_location(&mut stopped, &self.output.last_failure);
+                    self.output.stopped = Some(stopped);
+                    return Flow::Halt;
+                }
+                Flow::Failed => failed = true,
+            }
+        }
+        if failed { Flow::Failed } else { Flow::Ok }
+    }
+
+    pub(super) fn run_command(
+        &mut self,
+        command: &PreparedCommand,
+        next: Option<&Step>,
+        pending: &mut Option<Baseline>,
+        more: bool,
+    ) -> Flow {
+        let current_baseline = pending.take();
+        let (index, name) = (command.index, command.name.as_str());
+        let deadline = self.env.deadline;
+        if deadline.is_expired() {
+            let error = batch_timeout(index, name, self.env.timeout_ms);
+            self.push_entry(not_started_entry(index, name, "deadline", error));
+            return self.halt(json!({ "reason": "deadline", "index": index }));
+        }
+        if self.executions >= MAX_BATCH_EXECUTIONS {
+            return self.halt(json!({
+                "reason": "execution_limit",
+                "index": index,
+                "max_executions": MAX_BATCH_EXECUTIONS,
+            }));
+        }
+        self.executions += 1;
+
+        if let Some(Step::Command(wait)) = next
+            && let Some(filter) = &wait.signal_filter
+        {
+            match self.env.adapter.capture_signal_baseline(filter, deadline) {
+                Ok(baseline) => *pending = Some(Ok(baseline)),
+                Err(error) => {
+                    let error = baseline_error(index, name, wait.index, &wait.name, error);
+                    self.push_entry(not_started_entry(
+                        index,
+                        name,
+                        "pre_action_baseline_failed",
+                        error,
+                    ));
+                    return self.halt(json!({
+                        "reason": "pre_action_baseline_failed",
+                        "blocked_index": index,
+                        "blocked_command": name,
+                        "wait_index": wait.index,
+                        "wait_command": wait.name,
+                    }));
+                }
+            }
+        }
+
+        let frames = self.frames_json();
+        let traced = command.context.trace_lazy(
+            "batch.entry",
+            || json!({ "index": index, "command": name, "frames": frames }),
+        );
+        if let Err(error) = traced {
+            return self.trace_failed(&error);
+        }
+
+        let bound = match &command.args {
+            EntryArgs::Fixed(args) => Ok(args.clone()),
+            EntryArgs::Template(template) => {
+                bind_args(template, &self.captured).map_err(|error| ("capture_unavailable", error))
+            }
+        }
+        .and_then(|args| {
+            decode_bound(name, args, self.env.permission_report)
+                .map_err(|error| ("substitution_invalid", error))
+        });
+        let dispatched = match bound {
+            Ok(dispatched) => dispatched,
+            Err((reason, error)) => {
+                self.push_entry(not_started_entry(index, name, reason, error));
+                self.output.last_failure = json!({ "index": index });
+                return Flow::Failed;
+            }
+        };
+
+        let handled = if self.env.handlers.is_empty() {
+            Vec::new()
+        } else {
+            match surface_handler::run_handlers(
+                self.env.handlers,
+                self.env.adapter,
+                &command.context,
+                deadline,
+            ) {
+                Ok(handled) => handled,
+                Err(error) => return self.trace_failed(&error),
+            }
+        };
+
+        let item_context = command
+            .context
+            .clone()
+            .with_inherited_deadline(deadline)
+            .with_event_baseline(current_baseline);
+        let result = crate::dispatch::dispatch(
+            dispatched,
+            self.env.adapter,
+            self.env.permission_report,
+            &item_context,
+        );
+        let capture_outcome = match &result {
+            Ok(data) if !command.captures.is_empty() => Some(captures::capture(
+                &command.captures,
+                data,
+                &mut self.captured,
+            )),
+            _ => None,
+        };
+        let mut failed = result.is_err();
+        let (mut entry, oversized) = bounded_entry(index, name, result, self.output.results_bytes);
+        match capture_outcome {
+            Some(Ok(values)) => entry["captures"] = Value::Object(values),
+            Some(Err(detail)) => {
+                entry["capture_error"] = detail;
+                failed = true;
+            }
+            None => {}
+        }
+        if !handled.is_empty() {
+            self.output.handled_surfaces += handled.len();
+            entry["handled_surfaces"] = Value::Array(handled);
+        }
+        self.output.completed += 1;
+        if let Some(ran) = self.ran.get_mut(index) {
+            *ran = true;
+        }
+        self.push_entry(entry);
+
+        if oversized {
+            return self.halt(json!({ "reason": "output_limit", "index": index }));
+        }
+        if failed {
+            self.output.last_failure = json!({ "index": index });
+            return Flow::Failed;
+        }
+        if more && deadline.is_expired() {
+            return self.halt(json!({ "reason": "deadline", "after_index": index }));
+        }
+        Flow::Ok
+    }
+
+    pub(super) fn halt(&mut self, stopped: Value) -> Flow {
+        self.output.stopped = Some(stopped);
+        Flow::Halt
+    }
+
+    pub(super) fn trace_failed(&mut self, error: &AppError) -> Flow {
+        self.halt(json!({
+            "reason": "trace_failed",
+            "error": ErrorPayload::from_app_error(error),
+        }))
+    }
+
+    pub(super) fn frames_json(&self) -> Value {
+        Value::Array(
+            self.frames
+                .iter()
+                .map(|frame| {
+                    json!({
+                        "block": frame.block,
+                        "kind": frame.kind,
+                        "iteration": frame.iteration,
+                    })
+                })
+                .collect(),
+        )
+    }
+
+    /// Entries run inside a loop record the enclosing `frames`, so repeated
+    /// executions of one static `index` stay distinguishable.
+    fn push_entry(&mut self, mut entry: Value) {
+        if !self.frames.is_empty() {
+            entry["frames"] = self.frames_json();
+        }
+        self.output.results_bytes = self
+            .output
+            .results_bytes
+            .saturating_add(serialized_size(&entry).saturating_add(1));
+        self.output.results.push(entry);
+    }
+}
+
+fn merge_location(stopped: &mut Value, location: &Value) {
+    if let (Some(stopped), Some(location)) = (stopped.as_object_mut(), location.as_object()) {
+        stopped.extend(location.clone());
+    }
+}
+
+fn baseline_error(
+    blocked_index: usize,
+    blocked_command: &str,
+    wait_index: usize,
+    wait_command: &str,
+    mut source: AdapterError,
+) -> AppError {
+    let cause_details = source.details.take();
+    source.message = format!(
+        "Batch entry {blocked_index} ('{blocked_command}') was not started because the baseline for following wait entry {wait_index} ('{wait_command}') failed: {}",
+        source.message
+    );
+    let mut details = json!({
+        "kind": "pre_action_baseline_failed",
+        "blocked_index": blocked_index,
+        "blocked_command": blocked_command,
+        "wait_index": wait_index,
+        "wait_command": wait_command,
+    });
+    if let Some(cause_details) = cause_details {
+        details["cause_details"] = cause_details;
+    }
+    source.details = Some(details);
+    source.disposition = DeliverySemantics::not_delivered();
+    source.into()
+}
+
+fn batch_timeout(index: usize, command: &str, timeout_ms: u64) -> AppError {
+    AdapterError::timeout("Batch deadline elapsed before the entry started")
+        .with_details(json!({
+            "kind": "batch_deadline",
+            "batch_index": index,
+            "batch_command": command,
+            "timeout_ms": timeout_ms,
+        }))
+        .with_disposition(DeliverySemantics::not_delivered())
+        .into()
+}
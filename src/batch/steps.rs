@@ -0,0 +1,394 @@
+use agent_desktop_core::{AppError, PermissionReport, commands::batch::BatchCommand};
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+
+use super::captures::{self, DeclaredCaptures};
+use super::preparation::{
+    MAX_BATCH_ENTRIES, PreparedCommand, Program, limit_error, prepare_command,
+};
+
+pub(super) const MAX_BLOCK_DEPTH: usize = 4;
+pub(super) const MAX_REPEAT_ITERATIONS: u32 = 100;
+pub(super) const MAX_RETRY_ATTEMPTS: u32 = 10;
+pub(super) const MAX_RETRY_BACKOFF_MS: u64 = 60_000;
+
+/// One node of a batch program: a command entry or a control block whose
+/// bodies are nested step sequences.
+pub(super) enum Step {
+    Command(Box<PreparedCommand>),
+    If(IfBlock),
+    Repeat(RepeatBlock),
+    Retry(RetryBlock),
+}
+
+pub(super) struct IfBlock {
+    pub block: usize,
+    pub predicate: Predicate,
+    pub then: Vec<Step>,
+    pub otherwise: Vec<Step>,
+}
+
+pub(super) struct RepeatBlock {
+    pub block: usize,
+    pub max: u32,
+    pub until: Option<Predicate>,
+    pub body: Vec<Step>,
+}
+
+pub(super) struct RetryBlock {
+    pub block: usize,
+    pub attempts: u32,
+    pub backoff_ms: u64,
+    pub body: Vec<Step>,
+}
+
+pub(super) enum Predicate {
+    Capture { name: String, test: CaptureTest },
+    Is(Box<PreparedCommand>),
+    Not(Box<Predicate>),
+}
+
+pub(super) enum CaptureTest {
+    Exists(bool),
+    Equals(Value),
+    NotEquals(Value),
+    Compare(CompareOp, f64),
+}
+
+#[derive(Clone, Copy)]
+pub(super) enum CompareOp {
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+}
+
+impl Step {
+    pub(super) fn commands_mut(&mut self) -> Vec<&mut PreparedCommand> {
+        match self {
+            Step::Command(command) => vec![command.as_mut()],
+            Step::If(block) => block.predicate.commands_mut(),
+            Step::Repeat(block) => block
+                .until
+                .as_mut()
+                .map(Predicate::commands_mut)
+                .unwrap_or_default(),
+            Step::Retry(_) => Vec::new(),
+        }
+    }
+
+    pub(super) fn bodies_mut(&mut self) -> Vec<&mut Vec<Step>> {
+        match self {
+            Step::Command(_) => Vec::new(),
+            Step::If(block) => vec![&mut block.then, &mut block.otherwise],
+            Step::Repeat(block) => vec![&mut block.body],
+            Step::Retry(block) => vec![&mut block.body],
+        }
+    }
+}
+
+impl Predicate {
+    fn commands_mut(&mut self) -> Vec<&mut PreparedCommand> {
+        match self {
+            Predicate::Capture { .. } => Vec::new(),
+            Predicate::Is(command) => vec![command.as_mut()],
+            Predicate::Not(inner) => inner.commands_mut(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RepeatSpec {
+    max: u32,
+    until: Option<Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RetrySpec {
+    attempts: u32,
+    #[serde(default)]
+    backoff_ms: u64,
+}
+
+/// Turns batch JSON into a validated [`Program`]. Commands and blocks are
+/// numbered in preorder, and captures are declared in the same order, so a
+/// `${name}` or capture predicate must follow the entry that declares it.
+pub(super) struct ProgramBuilder<'a> {
+    permission_report: &'a PermissionReport,
+    declared: DeclaredCaptures,
+    commands: usize,
+    blocks: usize,
+}
+
+impl<'a> ProgramBuilder<'a> {
+    pub(super) fn new(permission_report: &'a PermissionReport) -> Self {
+        Self {
+            permission_report,
+            declared: DeclaredCaptures::new(),
+            commands: 0,
+            blocks: 0,
+        }
+    }
+
+    pub(super) fn build(mut self, items: Vec<Value>) -> Result<Program, AppError> {
+        let steps = self.sequence(items, 0)?;
+        Ok(Program {
+            steps,
+            commands: self.commands,
+            blocks: self.blocks,
+        })
+    }
+
+    fn sequence(&mut self, items: Vec<Value>, depth: usize) -> Result<Vec<Step>, AppError> {
+        items
+            .into_iter()
+            .map(|item| self.step(item, depth))
+            .collect()
+    }
+
+    fn step(&mut self, item: Value, depth: usize) -> Result<Step, AppError> {
+        let Value::Object(mut fields) = item else {
+            return Err(AppError::invalid_input(
+                "Invalid batch JSON: every entry must be an object",
+            ));
+        };
+        if fields.contains_key("command") {
+            let item: BatchCommand = serde_json::from_value(Value::Object(fields))
+                .map_err(|e| AppError::invalid_input(format!("Invalid batch JSON: {e}")))?;
+            return self
+                .command(item)
+                .map(|command| Step::Command(Box::new(command)));
+        }
+        let block = self.blocks;
+        self.blocks += 1;
+        let kind = ["if", "repeat", "retry"]
+            .into_iter()
+            .find(|kind| fields.contains_key(*kind))
+            .ok_or_else(|| {
+                AppError::invalid_input_with_suggestion(
+                    "Batch entry has no 'command', 'if', 'repeat', or 'retry'",
+                    "Write command entries as {\"command\": ..., \"args\": {...}} or wrap steps in a control block",
+                )
+            })?;
+        if depth >= MAX_BLOCK_DEPTH {
+            return Err(block_error(
+                block,
+                kind,
+                &format!("nests deeper than {MAX_BLOCK_DEPTH} blocks"),
+            ));
+        }
+        let step = match kind {
+            "if" => self.if_block(block, &mut fields, depth)?,
+            "repeat" => self.repeat_block(block, &mut fields, depth)?,
+            _ => self.retry_block(block, &mut fields, depth)?,
+        };
+        if let Some(field) = fields.keys().next() {
+            return Err(block_error(
+                block,
+                kind,
+                &format!(
+                    "has unknown field {}",
+                    crate::diagnostic::token_label(field)
+                ),
+            ));
+        }
+        Ok(step)
+    }
+
+    fn command(&mut self, item: BatchCommand) -> Result<PreparedCommand, AppError> {
+        let index = self.commands;
+        self.commands += 1;
+        if self.commands > MAX_BATCH_ENTRIES {
+            return Err(limit_error(
+                "Batch program contains too many commands",
+                json!({ "max_entries": MAX_BATCH_ENTRIES }),
+            ));
+        }
+        prepare_command(index, item, self.permission_report, &mut self.declared)
+    }
+
+    fn if_block(
+        &mut self,
+        block: usize,
+        fields: &mut Map<String, Value>,
+        depth: usize,
+    ) -> Result<Step, AppError> {
+        let predicate = fields.remove("if").unwrap_or_default();
+        let predicate = self.predicate(block, "if", predicate)?;
+        let then = take_body(block, "if", fields, "then")?;
+        let then = self.sequence(then, depth + 1)?;
+        let otherwise = match fields.remove("else") {
+            Some(otherwise) => body_items(block, "if", "else", otherwise)?,
+            None => Vec::new(),
+        };
+        let otherwise = self.sequence(otherwise, depth + 1)?;
+        Ok(Step::If(IfBlock {
+            block,
+            predicate,
+            then,
+            otherwise,
+        }))
+    }
+
+    fn repeat_block(
+        &mut self,
+        block: usize,
+        fields: &mut Map<String, Value>,
+        depth: usize,
+    ) -> Result<Step, AppError> {
+        let spec: RepeatSpec = block_spec(block, "repeat", fields.remove("repeat"))?;
+        if spec.max == 0 || spec.max > MAX_REPEAT_ITERATIONS {
+            return Err(block_error(
+                block,
+                "repeat",
+                &format!("needs 'max' between 1 and {MAX_REPEAT_ITERATIONS}"),
+            ));
+        }
+        let body = take_body(block, "repeat", fields, "do")?;
+        let body = self.sequence(body, depth + 1)?;
+        let until = spec
+            .until
+            .map(|until| self.predicate(block, "repeat", until))
+            .transpose()?;
+        Ok(Step::Repeat(RepeatBlock {
+            block,
+            max: spec.max,
+            until,
+            body,
+        }))
+    }
+
+    fn retry_block(
+        &mut self,
+        block: usize,
+        fields: &mut Map<String, Value>,
+        depth: usize,
+    ) -> Result<Step, AppError> {
+        let spec: RetrySpec = block_spec(block, "retry", fields.remove("retry"))?;
+        if spec.attempts == 0 || spec.attempts > MAX_RETRY_ATTEMPTS {
+            return Err(block_error(
+                block,
+                "retry",
+                &format!("needs 'attempts' between 1 and {MAX_RETRY_ATTEMPTS}"),
+            ));
+        }
+        if spec.backoff_ms > MAX_RETRY_BACKOFF_MS {
+            return Err(block_error(
+                block,
+                "retry",
+                &format!("needs 'backoff_ms' of at most {MAX_RETRY_BACKOFF_MS}"),
+            ));
+        }
+        let body = take_body(block, "retry", fields, "do")?;
+        let body = self.sequence(body, depth + 1)?;
+        Ok(Step::Retry(RetryBlock {
+            block,
+            attempts: spec.attempts,
+            backoff_ms: spec.backoff_ms,
+            body,
+        }))
+    }
+
+    fn predicate(
+        &mut self,
+        block: usize,
+        kind: &str,
+        predicate: Value,
+    ) -> Result<Predicate, AppError> {
+        let Value::Object(mut fields) = predicate else {
+            return Err(block_error(block, kind, "needs an object predicate"));
+        };
+        let single = fields.len() == 1;
+        if let Some(inner) = fields.remove("not").filter(|_| single) {
+            let inner = self.predicate(block, kind, inner)?;
+            return Ok(Predicate::Not(Box::new(inner)));
+        }
+        if let Some(args) = fields.remove("is").filter(|_| single) {
+            let item = BatchCommand {
+                command: "is".into(),
+                session: None,
+                args,
+                capture: Default::default(),
+            };
+            return Ok(Predicate::Is(Box::new(self.command(item)?)));
+        }
+        let Some(Value::String(name)) = fields.remove("capture") else {
+            return Err(block_error(
+                block,
+                kind,
+                "needs a predicate of {\"capture\": name, <test>: value}, {\"is\": {...}}, or {\"not\": {...}}",
+            ));
+        };
+        if !self.declared.contains_key(&name) {
+            return Err(captures::undeclared(&name));
+        }
+        let mut tests = fields.into_iter();
+        let (Some((test, operand)), None) = (tests.next(), tests.next()) else {
+            return Err(block_error(block, kind, "needs exactly one capture test"));
+        };
+        let test = capture_test(&test, operand)
+            .ok_or_else(|| block_error(block, kind, "has an unknown or mistyped capture test"))?;
+        Ok(Predicate::Capture { name, test })
+    }
+}
+
+fn capture_test(test: &str, operand: Value) -> Option<CaptureTest> {
+    let compare = |op| {
+        operand
+            .as_f64()
+            .map(|bound| CaptureTest::Compare(op, bound))
+    };
+    match test {
+        "exists" => operand.as_bool().map(CaptureTest::Exists),
+        "equals" => Some(CaptureTest::Equals(operand)),
+        "not_equals" => Some(CaptureTest::NotEquals(operand)),
+        "gt" => compare(CompareOp::Gt),
+        "gte" => compare(CompareOp::Gte),
+        "lt" => compare(CompareOp::Lt),
+        "lte" => compare(CompareOp::Lte),
+        _ => None,
+    }
+}
+
+fn block_spec<T: serde::de::DeserializeOwned>(
+    block: usize,
+    kind: &str,
+    spec: Option<Value>,
+) -> Result<T, AppError> {
+    serde_json::from_value(spec.unwrap_or_default()).map_err(|error| {
+        let diagnostic = crate::diagnostic::bounded_text(&error.to_string(), 512);
+        block_error(block, kind, &format!("has invalid settings: {diagnostic}"))
+    })
+}
+
+fn take_body(
+    block: usize,
+    kind: &str,
+    fields: &mut Map<String, Value>,
+    key: &str,
+) -> Result<Vec<Value>, AppError> {
+    let body = fields
+        .remove(key)
+        .ok_or_else(|| block_error(block, kind, &format!("needs a '{key}' array of steps")))?;
+    body_items(block, kind, key, body)
+}
+
+fn body_items(block: usize, kind: &str, key: &str, body: Value) -> Result<Vec<Value>, AppError> {
+    match body {
+        Value::Array(items) => Ok(items),
+        _ => Err(block_error(
+            block,
+            kind,
+            &format!("needs '{key}' to be an array of steps"),
+        )),
+    }
+}
+
+fn block_error(block: usize, kind: &str, problem: &str) -> AppError {
+    AppError::invalid_input_with_suggestion(
+        format!("Batch block {block} ('{kind}') {problem}"),
+        "See the batch control-flow reference: if/then/else, repeat {max, until}/do, retry {attempts, backoff_ms}/do",
+    )
+}
@@ -9,6 +9,7 @@ fn item(command: &str, args: Value) -> BatchCommand {
         command: command.to_string(),
         session: None,
         args,
+        capture: Default::default(),
     }
 }
 
@@ -36,6 +37,30 @@ fn session_batch_rejects_unknown_field() {
     assert!(err.to_string().contains("no_trce"));
 }
 
+#[test]
+fn alias_batch_entries_take_only_their_action_fields() {
+    let command = parse_command(item(
+        "alias",
+        serde_json::json!({ "action": "set", "name": "send", "ref_id": "@s1:e2" }),
+    ))
+    .expect("alias set parses");
+    assert!(matches!(
+        command,
+        Commands::Alias(AliasArgs {
+            action: AliasAction::Set(_)
+        })
+    ));
+    for invalid in [
+        serde_json::json!({ "action": "set", "name": "send" }),
+        serde_json::json!({ "action": "list", "name": "send" }),
+        serde_json::json!({ "action": "rm", "name": "send", "ref_id": "@s1:e2" }),
+        serde_json::json!({ "action": "rename", "name": "send" }),
+    ] {
+        let err = parse_command(item("alias", invalid.clone())).unwrap_err();
+        assert_eq!(err.code(), "INVALID_ARGS", "{invalid}");
+    }
+}
+
 #[test]
 fn parses_ref_command_into_cli_enum() {
     let command =
@@ -103,6 +128,7 @@ fn stop_on_error_halts_after_first_failure() {
         ])
         .to_string(),
         stop_on_error: true,
+        handlers_json: None,
         timeout_ms: 60_000,
     };
 
@@ -122,6 +148,32 @@ fn stop_on_error_halts_after_first_failure() {
     assert_eq!(results[0]["error"]["code"], "PLATFORM_NOT_SUPPORTED");
 }
 
+#[test]
+fn surface_handlers_are_validated_and_summarized() {
+    let run = |handlers: &str| {
+        execute(
+            BatchArgs {
+                commands_json: r#"[{"command": "version", "args": {}}]"#.into(),
+                stop_on_error: false,
+                handlers_json: Some(handlers.into()),
+                timeout_ms: 60_000,
+            },
+            &NoopAdapter,
+            &PermissionReport::default(),
+            &agent_desktop_core::CommandContext::default(),
+        )
+    };
+
+    let err = run(r#"[{"on-surface":"dialog","click":"OK"}]"#).unwrap_err();
+    assert_eq!(err.code(), "INVALID_ARGS");
+
+    let value =
+        run(r#"[{"on-surface":"alert","text":"Update available","click":"Later"}]"#).unwrap();
+    assert_eq!(value["surface_handlers"]["registered"], 1);
+    assert_eq!(value["surface_handlers"]["invocations"], 0);
+    assert!(value["results"][0].get("handled_surfaces").is_none());
+}
+
 #[test]
 fn no_args_rejection_has_suggestion() {
     let err = parse_command(item("status", serde_json::json!({"x": 1})))
@@ -256,6 +308,39 @@ fn session_batch_end_rejects_start_only_field() {
     assert!(err.to_string().contains("screenshots"));
 }
 
+#[test]
+fn session_batch_rejects_top_level_only_actions() {
+    for action in ["export", "import", "quota"] {
+        let err = parse_command(item(
+            "session",
+            serde_json::json!({ "action": action, "id": "s-1" }),
+        ))
+        .expect_err("bundle and quota actions are top-level only");
+        assert_eq!(err.code(), "INVALID_ARGS");
+    }
+}
+
+#[test]
+fn session_batch_start_parses_quota_fields() {
+    let command = parse_command(item(
+        "session",
+        serde_json::json!({ "action": "start", "max_trace_bytes": 4096, "max_age": 60 }),
+    ))
+    .expect("session start with quota parses");
+
+    match command {
+        Commands::Session(args) => match args.action {
+            crate::cli_args::session::SessionAction::Start(start) => {
+                assert_eq!(start.quota.max_trace_bytes, Some(4096));
+                assert_eq!(start.quota.max_age_secs, Some(60));
+                assert_eq!(start.quota.max_screenshots, None);
+            }
+            other => panic!("unexpected action: {other:?}"),
+        },
+        other => panic!("unexpected command: {other:?}"),
+    }
+}
+
 #[test]
 fn session_batch_gc_parses_older_than() {
     let command = parse_command(item(
@@ -6,6 +6,9 @@ use std::path::{Path, PathBuf};
 const NON_COMMAND_MODULES: &[&str] = &[
     "combo",
     "execute_by_ref",
+    "expect_matcher",
+    "expect_screenshot",
+    "expect_target",
     "find_live",
     "helpers",
     "helpers_test_support",
@@ -31,15 +34,22 @@ const NON_COMMAND_MODULES: &[&str] = &[
     "wait_event",
     "wait_event_input",
     "wait_selector",
+    "visual_compare",
     "window_target",
 ];
 
 const COMMAND_SPECIFIC_TESTS: &[&str] = &[
+    "alias",
+    "audit-a11y",
+    "expect",
+    "extract-table",
     "find",
     "focus-window",
     "is",
     "list-apps",
+    "read-text",
     "right-click",
+    "screenshot-compare",
     "skills",
     "session",
     "snapshot",
@@ -67,7 +77,7 @@ const SHARED_REF_ACTION_TESTS: &[&str] = &[
     "uncheck",
 ];
 
-const BINARY_CONTRACT_TESTS: &[&str] = &["batch", "permissions", "version"];
+const BINARY_CONTRACT_TESTS: &[&str] = &["batch", "permissions", "run", "version"];
 
 #[test]
 fn standard_version_flag_reports_the_package_version() {
@@ -170,12 +180,12 @@ fn macos_capability_count_includes_restored_notifications() {
     let commands = cli_command_names();
     assert_eq!(
         commands.len(),
-        58,
+        65,
         "the published CLI command count changed"
     );
     assert_eq!(
         commands.len(),
-        58,
+        65,
         "macOS operational command count changed; update capability documentation"
     );
 }
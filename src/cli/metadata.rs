@@ -0,0 +1,98 @@
+use super::Commands;
+
+#[derive(Clone, Copy)]
+struct CommandMetadata {
+    name: &'static str,
+    post_action_wait: bool,
+}
+
+impl CommandMetadata {
+    const fn new(name: &'static str, post_action_wait: bool) -> Self {
+        Self {
+            name,
+            post_action_wait,
+        }
+    }
+}
+
+impl Commands {
+    fn metadata(&self) -> CommandMetadata {
+        match self {
+            Self::Snapshot(_) => CommandMetadata::new("snapshot", true),
+            Self::Find(_) => CommandMetadata::new("find", false),
+            Self::Screenshot(_) => CommandMetadata::new("screenshot", false),
+            Self::ScreenshotCompare(_) => CommandMetadata::new("screenshot-compare", false),
+            Self::Get(_) => CommandMetadata::new("get", false),
+            Self::Is(_) => CommandMetadata::new("is", false),
+            Self::Expect(_) => CommandMetadata::new("expect", false),
+            Self::AuditA11y(_) => CommandMetadata::new("audit-a11y", false),
+            Self::ExtractTable(_) => CommandMetadata::new("extract-table", false),
+            Self::ReadText(_) => CommandMetadata::new("read-text", false),
+            Self::Click(_) => CommandMetadata::new("click", true),
+            Self::DoubleClick(_) => CommandMetadata::new("double-click", true),
+            Self::TripleClick(_) => CommandMetadata::new("triple-click", true),
+            Self::RightClick(_) => CommandMetadata::new("right-click", true),
+            Self::Type(_) => CommandMetadata::new("type", true),
+            Self::SetValue(_) => CommandMetadata::new("set-value", true),
+            Self::Clear(_) => CommandMetadata::new("clear", true),
+            Self::Focus(_) => CommandMetadata::new("focus", true),
+            Self::Select(_) => CommandMetadata::new("select", true),
+            Self::Toggle(_) => CommandMetadata::new("toggle", true),
+            Self::Check(_) => CommandMetadata::new("check", true),
+            Self::Uncheck(_) => CommandMetadata::new("uncheck", true),
+            Self::Expand(_) => CommandMetadata::new("expand", true),
+            Self::Collapse(_) => CommandMetadata::new("collapse", true),
+            Self::Scroll(_) => CommandMetadata::new("scroll", true),
+            Self::ScrollTo(_) => CommandMetadata::new("scroll-to", true),
+            Self::Press(_) => CommandMetadata::new("press", false),
+            Self::KeyDown(_) => CommandMetadata::new("key-down", false),
+            Self::KeyUp(_) => CommandMetadata::new("key-up", false),
+            Self::Hover(_) => CommandMetadata::new("hover", true),
+            Self::Drag(_) => CommandMetadata::new("drag", true),
+            Self::MouseMove(_) => CommandMetadata::new("mouse-move", false),
+            Self::MouseClick(_) => CommandMetadata::new("mouse-click", false),
+            Self::MouseDown(_) => CommandMetadata::new("mouse-down", false),
+            Self::MouseUp(_) => CommandMetadata::new("mouse-up", false),
+            Self::MouseWheel(_) => CommandMetadata::new("mouse-wheel", false),
+            Self::Launch(_) => CommandMetadata::new("launch", false),
+            Self::CloseApp(_) => CommandMetadata::new("close-app", false),
+            Self::ListWindows(_) => CommandMetadata::new("list-windows", false),
+            Self::ListDisplays => CommandMetadata::new("list-displays", false),
+            Self::ListApps(_) => CommandMetadata::new("list-apps", false),
+            Self::FocusWindow(_) => CommandMetadata::new("focus-window", false),
+            Self::ResizeWindow(_) => CommandMetadata::new("resize-window", false),
+            Self::MoveWindow(_) => CommandMetadata::new("move-window", false),
+            Self::Minimize(_) => CommandMetadata::new("minimize", false),
+            Self::Maximize(_) => CommandMetadata::new("maximize", false),
+            Self::Restore(_) => CommandMetadata::new("restore", false),
+            Self::ListSurfaces(_) => CommandMetadata::new("list-surfaces", false),
+            Self::ListNotifications(_) => CommandMetadata::new("list-notifications", false),
+            Self::DismissNotification(_) => CommandMetadata::new("dismiss-notification", false),
+            Self::DismissAllNotifications(_) => {
+                CommandMetadata::new("dismiss-all-notifications", false)
+            }
+            Self::NotificationAction(_) => CommandMetadata::new("notification-action", false),
+            Self::ClipboardGet(_) => CommandMetadata::new("clipboard-get", false),
+            Self::ClipboardSet(_) => CommandMetadata::new("clipboard-set", false),
+            Self::ClipboardClear => CommandMetadata::new("clipboard-clear", false),
+            Self::Wait(_) => CommandMetadata::new("wait", false),
+            Self::Status => CommandMetadata::new("status", false),
+            Self::Permissions(_) => CommandMetadata::new("permissions", false),
+            Self::Version => CommandMetadata::new("version", false),
+            Self::Batch(_) => CommandMetadata::new("batch", false),
+            Self::Run(_) => CommandMetadata::new("run", false),
+            Self::Skills(_) => CommandMetadata::new("skills", false),
+            Self::Session(_) => CommandMetadata::new("session", false),
+            Self::Trace(_) => CommandMetadata::new("trace", false),
+            Self::Alias(_) => CommandMetadata::new("alias", false),
+        }
+    }
+
+    pub(crate) fn name(&self) -> &'static str {
+        self.metadata().name
+    }
+
+    pub(crate) fn supports_post_action_wait(&self) -> bool {
+        self.metadata().post_action_wait
+    }
+}
@@ -1,5 +1,7 @@
 use clap::Subcommand;
+use serde::Serialize;
 
+mod metadata;
 mod post_action_wait;
 mod root;
 
@@ -11,13 +13,20 @@ use crate::cli_args::{
         HoverArgs, KeyComboArgs, MouseClickArgs, MouseMoveArgs, MousePointArgs, PressArgs,
         ScrollArgs, SelectArgs, SetValueArgs, TypeArgs,
     },
+    alias::AliasArgs,
+    audit::AuditA11yArgs,
     batch::BatchArgs,
     drag::DragCliArgs,
+    expect::ExpectArgs,
+    extract_table::ExtractTableArgs,
     mouse_wheel::MouseWheelArgs,
     notifications::{
         DismissAllNotificationsCliArgs, DismissNotificationCliArgs, ListNotificationsCliArgs,
         NotificationActionCliArgs,
     },
+    read_text::ReadTextArgs,
+    run::RunArgs,
+    screenshot_compare::ScreenshotCompareArgs,
     session::SessionArgs,
     skills::SkillsArgs,
     system::{
@@ -28,7 +37,11 @@ use crate::cli_args::{
     trace::TraceArgs,
 };
 
-#[derive(Subcommand, Debug)]
+/// Serializes to the batch item shape, `{"command": ..., "args": {...}}`,
+/// which is what `command.start` records for `trace replay`. Commands that
+/// manage the session, its trace, or other commands are never replayed.
+#[derive(Subcommand, Debug, Serialize)]
+#[serde(tag = "command", content = "args", rename_all = "kebab-case")]
 pub(crate) enum Commands {
     #[command(about = "Capture accessibility tree as structured JSON with @ref IDs")]
     Snapshot(SnapshotArgs),
@@ -36,10 +49,22 @@ pub(crate) enum Commands {
     Find(FindArgs),
     #[command(about = "Take a PNG screenshot of an application window")]
     Screenshot(ScreenshotArgs),
+    #[command(about = "Compare two PNGs and report a perceptual diff score")]
+    ScreenshotCompare(ScreenshotCompareArgs),
     #[command(about = "Read an element property (text, value, title, bounds, role, states)")]
     Get(GetArgs),
     #[command(about = "Check element state (visible, enabled, checked, focused, expanded)")]
     Is(IsArgs),
+    #[command(about = "Assert element state, re-checking until it holds or the timeout passes")]
+    Expect(ExpectArgs),
+    #[command(about = "Lint a window's accessibility tree; JSON or JUnit findings for CI")]
+    AuditA11y(AuditA11yArgs),
+    #[command(about = "Read a table, grid, outline, or list as {columns, rows} with cell refs")]
+    ExtractTable(ExtractTableArgs),
+    #[command(
+        about = "Read a window or subtree as markdown (headings, text, lists, links, fields)"
+    )]
+    ReadText(ReadTextArgs),
     #[command(about = "Click element via accessibility press action")]
     Click(RefArgs),
     #[command(about = "Physically double-click element; requires --headed")]
@@ -145,102 +170,50 @@ pub(crate) enum Commands {
     #[command(about = "Show version, target architecture, and OS")]
     Version,
     #[command(about = "Execute a bounded, sequential, non-atomic JSON command batch")]
+    #[serde(skip_serializing)]
     Batch(BatchArgs),
+    #[command(about = "Run a YAML/JSON scenario file of named steps with expectations")]
+    #[serde(skip_serializing)]
+    Run(RunArgs),
     #[command(about = "Bundled skill docs for AI agents (list, get, path)")]
+    #[serde(skip_serializing)]
     Skills(SkillsArgs),
     #[command(about = "Manage trace-enabled agent sessions (start, end, list, gc)")]
+    #[serde(skip_serializing)]
     Session(SessionArgs),
     #[command(about = "Read merged session trace timelines")]
+    #[serde(skip_serializing)]
     Trace(TraceArgs),
-}
-
-#[derive(Clone, Copy)]
-struct CommandMetadata {
-    name: &'static str,
-    post_action_wait: bool,
-}
-
-impl CommandMetadata {
-    const fn new(name: &'static str, post_action_wait: bool) -> Self {
-        Self {
-            name,
-            post_action_wait,
-        }
-    }
+    #[command(about = "Name elements as @NAME refs that survive re-snapshots (set, list, rm)")]
+    #[serde(skip_serializing)]
+    Alias(AliasArgs),
 }
 
 impl Commands {
-    fn metadata(&self) -> CommandMetadata {
-        match self {
-            Self::Snapshot(_) => CommandMetadata::new("snapshot", true),
-            Self::Find(_) => CommandMetadata::new("find", false),
-            Self::Screenshot(_) => CommandMetadata::new("screenshot", false),
-            Self::Get(_) => CommandMetadata::new("get", false),
-            Self::Is(_) => CommandMetadata::new("is", false),
-            Self::Click(_) => CommandMetadata::new("click", true),
-            Self::DoubleClick(_) => CommandMetadata::new("double-click", true),
-            Self::TripleClick(_) => CommandMetadata::new("triple-click", true),
-            Self::RightClick(_) => CommandMetadata::new("right-click", true),
-            Self::Type(_) => CommandMetadata::new("type", true),
-            Self::SetValue(_) => CommandMetadata::new("set-value", true),
-            Self::Clear(_) => CommandMetadata::new("clear", true),
-            Self::Focus(_) => CommandMetadata::new("focus", true),
-            Self::Select(_) => CommandMetadata::new("select", true),
-            Self::Toggle(_) => CommandMetadata::new("toggle", true),
-            Self::Check(_) => CommandMetadata::new("check", true),
-            Self::Uncheck(_) => CommandMetadata::new("uncheck", true),
-            Self::Expand(_) => CommandMetadata::new("expand", true),
-            Self::Collapse(_) => CommandMetadata::new("collapse", true),
-            Self::Scroll(_) => CommandMetadata::new("scroll", true),
-            Self::ScrollTo(_) => CommandMetadata::new("scroll-to", true),
-            Self::Press(_) => CommandMetadata::new("press", false),
-            Self::KeyDown(_) => CommandMetadata::new("key-down", false),
-            Self::KeyUp(_) => CommandMetadata::new("key-up", false),
-            Self::Hover(_) => CommandMetadata::new("hover", true),
-            Self::Drag(_) => CommandMetadata::new("drag", true),
-            Self::MouseMove(_) => CommandMetadata::new("mouse-move", false),
-            Self::MouseClick(_) => CommandMetadata::new("mouse-click", false),
-            Self::MouseDown(_) => CommandMetadata::new("mouse-down", false),
-            Self::MouseUp(_) => CommandMetadata::new("mouse-up", false),
-            Self::MouseWheel(_) => CommandMetadata::new("mouse-wheel", false),
-            Self::Launch(_) => CommandMetadata::new("launch", false),
-            Self::CloseApp(_) => CommandMetadata::new("close-app", false),
-            Self::ListWindows(_) => CommandMetadata::new("list-windows", false),
-            Self::ListDisplays => CommandMetadata::new("list-displays", false),
-            Self::ListApps(_) => CommandMetadata::new("list-apps", false),
-            Self::FocusWindow(_) => CommandMetadata::new("focus-window", false),
-            Self::ResizeWindow(_) => CommandMetadata::new("resize-window", false),
-            Self::MoveWindow(_) => CommandMetadata::new("move-window", false),
-            Self::Minimize(_) => CommandMetadata::new("minimize", false),
-            Self::Maximize(_) => CommandMetadata::new("maximize", false),
-            Self::Restore(_) => CommandMetadata::new("restore", false),
-            Self::ListSurfaces(_) => CommandMetadata::new("list-surfaces", false),
-            Self::ListNotifications(_) => CommandMetadata::new("list-notifications", false),
-            Self::DismissNotification(_) => CommandMetadata::new("dismiss-notification", false),
-            Self::DismissAllNotifications(_) => {
-                CommandMetadata::new("dismiss-all-notifications", false)
-            }
-            Self::NotificationAction(_) => CommandMetadata::new("notification-action", false),
-            Self::ClipboardGet(_) => CommandMetadata::new("clipboard-get", false),
-            Self::ClipboardSet(_) => CommandMetadata::new("clipboard-set", false),
-            Self::ClipboardClear => CommandMetadata::new("clipboard-clear", false),
-            Self::Wait(_) => CommandMetadata::new("wait", false),
-            Self::Status => CommandMetadata::new("status", false),
-            Self::Permissions(_) => CommandMetadata::new("permissions", false),
-            Self::Version => CommandMetadata::new("version", false),
-            Self::Batch(_) => CommandMetadata::new("batch", false),
-            Self::Skills(_) => CommandMetadata::new("skills", false),
-            Self::Session(_) => CommandMetadata::new("session", false),
-            Self::Trace(_) => CommandMetadata::new("trace", false),
-        }
-    }
-
-    pub(crate) fn name(&self) -> &'static str {
-        self.metadata().name
-    }
-
-    pub(crate) fn supports_post_action_wait(&self) -> bool {
-        self.metadata().post_action_wait
+    /// Ref actions whose stale ref `--heal` may re-identify, plus the
+    /// batch and scenario runners that carry the flag to their entries.
+    pub(crate) fn supports_heal(&self) -> bool {
+        matches!(
+            self,
+            Self::Click(_)
+                | Self::DoubleClick(_)
+                | Self::TripleClick(_)
+                | Self::RightClick(_)
+                | Self::Type(_)
+                | Self::SetValue(_)
+                | Self::Clear(_)
+                | Self::Focus(_)
+                | Self::Select(_)
+                | Self::Toggle(_)
+                | Self::Check(_)
+                | Self::Uncheck(_)
+                | Self::Expand(_)
+                | Self::Collapse(_)
+                | Self::Scroll(_)
+                | Self::ScrollTo(_)
+                | Self::Batch(_)
+                | Self::Run(_)
+        )
     }
 
     pub(crate) fn is_mutating(&self) -> bool {
@@ -284,20 +257,38 @@ impl Commands {
             | Self::NotificationAction(_)
             | Self::ClipboardSet(_)
             | Self::ClipboardClear
-            | Self::Batch(_) => true,
+            | Self::Batch(_)
+            | Self::Run(_) => true,
             Self::Screenshot(args) => args.output_path.is_some(),
+            Self::ScreenshotCompare(args) => args.diff_path.is_some(),
+            Self::AuditA11y(args) => args.junit.is_some(),
+            Self::ExtractTable(args) => args.scroll,
             Self::ClipboardGet(args) => args.out.is_some(),
             Self::Permissions(args) => args.request,
             Self::Session(args) => {
-                !matches!(&args.action, crate::cli_args::session::SessionAction::List)
+                use crate::cli_args::session::SessionAction;
+                match &args.action {
+                    SessionAction::List => false,
+                    SessionAction::Quota(quota) => quota.clear || !quota.quota.is_empty(),
+                    _ => true,
+                }
             }
             Self::Trace(args) => {
-                matches!(&args.action, crate::cli_args::trace::TraceAction::Export(_))
+                use crate::cli_args::trace::TraceAction;
+                match &args.action {
+                    TraceAction::Replay(replay) => !replay.dry_run,
+                    TraceAction::ToScript(to_script) => to_script.out.is_some(),
+                    TraceAction::Diff(diff) => diff.out.is_some(),
+                    action => matches!(action, TraceAction::Export(_)),
+                }
             }
+            Self::Alias(args) => !matches!(&args.action, crate::cli_args::alias::AliasAction::List),
             Self::Snapshot(_)
             | Self::Find(_)
             | Self::Get(_)
             | Self::Is(_)
+            | Self::Expect(_)
+            | Self::ReadText(_)
             | Self::ListWindows(_)
             | Self::ListDisplays
             | Self::ListApps(_)
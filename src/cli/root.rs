@@ -48,6 +48,12 @@ pub(crate) struct Cli {
         help = "Prefer physical delivery for natural input commands and permit focus/cursor side effects. Default is strict headless semantic delivery."
     )]
     pub headed: bool,
+    #[arg(
+        long,
+        global = true,
+        help = "On STALE_REF, re-identify the ref by its stored identity and act only on a unique confident match"
+    )]
+    pub heal: bool,
     #[command(flatten)]
     pub post_action_wait: PostActionWaitArgs,
     #[command(subcommand)]
@@ -153,3 +153,22 @@ fn mutation_metadata_distinguishes_read_only_and_file_writing_forms() {
     assert!(!command(&["trace", "show"]).is_mutating());
     assert!(command(&["trace", "export"]).is_mutating());
 }
+
+#[test]
+fn heal_is_accepted_only_for_ref_actions_and_runners() {
+    let parsed = Cli::try_parse_from(["agent-desktop", "click", "@s1:e1", "--heal"])
+        .expect("--heal is global");
+    assert!(parsed.heal);
+
+    for supported in [
+        command(&["click", "@s1:e1"]),
+        command(&["type", "@s1:e1", "hello"]),
+        command(&["batch", "[]"]),
+    ] {
+        assert!(crate::validate_heal_command(&supported).is_ok());
+    }
+    for unsupported in [command(&["snapshot"]), command(&["hover", "@s1:e1"])] {
+        let err = crate::validate_heal_command(&unsupported).unwrap_err();
+        assert_eq!(err.code(), "INVALID_ARGS");
+    }
+}
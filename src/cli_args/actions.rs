@@ -1,5 +1,5 @@
 use clap::Parser;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 fn default_scroll_amount() -> u32 {
     3
@@ -21,7 +21,7 @@ fn default_ref_timeout_ms() -> u64 {
     5000
 }
 
-#[derive(Parser, Debug, Deserialize)]
+#[derive(Parser, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct TypeArgs {
     #[arg(
@@ -35,8 +35,22 @@ pub(crate) struct TypeArgs {
         help = "Snapshot ID required for a legacy bare @eN ref; omit for a qualified ref"
     )]
     pub snapshot: Option<String>,
-    #[arg(value_name = "TEXT", allow_hyphen_values = true, help = "Text to type")]
-    pub text: String,
+    #[arg(
+        value_name = "TEXT",
+        allow_hyphen_values = true,
+        required_unless_present = "secret",
+        help = "Text to type"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[arg(
+        long,
+        value_name = "NAME",
+        conflicts_with = "text",
+        help = "Type the value of this secret (env var, private file, or command configured in secrets.json) instead of TEXT; it never appears in output or traces"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
     #[arg(
         long = "timeout-ms",
         default_value_t = 5000,
@@ -46,7 +60,7 @@ pub(crate) struct TypeArgs {
     pub timeout_ms: u64,
 }
 
-#[derive(Parser, Debug, Deserialize)]
+#[derive(Parser, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct SetValueArgs {
     #[arg(
@@ -63,9 +77,19 @@ pub(crate) struct SetValueArgs {
     #[arg(
         value_name = "VALUE",
         allow_hyphen_values = true,
+        required_unless_present = "secret",
         help = "Value to set"
     )]
-    pub value: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[arg(
+        long,
+        value_name = "NAME",
+        conflicts_with = "value",
+        help = "Set the value of this secret (env var, private file, or command configured in secrets.json) instead of VALUE; it never appears in output or traces"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
     #[arg(
         long = "timeout-ms",
         default_value_t = 5000,
@@ -75,7 +99,7 @@ pub(crate) struct SetValueArgs {
     pub timeout_ms: u64,
 }
 
-#[derive(Parser, Debug, Deserialize)]
+#[derive(Parser, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct SelectArgs {
     #[arg(
@@ -100,7 +124,7 @@ pub(crate) struct SelectArgs {
     pub timeout_ms: u64,
 }
 
-#[derive(Parser, Debug, Deserialize)]
+#[derive(Parser, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct ScrollArgs {
     #[arg(
@@ -133,7 +157,7 @@ pub(crate) struct ScrollArgs {
     pub timeout_ms: u64,
 }
 
-#[derive(Parser, Debug, Deserialize)]
+#[derive(Parser, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct PressArgs {
     #[arg(
@@ -151,7 +175,7 @@ pub(crate) struct PressArgs {
     pub force: bool,
 }
 
-#[derive(Parser, Debug, Deserialize)]
+#[derive(Parser, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct KeyComboArgs {
     #[arg(
@@ -167,7 +191,7 @@ pub(crate) struct KeyComboArgs {
     pub force: bool,
 }
 
-#[derive(Parser, Debug, Deserialize)]
+#[derive(Parser, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct HoverArgs {
     #[arg(
@@ -197,14 +221,14 @@ pub(crate) struct HoverArgs {
     pub timeout_ms: u64,
 }
 
-#[derive(Parser, Debug, Deserialize)]
+#[derive(Parser, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct MouseMoveArgs {
     #[arg(long, help = "Absolute coordinates as x,y; requires --headed")]
     pub xy: String,
 }
 
-#[derive(Parser, Debug, Deserialize)]
+#[derive(Parser, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct MouseClickArgs {
     #[arg(long, help = "Absolute coordinates as x,y; requires --headed")]
@@ -228,7 +252,7 @@ pub(crate) struct MouseClickArgs {
     pub modifiers: Vec<String>,
 }
 
-#[derive(Parser, Debug, Deserialize)]
+#[derive(Parser, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct MousePointArgs {
     #[arg(long, help = "Absolute coordinates as x,y; requires --headed")]
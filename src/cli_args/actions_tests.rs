@@ -28,6 +28,27 @@ fn type_args_batch_json_omitted_timeout_defaults_to_5000() {
     assert_eq!(args.timeout_ms, 5000);
 }
 
+#[test]
+fn type_args_take_either_text_or_a_secret_name() {
+    let args = TypeArgs::try_parse_from(["type", "@e1", "--secret", "github-token"]).unwrap();
+    assert_eq!(args.secret.as_deref(), Some("github-token"));
+    assert_eq!(args.text, None);
+
+    assert!(TypeArgs::try_parse_from(["type", "@e1", "hello", "--secret", "github"]).is_err());
+    assert!(TypeArgs::try_parse_from(["type", "@e1"]).is_err());
+}
+
+#[test]
+fn set_value_args_take_either_a_value_or_a_secret_name() {
+    let args = SetValueArgs::try_parse_from(["set-value", "@e1", "--secret", "db"]).unwrap();
+    assert_eq!(args.secret.as_deref(), Some("db"));
+
+    assert!(SetValueArgs::try_parse_from(["set-value", "@e1", "v", "--secret", "db"]).is_err());
+    let args: SetValueArgs =
+        serde_json::from_value(serde_json::json!({ "ref_id": "@e1", "secret": "db" })).unwrap();
+    assert_eq!(args.value, None);
+}
+
 #[test]
 fn set_value_args_cli_omitted_timeout_defaults_to_5000() {
     let args = SetValueArgs::try_parse_from(["set-value", "@e1", "value"]).unwrap();
@@ -0,0 +1,40 @@
+use clap::{Args, Subcommand};
+
+#[derive(Args, Debug)]
+pub(crate) struct AliasArgs {
+    #[command(subcommand)]
+    pub action: AliasAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum AliasAction {
+    #[command(about = "Name an element so @NAME resolves in later commands of this session")]
+    Set(AliasSetArgs),
+    #[command(about = "List ref aliases in this session")]
+    List,
+    #[command(about = "Remove a ref alias")]
+    Rm(AliasRmArgs),
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct AliasSetArgs {
+    #[arg(help = "Alias name: lowercase letters, digits, '-' or '_', starting with a letter")]
+    pub name: String,
+    #[arg(
+        value_name = "REF",
+        help = "Qualified ref (@<snapshot_id>:eN) whose identity to store"
+    )]
+    pub ref_id: String,
+    #[arg(
+        long,
+        value_name = "SNAPSHOT_ID",
+        help = "Snapshot ID required for a legacy bare @eN ref; omit for a qualified ref"
+    )]
+    pub snapshot: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct AliasRmArgs {
+    #[arg(help = "Alias name to remove")]
+    pub name: String,
+}
@@ -0,0 +1,52 @@
+use clap::{Parser, ValueEnum};
+use serde::{Deserialize, Serialize};
+
+use super::WindowScope;
+
+fn default_max_depth() -> u8 {
+    10
+}
+
+#[derive(Parser, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct AuditA11yArgs {
+    #[command(flatten)]
+    #[serde(flatten)]
+    pub scope: WindowScope,
+    #[arg(long, default_value = "10", help = "Maximum tree depth to audit")]
+    #[serde(default = "default_max_depth")]
+    pub max_depth: u8,
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = AuditFormatArg::Json,
+        help = "Report rendering: json findings, or a JUnit XML string in data.content"
+    )]
+    #[serde(default)]
+    pub format: AuditFormatArg,
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Also write the JUnit XML report to this file"
+    )]
+    #[serde(default)]
+    pub junit: Option<std::path::PathBuf>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum AuditFormatArg {
+    #[default]
+    Json,
+    Junit,
+}
+
+impl AuditFormatArg {
+    pub(crate) fn to_core(self) -> agent_desktop_core::commands::audit_a11y::AuditFormat {
+        use agent_desktop_core::commands::audit_a11y::AuditFormat;
+        match self {
+            Self::Json => AuditFormat::Json,
+            Self::Junit => AuditFormat::Junit,
+        }
+    }
+}
@@ -2,10 +2,19 @@ use clap::Parser;
 
 #[derive(Parser, Debug)]
 pub(crate) struct BatchArgs {
-    #[arg(value_name = "JSON", help = "JSON array of {command, args} objects")]
+    #[arg(
+        value_name = "JSON",
+        help = "JSON array of {command, args, capture} entries and if/repeat/retry blocks"
+    )]
     pub commands_json: String,
     #[arg(long, help = "Halt the batch on the first failed command")]
     pub stop_on_error: bool,
+    #[arg(
+        long = "handlers",
+        value_name = "JSON",
+        help = "JSON array of {on-surface, text, click, app} alert/sheet handlers checked before every entry"
+    )]
+    pub handlers_json: Option<String>,
     #[arg(
         long,
         default_value = "60000",
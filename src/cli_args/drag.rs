@@ -1,5 +1,5 @@
 use clap::Parser;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use super::drag_target::DragTargetArgs;
 
@@ -7,7 +7,7 @@ fn default_ref_timeout_ms() -> u64 {
     5000
 }
 
-#[derive(Parser, Debug, Deserialize)]
+#[derive(Parser, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct DragCliArgs {
     #[command(flatten)]
@@ -1,7 +1,7 @@
 use clap::Args;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Args, Debug, Deserialize)]
+#[derive(Args, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct DragTargetArgs {
     #[arg(long, help = "Source element ref; requires --headed")]
@@ -0,0 +1,63 @@
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+
+use super::WindowScope;
+
+fn default_expect_timeout_ms() -> u64 {
+    5000
+}
+
+#[derive(Parser, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct ExpectArgs {
+    #[arg(
+        value_name = "TARGET",
+        help = "Qualified ref (@<snapshot_id>:eN), legacy @eN with --snapshot, or a role:text locator"
+    )]
+    pub target: String,
+    #[arg(
+        value_name = "MATCHER",
+        num_args = 1..=3,
+        required = true,
+        allow_negative_numbers = true,
+        help = "[not] to-have-value X | to-have-text X | to-be-checked | to-be-enabled | to-be-visible | to-be-focused | to-have-count N | to-match-screenshot BASELINE.png"
+    )]
+    pub matcher: Vec<String>,
+    #[arg(
+        long,
+        value_name = "SNAPSHOT_ID",
+        help = "Snapshot ID required for a legacy bare @eN ref; omit for a qualified ref"
+    )]
+    #[serde(default)]
+    pub snapshot: Option<String>,
+    #[command(flatten)]
+    #[serde(flatten)]
+    pub scope: WindowScope,
+    #[arg(
+        long = "timeout-ms",
+        default_value_t = 5000,
+        help = "Keep re-checking for this long before failing with ASSERTION_FAILED"
+    )]
+    #[serde(default = "default_expect_timeout_ms")]
+    pub timeout_ms: u64,
+    #[arg(
+        long,
+        value_name = "SCORE",
+        help = "to-match-screenshot: largest share of differing pixels (0-1) that passes [default: 0.001]"
+    )]
+    #[serde(default)]
+    pub threshold: Option<f64>,
+    #[arg(
+        long,
+        value_name = "REGION",
+        help = "to-match-screenshot: leave out x,y,width,height in crop pixels, or a ref's bounds; repeatable"
+    )]
+    #[serde(default)]
+    pub ignore: Vec<String>,
+    #[arg(
+        long,
+        help = "Compare and report the value of a secure text field or configured secure locator (sensitive)"
+    )]
+    #[serde(default)]
+    pub reveal_secure: bool,
+}
@@ -0,0 +1,43 @@
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+
+fn default_max_depth() -> u8 {
+    10
+}
+
+fn default_max_rows() -> usize {
+    100
+}
+
+#[derive(Parser, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct ExtractTableArgs {
+    #[arg(
+        value_name = "REF",
+        help = "Ref of a table, grid, outline, list, or a container holding one"
+    )]
+    pub ref_id: String,
+    #[arg(
+        long,
+        value_name = "SNAPSHOT_ID",
+        help = "Snapshot ID required for a legacy bare @eN ref; omit for a qualified ref"
+    )]
+    pub snapshot: Option<String>,
+    #[arg(long, default_value = "10", help = "Maximum depth read below the ref")]
+    #[serde(default = "default_max_depth")]
+    pub max_depth: u8,
+    #[arg(
+        long,
+        value_name = "N",
+        default_value = "100",
+        help = "Maximum rows returned"
+    )]
+    #[serde(default = "default_max_rows")]
+    pub max_rows: usize,
+    #[arg(
+        long,
+        help = "Scroll the last collected row into view and re-read until no new rows appear"
+    )]
+    #[serde(default)]
+    pub scroll: bool,
+}
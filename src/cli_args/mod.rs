@@ -1,15 +1,22 @@
 use clap::{Args, Parser, ValueEnum};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 pub(crate) mod actions;
+pub(crate) mod alias;
+pub(crate) mod audit;
 pub(crate) mod batch;
 pub(crate) mod drag;
 pub(crate) mod drag_target;
+pub(crate) mod expect;
+pub(crate) mod extract_table;
 pub(crate) mod mouse_wheel;
 pub(crate) mod notifications;
+pub(crate) mod read_text;
+pub(crate) mod run;
+pub(crate) mod screenshot_compare;
 pub(crate) mod session;
 pub(crate) mod skills;
-mod snapshot;
+pub(crate) mod snapshot;
 pub(crate) mod snapshot_tree;
 pub(crate) mod system;
 pub(crate) mod trace;
@@ -24,7 +31,7 @@ fn default_is_property() -> String {
     "visible".to_string()
 }
 
-#[derive(ValueEnum, Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[derive(ValueEnum, Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
 #[serde(rename_all = "kebab-case")]
 pub(crate) enum Surface {
     #[default]
@@ -81,7 +88,7 @@ impl Surface {
 /// (`click`/`type`/`get`/`is`) deliberately omit it — a ref already carries its
 /// source window through its `RefEntry` — and keyboard input targets the focused
 /// window, so neither needs an explicit window selector.
-#[derive(Args, Debug, Deserialize, Default)]
+#[derive(Args, Debug, Deserialize, Serialize, Default)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct WindowScope {
     #[arg(long, help = "Filter to application by name")]
@@ -98,7 +105,7 @@ pub(crate) struct WindowScope {
 
 /// Match-criteria fields, grouped out of [`FindArgs`] to keep it under the
 /// repo's field-count limit. Mirrors `core::commands::find::FindFilterArgs`.
-#[derive(Args, Debug, Deserialize)]
+#[derive(Args, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct FindFilterArgs {
     #[arg(
@@ -128,7 +135,7 @@ pub(crate) struct FindFilterArgs {
 /// repo's field-count limit. Mutually exclusive at the CLI layer (enforced
 /// via `conflicts_with_all` on each field, unaffected by the grouping since
 /// clap arg ids stay global across `#[command(flatten)]` boundaries).
-#[derive(Args, Debug, Deserialize)]
+#[derive(Args, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct FindSelectionArgs {
     #[arg(
@@ -166,7 +173,7 @@ pub(crate) struct FindSelectionArgs {
     pub limit: Option<usize>,
 }
 
-#[derive(Parser, Debug, Deserialize)]
+#[derive(Parser, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct FindArgs {
     #[command(flatten)]
@@ -204,9 +211,15 @@ pub(crate) struct FindArgs {
     #[command(flatten)]
     #[serde(flatten)]
     pub selection: FindSelectionArgs,
+    #[arg(
+        long,
+        help = "Report the values of secure text fields and configured secure locators (sensitive)"
+    )]
+    #[serde(default)]
+    pub reveal_secure: bool,
 }
 
-#[derive(Parser, Debug, Deserialize)]
+#[derive(Parser, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct ScreenshotArgs {
     #[command(flatten)]
@@ -219,9 +232,16 @@ pub(crate) struct ScreenshotArgs {
     pub screen: Option<usize>,
     #[arg(value_name = "PATH", help = "Save to file instead of returning base64")]
     pub output_path: Option<std::path::PathBuf>,
+    #[arg(
+        long,
+        value_name = "SNAPSHOT_ID",
+        help = "Draw numbered boxes for the snapshot's refs and return a legend; captures the snapshot's window unless a target is given"
+    )]
+    #[serde(default)]
+    pub annotate: Option<String>,
 }
 
-#[derive(Parser, Debug, Deserialize)]
+#[derive(Parser, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct GetArgs {
     #[arg(
@@ -242,9 +262,15 @@ pub(crate) struct GetArgs {
     )]
     #[serde(default = "default_get_property")]
     pub property: String,
+    #[arg(
+        long,
+        help = "Read the value of a secure text field or configured secure locator (sensitive)"
+    )]
+    #[serde(default)]
+    pub reveal_secure: bool,
 }
 
-#[derive(Parser, Debug, Deserialize)]
+#[derive(Parser, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct IsArgs {
     #[arg(
@@ -267,7 +293,7 @@ pub(crate) struct IsArgs {
     pub property: String,
 }
 
-#[derive(Parser, Debug, Deserialize)]
+#[derive(Parser, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct RefArgs {
     #[arg(
@@ -295,7 +321,7 @@ fn default_ref_timeout_ms() -> u64 {
     5000
 }
 
-#[derive(Parser, Debug, Deserialize)]
+#[derive(Parser, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct ListSurfacesArgs {
     #[arg(long, help = "Filter to application by name")]
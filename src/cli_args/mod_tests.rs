@@ -248,3 +248,32 @@ fn screenshot_args_batch_json_rejects_scope_field_typo() {
     .unwrap_err();
     assert!(err.to_string().contains("`windo_id`"));
 }
+
+/// `expect` takes its matcher as trailing words so `not` and the expected
+/// value need no flags; a batch entry sends the same words as an array.
+#[test]
+fn expect_args_take_the_matcher_phrase_from_cli_words_and_batch_arrays() {
+    let args = expect::ExpectArgs::try_parse_from([
+        "expect",
+        "@s1:e2",
+        "not",
+        "to-have-value",
+        "-1",
+        "--timeout-ms",
+        "250",
+    ])
+    .unwrap();
+    assert_eq!(args.matcher, ["not", "to-have-value", "-1"]);
+    assert_eq!(args.timeout_ms, 250);
+
+    let args: expect::ExpectArgs = serde_json::from_value(serde_json::json!({
+        "target": "button:Save",
+        "matcher": ["to-have-count", "2"],
+        "app": "TextEdit"
+    }))
+    .unwrap();
+    assert_eq!(args.scope.app.as_deref(), Some("TextEdit"));
+    assert_eq!(args.timeout_ms, 5000);
+
+    assert!(expect::ExpectArgs::try_parse_from(["expect", "@s1:e2"]).is_err());
+}
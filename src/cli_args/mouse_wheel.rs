@@ -1,11 +1,11 @@
 use clap::Parser;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 fn default_wheel_delta() -> f64 {
     -3.0
 }
 
-#[derive(Parser, Debug, Deserialize)]
+#[derive(Parser, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct MouseWheelArgs {
     #[arg(long, help = "Absolute X coordinate for wheel event")]
@@ -1,7 +1,7 @@
 use clap::Parser;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Parser, Debug, Deserialize)]
+#[derive(Parser, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct ListNotificationsCliArgs {
     #[arg(long, help = "Filter to notifications from this app")]
@@ -12,7 +12,7 @@ pub(crate) struct ListNotificationsCliArgs {
     pub limit: Option<usize>,
 }
 
-#[derive(Parser, Debug, Deserialize)]
+#[derive(Parser, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct DismissNotificationCliArgs {
     #[arg(value_name = "INDEX", help = "1-based notification index from list-notifications",
@@ -34,14 +34,14 @@ pub(crate) struct DismissNotificationCliArgs {
     pub expected_title: Option<String>,
 }
 
-#[derive(Parser, Debug, Deserialize)]
+#[derive(Parser, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct DismissAllNotificationsCliArgs {
     #[arg(long, help = "Only dismiss notifications from this app")]
     pub app: Option<String>,
 }
 
-#[derive(Parser, Debug, Deserialize)]
+#[derive(Parser, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct NotificationActionCliArgs {
     #[arg(value_name = "INDEX", help = "1-based notification index from list-notifications",
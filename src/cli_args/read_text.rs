@@ -0,0 +1,51 @@
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+
+use super::WindowScope;
+
+fn default_max_depth() -> u8 {
+    20
+}
+
+fn default_max_chars() -> usize {
+    20_000
+}
+
+#[derive(Parser, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct ReadTextArgs {
+    #[command(flatten)]
+    #[serde(flatten)]
+    pub scope: WindowScope,
+    #[arg(
+        long,
+        help = "Read only this ref's subtree instead of the whole window"
+    )]
+    #[serde(default)]
+    pub root: Option<String>,
+    #[arg(
+        long,
+        value_name = "SNAPSHOT_ID",
+        help = "Snapshot ID to use when resolving a legacy bare --root ref"
+    )]
+    #[serde(default)]
+    pub snapshot: Option<String>,
+    #[arg(long, default_value = "20", help = "Maximum tree depth to read")]
+    #[serde(default = "default_max_depth")]
+    pub max_depth: u8,
+    #[arg(
+        long,
+        value_name = "N",
+        default_value = "20000",
+        help = "Maximum characters of markdown per page"
+    )]
+    #[serde(default = "default_max_chars")]
+    pub max_chars: usize,
+    #[arg(
+        long,
+        value_name = "CURSOR",
+        help = "Continue from the next_cursor of a previous read-text page"
+    )]
+    #[serde(default)]
+    pub cursor: Option<String>,
+}
@@ -0,0 +1,29 @@
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+pub(crate) struct RunArgs {
+    #[arg(
+        value_name = "FILE",
+        help = "Scenario file: YAML, or JSON when the extension is .json"
+    )]
+    pub file: PathBuf,
+    #[arg(
+        long = "param",
+        value_name = "KEY=VALUE",
+        help = "Value for a declared ${KEY} scenario parameter (repeatable)"
+    )]
+    pub params: Vec<String>,
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Also write a JUnit XML report with one testcase per step"
+    )]
+    pub junit: Option<PathBuf>,
+    #[arg(
+        long,
+        default_value = "300000",
+        help = "Absolute wall-clock budget for the whole scenario"
+    )]
+    pub timeout_ms: u64,
+}
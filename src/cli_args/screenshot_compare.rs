@@ -0,0 +1,33 @@
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Parser, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct ScreenshotCompareArgs {
+    #[arg(value_name = "EXPECTED", help = "Baseline PNG")]
+    pub expected: PathBuf,
+    #[arg(value_name = "ACTUAL", help = "PNG to compare against the baseline")]
+    pub actual: PathBuf,
+    #[arg(
+        long,
+        value_name = "SCORE",
+        help = "Largest share of differing pixels (0-1) that still passes [default: 0.001]"
+    )]
+    #[serde(default)]
+    pub threshold: Option<f64>,
+    #[arg(
+        long,
+        value_name = "REGION",
+        help = "Leave out x,y,width,height in image pixels, or a ref's bounds; repeatable"
+    )]
+    #[serde(default)]
+    pub ignore: Vec<String>,
+    #[arg(
+        long = "diff",
+        value_name = "PATH",
+        help = "Write a PNG mask with differing pixels in red"
+    )]
+    #[serde(default, rename = "diff")]
+    pub diff_path: Option<PathBuf>,
+}
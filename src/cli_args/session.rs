@@ -1,4 +1,5 @@
 use clap::{Args, Subcommand};
+use std::path::PathBuf;
 
 #[derive(Args, Debug)]
 pub(crate) struct SessionArgs {
@@ -16,6 +17,12 @@ pub(crate) enum SessionAction {
     List,
     #[command(about = "Remove ended or provably-stale sessions")]
     Gc(SessionGcArgs),
+    #[command(about = "Pack a session into a portable .tar.zst bundle")]
+    Export(SessionExportArgs),
+    #[command(about = "Register a session bundle as a read-only session")]
+    Import(SessionImportArgs),
+    #[command(about = "Show or set the global session quota")]
+    Quota(SessionQuotaCommandArgs),
 }
 
 #[derive(Args, Debug)]
@@ -29,6 +36,61 @@ pub(crate) struct SessionStartArgs {
         help = "Capture pre/post-action screenshots and refmap copies (requires tracing; sensitive)"
     )]
     pub screenshots: bool,
+    #[command(flatten)]
+    pub quota: SessionQuotaArgs,
+}
+
+/// Limits shared by `session start` (this session) and `session quota`
+/// (every session without its own value).
+#[derive(Args, Debug, Default)]
+pub(crate) struct SessionQuotaArgs {
+    #[arg(
+        long,
+        help = "Stop tracing once the session's segments reach this many bytes"
+    )]
+    pub max_trace_bytes: Option<u64>,
+    #[arg(
+        long,
+        help = "Skip screenshots beyond this many per session (default 200)"
+    )]
+    pub max_screenshots: Option<u32>,
+    #[arg(
+        long,
+        help = "Skip screenshots beyond this many bytes per session (default 128 MiB)"
+    )]
+    pub max_screenshot_bytes: Option<u64>,
+    #[arg(
+        long = "max-age",
+        value_name = "SECS",
+        help = "Collect the session this many seconds after it ended (or started) on a later `session start`"
+    )]
+    pub max_age_secs: Option<u64>,
+}
+
+impl SessionQuotaArgs {
+    pub(crate) fn to_quota(&self) -> agent_desktop_core::session::SessionQuota {
+        agent_desktop_core::session::SessionQuota {
+            max_trace_bytes: self.max_trace_bytes,
+            max_screenshots: self.max_screenshots,
+            max_screenshot_bytes: self.max_screenshot_bytes,
+            max_age_secs: self.max_age_secs,
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.to_quota().is_empty()
+    }
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct SessionQuotaCommandArgs {
+    #[command(flatten)]
+    pub quota: SessionQuotaArgs,
+    #[arg(
+        long,
+        help = "Drop every stored global limit before applying the flags"
+    )]
+    pub clear: bool,
 }
 
 #[derive(Args, Debug)]
@@ -47,3 +109,23 @@ pub(crate) struct SessionGcArgs {
     #[arg(long, help = "Only consider sessions that already have ended_at set")]
     pub ended: bool,
 }
+
+#[derive(Args, Debug)]
+pub(crate) struct SessionExportArgs {
+    #[arg(help = "Session id to export")]
+    pub id: String,
+    #[arg(long, help = "Bundle path (default session-<id>.tar.zst)")]
+    pub out: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct SessionImportArgs {
+    #[arg(help = "Bundle written by `session export`")]
+    pub path: PathBuf,
+    #[arg(
+        long = "as",
+        value_name = "ID",
+        help = "Session id to register the bundle under (default: its original id)"
+    )]
+    pub as_id: Option<String>,
+}
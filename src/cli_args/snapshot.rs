@@ -1,9 +1,9 @@
-use clap::Parser;
-use serde::Deserialize;
+use clap::{Parser, ValueEnum};
+use serde::{Deserialize, Serialize};
 
 use super::{Surface, WindowScope, snapshot_tree::SnapshotTreeArgs};
 
-#[derive(Parser, Debug, Deserialize)]
+#[derive(Parser, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct SnapshotArgs {
     #[command(flatten)]
@@ -28,4 +28,44 @@ pub(crate) struct SnapshotArgs {
         help = "Snapshot ID to use when resolving --root"
     )]
     pub snapshot: Option<String>,
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = SnapshotFormatArg::Json,
+        help = "Tree rendering: json, or a line-oriented outline / yaml string in data.content"
+    )]
+    #[serde(default)]
+    pub format: SnapshotFormatArg,
+    #[arg(
+        long,
+        help = "Keep the previous snapshot's ref numbers for elements whose identity still matches; report retired refs"
+    )]
+    #[serde(default)]
+    pub stable_refs: bool,
+    #[arg(
+        long,
+        help = "Show the values of secure text fields and configured secure locators (sensitive)"
+    )]
+    #[serde(default)]
+    pub reveal_secure: bool,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum SnapshotFormatArg {
+    #[default]
+    Json,
+    Outline,
+    Yaml,
+}
+
+impl SnapshotFormatArg {
+    pub(crate) fn to_core(self) -> agent_desktop_core::SnapshotFormat {
+        use agent_desktop_core::SnapshotFormat;
+        match self {
+            Self::Json => SnapshotFormat::Json,
+            Self::Outline => SnapshotFormat::Outline,
+            Self::Yaml => SnapshotFormat::Yaml,
+        }
+    }
 }
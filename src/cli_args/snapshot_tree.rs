@@ -1,11 +1,11 @@
 use clap::Args;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 fn default_max_depth() -> u8 {
     10
 }
 
-#[derive(Args, Debug, Deserialize)]
+#[derive(Args, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct SnapshotTreeArgs {
     #[arg(long, default_value = "10", help = "Maximum tree depth")]
@@ -29,4 +29,11 @@ pub(crate) struct SnapshotTreeArgs {
     )]
     #[serde(default)]
     pub skeleton: bool,
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Greedily truncate the tree to about N estimated tokens, keeping interactive, named, and focused branches"
+    )]
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
 }
@@ -1,5 +1,5 @@
 use clap::{Args, Parser};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use super::WindowScope;
 
@@ -11,7 +11,7 @@ fn default_wait_timeout() -> u64 {
     30000
 }
 
-#[derive(Parser, Debug, Deserialize)]
+#[derive(Parser, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct LaunchArgs {
     #[arg(value_name = "APP", help = "Application name or bundle ID")]
@@ -63,7 +63,7 @@ pub(crate) struct LaunchArgs {
     pub cdp: Option<u16>,
 }
 
-#[derive(Parser, Debug, Deserialize)]
+#[derive(Parser, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct CloseAppArgs {
     #[arg(value_name = "APP", help = "Application name")]
@@ -76,21 +76,21 @@ pub(crate) struct CloseAppArgs {
     pub force: bool,
 }
 
-#[derive(Parser, Debug, Deserialize)]
+#[derive(Parser, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct ListWindowsArgs {
     #[arg(long, help = "Filter to application by exact case-insensitive name")]
     pub app: Option<String>,
 }
 
-#[derive(Parser, Debug, Deserialize)]
+#[derive(Parser, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct ListAppsArgs {
     #[arg(long, help = "Filter to application by name")]
     pub app: Option<String>,
 }
 
-#[derive(Parser, Debug, Deserialize)]
+#[derive(Parser, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct FocusWindowArgs {
     #[arg(long, name = "window-id", help = "Window ID from list-windows")]
@@ -101,7 +101,7 @@ pub(crate) struct FocusWindowArgs {
     pub title: Option<String>,
 }
 
-#[derive(Parser, Debug, Deserialize)]
+#[derive(Parser, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct ResizeWindowCliArgs {
     #[command(flatten)]
@@ -113,7 +113,7 @@ pub(crate) struct ResizeWindowCliArgs {
     pub height: f64,
 }
 
-#[derive(Parser, Debug, Deserialize)]
+#[derive(Parser, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct MoveWindowCliArgs {
     #[command(flatten)]
@@ -125,7 +125,7 @@ pub(crate) struct MoveWindowCliArgs {
     pub y: f64,
 }
 
-#[derive(Parser, Debug, Deserialize)]
+#[derive(Parser, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct AppRefArgs {
     #[command(flatten)]
@@ -133,7 +133,7 @@ pub(crate) struct AppRefArgs {
     pub scope: WindowScope,
 }
 
-#[derive(Parser, Debug, Deserialize)]
+#[derive(Parser, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct ClipboardGetArgs {
     #[arg(
@@ -150,7 +150,7 @@ pub(crate) struct ClipboardGetArgs {
     pub out: Option<std::path::PathBuf>,
 }
 
-#[derive(Parser, Debug, Deserialize)]
+#[derive(Parser, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct ClipboardSetArgs {
     #[arg(
@@ -183,7 +183,7 @@ pub(crate) struct ClipboardSetArgs {
 /// `predicate` split) sidesteps that limitation; the CLI surface is
 /// unaffected since `#[command(flatten)]` merges flags regardless of Rust
 /// struct nesting depth.
-#[derive(Parser, Debug, Deserialize)]
+#[derive(Parser, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct WaitArgs {
     #[command(flatten)]
@@ -209,7 +209,7 @@ pub(crate) struct WaitArgs {
 /// The `--event`/`--window-id` pair, grouped out of [`WaitModeArgs`] to keep
 /// it under the repo's field-count limit. `window_id` only ever narrows an
 /// `--event` wait, so the two travel together.
-#[derive(Args, Debug, Deserialize)]
+#[derive(Args, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct WaitEventArgs {
     #[arg(
@@ -225,7 +225,7 @@ pub(crate) struct WaitEventArgs {
     pub window_id: Option<String>,
 }
 
-#[derive(Args, Debug, Deserialize)]
+#[derive(Args, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct WaitModeArgs {
     #[arg(value_name = "MS", help = "Milliseconds to pause")]
@@ -253,7 +253,7 @@ pub(crate) struct WaitModeArgs {
     pub notification: bool,
 }
 
-#[derive(Args, Debug, Deserialize)]
+#[derive(Args, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct WaitPredicateArgs {
     #[arg(
@@ -288,7 +288,7 @@ pub(crate) struct WaitPredicateArgs {
     pub count: Option<usize>,
 }
 
-#[derive(Parser, Debug, Deserialize)]
+#[derive(Parser, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct PermissionsArgs {
     #[arg(
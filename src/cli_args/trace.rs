@@ -1,4 +1,4 @@
-use clap::{Args, Subcommand};
+use clap::{Args, Subcommand, ValueEnum};
 use std::path::PathBuf;
 
 #[derive(Args, Debug)]
@@ -13,6 +13,14 @@ pub(crate) enum TraceAction {
     Show(TraceShowArgs),
     #[command(about = "Export a self-contained HTML trace viewer")]
     Export(TraceExportArgs),
+    #[command(about = "Summarize a session's command latency, failures, waits, and delivery")]
+    Stats(TraceStatsArgs),
+    #[command(about = "Align two recorded sessions and report where they diverge")]
+    Diff(TraceDiffArgs),
+    #[command(about = "Re-run a recorded session's commands and compare outcomes")]
+    Replay(TraceReplayArgs),
+    #[command(about = "Convert a recorded session into an editable batch or scenario script")]
+    ToScript(TraceToScriptArgs),
 }
 
 #[derive(Args, Debug)]
@@ -25,6 +33,11 @@ pub(crate) struct TraceShowArgs {
     pub limit: usize,
     #[arg(long, help = "Filter events by name prefix before applying --limit")]
     pub event: Option<String>,
+    #[arg(
+        long,
+        help = "Keep streaming new events as JSONL lines until the session ends"
+    )]
+    pub follow: bool,
 }
 
 #[derive(Args, Debug)]
@@ -38,3 +51,58 @@ pub(crate) struct TraceExportArgs {
     )]
     pub limit: usize,
 }
+
+#[derive(Args, Debug)]
+pub(crate) struct TraceStatsArgs {
+    #[arg(help = "Session whose trace to summarize")]
+    pub session: String,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct TraceDiffArgs {
+    #[arg(help = "Baseline session (A)")]
+    pub a: String,
+    #[arg(help = "Session to compare against the baseline (B)")]
+    pub b: String,
+    #[arg(
+        long,
+        default_value_t = agent_desktop_core::trace_diff::DEFAULT_LATENCY_SHIFT_MS,
+        help = "Smallest duration change, in ms, reported as a latency shift (default 500)"
+    )]
+    pub latency_ms: u64,
+    #[arg(long, help = "Also write an HTML view of the diff to this path")]
+    pub out: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct TraceReplayArgs {
+    #[arg(help = "Session whose trace to replay")]
+    pub session: String,
+    #[arg(long, help = "First recorded step to replay (1-based; default 1)")]
+    pub from: Option<usize>,
+    #[arg(long, help = "Last recorded step to replay (default last)")]
+    pub to: Option<usize>,
+    #[arg(long, help = "List the steps and their refs without running anything")]
+    pub dry_run: bool,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ScriptFormat {
+    Batch,
+    Yaml,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct TraceToScriptArgs {
+    #[arg(help = "Session whose trace to convert")]
+    pub session: String,
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = ScriptFormat::Yaml,
+        help = "batch: JSON array for `batch`; yaml: scenario file for `run`"
+    )]
+    pub format: ScriptFormat,
+    #[arg(long, help = "Write the script to this path instead of returning it")]
+    pub out: Option<PathBuf>,
+}
@@ -16,23 +16,36 @@ pub(crate) fn policy_for(cmd: &Commands) -> PermissionNeed {
     use PermissionNeed::{Accessibility, AccessibilityAndScreenRecording, None, ScreenRecording};
     match cmd {
         Commands::Version | Commands::Skills(_) | Commands::Session(_) | Commands::Trace(_) => None,
+        Commands::Alias(_) => None,
         Commands::Status | Commands::Permissions(_) => None,
         Commands::ListWindows(_) | Commands::ListDisplays | Commands::ListApps(_) => None,
         Commands::ClipboardGet(_) | Commands::ClipboardSet(_) | Commands::ClipboardClear => None,
-        Commands::Batch(_) => None,
+        Commands::Batch(_) | Commands::Run(_) => None,
 
         Commands::Snapshot(_)
         | Commands::Find(_)
+        | Commands::AuditA11y(_)
+        | Commands::ReadText(_)
         | Commands::ListSurfaces(_)
         | Commands::Wait(_)
         | Commands::ListNotifications(_) => Accessibility,
 
-        Commands::Screenshot(a) if a.scope.app.is_some() || a.scope.window_id.is_some() => {
+        Commands::Screenshot(a)
+            if a.scope.app.is_some() || a.scope.window_id.is_some() || a.annotate.is_some() =>
+        {
             AccessibilityAndScreenRecording
         }
         Commands::Screenshot(_) => ScreenRecording,
+        Commands::ScreenshotCompare(a) if a.ignore.iter().any(|v| v.starts_with('@')) => {
+            Accessibility
+        }
+        Commands::ScreenshotCompare(_) => None,
 
-        Commands::Get(_) | Commands::Is(_) => Accessibility,
+        Commands::Get(_) | Commands::Is(_) | Commands::ExtractTable(_) => Accessibility,
+        Commands::Expect(a) if a.matcher.iter().any(|word| word == "to-match-screenshot") => {
+            AccessibilityAndScreenRecording
+        }
+        Commands::Expect(_) => Accessibility,
 
         Commands::Click(_)
         | Commands::DoubleClick(_)
@@ -110,8 +123,9 @@ pub(crate) fn requires_permission_report(cmd: &Commands) -> bool {
     policy_for(cmd) != PermissionNeed::None
         || matches!(
             cmd,
-            Commands::Status | Commands::Permissions(_) | Commands::Batch(_)
+            Commands::Status | Commands::Permissions(_) | Commands::Batch(_) | Commands::Run(_)
         )
+        || matches!(cmd, Commands::Trace(args) if matches!(args.action, crate::cli_args::trace::TraceAction::Replay(_)))
 }
 
 fn validate_args(cmd: &Commands) -> Result<(), AppError> {
@@ -129,9 +143,24 @@ fn validate_args(cmd: &Commands) -> Result<(), AppError> {
         Commands::Get(args) => {
             validate_ref_id(&args.ref_id)?;
         }
+        Commands::ExtractTable(args) => {
+            validate_ref_id(&args.ref_id)?;
+        }
+        Commands::ReadText(args) => {
+            if let Some(root) = &args.root {
+                validate_ref_id(root)?;
+            }
+        }
         Commands::Is(args) => {
             validate_ref_id(&args.ref_id)?;
         }
+        Commands::Expect(args) => {
+            if args.target.starts_with('@') {
+                validate_ref_id(&args.target)?;
+            }
+            validate_ignore_refs(&args.ignore)?;
+        }
+        Commands::ScreenshotCompare(args) => validate_ignore_refs(&args.ignore)?,
         Commands::Click(args)
         | Commands::DoubleClick(args)
         | Commands::TripleClick(args)
@@ -170,6 +199,11 @@ fn validate_args(cmd: &Commands) -> Result<(), AppError> {
                 validate_ref_id(ref_id)?;
             }
         }
+        Commands::Alias(args) => {
+            if let crate::cli_args::alias::AliasAction::Set(set) = &args.action {
+                validate_ref_id(&set.ref_id)?;
+            }
+        }
         Commands::DismissNotification(args)
             if args.expected_app.as_deref().is_none_or(str::is_empty)
                 && args.expected_title.as_deref().is_none_or(str::is_empty) =>
@@ -187,6 +221,7 @@ fn validate_args(cmd: &Commands) -> Result<(), AppError> {
             ));
         }
         Commands::Find(_)
+        | Commands::AuditA11y(_)
         | Commands::Screenshot(_)
         | Commands::Press(_)
         | Commands::KeyDown(_)
@@ -219,6 +254,7 @@ fn validate_args(cmd: &Commands) -> Result<(), AppError> {
         | Commands::Permissions(_)
         | Commands::Version
         | Commands::Batch(_)
+        | Commands::Run(_)
         | Commands::Skills(_)
         | Commands::Session(_)
         | Commands::Trace(_) => {}
@@ -226,6 +262,13 @@ fn validate_args(cmd: &Commands) -> Result<(), AppError> {
     Ok(())
 }
 
+fn validate_ignore_refs(ignore: &[String]) -> Result<(), AppError> {
+    for region in ignore.iter().filter(|region| region.starts_with('@')) {
+        validate_ref_id(region)?;
+    }
+    Ok(())
+}
+
 fn requires_accessibility(permission: PermissionNeed) -> bool {
     matches!(
         permission,
@@ -14,6 +14,7 @@ fn permission_report_is_collected_only_for_permission_consumers() {
     let batch = Commands::Batch(crate::cli_args::batch::BatchArgs {
         commands_json: "[]".into(),
         stop_on_error: false,
+        handlers_json: None,
         timeout_ms: 1,
     });
 
@@ -32,6 +33,7 @@ fn unknown_permission_does_not_mask_platform_errors() {
         },
         screen: None,
         output_path: None,
+        annotate: None,
     });
 
     assert!(preflight(&command, &report).is_ok());
@@ -53,6 +55,7 @@ fn screen_recording_denial_is_preflighted() {
         },
         screen: None,
         output_path: None,
+        annotate: None,
     });
 
     let err = preflight(&command, &report).expect_err("denied screen capture fails");
@@ -121,10 +124,14 @@ fn invalid_snapshot_root_is_rejected_before_permission_preflight() {
             interactive_only: false,
             compact: false,
             skeleton: false,
+            max_tokens: None,
         },
         surface: crate::cli_args::Surface::Window,
         root: Some("bad-root".into()),
         snapshot: None,
+        format: crate::cli_args::snapshot::SnapshotFormatArg::Json,
+        stable_refs: false,
+        reveal_secure: false,
     });
 
     let err = preflight(&command, &report).expect_err("invalid root fails first");
@@ -197,7 +204,45 @@ fn trace_show_passes_preflight_with_permissions_denied() {
         action: crate::cli_args::trace::TraceAction::Show(crate::cli_args::trace::TraceShowArgs {
             limit: 500,
             event: None,
+            follow: false,
         }),
     });
     assert!(preflight(&command, &report).is_ok());
 }
+
+#[test]
+fn trace_replay_needs_the_permission_report_its_steps_preflight_against() {
+    let replay = Commands::Trace(crate::cli_args::trace::TraceArgs {
+        action: crate::cli_args::trace::TraceAction::Replay(
+            crate::cli_args::trace::TraceReplayArgs {
+                session: "recorded".into(),
+                from: None,
+                to: None,
+                dry_run: false,
+            },
+        ),
+    });
+
+    assert_eq!(policy_for(&replay), PermissionNeed::None);
+    assert!(requires_permission_report(&replay));
+}
+
+#[test]
+fn screenshot_compare_needs_accessibility_only_for_ref_ignores() {
+    let compare = |ignore: &[&str]| {
+        Commands::ScreenshotCompare(crate::cli_args::screenshot_compare::ScreenshotCompareArgs {
+            expected: "a.png".into(),
+            actual: "b.png".into(),
+            threshold: None,
+            ignore: ignore.iter().map(|value| value.to_string()).collect(),
+            diff_path: None,
+        })
+    };
+
+    assert_eq!(policy_for(&compare(&["0,0,4,4"])), PermissionNeed::None);
+    assert_eq!(
+        policy_for(&compare(&["0,0,4,4", VALID_REF_ID])),
+        PermissionNeed::Accessibility
+    );
+    assert!(validate_args(&compare(&["@bad ref"])).is_err());
+}
@@ -0,0 +1,17 @@
+use agent_desktop_core::{AppError, commands::alias, context::CommandContext};
+use serde_json::Value;
+
+use crate::cli_args::alias::{AliasAction, AliasArgs};
+
+pub(crate) fn dispatch(args: AliasArgs, context: &CommandContext) -> Result<Value, AppError> {
+    let action = match args.action {
+        AliasAction::Set(set) => alias::AliasAction::Set {
+            name: set.name,
+            ref_id: set.ref_id,
+            snapshot_id: set.snapshot,
+        },
+        AliasAction::List => alias::AliasAction::List,
+        AliasAction::Rm(rm) => alias::AliasAction::Rm { name: rm.name },
+    };
+    alias::execute(action, context)
+}
@@ -56,11 +56,13 @@ pub(super) fn type_text(
     adapter: &dyn PlatformAdapter,
     context: &CommandContext,
 ) -> Result<Value, AppError> {
+    let text = literal_or_secret("text", args.text, args.secret.as_deref())?;
     type_text_command::execute(
         type_text_command::TypeArgs {
             ref_id: args.ref_id,
             snapshot_id: args.snapshot,
-            text: args.text,
+            text,
+            secret: args.secret,
             timeout_ms: helpers::normalize_action_timeout_ms(args.timeout_ms),
         },
         adapter,
@@ -73,11 +75,13 @@ pub(super) fn set_value(
     adapter: &dyn PlatformAdapter,
     context: &CommandContext,
 ) -> Result<Value, AppError> {
+    let value = literal_or_secret("value", args.value, args.secret.as_deref())?;
     set_value_command::execute(
         set_value_command::SetValueArgs {
             ref_id: args.ref_id,
             snapshot_id: args.snapshot,
-            value: args.value,
+            value,
+            secret: args.secret,
             timeout_ms: helpers::normalize_action_timeout_ms(args.timeout_ms),
         },
         adapter,
@@ -184,6 +188,25 @@ pub(super) fn scroll_to(
     scroll_to_command::execute(ref_args(args), adapter, context)
 }
 
+/// Batch items skip clap's `required_unless_present`/`conflicts_with`, so the
+/// choice between a literal and `secret` is checked again here.
+fn literal_or_secret(
+    field: &str,
+    literal: Option<String>,
+    secret: Option<&str>,
+) -> Result<String, AppError> {
+    match (literal, secret) {
+        (Some(literal), None) => Ok(literal),
+        (None, Some(_)) => Ok(String::new()),
+        (Some(_), Some(_)) => Err(AppError::invalid_input(format!(
+            "Pass either {field} or secret, not both"
+        ))),
+        (None, None) => Err(AppError::invalid_input(format!(
+            "Missing {field}; pass it or name a secret"
+        ))),
+    }
+}
+
 fn ref_args(args: RefArgs) -> helpers::RefArgs {
     helpers::RefArgs {
         ref_id: args.ref_id,
@@ -1,3 +1,4 @@
+mod alias;
 mod app_window;
 mod clipboard;
 mod interaction;
@@ -21,17 +22,24 @@ pub(crate) fn dispatch(
     context: &CommandContext,
 ) -> Result<Value, AppError> {
     tracing::debug!("dispatch: {}", cmd.name());
-    let scope = if cmd.is_mutating() {
-        context.mutating_command_scope(cmd.name())?
-    } else {
-        context.command_scope(cmd.name())?
+    let scope = match replay_args(&cmd, context) {
+        Some(args) => context.replayable_command_scope(cmd.name(), cmd.is_mutating(), args)?,
+        None if cmd.is_mutating() => context.mutating_command_scope(cmd.name())?,
+        None => context.command_scope(cmd.name())?,
     };
     let result = match cmd {
         Commands::Snapshot(args) => observation::snapshot(args, adapter, context),
         Commands::Find(args) => observation::find(args, adapter, context),
-        Commands::Screenshot(args) => observation::screenshot(args, adapter),
+        Commands::Screenshot(args) => observation::screenshot(args, adapter, context),
+        Commands::ScreenshotCompare(args) => {
+            observation::screenshot_compare(args, adapter, context)
+        }
         Commands::Get(args) => observation::get(args, adapter, context),
         Commands::Is(args) => observation::is(args, adapter, context),
+        Commands::AuditA11y(args) => observation::audit_a11y(args, adapter, context),
+        Commands::ExtractTable(args) => observation::extract_table(args, adapter, context),
+        Commands::Expect(args) => observation::expect(args, adapter, context),
+        Commands::ReadText(args) => observation::read_text(args, adapter, context),
         Commands::Click(args) => interaction::click(args, adapter, context),
         Commands::DoubleClick(args) => interaction::double_click(args, adapter, context),
         Commands::TripleClick(args) => interaction::triple_click(args, adapter, context),
@@ -84,10 +92,30 @@ pub(crate) fn dispatch(
         Commands::Permissions(args) => system::permissions(args, adapter, permission_report),
         Commands::Version => system::version(),
         Commands::Batch(args) => system::batch(args, adapter, permission_report, context),
+        Commands::Run(args) => system::run(args, adapter, permission_report, context),
         Commands::Skills(args) => system::skills(args),
         Commands::Session(args) => system::session(args, context),
-        Commands::Trace(args) => system::trace(args, context),
+        Commands::Trace(args) => system::trace(args, adapter, permission_report, context),
+        Commands::Alias(args) => system::alias(args, context),
     };
     scope.complete(&result)?;
     result
 }
+
+/// Largest batch-shaped argument object `command.start` records for replay.
+const MAX_REPLAY_ARGS_BYTES: usize = 64 * 1024;
+
+/// The batch-shaped args `trace replay` needs to run `cmd` again, or `None`
+/// when tracing is off or the command is a runner that is never replayed.
+/// `Some(None)` marks args too large to record.
+fn replay_args(cmd: &Commands, context: &CommandContext) -> Option<Option<Value>> {
+    if !context.trace_enabled() {
+        return None;
+    }
+    let mut tagged = serde_json::to_value(cmd).ok()?;
+    let args = tagged
+        .get_mut("args")
+        .map(Value::take)
+        .unwrap_or_else(|| Value::Object(Default::default()));
+    Some(crate::batch::serialized_fits(&args, MAX_REPLAY_ARGS_BYTES).then_some(args))
+}
@@ -1,14 +1,20 @@
 use agent_desktop_core::{
     AppError, PlatformAdapter,
     commands::{
-        find as find_command, get as get_command, is_check as is_command,
-        screenshot as screenshot_command, snapshot as snapshot_command,
+        audit_a11y as audit_a11y_command, expect as expect_command,
+        extract_table as extract_table_command, find as find_command, get as get_command,
+        is_check as is_command, read_text as read_text_command, screenshot as screenshot_command,
+        screenshot_compare as screenshot_compare_command, snapshot as snapshot_command,
     },
     context::CommandContext,
 };
 use serde_json::Value;
 
-use crate::cli_args::{FindArgs, GetArgs, IsArgs, ScreenshotArgs, SnapshotArgs};
+use crate::cli_args::{
+    FindArgs, GetArgs, IsArgs, ScreenshotArgs, SnapshotArgs, audit::AuditA11yArgs,
+    expect::ExpectArgs, extract_table::ExtractTableArgs, read_text::ReadTextArgs,
+    screenshot_compare::ScreenshotCompareArgs,
+};
 use crate::dispatch::parse::{parse_get_property, parse_is_property};
 
 pub(super) fn snapshot(
@@ -28,6 +34,10 @@ pub(super) fn snapshot(
             skeleton: args.tree.skeleton,
             root_ref: args.root,
             snapshot_id: args.snapshot,
+            max_tokens: args.tree.max_tokens,
+            format: args.format.to_core(),
+            stable_refs: args.stable_refs,
+            reveal_secure: args.reveal_secure,
         },
         adapter,
         context,
@@ -68,6 +78,7 @@ pub(super) fn find(
                 nth: args.selection.nth,
                 limit: args.selection.limit,
             },
+            reveal_secure: args.reveal_secure,
         },
         adapter,
         context,
@@ -77,6 +88,7 @@ pub(super) fn find(
 pub(super) fn screenshot(
     args: ScreenshotArgs,
     adapter: &dyn PlatformAdapter,
+    context: &CommandContext,
 ) -> Result<Value, AppError> {
     screenshot_command::execute(
         screenshot_command::ScreenshotArgs {
@@ -84,8 +96,28 @@ pub(super) fn screenshot(
             window_id: args.scope.window_id,
             screen: args.screen,
             output_path: args.output_path,
+            annotate: args.annotate,
         },
         adapter,
+        context,
+    )
+}
+
+pub(super) fn screenshot_compare(
+    args: ScreenshotCompareArgs,
+    adapter: &dyn PlatformAdapter,
+    context: &CommandContext,
+) -> Result<Value, AppError> {
+    screenshot_compare_command::execute(
+        screenshot_compare_command::ScreenshotCompareArgs {
+            expected: args.expected,
+            actual: args.actual,
+            threshold: args.threshold,
+            ignore: args.ignore,
+            diff_path: args.diff_path,
+        },
+        adapter,
+        context,
     )
 }
 
@@ -99,6 +131,7 @@ pub(super) fn get(
             ref_id: args.ref_id,
             snapshot_id: args.snapshot,
             property: parse_get_property(&args.property)?,
+            reveal_secure: args.reveal_secure,
         },
         adapter,
         context,
@@ -120,3 +153,83 @@ pub(super) fn is(
         context,
     )
 }
+
+pub(super) fn expect(
+    args: ExpectArgs,
+    adapter: &dyn PlatformAdapter,
+    context: &CommandContext,
+) -> Result<Value, AppError> {
+    expect_command::execute(
+        expect_command::ExpectArgs {
+            target: args.target,
+            matcher: args.matcher,
+            snapshot_id: args.snapshot,
+            app: args.scope.app,
+            window_id: args.scope.window_id,
+            timeout_ms: args.timeout_ms,
+            screenshot: expect_command::ScreenshotOptions {
+                threshold: args.threshold,
+                ignore: args.ignore,
+            },
+            reveal_secure: args.reveal_secure,
+        },
+        adapter,
+        context,
+    )
+}
+
+pub(super) fn audit_a11y(
+    args: AuditA11yArgs,
+    adapter: &dyn PlatformAdapter,
+    context: &CommandContext,
+) -> Result<Value, AppError> {
+    audit_a11y_command::execute(
+        audit_a11y_command::AuditA11yArgs {
+            app: args.scope.app,
+            window_id: args.scope.window_id,
+            max_depth: args.max_depth,
+            format: args.format.to_core(),
+            junit_path: args.junit,
+        },
+        adapter,
+        context,
+    )
+}
+
+pub(super) fn extract_table(
+    args: ExtractTableArgs,
+    adapter: &dyn PlatformAdapter,
+    context: &CommandContext,
+) -> Result<Value, AppError> {
+    extract_table_command::execute(
+        extract_table_command::ExtractTableArgs {
+            ref_id: args.ref_id,
+            snapshot_id: args.snapshot,
+            max_depth: args.max_depth,
+            max_rows: args.max_rows,
+            scroll: args.scroll,
+        },
+        adapter,
+        context,
+    )
+}
+
+pub(super) fn read_text(
+    args: ReadTextArgs,
+    adapter: &dyn PlatformAdapter,
+    context: &CommandContext,
+) -> Result<Value, AppError> {
+    read_text_command::execute(
+        read_text_command::ReadTextArgs {
+            app: args.scope.app,
+            window_id: args.scope.window_id,
+            root_ref: args.root,
+            snapshot_id: args.snapshot,
+            max_depth: args.max_depth,
+            max_chars: args.max_chars,
+            cursor: args.cursor,
+        },
+        adapter,
+        context,
+    )
+}
@@ -9,6 +9,7 @@ pub(crate) fn dispatch(args: SessionArgs, context: &CommandContext) -> Result<Va
             name: s.name,
             no_trace: s.no_trace,
             screenshots: s.screenshots,
+            quota: s.quota.to_quota(),
         }),
         SessionAction::End(e) => {
             let id = resolve_end_session_id(e.id, context.session_id())?;
@@ -19,6 +20,18 @@ pub(crate) fn dispatch(args: SessionArgs, context: &CommandContext) -> Result<Va
             older_than_secs: g.older_than,
             ended_only: g.ended,
         }),
+        SessionAction::Export(e) => session::execute(session::SessionAction::Export {
+            id: e.id,
+            out: e.out,
+        }),
+        SessionAction::Import(i) => session::execute(session::SessionAction::Import {
+            path: i.path,
+            as_id: i.as_id,
+        }),
+        SessionAction::Quota(q) => session::execute(session::SessionAction::Quota {
+            update: q.quota.to_quota(),
+            clear: q.clear,
+        }),
     }
 }
 
@@ -9,13 +9,17 @@ use agent_desktop_core::{
 use serde_json::Value;
 
 use crate::cli_args::{
+    alias::AliasArgs,
     batch::BatchArgs,
+    run::RunArgs,
     session::SessionArgs,
     skills::{SkillsAction, SkillsArgs},
     system::{PermissionsArgs, WaitArgs},
     trace::TraceArgs,
 };
-use crate::dispatch::{session as session_dispatch, trace as trace_dispatch};
+use crate::dispatch::{
+    alias as alias_dispatch, session as session_dispatch, trace as trace_dispatch,
+};
 
 pub(super) fn wait(
     args: WaitArgs,
@@ -94,8 +98,17 @@ pub(super) fn session(args: SessionArgs, context: &CommandContext) -> Result<Val
     session_dispatch::dispatch(args, context)
 }
 
-pub(super) fn trace(args: TraceArgs, context: &CommandContext) -> Result<Value, AppError> {
-    trace_dispatch::dispatch(args, context)
+pub(super) fn trace(
+    args: TraceArgs,
+    adapter: &dyn PlatformAdapter,
+    permission_report: &PermissionReport,
+    context: &CommandContext,
+) -> Result<Value, AppError> {
+    trace_dispatch::dispatch(args, adapter, permission_report, context)
+}
+
+pub(super) fn alias(args: AliasArgs, context: &CommandContext) -> Result<Value, AppError> {
+    alias_dispatch::dispatch(args, context)
 }
 
 pub(super) fn batch(
@@ -106,3 +119,12 @@ pub(super) fn batch(
 ) -> Result<Value, AppError> {
     crate::batch::execute(args, adapter, permission_report, context)
 }
+
+pub(super) fn run(
+    args: RunArgs,
+    adapter: &dyn PlatformAdapter,
+    permission_report: &PermissionReport,
+    context: &CommandContext,
+) -> Result<Value, AppError> {
+    crate::scenario::execute(args, adapter, permission_report, context)
+}
@@ -1,10 +1,21 @@
-use agent_desktop_core::{AppError, commands::trace, context::CommandContext};
+use agent_desktop_core::{
+    AppError, PermissionReport, PlatformAdapter, commands::trace, context::CommandContext,
+};
 use serde_json::Value;
+use std::io::Write;
 
 use crate::cli_args::trace::{TraceAction, TraceArgs};
 
-pub(crate) fn dispatch(args: TraceArgs, context: &CommandContext) -> Result<Value, AppError> {
+pub(crate) fn dispatch(
+    args: TraceArgs,
+    adapter: &dyn PlatformAdapter,
+    permission_report: &PermissionReport,
+    context: &CommandContext,
+) -> Result<Value, AppError> {
     match args.action {
+        TraceAction::Show(show) if show.follow => {
+            trace::follow(context, show.limit, show.event, &mut stream_line)
+        }
         TraceAction::Show(show) => trace::execute(
             trace::TraceAction::Show {
                 limit: show.limit,
@@ -19,5 +30,21 @@ pub(crate) fn dispatch(args: TraceArgs, context: &CommandContext) -> Result<Valu
             },
             context,
         ),
+        TraceAction::Stats(stats) => trace::stats(&stats.session),
+        TraceAction::Diff(diff) => trace::diff(&diff.a, &diff.b, diff.latency_ms, diff.out),
+        TraceAction::Replay(replay) => {
+            crate::trace_replay::execute(replay, adapter, permission_report, context)
+        }
+        TraceAction::ToScript(to_script) => crate::trace_script::execute(to_script),
     }
 }
+
+/// Writes one followed event as its own stdout line, ahead of the final
+/// response, flushing so a reader sees it as soon as it is recorded.
+fn stream_line(event: &Value) -> Result<(), AppError> {
+    let mut stdout = std::io::stdout().lock();
+    serde_json::to_writer(&mut stdout, event).map_err(std::io::Error::other)?;
+    stdout.write_all(b"\n")?;
+    stdout.flush()?;
+    Ok(())
+}
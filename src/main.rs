@@ -6,6 +6,9 @@ mod cli_args;
 mod command_policy;
 mod diagnostic;
 mod dispatch;
+mod scenario;
+mod trace_replay;
+mod trace_script;
 
 /// Shared blanket-default `PlatformAdapter` test double, sourced once from
 /// `tests/support/noop_ops.rs` (also consumed by the standalone
@@ -133,11 +136,17 @@ fn run() -> ExitCode {
             let context = match CommandContext::new(session_id, cli.trace, cli.trace_strict) {
                 Ok(context) => context
                     .with_headed(cli.headed)
+                    .with_heal(cli.heal)
                     .with_wait_selector(wait_selector.clone()),
                 Err(err) => {
                     return finish(cmd_name, Err(pre_dispatch_error(err)));
                 }
             };
+            if cli.heal {
+                if let Err(err) = validate_heal_command(&cmd) {
+                    return finish(cmd_name, Err(err));
+                }
+            }
             if let Some(wait) = wait_selector.as_ref() {
                 if let Err(err) = validate_wait_for_command(&cmd, wait) {
                     return finish(cmd_name, Err(err));
@@ -190,6 +199,16 @@ fn validate_wait_for_command(cmd: &Commands, wait: &WaitSelector) -> Result<(),
     Ok(())
 }
 
+fn validate_heal_command(cmd: &Commands) -> Result<(), AppError> {
+    if cmd.supports_heal() {
+        return Ok(());
+    }
+    Err(AppError::invalid_input_with_suggestion(
+        format!("Command '{}' does not support --heal", cmd.name()),
+        "Use --heal with a ref action (click, type, check, …), batch, or run.",
+    ))
+}
+
 fn run_with_adapter(cmd: Commands, cmd_name: &str, context: &CommandContext) -> ExitCode {
     let adapter = build_adapter();
     let adapter: &dyn agent_desktop_core::PlatformAdapter = &adapter;
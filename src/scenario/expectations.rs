@@ -0,0 +1,125 @@
+use agent_desktop_core::{
+    AppError,
+    commands::run::{CountAssertion, Expectation, ValueAssertion},
+};
+use serde_json::{Map, Value, json};
+
+use crate::cli::Commands;
+
+/// What an expectation's command result must satisfy.
+pub(super) enum Check {
+    /// The value at `pointer` in the command's `data` equals `expected`.
+    Equals {
+        pointer: &'static str,
+        expected: Value,
+    },
+    Count {
+        equals: Option<u64>,
+        min: Option<u64>,
+        max: Option<u64>,
+    },
+}
+
+pub(super) struct PreparedExpectation {
+    pub kind: &'static str,
+    pub args: Value,
+    pub command: Commands,
+    pub check: Check,
+}
+
+type Bind<'a> = dyn FnMut(&Value) -> Result<Value, AppError> + 'a;
+
+/// Turns an expectation into the read-only command that checks it, with
+/// parameters already bound into its args and expected value.
+pub(super) fn prepare(
+    expectation: Expectation,
+    bind: &mut Bind<'_>,
+) -> Result<(&'static str, Value, Check), AppError> {
+    match expectation {
+        Expectation::Is(ValueAssertion { equals, args }) => {
+            let expected = match equals {
+                None => Value::Bool(true),
+                Some(Value::Bool(expected)) => Value::Bool(expected),
+                Some(_) => {
+                    return Err(AppError::invalid_input(
+                        "expect.is 'equals' must be true or false",
+                    ));
+                }
+            };
+            let check = Check::Equals {
+                pointer: "/result",
+                expected,
+            };
+            Ok(("is", bind(&Value::Object(args))?, check))
+        }
+        Expectation::Get(ValueAssertion { equals, args }) => {
+            let expected = equals.ok_or_else(|| {
+                AppError::invalid_input_with_suggestion(
+                    "expect.get requires 'equals'",
+                    "Write {get: {ref_id: '@e3', property: value, equals: 'expected'}}",
+                )
+            })?;
+            let check = Check::Equals {
+                pointer: "/value",
+                expected: bind(&expected)?,
+            };
+            Ok(("get", bind(&Value::Object(args))?, check))
+        }
+        Expectation::Count(CountAssertion {
+            equals,
+            min,
+            max,
+            args,
+        }) => {
+            if equals.is_none() && min.is_none() && max.is_none() {
+                return Err(AppError::invalid_input(
+                    "expect.count requires at least one of 'equals', 'min', or 'max'",
+                ));
+            }
+            if args.contains_key("count") {
+                return Err(AppError::invalid_input(
+                    "expect.count sets find's 'count' itself; remove it from the args",
+                ));
+            }
+            let mut args: Map<String, Value> = args;
+            args.insert("count".into(), Value::Bool(true));
+            let check = Check::Count { equals, min, max };
+            Ok(("find", bind(&Value::Object(args))?, check))
+        }
+    }
+}
+
+/// The command an expectation runs, for reports about it.
+pub(super) fn kind(expectation: &Expectation) -> &'static str {
+    match expectation {
+        Expectation::Is(_) => "is",
+        Expectation::Get(_) => "get",
+        Expectation::Count(_) => "find",
+    }
+}
+
+/// Judges a command result against its check, returning whether it held
+/// with the expected and actual values for the report.
+pub(super) fn judge(check: &Check, data: &Value) -> (bool, Value, Value) {
+    match check {
+        Check::Equals { pointer, expected } => {
+            let actual = data.pointer(pointer).cloned().unwrap_or(Value::Null);
+            (actual == *expected, expected.clone(), actual)
+        }
+        Check::Count { equals, min, max } => {
+            let actual = data.get("count").and_then(Value::as_u64);
+            let holds = actual.is_some_and(|count| {
+                equals.is_none_or(|equals| count == equals)
+                    && min.is_none_or(|min| count >= min)
+                    && max.is_none_or(|max| count <= max)
+            });
+            let mut expected = json!({});
+            for (key, bound) in [("equals", equals), ("min", min), ("max", max)] {
+                if let Some(bound) = bound {
+                    expected[key] = json!(bound);
+                }
+            }
+            (holds, expected, json!(actual))
+        }
+    }
+}
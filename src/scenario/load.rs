@@ -0,0 +1,313 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use agent_desktop_core::{
+    AdapterError, AppError, DeliverySemantics, ErrorCode, PermissionReport,
+    commands::{
+        batch::BatchCommand,
+        run::{
+            self as run_command, Expectation, MAX_SCENARIO_STEPS, MAX_STEP_EXPECTATIONS, Scenario,
+        },
+    },
+    surface_handler::{self, SurfaceHandler},
+};
+use serde_json::{Value, json};
+
+use crate::batch::captures::{self, CaptureSpec, CapturedValues, DeclaredCaptures};
+use crate::cli::Commands;
+
+use super::expectations::{self, PreparedExpectation};
+
+/// What a prepared step dispatches. `Template` args still reference
+/// captures of earlier steps and were validated with typed placeholders;
+/// they are bound and decoded again when the step runs.
+pub(super) enum StepCommand {
+    Fixed(Box<Commands>),
+    Template(Value),
+}
+
+/// An expectation of a prepared step. `Template` ones reference captures,
+/// including the step's own, and were validated with typed placeholders;
+/// they are prepared again from the captured values after the step runs.
+pub(super) enum StepExpectation {
+    Fixed(Box<PreparedExpectation>),
+    Template(Expectation),
+}
+
+pub(super) struct PreparedStep {
+    pub index: usize,
+    pub name: String,
+    pub command_name: String,
+    pub command: StepCommand,
+    pub timeout_ms: Option<u64>,
+    pub expectations: Vec<StepExpectation>,
+    pub captures: Vec<CaptureSpec>,
+}
+
+pub(super) struct PreparedScenario {
+    pub name: String,
+    pub handlers: Vec<SurfaceHandler>,
+    pub params: BTreeMap<String, String>,
+    pub steps: Vec<PreparedStep>,
+}
+
+/// Parses, binds, and validates the whole scenario before any step runs,
+/// so a typo in step 40 does not leave the desktop half-driven.
+pub(super) fn prepare(
+    path: &Path,
+    text: &str,
+    params: &[String],
+    permission_report: &PermissionReport,
+) -> Result<PreparedScenario, AppError> {
+    let scenario = parse(path, text)?;
+    if scenario.steps.is_empty() || scenario.steps.len() > MAX_SCENARIO_STEPS {
+        return Err(AppError::invalid_input(format!(
+            "Scenario must contain between 1 and {MAX_SCENARIO_STEPS} steps, found {}",
+            scenario.steps.len()
+        )));
+    }
+    surface_handler::validate_handlers(&scenario.handlers)?;
+    let bound = run_command::bind_params(&scenario.params, params)?;
+    let name = scenario
+        .name
+        .filter(|name| !name.trim().is_empty())
+        .or_else(|| {
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+        })
+        .unwrap_or_else(|| "scenario".to_string());
+
+    let mut steps = Vec::with_capacity(scenario.steps.len());
+    let mut declared = DeclaredCaptures::new();
+    for (index, mut step) in scenario.steps.into_iter().enumerate() {
+        let located = |error| step_error(index, &step.name, error);
+        if step.name.trim().is_empty() {
+            return Err(located(AppError::invalid_input(
+                "Step name must not be empty",
+            )));
+        }
+        if step.expect.len() > MAX_STEP_EXPECTATIONS {
+            return Err(located(AppError::invalid_input(format!(
+                "Step has more than {MAX_STEP_EXPECTATIONS} expectations"
+            ))));
+        }
+        let capture_specs =
+            captures::parse_specs(std::mem::take(&mut step.capture)).map_err(located)?;
+        if let Some(spec) = capture_specs
+            .iter()
+            .find(|spec| bound.contains_key(&spec.name))
+        {
+            return Err(located(AppError::invalid_input(format!(
+                "Capture '{}' has the same name as a scenario parameter",
+                spec.name
+            ))));
+        }
+        let templated = references_capture(&step.args, &bound, &declared).map_err(located)?;
+        let args =
+            bind(&step.args, &bound, &mut |name| placeholder(&declared, name)).map_err(located)?;
+        let decoded = decode(&step.command, args, permission_report).map_err(located)?;
+        let command = if templated {
+            StepCommand::Template(step.args)
+        } else {
+            StepCommand::Fixed(Box::new(decoded))
+        };
+        for spec in &capture_specs {
+            declared.insert(spec.name.clone(), spec.kind);
+        }
+        let expectations = step
+            .expect
+            .into_iter()
+            .map(|expectation| {
+                let mut templated = false;
+                let prepared = prepare_expectation(
+                    expectation.clone(),
+                    &bound,
+                    &mut |name| {
+                        templated = true;
+                        placeholder(&declared, name)
+                    },
+                    permission_report,
+                )?;
+                Ok(if templated {
+                    StepExpectation::Template(expectation)
+                } else {
+                    StepExpectation::Fixed(Box::new(prepared))
+                })
+            })
+            .collect::<Result<Vec<_>, AppError>>()
+            .map_err(located)?;
+        steps.push(PreparedStep {
+            index,
+            name: step.name,
+            command_name: step.command,
+            command,
+            timeout_ms: step.timeout_ms,
+            expectations,
+            captures: capture_specs,
+        });
+    }
+    Ok(PreparedScenario {
+        name,
+        handlers: scenario.handlers,
+        params: bound,
+        steps,
+    })
+}
+
+fn parse(path: &Path, text: &str) -> Result<Scenario, AppError> {
+    let is_json = path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+    let parsed = if is_json {
+        serde_json::from_str(text).map_err(|error| error.to_string())
+    } else {
+        serde_yaml_bw::from_str(text).map_err(|error| error.to_string())
+    };
+    parsed.map_err(|error| {
+        AppError::invalid_input_with_suggestion(
+            format!(
+                "Invalid scenario {}: {}",
+                if is_json { "JSON" } else { "YAML" },
+                crate::diagnostic::bounded_text(&error, 512)
+            ),
+            "A scenario is {name, params, steps: [{name, command, args, timeout_ms, expect, capture}]}",
+        )
+    })
+}
+
+/// Replaces `${name}` references with bound parameter text, or with what
+/// `capture` yields for a name that is not a parameter.
+fn bind(
+    value: &Value,
+    params: &BTreeMap<String, String>,
+    capture: &mut dyn FnMut(&str) -> Result<Value, AppError>,
+) -> Result<Value, AppError> {
+    crate::batch::substitute(value, &mut |reference| match params.get(reference) {
+        Some(value) => Ok(Value::String(value.clone())),
+        None => capture(reference),
+    })
+}
+
+/// The typed placeholder validating a reference to a declared capture.
+fn placeholder(declared: &DeclaredCaptures, reference: &str) -> Result<Value, AppError> {
+    declared
+        .get(reference)
+        .map(|kind| kind.placeholder())
+        .ok_or_else(|| {
+            AppError::invalid_input_with_suggestion(
+                format!("Parameter '{reference}' is not declared by the scenario"),
+                "Declare it under params or capture it in an earlier step, or write $${ for a literal ${",
+            )
+        })
+}
+
+/// The value a step captured for `reference` during this run.
+fn captured_value(values: &CapturedValues, reference: &str) -> Result<Value, AppError> {
+    values.get(reference).cloned().ok_or_else(|| {
+        AppError::invalid_input_with_suggestion(
+            format!("Capture '{reference}' has no value because its step did not capture it"),
+            "Check the producing step's error or capture_error",
+        )
+    })
+}
+
+/// True when `args` references a capture declared by an earlier step, so
+/// the step can only be bound once that step has run.
+fn references_capture(
+    args: &Value,
+    params: &BTreeMap<String, String>,
+    declared: &DeclaredCaptures,
+) -> Result<bool, AppError> {
+    let mut found = false;
+    if captures::is_template(args) {
+        crate::batch::substitute(args, &mut |reference| {
+            found |= !params.contains_key(reference) && declared.contains_key(reference);
+            Ok(Value::Null)
+        })?;
+    }
+    Ok(found)
+}
+
+/// Binds a templated step's args against the parameters and the values
+/// earlier steps captured, then decodes them for this run.
+pub(super) fn bind_template(
+    name: &str,
+    template: &Value,
+    params: &BTreeMap<String, String>,
+    values: &CapturedValues,
+    permission_report: &PermissionReport,
+) -> Result<Commands, AppError> {
+    let args = bind(template, params, &mut |reference| {
+        captured_value(values, reference)
+    })?;
+    decode(name, args, permission_report)
+}
+
+/// Prepares a templated expectation from the parameters and the values
+/// captured so far, including by its own step.
+pub(super) fn bind_expectation(
+    expectation: Expectation,
+    params: &BTreeMap<String, String>,
+    values: &CapturedValues,
+    permission_report: &PermissionReport,
+) -> Result<PreparedExpectation, AppError> {
+    prepare_expectation(
+        expectation,
+        params,
+        &mut |reference| captured_value(values, reference),
+        permission_report,
+    )
+}
+
+fn prepare_expectation(
+    expectation: Expectation,
+    params: &BTreeMap<String, String>,
+    capture: &mut dyn FnMut(&str) -> Result<Value, AppError>,
+    permission_report: &PermissionReport,
+) -> Result<PreparedExpectation, AppError> {
+    let (kind, args, check) =
+        expectations::prepare(expectation, &mut |value| bind(value, params, capture))?;
+    let command = decode(kind, args.clone(), permission_report)?;
+    Ok(PreparedExpectation {
+        kind,
+        args,
+        command,
+        check,
+    })
+}
+
+fn decode(
+    name: &str,
+    args: Value,
+    permission_report: &PermissionReport,
+) -> Result<Commands, AppError> {
+    let command = crate::batch::parse_command(BatchCommand {
+        command: name.to_string(),
+        session: None,
+        args,
+        capture: Default::default(),
+    })?;
+    crate::command_policy::preflight(&command, permission_report)?;
+    Ok(command)
+}
+
+fn step_error(index: usize, step: &str, error: AppError) -> AppError {
+    let message = format!("Scenario step {index} ('{step}') failed validation");
+    match error {
+        AppError::Adapter(mut source) => {
+            let cause_details = source.details.take();
+            let mut details = json!({ "step_index": index, "step_name": step });
+            if let Some(cause_details) = cause_details {
+                details["cause_details"] = cause_details;
+            }
+            source.message = format!("{message}: {}", source.message);
+            source.details = Some(details);
+            source.disposition = DeliverySemantics::not_delivered();
+            source.into()
+        }
+        other => AdapterError::new(ErrorCode::Internal, format!("{message}: {other}"))
+            .with_details(json!({ "step_index": index, "step_name": step }))
+            .with_disposition(DeliverySemantics::not_delivered())
+            .into(),
+    }
+}
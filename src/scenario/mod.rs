@@ -0,0 +1,264 @@
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use agent_desktop_core::{
+    AppError, Deadline, DeliverySemantics, PermissionReport, PlatformAdapter,
+    commands::run::{self as run_command, StepCase, StepStatus},
+    context::CommandContext,
+    output::ErrorPayload,
+    surface_handler,
+};
+use serde_json::{Value, json};
+
+use crate::batch::captures::{self, CapturedValues};
+use crate::cli_args::run::RunArgs;
+
+mod expectations;
+mod load;
+
+use load::{PreparedStep, StepCommand, StepExpectation};
+
+/// A step's command data is echoed in the report only below this size;
+/// larger payloads are replaced by `data_omitted` to keep reports readable.
+const MAX_STEP_DATA_BYTES: usize = 64 * 1024;
+
+struct StepRecord {
+    name: String,
+    command: String,
+    status: StepStatus,
+    elapsed_ms: u64,
+    failure: Option<String>,
+    report: Value,
+}
+
+pub(crate) fn execute(
+    args: RunArgs,
+    adapter: &dyn PlatformAdapter,
+    permission_report: &PermissionReport,
+    context: &CommandContext,
+) -> Result<Value, AppError> {
+    let deadline = Deadline::after(args.timeout_ms)
+        .map_err(|error| error.with_disposition(DeliverySemantics::not_delivered()))?;
+    let text = run_command::read_scenario(&args.file)?;
+    let scenario = load::prepare(&args.file, &text, &args.params, permission_report)?;
+
+    let mut records = Vec::with_capacity(scenario.steps.len());
+    let mut captured = CapturedValues::new();
+    let mut skip_reason = None;
+    for step in scenario.steps {
+        if skip_reason.is_none() && deadline.is_expired() {
+            skip_reason = Some("deadline");
+        }
+        let record = match skip_reason {
+            Some(reason) => skipped(step, reason),
+            None => {
+                let handled =
+                    surface_handler::run_handlers(&scenario.handlers, adapter, context, deadline)?;
+                let run = StepRun {
+                    params: &scenario.params,
+                    captured: &mut captured,
+                    adapter,
+                    permission_report,
+                    context,
+                    deadline,
+                };
+                let mut record = run_step(step, run);
+                if !handled.is_empty() {
+                    record.report["handled_surfaces"] = Value::Array(handled);
+                }
+                record
+            }
+        };
+        context.trace_lazy("scenario.step", || {
+            json!({
+                "index": record.report["index"],
+                "name": record.name,
+                "command": record.command,
+                "status": record.status.as_str(),
+                "elapsed_ms": record.elapsed_ms,
+            })
+        })?;
+        if record.status == StepStatus::Failed {
+            skip_reason = Some("previous_step_failed");
+        }
+        records.push(record);
+    }
+
+    let cases: Vec<StepCase<'_>> = records
+        .iter()
+        .map(|record| StepCase {
+            name: &record.name,
+            command: &record.command,
+            status: record.status,
+            elapsed_ms: record.elapsed_ms,
+            failure: record.failure.clone(),
+        })
+        .collect();
+    if let Some(path) = &args.junit {
+        run_command::write_junit(path, &scenario.name, &cases)?;
+    }
+    let count = |status| records.iter().filter(|r| r.status == status).count();
+    let mut body = json!({
+        "scenario": scenario.name,
+        "file": args.file.to_string_lossy(),
+        "passed": count(StepStatus::Passed) == records.len(),
+        "summary": {
+            "total": records.len(),
+            "passed": count(StepStatus::Passed),
+            "failed": count(StepStatus::Failed),
+            "skipped": count(StepStatus::Skipped),
+        },
+        "steps": records.into_iter().map(|record| record.report).collect::<Vec<_>>(),
+        "timeout_ms": args.timeout_ms,
+        "elapsed_ms": deadline.elapsed().as_millis(),
+    });
+    if let Some(path) = &args.junit {
+        body["junit_path"] = json!(path.to_string_lossy());
+    }
+    Ok(body)
+}
+
+/// What a step runs against: the bound parameters, the values earlier
+/// steps captured, and the run's adapter, context, and deadline.
+struct StepRun<'a> {
+    params: &'a BTreeMap<String, String>,
+    captured: &'a mut CapturedValues,
+    adapter: &'a dyn PlatformAdapter,
+    permission_report: &'a PermissionReport,
+    context: &'a CommandContext,
+    deadline: Deadline,
+}
+
+/// Runs one step and then each of its expectations within the step's
+/// budget. The step fails on a command error, a failed capture, or any
+/// unmet expectation.
+fn run_step(step: PreparedStep, run: StepRun<'_>) -> StepRecord {
+    let StepRun {
+        params,
+        captured,
+        adapter,
+        permission_report,
+        context,
+        deadline,
+    } = run;
+    let started = Instant::now();
+    let step_deadline = step
+        .timeout_ms
+        .map_or(deadline, |ms| deadline.capped(Duration::from_millis(ms)));
+    let step_context = context.clone().with_inherited_deadline(step_deadline);
+    let dispatch =
+        |command| crate::dispatch::dispatch(command, adapter, permission_report, &step_context);
+
+    let mut report = json!({
+        "index": step.index,
+        "name": step.name,
+        "command": step.command_name,
+    });
+    let mut failures = Vec::new();
+    let command = match step.command {
+        StepCommand::Fixed(command) => Ok(*command),
+        StepCommand::Template(template) => load::bind_template(
+            &step.command_name,
+            &template,
+            params,
+            captured,
+            permission_report,
+        ),
+    };
+    match command.and_then(dispatch) {
+        Err(error) => {
+            failures.push(format!("{}: {}", error.code(), error));
+            report["error"] = json!(ErrorPayload::from_app_error(&error));
+        }
+        Ok(data) => {
+            if !step.captures.is_empty() {
+                match captures::capture(&step.captures, &data, captured) {
+                    Ok(values) => report["captured"] = Value::Object(values),
+                    Err(detail) => {
+                        failures.push(format!("capture {}: {}", detail["name"], detail["reason"]));
+                        report["capture_error"] = detail;
+                    }
+                }
+            }
+            if crate::batch::serialized_fits(&data, MAX_STEP_DATA_BYTES) {
+                report["data"] = data;
+            } else {
+                report["data_omitted"] = json!(true);
+            }
+            let mut checked = Vec::with_capacity(step.expectations.len());
+            for expectation in step.expectations {
+                let (kind, prepared) = match expectation {
+                    StepExpectation::Fixed(prepared) => (prepared.kind, Ok(*prepared)),
+                    StepExpectation::Template(template) => (
+                        expectations::kind(&template),
+                        load::bind_expectation(template, params, captured, permission_report),
+                    ),
+                };
+                let mut record = json!({ "kind": kind });
+                let outcome = prepared.and_then(|expectation| {
+                    record["args"] = expectation.args;
+                    dispatch(expectation.command).map(|data| (expectation.check, data))
+                });
+                match outcome {
+                    Ok((check, data)) => {
+                        let (held, expected, actual) = expectations::judge(&check, &data);
+                        if !held {
+                            failures.push(format!(
+                                "expect {kind}: expected {expected}, actual {actual}"
+                            ));
+                        }
+                        record["passed"] = json!(held);
+                        record["expected"] = expected;
+                        record["actual"] = actual;
+                    }
+                    Err(error) => {
+                        failures.push(format!("expect {kind}: {}: {}", error.code(), error));
+                        record["passed"] = json!(false);
+                        record["error"] = json!(ErrorPayload::from_app_error(&error));
+                    }
+                }
+                checked.push(record);
+            }
+            if !checked.is_empty() {
+                report["expectations"] = Value::Array(checked);
+            }
+        }
+    }
+
+    let status = if failures.is_empty() {
+        StepStatus::Passed
+    } else {
+        StepStatus::Failed
+    };
+    let elapsed_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+    report["status"] = json!(status.as_str());
+    report["elapsed_ms"] = json!(elapsed_ms);
+    StepRecord {
+        name: step.name,
+        command: step.command_name,
+        status,
+        elapsed_ms,
+        failure: (!failures.is_empty()).then(|| failures.join("\n")),
+        report,
+    }
+}
+
+fn skipped(step: PreparedStep, reason: &str) -> StepRecord {
+    StepRecord {
+        report: json!({
+            "index": step.index,
+            "name": step.name,
+            "command": step.command_name,
+            "status": StepStatus::Skipped.as_str(),
+            "skipped_reason": reason,
+        }),
+        name: step.name,
+        command: step.command_name,
+        status: StepStatus::Skipped,
+        elapsed_ms: 0,
+        failure: None,
+    }
+}
+
+#[cfg(test)]
+mod tests;
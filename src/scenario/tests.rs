@@ -0,0 +1,316 @@
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use agent_desktop_core::{
+    AdapterError, ClipboardContent, ClipboardFormat, CommandContext, Deadline, ErrorCode,
+    InteractionLease, PermissionReport,
+};
+use serde_json::{Value, json};
+
+use super::expectations::{Check, judge};
+use crate::cli_args::run::RunArgs;
+
+struct ClipboardAdapter {
+    clears: AtomicUsize,
+    fail_clears: bool,
+    text: Mutex<Option<String>>,
+}
+
+impl agent_desktop_core::ObservationOps for ClipboardAdapter {}
+impl agent_desktop_core::ActionOps for ClipboardAdapter {}
+
+impl agent_desktop_core::InputOps for ClipboardAdapter {
+    fn clear_clipboard(&self, _lease: &InteractionLease) -> Result<(), AdapterError> {
+        self.clears.fetch_add(1, Ordering::SeqCst);
+        if self.fail_clears {
+            return Err(AdapterError::new(
+                ErrorCode::ActionFailed,
+                "pasteboard busy",
+            ));
+        }
+        Ok(())
+    }
+
+    fn get_clipboard_content(
+        &self,
+        _format: ClipboardFormat,
+        _deadline: Deadline,
+    ) -> Result<Option<ClipboardContent>, AdapterError> {
+        Ok(self
+            .text
+            .lock()
+            .unwrap()
+            .clone()
+            .map(ClipboardContent::Text))
+    }
+
+    fn set_clipboard_content(
+        &self,
+        content: &ClipboardContent,
+        _lease: &InteractionLease,
+    ) -> Result<(), AdapterError> {
+        if let ClipboardContent::Text(text) = content {
+            *self.text.lock().unwrap() = Some(text.clone());
+        }
+        Ok(())
+    }
+}
+
+impl agent_desktop_core::SystemOps for ClipboardAdapter {
+    fn acquire_interaction_lease(
+        &self,
+        deadline: Deadline,
+    ) -> Result<InteractionLease, AdapterError> {
+        InteractionLease::guarded(deadline, ())
+    }
+}
+
+fn adapter(fail_clears: bool) -> ClipboardAdapter {
+    ClipboardAdapter {
+        clears: AtomicUsize::new(0),
+        fail_clears,
+        text: Mutex::new(None),
+    }
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "agent-desktop-scenario-{}-{}-{name}",
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0)
+    ))
+}
+
+fn run(
+    adapter: &ClipboardAdapter,
+    file: &str,
+    text: &str,
+    params: &[&str],
+    junit: Option<PathBuf>,
+) -> Result<Value, agent_desktop_core::AppError> {
+    let path = temp_path(file);
+    std::fs::write(&path, text).unwrap();
+    let result = super::execute(
+        RunArgs {
+            file: path.clone(),
+            params: params.iter().map(|param| param.to_string()).collect(),
+            junit,
+            timeout_ms: 60_000,
+        },
+        adapter,
+        &PermissionReport::default(),
+        &CommandContext::default(),
+    );
+    let _ = std::fs::remove_file(&path);
+    result
+}
+
+const GREETING_YAML: &str = r#"
+name: clipboard round trip
+params:
+  greeting: hello
+  target: null
+steps:
+  - name: write greeting
+    command: clipboard-set
+    args: { text: "${greeting} ${target}" }
+    timeout_ms: 5000
+  - name: read it back
+    command: clipboard-get
+"#;
+
+#[test]
+fn yaml_scenario_binds_params_and_reports_each_step() {
+    let adapter = adapter(false);
+    let output = run(
+        &adapter,
+        "greeting.yaml",
+        GREETING_YAML,
+        &["target=world"],
+        None,
+    )
+    .unwrap();
+
+    assert_eq!(adapter.text.lock().unwrap().as_deref(), Some("hello world"));
+    assert_eq!(output["scenario"], "clipboard round trip");
+    assert_eq!(output["passed"], true);
+    assert_eq!(output["summary"]["passed"], 2);
+    let steps = output["steps"].as_array().unwrap();
+    assert_eq!(steps[0]["name"], "write greeting");
+    assert_eq!(steps[0]["status"], "passed");
+    assert_eq!(steps[1]["data"]["text"], "hello world");
+}
+
+#[test]
+fn failed_step_skips_the_rest_and_lands_in_the_junit_report() {
+    let adapter = adapter(true);
+    let junit = temp_path("report.xml");
+    let scenario = json!({
+        "steps": [
+            { "name": "clear", "command": "clipboard-clear" },
+            { "name": "write", "command": "clipboard-set", "args": { "text": "late" } }
+        ]
+    });
+    let output = run(
+        &adapter,
+        "failing.json",
+        &scenario.to_string(),
+        &[],
+        Some(junit.clone()),
+    )
+    .unwrap();
+
+    let xml = std::fs::read_to_string(&junit).unwrap();
+    let _ = std::fs::remove_file(&junit);
+    assert_eq!(output["passed"], false);
+    assert!(output["scenario"].as_str().unwrap().ends_with("failing"));
+    assert_eq!(output["steps"][0]["status"], "failed");
+    assert_eq!(output["steps"][0]["error"]["code"], "ACTION_FAILED");
+    assert_eq!(output["steps"][1]["status"], "skipped");
+    assert_eq!(output["steps"][1]["skipped_reason"], "previous_step_failed");
+    assert!(adapter.text.lock().unwrap().is_none());
+    assert!(xml.contains("tests=\"2\" failures=\"1\" skipped=\"1\""));
+    assert!(xml.contains("<failure message=\"ACTION_FAILED: "));
+}
+
+#[test]
+fn invalid_scenarios_are_rejected_before_any_step_runs() {
+    for (file, text, params) in [
+        ("missing.yaml", GREETING_YAML, &[][..]),
+        ("typo.yaml", GREETING_YAML, &["target=x", "greting=y"][..]),
+        (
+            "undeclared.json",
+            r#"{"steps":[{"name":"a","command":"clipboard-clear"},{"name":"b","command":"clipboard-set","args":{"text":"${who}"}}]}"#,
+            &[][..],
+        ),
+        (
+            "unknown.json",
+            r#"{"steps":[{"name":"a","command":"clipboard-clear"},{"name":"b","command":"teleport"}]}"#,
+            &[][..],
+        ),
+        (
+            "nested.json",
+            r#"{"steps":[{"name":"a","command":"clipboard-clear"},{"name":"b","command":"run"}]}"#,
+            &[][..],
+        ),
+        (
+            "expect.json",
+            r#"{"steps":[{"name":"a","command":"clipboard-clear","expect":[{"count":{"role":"button"}}]}]}"#,
+            &[][..],
+        ),
+        (
+            "handler.json",
+            r#"{"handlers":[{"on-surface":"alert","click":""}],"steps":[{"name":"a","command":"clipboard-clear"}]}"#,
+            &[][..],
+        ),
+        (
+            "capture_later.json",
+            r#"{"steps":[{"name":"a","command":"clipboard-set","args":{"text":"${x}"}},{"name":"b","command":"clipboard-get","capture":{"x":"/text"}}]}"#,
+            &[][..],
+        ),
+        (
+            "capture_shadows.yaml",
+            "params: {x: a}\nsteps: [{name: a, command: clipboard-get, capture: {x: /text}}]",
+            &[][..],
+        ),
+        ("empty.yaml", "steps: []", &[][..]),
+        ("broken.yaml", "steps: [", &[][..]),
+    ] {
+        let adapter = adapter(false);
+        let error = run(&adapter, file, text, params, None)
+            .expect_err("invalid scenario is rejected during preparation");
+        assert_eq!(error.code(), "INVALID_ARGS", "{file}");
+        assert_eq!(adapter.clears.load(Ordering::SeqCst), 0, "{file}");
+        assert!(adapter.text.lock().unwrap().is_none(), "{file}");
+    }
+}
+
+#[test]
+fn expectation_checks_compare_values_and_count_bounds() {
+    let equals = Check::Equals {
+        pointer: "/value",
+        expected: json!("Done"),
+    };
+    assert_eq!(
+        judge(&equals, &json!({ "value": "Done" })),
+        (true, json!("Done"), json!("Done"))
+    );
+    assert!(!judge(&equals, &json!({ "value": "Draft" })).0);
+    assert_eq!(judge(&equals, &json!({})).2, Value::Null);
+
+    let count = Check::Count {
+        equals: None,
+        min: Some(2),
+        max: Some(3),
+    };
+    assert_eq!(
+        judge(&count, &json!({ "count": 1 })),
+        (false, json!({ "min": 2, "max": 3 }), json!(1))
+    );
+    assert!(judge(&count, &json!({ "count": 3 })).0);
+    assert!(!judge(&count, &json!({ "match": null })).0);
+}
+
+#[test]
+fn captured_values_feed_later_steps_of_the_scenario() {
+    let adapter = adapter(false);
+    let scenario = r#"
+steps:
+  - name: seed
+    command: clipboard-set
+    args: { text: hello }
+  - name: read
+    command: clipboard-get
+    capture: { copied: /text }
+  - name: echo
+    command: clipboard-set
+    args: { text: "${copied} again, $${literal}" }
+"#;
+    let output = run(&adapter, "capture.yaml", scenario, &[], None).unwrap();
+
+    assert_eq!(output["passed"], true);
+    assert_eq!(output["steps"][1]["captured"]["copied"], "hello");
+    assert_eq!(
+        adapter.text.lock().unwrap().as_deref(),
+        Some("hello again, ${literal}")
+    );
+
+    let missing = r#"
+steps:
+  - name: read
+    command: clipboard-get
+    capture: { copied: /nothing }
+  - name: echo
+    command: clipboard-set
+    args: { text: "${copied}" }
+"#;
+    let output = run(&adapter, "missing-capture.yaml", missing, &[], None).unwrap();
+    assert_eq!(output["steps"][0]["status"], "failed");
+    assert_eq!(output["steps"][0]["capture_error"]["reason"], "missing");
+    assert_eq!(output["steps"][1]["status"], "skipped");
+}
+
+#[test]
+fn expectations_are_bound_to_values_captured_by_their_step() {
+    let adapter = adapter(false);
+    *adapter.text.lock().unwrap() = Some("@e4".into());
+    let scenario = r#"
+steps:
+  - name: read
+    command: clipboard-get
+    capture: { btn: /text }
+    expect:
+      - get: { ref_id: "${btn}", property: text, equals: "${btn}" }
+"#;
+    let output = run(&adapter, "expect-capture.yaml", scenario, &[], None)
+        .expect("expectations may reference captures");
+
+    let checked = &output["steps"][0]["expectations"][0];
+    assert_eq!(checked["kind"], "get");
+    assert_eq!(checked["args"]["ref_id"], "@e4");
+    assert_eq!(checked["passed"], false);
+}
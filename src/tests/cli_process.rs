@@ -182,6 +182,118 @@ fn invalid_state_root_env_fails_batch_as_single_envelope() {
     );
 }
 
+#[test]
+fn session_export_and_import_move_a_run_between_state_roots() {
+    let root = |name: &str| {
+        let dir = std::env::temp_dir().join(format!(
+            "agent-desktop-cli-bundle-{name}-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_nanos())
+                .unwrap_or(0)
+        ));
+        std::fs::create_dir_all(&dir).expect("create state root");
+        dir
+    };
+    let (home, teammate) = (root("home"), root("teammate"));
+    let run = |dir: &std::path::Path, args: &[&str]| -> serde_json::Value {
+        let output = binary()
+            .args(args)
+            .env("AGENT_DESKTOP_HOME", dir)
+            .output()
+            .expect("binary starts");
+        serde_json::from_slice(&output.stdout).expect("stdout is one JSON envelope")
+    };
+    let started = run(&home, &["session", "start"]);
+    let session = started["data"]["session_id"].as_str().expect("session id");
+    run(&home, &["--session", session, "wait", "10"]);
+
+    let bundle = home.join("run.tar.zst");
+    let exported = run(
+        &home,
+        &[
+            "session",
+            "export",
+            session,
+            "--out",
+            bundle.to_str().unwrap(),
+        ],
+    );
+    assert_eq!(exported["ok"], true, "{exported}");
+    let imported = run(&teammate, &["session", "import", bundle.to_str().unwrap()]);
+    assert_eq!(imported["ok"], true, "{imported}");
+    assert_eq!(imported["data"]["session_id"], session);
+
+    let listed = run(&teammate, &["session", "list"]);
+    assert_eq!(listed["data"]["sessions"][0]["read_only"], true);
+    let stats = run(&teammate, &["trace", "stats", session]);
+    assert_eq!(stats["data"]["total_commands"], 1, "{stats}");
+    let segments = |dir: &std::path::Path| {
+        let trace = dir.join("sessions").join(session).join("trace");
+        let mut files: Vec<_> = std::fs::read_dir(&trace)
+            .expect("imported trace")
+            .map(|entry| {
+                let path = entry.expect("trace entry").path();
+                (path.clone(), std::fs::read(&path).unwrap_or_default())
+            })
+            .collect();
+        files.sort();
+        files
+    };
+    let recorded = segments(&teammate);
+    let live = run(&teammate, &["--session", session, "wait", "10"]);
+    assert_eq!(live["ok"], true, "{live}");
+    assert_eq!(
+        segments(&teammate),
+        recorded,
+        "the imported trace is unchanged"
+    );
+    let again = run(&teammate, &["session", "import", bundle.to_str().unwrap()]);
+    assert_eq!(again["error"]["code"], "INVALID_ARGS");
+    let _ = std::fs::remove_dir_all(&home);
+    let _ = std::fs::remove_dir_all(&teammate);
+}
+
+#[test]
+fn session_quota_sets_global_limits_and_start_records_its_own() {
+    let dir = std::env::temp_dir().join(format!(
+        "agent-desktop-cli-quota-{}-{}",
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0)
+    ));
+    std::fs::create_dir_all(&dir).expect("create state root");
+    let run = |args: &[&str]| -> serde_json::Value {
+        let output = binary()
+            .args(args)
+            .env("AGENT_DESKTOP_HOME", &dir)
+            .output()
+            .expect("binary starts");
+        serde_json::from_slice(&output.stdout).expect("stdout is one JSON envelope")
+    };
+
+    let set = run(&["session", "quota", "--max-screenshots", "5"]);
+    assert_eq!(set["data"]["quota"]["max_screenshots"], 5, "{set}");
+    let shown = run(&["session", "quota"]);
+    assert_eq!(shown["data"]["quota"], set["data"]["quota"]);
+    let started = run(&["session", "start", "--max-trace-bytes", "4096"]);
+    assert_eq!(
+        started["data"]["quota"]["max_trace_bytes"], 4096,
+        "{started}"
+    );
+    let listed = run(&["session", "list"]);
+    assert_eq!(
+        listed["data"]["sessions"][0]["quota"]["max_trace_bytes"],
+        4096
+    );
+    let cleared = run(&["session", "quota", "--clear"]);
+    assert_eq!(cleared["data"]["quota"], serde_json::json!({}));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
 #[cfg(target_os = "macos")]
 #[test]
 fn malformed_permission_helper_invocation_bypasses_clap_and_tracing() {
@@ -0,0 +1,238 @@
+use std::process::Command;
+
+fn binary() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_agent-desktop"))
+}
+
+#[test]
+fn trace_replay_reruns_a_recorded_session_and_matches_outcomes() {
+    let dir = std::env::temp_dir().join(format!(
+        "agent-desktop-cli-trace-replay-{}-{}",
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0)
+    ));
+    std::fs::create_dir_all(&dir).expect("create state root");
+    let run = |args: &[&str]| -> serde_json::Value {
+        let output = binary()
+            .args(args)
+            .env("AGENT_DESKTOP_HOME", &dir)
+            .output()
+            .expect("binary starts");
+        serde_json::from_slice(&output.stdout).expect("stdout is one JSON envelope")
+    };
+    let started = run(&["session", "start"]);
+    let session = started["data"]["session_id"].as_str().expect("session id");
+    run(&["--session", session, "wait", "10"]);
+    run(&["--session", session, "click", "@e1", "--snapshot", "s-gone"]);
+
+    let stats = run(&["trace", "stats", session]);
+    assert_eq!(stats["ok"], true, "{stats}");
+    assert_eq!(stats["data"]["total_commands"], 2);
+    assert_eq!(stats["data"]["errors"]["SNAPSHOT_NOT_FOUND"], 1);
+
+    let plan = run(&["trace", "replay", session, "--dry-run"]);
+    assert_eq!(plan["ok"], true, "{plan}");
+    assert_eq!(plan["data"]["steps"][0]["command"], "wait");
+    assert_eq!(plan["data"]["steps"][1]["refs"][0], "@e1");
+
+    let replay = run(&["trace", "replay", session]);
+    assert_eq!(replay["ok"], true, "{replay}");
+    assert_eq!(replay["data"]["summary"]["matched"], 2);
+    assert_eq!(replay["data"]["reproduced"], true);
+    assert_eq!(
+        replay["data"]["steps"][1]["replayed"]["code"],
+        "SNAPSHOT_NOT_FOUND"
+    );
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn trace_diff_reports_the_first_divergence_between_two_sessions() {
+    let dir = std::env::temp_dir().join(format!(
+        "agent-desktop-cli-trace-diff-{}-{}",
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0)
+    ));
+    std::fs::create_dir_all(&dir).expect("create state root");
+    let run = |args: &[&str]| -> serde_json::Value {
+        let output = binary()
+            .args(args)
+            .env("AGENT_DESKTOP_HOME", &dir)
+            .output()
+            .expect("binary starts");
+        serde_json::from_slice(&output.stdout).expect("stdout is one JSON envelope")
+    };
+    let start = || {
+        run(&["session", "start"])["data"]["session_id"]
+            .as_str()
+            .expect("session id")
+            .to_string()
+    };
+    let (a, b) = (start(), start());
+    run(&["--session", &a, "wait", "10"]);
+    run(&["--session", &a, "click", "@e1", "--snapshot", "s-gone"]);
+    run(&["--session", &b, "wait", "10"]);
+    run(&["--session", &b, "wait", "10"]);
+
+    let out = dir.join("diff.html");
+    let diff = run(&["trace", "diff", &a, &b, "--out", out.to_str().unwrap()]);
+    assert_eq!(diff["ok"], true, "{diff}");
+    assert_eq!(diff["data"]["first_divergence"], 1);
+    assert_eq!(diff["data"]["rows"][1]["status"], "missing");
+    assert_eq!(diff["data"]["rows"][1]["a"]["code"], "SNAPSHOT_NOT_FOUND");
+    assert_eq!(diff["data"]["rows"][2]["status"], "added");
+    let html = std::fs::read_to_string(&out).expect("html written");
+    assert!(html.contains("trace diff"));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn trace_to_script_keeps_the_successful_actions_of_a_session() {
+    let dir = std::env::temp_dir().join(format!(
+        "agent-desktop-cli-trace-script-{}-{}",
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0)
+    ));
+    std::fs::create_dir_all(&dir).expect("create state root");
+    let run = |args: &[&str]| -> serde_json::Value {
+        let output = binary()
+            .args(args)
+            .env("AGENT_DESKTOP_HOME", &dir)
+            .output()
+            .expect("binary starts");
+        serde_json::from_slice(&output.stdout).expect("stdout is one JSON envelope")
+    };
+    let started = run(&["session", "start"]);
+    let session = started["data"]["session_id"].as_str().expect("session id");
+    run(&["--session", session, "wait", "10"]);
+    run(&["--session", session, "click", "@e1", "--snapshot", "s-gone"]);
+
+    let batch = run(&["trace", "to-script", session, "--format", "batch"]);
+    assert_eq!(batch["ok"], true, "{batch}");
+    assert_eq!(batch["data"]["script"][0]["command"], "wait");
+    assert_eq!(batch["data"]["omitted"][0]["reason"], "failed");
+
+    let out = dir.join("flow.yaml");
+    let yaml = run(&[
+        "trace",
+        "to-script",
+        session,
+        "--out",
+        out.to_str().unwrap(),
+    ]);
+    assert_eq!(yaml["ok"], true, "{yaml}");
+    let text = std::fs::read_to_string(&out).expect("script written");
+    assert!(text.contains("command: wait"), "{text}");
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn trace_show_follow_streams_events_until_the_session_ends() {
+    let dir = std::env::temp_dir().join(format!(
+        "agent-desktop-cli-trace-follow-{}-{}",
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0)
+    ));
+    std::fs::create_dir_all(&dir).expect("create state root");
+    let run = |args: &[&str]| -> serde_json::Value {
+        let output = binary()
+            .args(args)
+            .env("AGENT_DESKTOP_HOME", &dir)
+            .output()
+            .expect("binary starts");
+        serde_json::from_slice(&output.stdout).expect("stdout is one JSON envelope")
+    };
+    let started = run(&["session", "start"]);
+    let session = started["data"]["session_id"].as_str().expect("session id");
+    run(&["--session", session, "wait", "10"]);
+
+    let follower = binary()
+        .args([
+            "--session",
+            session,
+            "trace",
+            "show",
+            "--follow",
+            "--event",
+            "command.start",
+        ])
+        .env("AGENT_DESKTOP_HOME", &dir)
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .expect("binary starts");
+    std::thread::sleep(std::time::Duration::from_millis(300));
+    run(&["--session", session, "wait", "20"]);
+    run(&["session", "end", session]);
+    let output = follower.wait_with_output().expect("follower exits");
+
+    let lines: Vec<serde_json::Value> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("each line is JSON"))
+        .collect();
+    let (envelope, events) = lines.split_last().expect("final envelope");
+    assert_eq!(envelope["ok"], true, "{envelope}");
+    assert_eq!(envelope["data"]["streamed_events"], events.len());
+    assert!(envelope["data"]["ended_at"].is_u64());
+    let waits = events
+        .iter()
+        .filter(|event| event["command"] == "wait")
+        .count();
+    assert_eq!(waits, 2, "{events:?}");
+    assert!(events.iter().all(|event| event["event"] == "command.start"));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn trace_replay_stops_at_a_step_whose_args_were_redacted() {
+    let dir = std::env::temp_dir().join(format!(
+        "agent-desktop-cli-trace-replay-redacted-{}-{}",
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0)
+    ));
+    std::fs::create_dir_all(&dir).expect("create state root");
+    let run = |args: &[&str]| -> serde_json::Value {
+        let output = binary()
+            .args(args)
+            .env("AGENT_DESKTOP_HOME", &dir)
+            .output()
+            .expect("binary starts");
+        serde_json::from_slice(&output.stdout).expect("stdout is one JSON envelope")
+    };
+    let started = run(&["session", "start"]);
+    let session = started["data"]["session_id"].as_str().expect("session id");
+    run(&[
+        "--session",
+        session,
+        "type",
+        "@e1",
+        "hello",
+        "--snapshot",
+        "s-gone",
+    ]);
+    run(&["--session", session, "wait", "10"]);
+
+    let replay = run(&["trace", "replay", session]);
+    assert_eq!(replay["ok"], true, "{replay}");
+    let steps = &replay["data"]["steps"];
+    assert_eq!(steps[0]["skipped_reason"], "args_redacted");
+    assert_eq!(steps[1]["skipped_reason"], "previous_step_not_replayed");
+    assert!(steps[1].get("replayed").is_none());
+    assert_eq!(replay["data"]["summary"]["matched"], 0);
+    assert_eq!(replay["data"]["reproduced"], false);
+    let _ = std::fs::remove_dir_all(&dir);
+}
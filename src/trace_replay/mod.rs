@@ -0,0 +1,244 @@
+use std::time::Instant;
+
+use agent_desktop_core::{
+    AppError, PermissionReport, PlatformAdapter,
+    commands::{batch::BatchCommand, trace},
+    context::CommandContext,
+    output::ErrorPayload,
+    replay_refs::{RefRemapper, RefRewrite},
+    trace_read::{RecordedCommand, args_redacted},
+    trace_sanitize::sanitize_trace_value,
+};
+use serde_json::{Value, json};
+
+use crate::cli_args::trace::TraceReplayArgs;
+
+/// A replayed step's command data is echoed below this size, like a
+/// scenario step's; larger payloads are replaced by `data_omitted`.
+const MAX_STEP_DATA_BYTES: usize = 64 * 1024;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ReplayStatus {
+    Matched,
+    Diverged,
+    Skipped,
+    Planned,
+}
+
+impl ReplayStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Matched => "matched",
+            Self::Diverged => "diverged",
+            Self::Skipped => "skipped",
+            Self::Planned => "planned",
+        }
+    }
+}
+
+/// Re-runs the commands `args.session` recorded, in order, against the
+/// live desktop under the current context, and compares each outcome with
+/// the recorded one. Snapshot ids the replay produces stand in for the
+/// recorded ones, so later refs re-resolve by identity. The first
+/// divergence or step that cannot be replayed stops the run, since later
+/// steps assume the UI the recorded step left.
+pub(crate) fn execute(
+    args: TraceReplayArgs,
+    adapter: &dyn PlatformAdapter,
+    permission_report: &PermissionReport,
+    context: &CommandContext,
+) -> Result<Value, AppError> {
+    let recorded = trace::recorded_commands(&args.session)?;
+    let (from, to) = step_range(&args, recorded.len())?;
+    let mut remapper = RefRemapper::new(&args.session, context)?;
+    let mut reports = Vec::new();
+    let mut statuses = Vec::new();
+    let mut halted = None;
+    for step in recorded
+        .into_iter()
+        .filter(|step| (from..=to).contains(&step.index))
+    {
+        let (status, report) = if args.dry_run {
+            plan(&step, &remapper)
+        } else if let Some(reason) = halted {
+            skipped(&step, reason)
+        } else {
+            replay(&step, &mut remapper, adapter, permission_report, context)
+        };
+        context.trace_lazy(
+            "trace.replay.step",
+            || json!({ "index": step.index, "command": step.command, "status": status.as_str() }),
+        )?;
+        if halted.is_none() {
+            halted = halt_reason(status);
+        }
+        statuses.push(status);
+        reports.push(report);
+    }
+    let count = |status| statuses.iter().filter(|s| **s == status).count();
+    let mut body = json!({
+        "session": args.session,
+        "dry_run": args.dry_run,
+        "from": from,
+        "to": to,
+        "summary": {
+            "total": statuses.len(),
+            "matched": count(ReplayStatus::Matched),
+            "diverged": count(ReplayStatus::Diverged),
+            "skipped": count(ReplayStatus::Skipped),
+        },
+        "steps": reports,
+    });
+    if !args.dry_run {
+        body["reproduced"] = json!(count(ReplayStatus::Matched) == statuses.len());
+    }
+    Ok(body)
+}
+
+fn step_range(args: &TraceReplayArgs, recorded: usize) -> Result<(usize, usize), AppError> {
+    let from = args.from.unwrap_or(1);
+    let to = args.to.unwrap_or(recorded);
+    if from == 0 || from > to || to > recorded {
+        return Err(AppError::invalid_input_with_suggestion(
+            format!("Replay range {from}..{to} is outside the {recorded} recorded steps"),
+            "Pass 1-based --from/--to within the recorded steps; `trace replay --dry-run` lists them.",
+        ));
+    }
+    Ok((from, to))
+}
+
+/// Why a recorded step cannot be run as recorded, if it cannot.
+fn unreplayable(step: &RecordedCommand) -> Option<&'static str> {
+    match &step.args {
+        None => Some("args_not_recorded"),
+        Some(args) if args_redacted(args) => Some("args_redacted"),
+        Some(_) => None,
+    }
+}
+
+/// Why steps after one that ended with `status` are not run. A skipped
+/// step never delivered its input, so what follows would run against a UI
+/// the recording never saw.
+fn halt_reason(status: ReplayStatus) -> Option<&'static str> {
+    match status {
+        ReplayStatus::Diverged => Some("previous_step_diverged"),
+        ReplayStatus::Skipped => Some("previous_step_not_replayed"),
+        ReplayStatus::Matched | ReplayStatus::Planned => None,
+    }
+}
+
+fn base_report(step: &RecordedCommand, status: ReplayStatus) -> Value {
+    json!({
+        "index": step.index,
+        "command": step.command,
+        "status": status.as_str(),
+        "original": { "ok": step.ok, "code": step.code },
+    })
+}
+
+fn plan(step: &RecordedCommand, remapper: &RefRemapper) -> (ReplayStatus, Value) {
+    let mut report = base_report(step, ReplayStatus::Planned);
+    let args = step.args.clone().unwrap_or(Value::Null);
+    let refs: Vec<String> = remapper
+        .rewrite(&mut args.clone())
+        .into_iter()
+        .map(|rewrite| rewrite.recorded)
+        .collect();
+    report["replayable"] = json!(unreplayable(step).is_none());
+    if let Some(reason) = unreplayable(step) {
+        report["reason"] = json!(reason);
+    }
+    report["args"] = sanitize_trace_value(args);
+    report["refs"] = json!(refs);
+    (ReplayStatus::Planned, report)
+}
+
+fn skipped(step: &RecordedCommand, reason: &str) -> (ReplayStatus, Value) {
+    let mut report = base_report(step, ReplayStatus::Skipped);
+    report["skipped_reason"] = json!(reason);
+    (ReplayStatus::Skipped, report)
+}
+
+fn replay(
+    step: &RecordedCommand,
+    remapper: &mut RefRemapper,
+    adapter: &dyn PlatformAdapter,
+    permission_report: &PermissionReport,
+    context: &CommandContext,
+) -> (ReplayStatus, Value) {
+    if let Some(reason) = unreplayable(step) {
+        let (status, mut report) = skipped(step, reason);
+        if reason == "args_redacted" {
+            report["suggestion"] =
+                json!("Use `trace to-script` to turn the redacted values into scenario parameters");
+        }
+        return (status, report);
+    }
+    let started = Instant::now();
+    let mut args = step.args.clone().unwrap_or(Value::Null);
+    let rewrites = remapper.rewrite(&mut args);
+    let result = match rewrites.iter().find(|rewrite| !rewrite.resolved()) {
+        Some(unresolved) => Err(unresolved_ref(unresolved)),
+        None => run(step, args, adapter, permission_report, context),
+    };
+    let (ok, code) = match &result {
+        Ok(_) => (true, None),
+        Err(error) => (false, Some(error.code().to_string())),
+    };
+    let status = if step.ok == Some(ok) && step.code == code {
+        ReplayStatus::Matched
+    } else {
+        ReplayStatus::Diverged
+    };
+    let mut report = base_report(step, status);
+    report["replayed"] = json!({ "ok": ok, "code": code });
+    report["refs"] = json!(rewrites);
+    match result {
+        Ok(data) => {
+            let replayed_id = data.get("snapshot_id").and_then(Value::as_str);
+            if let (Some(recorded_id), Some(replayed_id)) = (step.snapshot_ids.last(), replayed_id)
+            {
+                remapper.map_snapshot(recorded_id, replayed_id);
+            }
+            if crate::batch::serialized_fits(&data, MAX_STEP_DATA_BYTES) {
+                report["data"] = data;
+            } else {
+                report["data_omitted"] = json!(true);
+            }
+        }
+        Err(error) => report["error"] = json!(ErrorPayload::from_app_error(&error)),
+    }
+    report["elapsed_ms"] = json!(u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX));
+    (status, report)
+}
+
+fn run(
+    step: &RecordedCommand,
+    args: Value,
+    adapter: &dyn PlatformAdapter,
+    permission_report: &PermissionReport,
+    context: &CommandContext,
+) -> Result<Value, AppError> {
+    let command = crate::batch::parse_command(BatchCommand {
+        command: step.command.clone(),
+        session: None,
+        args,
+        capture: Default::default(),
+    })?;
+    crate::command_policy::preflight(&command, permission_report)?;
+    crate::dispatch::dispatch(command, adapter, permission_report, context)
+}
+
+fn unresolved_ref(rewrite: &RefRewrite) -> AppError {
+    AppError::invalid_input_with_suggestion(
+        format!(
+            "Recorded ref {} has no counterpart in the replay ({})",
+            rewrite.recorded,
+            rewrite.reason.unwrap_or("ref_not_found")
+        ),
+        "The UI differs from the recording; snapshot it and compare with the recorded refmap.",
+    )
+}
+
+#[cfg(test)]
+mod tests;
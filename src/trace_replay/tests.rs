@@ -0,0 +1,105 @@
+use agent_desktop_core::{CommandContext, commands::batch::BatchCommand};
+use clap::Parser;
+use serde_json::json;
+
+use super::*;
+use crate::cli::Cli;
+
+fn recorded(index: usize, args: Option<Value>) -> RecordedCommand {
+    RecordedCommand {
+        index,
+        command: "click".into(),
+        args,
+        ok: Some(true),
+        code: None,
+        duration_ms: None,
+        snapshot_ids: Vec::new(),
+        surfaces: Vec::new(),
+    }
+}
+
+fn replay_args(from: Option<usize>, to: Option<usize>) -> TraceReplayArgs {
+    TraceReplayArgs {
+        session: "recorded".into(),
+        from,
+        to,
+        dry_run: false,
+    }
+}
+
+#[test]
+fn cli_commands_round_trip_through_their_recorded_batch_shape() {
+    for line in [
+        vec!["click", "@e3", "--snapshot", "s-1"],
+        vec!["type", "@s-1:e2", "hello"],
+        vec!["snapshot", "--app", "Mail", "-i"],
+        vec!["press", "cmd+s"],
+        vec!["wait", "250"],
+        vec!["list-displays"],
+    ] {
+        let cli = Cli::try_parse_from(std::iter::once("agent-desktop").chain(line.clone()))
+            .unwrap_or_else(|error| panic!("{line:?}: {error}"));
+        let command = cli.command.unwrap();
+        let name = command.name();
+        let tagged = serde_json::to_value(&command).unwrap();
+        assert_eq!(tagged["command"], name, "{line:?}");
+
+        let reparsed = crate::batch::parse_command(BatchCommand {
+            command: name.into(),
+            session: None,
+            args: tagged.get("args").cloned().unwrap_or(Value::Null),
+            capture: Default::default(),
+        })
+        .unwrap_or_else(|error| panic!("{line:?}: {error}"));
+        assert_eq!(serde_json::to_value(&reparsed).unwrap(), tagged, "{line:?}");
+    }
+}
+
+#[test]
+fn runner_commands_are_never_recorded_for_replay() {
+    let cli = Cli::try_parse_from(["agent-desktop", "trace", "show"]).unwrap();
+    assert!(serde_json::to_value(cli.command.unwrap()).is_err());
+}
+
+#[test]
+fn replay_range_is_one_based_and_bounded_by_the_recording() {
+    assert_eq!(step_range(&replay_args(None, None), 4).unwrap(), (1, 4));
+    assert_eq!(
+        step_range(&replay_args(Some(2), Some(3)), 4).unwrap(),
+        (2, 3)
+    );
+    for (from, to) in [(Some(0), None), (Some(3), Some(2)), (None, Some(5))] {
+        let error = step_range(&replay_args(from, to), 4).unwrap_err();
+        assert_eq!(error.code(), "INVALID_ARGS");
+    }
+}
+
+#[test]
+fn steps_without_usable_args_are_skipped_with_a_reason() {
+    assert_eq!(unreplayable(&recorded(1, None)), Some("args_not_recorded"));
+    assert_eq!(
+        unreplayable(&recorded(1, Some(json!({ "text": { "redacted": true } })))),
+        Some("args_redacted")
+    );
+    assert_eq!(
+        unreplayable(&recorded(1, Some(json!({ "ref_id": "@e1" })))),
+        None
+    );
+}
+
+#[test]
+fn dry_run_lists_refs_and_sanitized_args_without_running() {
+    let remapper = RefRemapper::new("recorded", &CommandContext::default()).unwrap();
+    let step = recorded(
+        2,
+        Some(json!({ "ref_id": "@s-1:e4", "text": "hunter2", "timeout_ms": 5000 })),
+    );
+
+    let (status, report) = plan(&step, &remapper);
+
+    assert!(status == ReplayStatus::Planned);
+    assert_eq!(report["replayable"], true);
+    assert_eq!(report["refs"], json!(["@s-1:e4"]));
+    assert_eq!(report["args"]["text"], json!({ "redacted": true }));
+    assert_eq!(report["args"]["timeout_ms"], 5000);
+}
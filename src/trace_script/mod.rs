@@ -0,0 +1,316 @@
+use std::collections::BTreeMap;
+
+use agent_desktop_core::{
+    AdapterError, AppError, ErrorCode,
+    commands::{batch::BatchCommand, trace},
+    script_refs::{RefLocation, RefLocator},
+    trace_read::RecordedCommand,
+};
+use serde::Serialize;
+use serde_json::{Map, Value, json};
+
+use crate::cli::Commands;
+use crate::cli_args::trace::{ScriptFormat, TraceToScriptArgs};
+
+/// Generated parameter names stay under the scenario limit even after a
+/// `_N` suffix is added to tell repeated fields apart.
+const MAX_PARAM_STEM_CHARS: usize = 56;
+
+/// One entry of the generated script. Batch entries have no `name`.
+#[derive(Serialize)]
+struct ScriptStep {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    command: String,
+    args: Value,
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    capture: Map<String, Value>,
+}
+
+#[derive(Serialize)]
+struct ScenarioScript<'a> {
+    name: String,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    params: BTreeMap<&'a str, Option<String>>,
+    steps: &'a [ScriptStep],
+}
+
+/// Converts the commands `args.session` recorded into a script a person can
+/// curate and run again. Failed steps and read-only observations are left
+/// out; waits and expectations stay. Each snapshot ref becomes a `find` step
+/// on the recorded element's identity whose result is captured as
+/// `${refN}`, and each value the trace redacted becomes a `${name}`
+/// parameter: required `params` in a scenario, text to fill in for a batch.
+/// Recorded args pass through the trace sanitizer again, so a literal that
+/// reached the trace unredacted never reaches the script.
+pub(crate) fn execute(args: TraceToScriptArgs) -> Result<Value, AppError> {
+    let recorded = trace::recorded_commands(&args.session)?;
+    let mut script = ScriptBuilder::new(&args.session, args.format)?;
+    for step in &recorded {
+        script.push(step);
+    }
+    if script.steps.is_empty() {
+        return Err(AppError::invalid_input_with_suggestion(
+            format!(
+                "Session '{}' recorded no successful steps that change the desktop",
+                args.session
+            ),
+            "Record a session that acts on the UI; observations alone make no script.",
+        ));
+    }
+    let text = script.render()?;
+    let mut body = json!({
+        "session": args.session,
+        "format": format_name(args.format),
+        "steps": script.steps.len(),
+        "params": script.params,
+        "omitted": script.omitted,
+        "unlocated_refs": script.unlocated,
+    });
+    match &args.out {
+        Some(out) => {
+            trace::write_script(out, &text)?;
+            body["path"] = json!(out.to_string_lossy());
+        }
+        None if args.format == ScriptFormat::Batch => body["script"] = json!(script.steps),
+        None => body["script"] = json!(text),
+    }
+    Ok(body)
+}
+
+fn format_name(format: ScriptFormat) -> &'static str {
+    match format {
+        ScriptFormat::Batch => "batch",
+        ScriptFormat::Yaml => "yaml",
+    }
+}
+
+struct ScriptBuilder {
+    session: String,
+    format: ScriptFormat,
+    locator: RefLocator,
+    steps: Vec<ScriptStep>,
+    params: Vec<Value>,
+    omitted: Vec<Value>,
+    unlocated: Vec<Value>,
+}
+
+impl ScriptBuilder {
+    fn new(session: &str, format: ScriptFormat) -> Result<Self, AppError> {
+        Ok(Self {
+            session: session.to_string(),
+            format,
+            locator: RefLocator::new(session)?,
+            steps: Vec::new(),
+            params: Vec::new(),
+            omitted: Vec::new(),
+            unlocated: Vec::new(),
+        })
+    }
+
+    fn push(&mut self, step: &RecordedCommand) {
+        let Some(recorded) = &step.args else {
+            return self.omit(step, "args_not_recorded");
+        };
+        let recorded = &agent_desktop_core::sanitize_trace_value(recorded.clone());
+        if step.ok == Some(false) {
+            return self.omit(step, "failed");
+        }
+        match kept(&step.command, recorded) {
+            Err(_) => return self.omit(step, "args_invalid"),
+            Ok(false) => return self.omit(step, "observation"),
+            Ok(true) => {}
+        }
+        let mut args = escape_literals(recorded);
+        for location in self.locator.locate(&mut args) {
+            self.push_locator(step, location);
+        }
+        let mut redacted = Vec::new();
+        parameterize(&mut args, "value", &mut |field| {
+            redacted.push(field.to_string())
+        });
+        let mut fields = redacted.into_iter();
+        let args = rename_params(&args, &mut || {
+            let field = fields.next().unwrap_or_default();
+            json!(format!("${{{}}}", self.declare_param(step, &field)))
+        });
+        self.steps.push(ScriptStep {
+            name: self.step_name(format!("{} #{}", step.command, step.index)),
+            command: step.command.clone(),
+            args,
+            capture: Map::new(),
+        });
+    }
+
+    fn push_locator(&mut self, step: &RecordedCommand, location: RefLocation) {
+        let (Some(capture), Some(locator)) = (location.capture.clone(), location.locator.clone())
+        else {
+            self.unlocated.push(json!({
+                "index": step.index,
+                "command": step.command,
+                "ref": location.recorded,
+                "reason": location.reason,
+            }));
+            return;
+        };
+        self.steps.push(ScriptStep {
+            name: self.step_name(format!(
+                "locate {capture} for {} #{}",
+                step.command, step.index
+            )),
+            command: "find".into(),
+            args: locator,
+            capture: Map::from_iter([(capture, json!("/match/ref_id"))]),
+        });
+    }
+
+    fn declare_param(&mut self, step: &RecordedCommand, field: &str) -> String {
+        let stem: String = format!("{}_{field}", step.command)
+            .chars()
+            .map(|ch| {
+                if ch.is_ascii_alphanumeric() {
+                    ch.to_ascii_lowercase()
+                } else {
+                    '_'
+                }
+            })
+            .take(MAX_PARAM_STEM_CHARS)
+            .collect();
+        let taken = |name: &str| self.params.iter().any(|param| param["name"] == name);
+        let name = (1..)
+            .map(|n| {
+                if n == 1 {
+                    stem.clone()
+                } else {
+                    format!("{stem}_{n}")
+                }
+            })
+            .find(|name| !taken(name))
+            .unwrap_or(stem);
+        self.params.push(json!({
+            "name": name,
+            "index": step.index,
+            "command": step.command,
+            "field": field,
+        }));
+        name
+    }
+
+    fn step_name(&self, name: String) -> Option<String> {
+        (self.format == ScriptFormat::Yaml).then_some(name)
+    }
+
+    fn omit(&mut self, step: &RecordedCommand, reason: &str) {
+        self.omitted.push(json!({
+            "index": step.index,
+            "command": step.command,
+            "reason": reason,
+        }));
+    }
+
+    fn render(&self) -> Result<String, AppError> {
+        let rendered = match self.format {
+            ScriptFormat::Batch => serde_json::to_string_pretty(&self.steps)
+                .map(|json| json + "\n")
+                .map_err(|error| error.to_string()),
+            ScriptFormat::Yaml => serde_yaml_bw::to_string(&ScenarioScript {
+                name: format!("replay of {}", self.session),
+                params: self
+                    .params
+                    .iter()
+                    .filter_map(|param| param["name"].as_str())
+                    .map(|name| (name, None))
+                    .collect(),
+                steps: &self.steps,
+            })
+            .map_err(|error| error.to_string()),
+        };
+        rendered.map_err(|error| {
+            AdapterError::new(
+                ErrorCode::Internal,
+                format!("Cannot render script: {error}"),
+            )
+            .into()
+        })
+    }
+}
+
+/// Whether a curated script keeps this step: anything that changes the
+/// desktop, plus waits and expectations, which pace and check it. Reads
+/// that only write a file, such as `screenshot --out`, are observations.
+fn kept(command: &str, recorded: &Value) -> Result<bool, AppError> {
+    let mut probe = recorded.clone();
+    parameterize(&mut probe, "value", &mut |_| {});
+    let probe = rename_params(&probe, &mut || json!("@e1"));
+    let command = crate::batch::parse_command(BatchCommand {
+        command: command.to_string(),
+        session: None,
+        args: probe,
+        capture: Default::default(),
+    })?;
+    Ok(match command {
+        Commands::Wait(_) | Commands::Expect(_) => true,
+        Commands::Screenshot(_)
+        | Commands::ScreenshotCompare(_)
+        | Commands::AuditA11y(_)
+        | Commands::ExtractTable(_)
+        | Commands::ClipboardGet(_)
+        | Commands::Permissions(_) => false,
+        command => command.is_mutating(),
+    })
+}
+
+/// Marker a redacted value is replaced with until it gets its name. A NUL
+/// never occurs in recorded text, so it cannot collide with a literal.
+const REDACTED_MARKER: &str = "\u{0}redacted";
+
+/// Replaces each `{"redacted": true}` the trace writer left with a marker,
+/// reporting the field it was recorded under, in document order.
+fn parameterize(value: &mut Value, field: &str, found: &mut dyn FnMut(&str)) {
+    match value {
+        Value::Object(map) if map.len() == 1 && map.get("redacted") == Some(&Value::Bool(true)) => {
+            found(field);
+            *value = json!(REDACTED_MARKER);
+        }
+        Value::Object(map) => {
+            for (key, item) in map.iter_mut() {
+                parameterize(item, key, found);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                parameterize(item, field, found);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Swaps each marker, in document order, for the value `next` yields.
+fn rename_params(value: &Value, next: &mut dyn FnMut() -> Value) -> Value {
+    match value {
+        Value::String(text) if text == REDACTED_MARKER => next(),
+        Value::Array(items) => items.iter().map(|item| rename_params(item, next)).collect(),
+        Value::Object(map) => map
+            .iter()
+            .map(|(key, item)| (key.clone(), rename_params(item, next)))
+            .collect(),
+        other => other.clone(),
+    }
+}
+
+/// Escapes `${` in recorded text so both script formats read it literally.
+fn escape_literals(value: &Value) -> Value {
+    match value {
+        Value::String(text) => json!(text.replace("${", "$${")),
+        Value::Array(items) => items.iter().map(escape_literals).collect(),
+        Value::Object(map) => map
+            .iter()
+            .map(|(key, item)| (key.clone(), escape_literals(item)))
+            .collect(),
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests;
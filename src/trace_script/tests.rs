@@ -0,0 +1,149 @@
+use agent_desktop_core::commands::run::Scenario;
+use serde_json::json;
+
+use super::*;
+
+fn recorded(index: usize, command: &str, args: Option<Value>, ok: bool) -> RecordedCommand {
+    RecordedCommand {
+        index,
+        command: command.into(),
+        args,
+        ok: Some(ok),
+        code: None,
+        duration_ms: None,
+        snapshot_ids: Vec::new(),
+        surfaces: Vec::new(),
+    }
+}
+
+fn build(format: ScriptFormat, steps: &[RecordedCommand]) -> ScriptBuilder {
+    let mut script = ScriptBuilder::new("recorded", format).unwrap();
+    for step in steps {
+        script.push(step);
+    }
+    script
+}
+
+#[test]
+fn observations_failures_and_unrecorded_args_are_omitted() {
+    let script = build(
+        ScriptFormat::Batch,
+        &[
+            recorded(1, "snapshot", Some(json!({ "app": "Mail" })), true),
+            recorded(2, "wait", Some(json!({ "ms": 250 })), true),
+            recorded(3, "press", Some(json!({ "combo": "cmd+n" })), false),
+            recorded(4, "press", None, true),
+            recorded(
+                5,
+                "screenshot",
+                Some(json!({ "output_path": "a.png" })),
+                true,
+            ),
+            recorded(6, "press", Some(json!({ "combo": "cmd+s" })), true),
+        ],
+    );
+
+    let kept: Vec<_> = script
+        .steps
+        .iter()
+        .map(|step| step.command.as_str())
+        .collect();
+    assert_eq!(kept, ["wait", "press"]);
+    assert!(script.steps.iter().all(|step| step.name.is_none()));
+    let reasons: Vec<_> = script
+        .omitted
+        .iter()
+        .map(|omitted| {
+            (
+                omitted["index"].as_u64().unwrap(),
+                omitted["reason"].clone(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        reasons,
+        [
+            (1, json!("observation")),
+            (3, json!("failed")),
+            (4, json!("args_not_recorded")),
+            (5, json!("observation")),
+        ]
+    );
+}
+
+#[test]
+fn redacted_values_become_named_parameters_and_literals_stay_literal() {
+    let script = build(
+        ScriptFormat::Yaml,
+        &[
+            recorded(
+                1,
+                "clipboard-set",
+                Some(json!({ "text": { "redacted": true } })),
+                true,
+            ),
+            recorded(
+                2,
+                "clipboard-set",
+                Some(json!({ "text": { "redacted": true } })),
+                true,
+            ),
+            recorded(3, "press", Some(json!({ "combo": "cmd+${key}" })), true),
+        ],
+    );
+
+    assert_eq!(
+        script.steps[0].args,
+        json!({ "text": "${clipboard_set_text}" })
+    );
+    assert_eq!(
+        script.steps[1].args,
+        json!({ "text": "${clipboard_set_text_2}" })
+    );
+    assert_eq!(script.steps[2].args, json!({ "combo": "cmd+$${key}" }));
+    assert_eq!(script.params[1]["index"], 2);
+    assert_eq!(script.params[1]["field"], "text");
+
+    let text = script.render().unwrap();
+    let scenario: Scenario = serde_yaml_bw::from_str(&text).unwrap();
+    assert_eq!(scenario.steps.len(), 3);
+    assert_eq!(scenario.steps[0].name, "clipboard-set #1");
+    assert_eq!(scenario.params.get("clipboard_set_text"), Some(&None));
+}
+
+#[test]
+fn literals_a_full_artifacts_session_recorded_become_parameters() {
+    let script = build(
+        ScriptFormat::Batch,
+        &[recorded(
+            1,
+            "clipboard-set",
+            Some(json!({ "text": "hunter2" })),
+            true,
+        )],
+    );
+
+    let text = script.render().unwrap();
+    assert!(!text.contains("hunter2"), "{text}");
+    assert_eq!(script.steps[0].args["text"], "${clipboard_set_text}");
+    assert_eq!(script.params[0]["field"], "text");
+}
+
+#[test]
+fn refs_without_a_recorded_refmap_are_reported_and_kept() {
+    let script = build(
+        ScriptFormat::Batch,
+        &[recorded(
+            1,
+            "click",
+            Some(json!({ "ref_id": "@e1", "snapshot": "s-gone", "timeout_ms": 5000 })),
+            true,
+        )],
+    );
+
+    assert_eq!(script.steps.len(), 1);
+    assert_eq!(script.steps[0].args["ref_id"], "@e1");
+    assert_eq!(script.steps[0].args["snapshot"], "s-gone");
+    assert_eq!(script.unlocated[0]["ref"], "@e1");
+    assert_eq!(script.unlocated[0]["reason"], "ref_not_found");
+}